@@ -0,0 +1,113 @@
+//! # RC Channels Encoder Benchmarks
+//!
+//! Compares the allocation-free, word-wise RC channels encoder against the
+//! original three-`Vec`, bit-by-bit implementation.
+//!
+//! Run with `cargo bench --bench rc_encoder`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use fpv_bridge::crsf::crc::crc8_dvb_s2;
+use fpv_bridge::crsf::decoder::unpack_rc_channels;
+use fpv_bridge::crsf::encoder::{encode_rc_channels_frame_into, pack_rc_channels};
+use fpv_bridge::crsf::protocol::*;
+
+/// Representative stick/switch positions
+const CHANNELS: RcChannels = [
+    992, 1500, 172, 1811, 1024, 172, 1811, 992, 1200, 800, 2047, 0, 1024, 1024, 500, 1600,
+];
+
+/// Original payload encoder: one bit at a time into a fresh `Vec`
+fn legacy_encode_payload(channels: &RcChannels) -> Vec<u8> {
+    let mut payload = vec![0u8; CRSF_RC_CHANNELS_PAYLOAD_SIZE];
+    let mut bit_index = 0;
+
+    for &channel in channels.iter() {
+        let value = channel.min(CRSF_CHANNEL_VALUE_MAX);
+        for bit in 0..11 {
+            if (value >> bit) & 1 == 1 {
+                payload[bit_index / 8] |= 1 << (bit_index % 8);
+            }
+            bit_index += 1;
+        }
+    }
+
+    payload
+}
+
+/// Original frame encoder: three allocations per packet
+fn legacy_encode_frame(channels: &RcChannels) -> Vec<u8> {
+    let payload = legacy_encode_payload(channels);
+
+    let mut frame_data = Vec::with_capacity(2 + payload.len());
+    frame_data.push(CRSF_RC_CHANNELS_FRAME_LENGTH);
+    frame_data.push(CRSF_FRAMETYPE_RC_CHANNELS_PACKED);
+    frame_data.extend_from_slice(&payload);
+    let crc = crc8_dvb_s2(&frame_data);
+
+    let mut complete_frame = Vec::with_capacity(CRSF_RC_CHANNELS_FRAME_SIZE);
+    complete_frame.push(CRSF_SYNC_BYTE);
+    complete_frame.extend_from_slice(&frame_data);
+    complete_frame.push(crc);
+    complete_frame
+}
+
+/// Original-style bit-by-bit payload decoder
+fn legacy_decode_payload(payload: &[u8]) -> RcChannels {
+    let mut channels = [0u16; CRSF_NUM_CHANNELS];
+    for (ch, value) in channels.iter_mut().enumerate() {
+        for bit in 0..11 {
+            let bit_index = ch * 11 + bit;
+            if (payload[bit_index / 8] >> (bit_index % 8)) & 1 == 1 {
+                *value |= 1 << bit;
+            }
+        }
+    }
+    channels
+}
+
+fn bench_payload(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rc_payload_pack");
+    group.bench_function("bitwise_vec", |b| {
+        b.iter(|| legacy_encode_payload(black_box(&CHANNELS)))
+    });
+    group.bench_function("wordwise_array", |b| {
+        let mut payload = [0u8; CRSF_RC_CHANNELS_PAYLOAD_SIZE];
+        b.iter(|| {
+            pack_rc_channels(black_box(&CHANNELS), &mut payload);
+            black_box(&payload);
+        })
+    });
+    group.finish();
+}
+
+fn bench_frame(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rc_frame_encode");
+    group.bench_function("legacy_three_vecs", |b| {
+        b.iter(|| legacy_encode_frame(black_box(&CHANNELS)))
+    });
+    group.bench_function("into_reused_buffer", |b| {
+        let mut frame = [0u8; CRSF_RC_CHANNELS_FRAME_SIZE];
+        b.iter(|| {
+            encode_rc_channels_frame_into(black_box(&CHANNELS), &mut frame);
+            black_box(&frame);
+        })
+    });
+    group.finish();
+}
+
+fn bench_unpack(c: &mut Criterion) {
+    let mut payload = [0u8; CRSF_RC_CHANNELS_PAYLOAD_SIZE];
+    pack_rc_channels(&CHANNELS, &mut payload);
+
+    let mut group = c.benchmark_group("rc_payload_unpack");
+    group.bench_function("bitwise", |b| {
+        b.iter(|| legacy_decode_payload(black_box(&payload)))
+    });
+    group.bench_function("wordwise", |b| {
+        b.iter(|| unpack_rc_channels(black_box(&payload)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_payload, bench_frame, bench_unpack);
+criterion_main!(benches);
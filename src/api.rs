@@ -0,0 +1,646 @@
+//! # Status and Control API
+//!
+//! Embedded HTTP server for a ground-station tablet or scripts next to the
+//! bridge (`[api]` in the configuration). It reads the same buses as the
+//! terminal dashboard: the controller state and the [`BridgeStatus`]
+//! published by the control loop.
+//!
+//! ## Endpoints
+//!
+//! | Method | Path             | Response                                      |
+//! |--------|------------------|-----------------------------------------------|
+//! | GET    | `/api/status`    | Full [`StatusSnapshot`]                       |
+//! | GET    | `/api/controller`| Controller state (sticks, triggers, buttons)  |
+//! | GET    | `/api/channels`  | RC channels (raw and µs) and arm state        |
+//! | GET    | `/api/telemetry` | Link, battery, GPS, attitude, mode, home      |
+//! | GET    | `/api/stream`    | WebSocket of status snapshots (`?rate=HZ`)    |
+//! | POST   | `/api/model`     | Switch model profile (`{"name": "whoop"}`)    |
+//! | POST   | `/api/reload`    | Reload the configuration file                 |
+//! | POST   | `/api/disarm`    | Disarm until L1 is released                   |
+//!
+//! POST endpoints need `Authorization: Bearer <api.token>` and are disabled
+//! while no token is configured. They are forwarded to the control loop as
+//! [`ApiRequest`]s, which answers each one.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, oneshot, watch};
+use tokio::time::{interval, Duration, Instant, MissedTickBehavior};
+
+use crate::config::ApiConfig;
+use crate::controller::mapper::ControllerState;
+use crate::crsf::protocol::{crsf_value_to_us, Attitude, BatterySensor, GpsData, LinkStatistics, RcChannels};
+use crate::dashboard::BridgeStatus;
+use crate::error::{FpvBridgeError, Result};
+use crate::latency::LatencySummary;
+use crate::navigation::Navigation;
+use crate::scheduler::TickReport;
+use crate::serial::TxQueueStats;
+
+/// Highest WebSocket snapshot rate
+pub const MAX_STREAM_RATE_HZ: u32 = 50;
+
+/// Control requests waiting for the control loop
+const REQUEST_QUEUE_SIZE: usize = 8;
+
+/// Control action requested over the API
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiCommand {
+    /// Switch to the named model profile
+    SelectModel(String),
+    /// Reload the configuration file
+    ReloadConfig,
+    /// Disarm until the arm button is released
+    Disarm,
+}
+
+/// Control request for the control loop, answered on `reply` with a
+/// message for the client or an error
+#[derive(Debug)]
+pub struct ApiRequest {
+    pub command: ApiCommand,
+    pub reply: oneshot::Sender<std::result::Result<String, String>>,
+}
+
+/// Link statistics with decoded TX power and age
+#[derive(Debug, Clone, Serialize)]
+pub struct LinkSnapshot {
+    #[serde(flatten)]
+    pub stats: LinkStatistics,
+    pub uplink_tx_power_mw: Option<u16>,
+    /// Time since the statistics arrived
+    pub age_ms: u64,
+}
+
+/// Telemetry from the module
+#[derive(Debug, Clone, Serialize)]
+pub struct TelemetrySnapshot {
+    pub link: Option<LinkSnapshot>,
+    pub battery: Option<BatterySensor>,
+    pub gps: Option<GpsData>,
+    pub attitude: Option<Attitude>,
+    pub flight_mode: Option<String>,
+    /// Home position, distance and bearing to home, flight statistics
+    pub navigation: Navigation,
+}
+
+/// RC channels and arm state
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelsSnapshot {
+    pub channels: RcChannels,
+    pub channels_us: Vec<i32>,
+    pub armed: bool,
+}
+
+/// Input-to-flush latency percentiles, in milliseconds
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct LatencySnapshot {
+    pub samples: u64,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl From<LatencySummary> for LatencySnapshot {
+    fn from(summary: LatencySummary) -> Self {
+        let ms = |duration: Duration| duration.as_micros() as f64 / 1000.0;
+        Self {
+            samples: summary.samples,
+            mean_ms: ms(summary.mean),
+            p50_ms: ms(summary.p50),
+            p95_ms: ms(summary.p95),
+            p99_ms: ms(summary.p99),
+            max_ms: ms(summary.max),
+        }
+    }
+}
+
+/// Transmit counters and timing
+#[derive(Debug, Clone, Serialize)]
+pub struct TxSnapshot {
+    pub frames_sent: u64,
+    pub send_errors: u64,
+    pub consecutive_failures: u32,
+    pub stalled: bool,
+    pub last_error: Option<String>,
+    /// Timing of the last complete second
+    pub timing: Option<TickReport>,
+    pub serial: Option<TxQueueStats>,
+    /// Input latency of the last complete second
+    pub latency: Option<LatencySnapshot>,
+}
+
+/// Everything the API reports, as served by `/api/status` and streamed
+/// over `/api/stream`
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusSnapshot {
+    pub profile: String,
+    #[serde(flatten)]
+    pub channels: ChannelsSnapshot,
+    pub disarm_latched: bool,
+    /// Reasons the drone is, or is about to be, in failsafe
+    pub failsafe: Vec<&'static str>,
+    /// Whether the input is still delivering events
+    pub input_running: bool,
+    pub controller: ControllerState,
+    pub telemetry: TelemetrySnapshot,
+    pub tx: TxSnapshot,
+}
+
+impl StatusSnapshot {
+    /// Snapshot of the bridge status and controller state at `now`
+    pub fn new(status: &BridgeStatus, controller: &ControllerState, input_running: bool, now: Instant) -> Self {
+        Self {
+            profile: status.profile.clone(),
+            channels: channels_snapshot(status),
+            disarm_latched: status.disarm_latched,
+            failsafe: status.failsafe_reasons(now),
+            input_running,
+            controller: controller.clone(),
+            telemetry: telemetry_snapshot(status, now),
+            tx: TxSnapshot {
+                frames_sent: status.frames_sent,
+                send_errors: status.send_errors,
+                consecutive_failures: status.consecutive_failures,
+                stalled: status.stalled,
+                last_error: status.last_error.clone(),
+                timing: status.tx,
+                serial: status.serial,
+                latency: status.latency.map(LatencySnapshot::from),
+            },
+        }
+    }
+}
+
+fn channels_snapshot(status: &BridgeStatus) -> ChannelsSnapshot {
+    ChannelsSnapshot {
+        channels: status.channels,
+        channels_us: status.channels.iter().map(|&value| crsf_value_to_us(value)).collect(),
+        armed: status.armed(),
+    }
+}
+
+fn telemetry_snapshot(status: &BridgeStatus, now: Instant) -> TelemetrySnapshot {
+    TelemetrySnapshot {
+        link: status.link.map(|(stats, at)| LinkSnapshot {
+            stats,
+            uplink_tx_power_mw: stats.uplink_tx_power_mw(),
+            age_ms: now.duration_since(at).as_millis() as u64,
+        }),
+        battery: status.battery,
+        gps: status.gps,
+        attitude: status.attitude,
+        flight_mode: status.flight_mode.clone(),
+        navigation: status.navigation.clone(),
+    }
+}
+
+/// Shared state of the request handlers
+#[derive(Debug, Clone)]
+pub struct ApiState {
+    status: watch::Receiver<BridgeStatus>,
+    input: watch::Receiver<ControllerState>,
+    requests: mpsc::Sender<ApiRequest>,
+    token: Option<Arc<str>>,
+    stream_rate_hz: u32,
+}
+
+impl ApiState {
+    /// Handler state reading the status and controller buses
+    ///
+    /// # Returns
+    ///
+    /// * `(ApiState, mpsc::Receiver<ApiRequest>)` - State for [`ApiServer`]
+    ///   and the control requests the control loop has to answer
+    pub fn new(
+        config: &ApiConfig,
+        status: watch::Receiver<BridgeStatus>,
+        input: watch::Receiver<ControllerState>,
+    ) -> (Self, mpsc::Receiver<ApiRequest>) {
+        let (requests, receiver) = mpsc::channel(REQUEST_QUEUE_SIZE);
+        let token = (!config.token.is_empty()).then(|| Arc::from(config.token.as_str()));
+        let state = Self { status, input, requests, token, stream_rate_hz: config.stream_rate_hz };
+        (state, receiver)
+    }
+
+    fn snapshot(&self) -> StatusSnapshot {
+        let controller = self.input.borrow().clone();
+        let running = self.input.has_changed().is_ok();
+        StatusSnapshot::new(&self.status.borrow(), &controller, running, Instant::now())
+    }
+
+    /// Check the bearer token of a control request
+    fn authorize(&self, headers: &HeaderMap) -> std::result::Result<(), (StatusCode, &'static str)> {
+        let Some(token) = &self.token else {
+            return Err((StatusCode::FORBIDDEN, "control endpoints are disabled (no api.token)"));
+        };
+        let given = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        match given {
+            Some(given) if constant_time_eq(given.as_bytes(), token.as_bytes()) => Ok(()),
+            _ => Err((StatusCode::UNAUTHORIZED, "missing or wrong bearer token")),
+        }
+    }
+
+    /// Forward a command to the control loop and wait for its answer
+    async fn request(&self, command: ApiCommand) -> Response {
+        let (reply, answer) = oneshot::channel();
+        if self.requests.send(ApiRequest { command, reply }).await.is_err() {
+            return error_response(StatusCode::SERVICE_UNAVAILABLE, "bridge is shutting down");
+        }
+        match answer.await {
+            Ok(Ok(message)) => Json(json!({ "ok": true, "message": message })).into_response(),
+            Ok(Err(error)) => error_response(StatusCode::BAD_REQUEST, &error),
+            Err(_) => error_response(StatusCode::SERVICE_UNAVAILABLE, "bridge is shutting down"),
+        }
+    }
+}
+
+/// Compare two byte strings without leaking where they differ
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn error_response(status: StatusCode, error: &str) -> Response {
+    (status, Json(json!({ "ok": false, "error": error }))).into_response()
+}
+
+/// Status and control HTTP server
+#[derive(Debug)]
+pub struct ApiServer {
+    listener: TcpListener,
+    state: ApiState,
+}
+
+impl ApiServer {
+    /// Listen on `address`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Api` error if the address cannot be bound.
+    pub async fn bind(address: &str, state: ApiState) -> Result<Self> {
+        let listener = TcpListener::bind(address)
+            .await
+            .map_err(|e| FpvBridgeError::Api(format!("Failed to bind {}: {}", address, e)))?;
+        Ok(Self { listener, state })
+    }
+
+    /// Address the server is listening on
+    ///
+    /// # Errors
+    ///
+    /// Returns `Io` error if the socket address cannot be read.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Serve requests until the task is dropped
+    ///
+    /// # Errors
+    ///
+    /// Returns `Api` error if accepting connections fails.
+    pub async fn run(self) -> Result<()> {
+        axum::serve(self.listener, router(self.state))
+            .await
+            .map_err(|e| FpvBridgeError::Api(e.to_string()))
+    }
+}
+
+fn router(state: ApiState) -> Router {
+    Router::new()
+        .route("/api/status", get(get_status))
+        .route("/api/controller", get(get_controller))
+        .route("/api/channels", get(get_channels))
+        .route("/api/telemetry", get(get_telemetry))
+        .route("/api/stream", get(stream))
+        .route("/api/model", post(select_model))
+        .route("/api/reload", post(reload))
+        .route("/api/disarm", post(disarm))
+        .with_state(state)
+}
+
+async fn get_status(State(state): State<ApiState>) -> Json<StatusSnapshot> {
+    Json(state.snapshot())
+}
+
+async fn get_controller(State(state): State<ApiState>) -> Json<ControllerState> {
+    Json(state.input.borrow().clone())
+}
+
+async fn get_channels(State(state): State<ApiState>) -> Json<ChannelsSnapshot> {
+    Json(channels_snapshot(&state.status.borrow()))
+}
+
+async fn get_telemetry(State(state): State<ApiState>) -> Json<TelemetrySnapshot> {
+    Json(telemetry_snapshot(&state.status.borrow(), Instant::now()))
+}
+
+/// Query of `/api/stream`
+#[derive(Debug, Deserialize)]
+struct StreamQuery {
+    /// Snapshots per second
+    rate: Option<u32>,
+}
+
+async fn stream(State(state): State<ApiState>, Query(query): Query<StreamQuery>, ws: WebSocketUpgrade) -> Response {
+    let rate = query.rate.unwrap_or(state.stream_rate_hz).clamp(1, MAX_STREAM_RATE_HZ);
+    ws.on_upgrade(move |socket| stream_snapshots(socket, state, rate))
+}
+
+/// Send a snapshot `rate` times a second until the client goes away or the
+/// bridge stops
+async fn stream_snapshots(mut socket: WebSocket, state: ApiState, rate: u32) {
+    let mut ticks = interval(Duration::from_secs(1) / rate);
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    loop {
+        tokio::select! {
+            _ = ticks.tick() => {
+                if state.status.has_changed().is_err() {
+                    let _ = socket.send(Message::Close(None)).await;
+                    return;
+                }
+                let Ok(text) = serde_json::to_string(&state.snapshot()) else {
+                    return;
+                };
+                if socket.send(Message::Text(text.into())).await.is_err() {
+                    return;
+                }
+            }
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+/// Body of `/api/model`
+#[derive(Debug, Deserialize)]
+struct SelectModel {
+    name: String,
+}
+
+async fn select_model(State(state): State<ApiState>, headers: HeaderMap, Json(body): Json<SelectModel>) -> Response {
+    if let Err((status, error)) = state.authorize(&headers) {
+        return error_response(status, error);
+    }
+    state.request(ApiCommand::SelectModel(body.name)).await
+}
+
+async fn reload(State(state): State<ApiState>, headers: HeaderMap) -> Response {
+    if let Err((status, error)) = state.authorize(&headers) {
+        return error_response(status, error);
+    }
+    state.request(ApiCommand::ReloadConfig).await
+}
+
+async fn disarm(State(state): State<ApiState>, headers: HeaderMap) -> Response {
+    if let Err((status, error)) = state.authorize(&headers) {
+        return error_response(status, error);
+    }
+    state.request(ApiCommand::Disarm).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::channel_mapper::{channels, SWITCH_ON};
+    use crate::crsf::protocol::{CRSF_CHANNEL_VALUE_CENTER, CRSF_NUM_CHANNELS};
+    use crate::serial::virtual_module::VirtualModule;
+    use futures_util::StreamExt;
+    use serde_json::Value;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    const TOKEN: &str = "s3cret";
+
+    struct TestApi {
+        address: SocketAddr,
+        status: watch::Sender<BridgeStatus>,
+        input: watch::Sender<ControllerState>,
+        requests: mpsc::Receiver<ApiRequest>,
+    }
+
+    async fn start(token: &str) -> TestApi {
+        let config = ApiConfig { enabled: true, token: token.to_string(), ..ApiConfig::default() };
+        let (status, status_rx) = watch::channel(BridgeStatus::new("base", Duration::from_millis(500)));
+        let (input, input_rx) = watch::channel(ControllerState::default());
+        let (state, requests) = ApiState::new(&config, status_rx, input_rx);
+        let server = ApiServer::bind("127.0.0.1:0", state).await.unwrap();
+        let address = server.local_addr().unwrap();
+        tokio::spawn(server.run());
+        TestApi { address, status, input, requests }
+    }
+
+    /// Minimal HTTP/1.1 client: returns the status code and JSON body
+    async fn http(address: SocketAddr, method: &str, path: &str, token: Option<&str>, body: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        let auth = token.map(|token| format!("Authorization: Bearer {}\r\n", token)).unwrap_or_default();
+        let request = format!(
+            "{} {} HTTP/1.1\r\nHost: {}\r\n{}Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            method,
+            path,
+            address,
+            auth,
+            body.len(),
+            body
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+        (status, serde_json::from_str(body).unwrap_or(Value::Null))
+    }
+
+    /// Answer one control request like the control loop would
+    async fn answer(requests: &mut mpsc::Receiver<ApiRequest>) -> ApiCommand {
+        let request = requests.recv().await.unwrap();
+        let reply = match &request.command {
+            ApiCommand::SelectModel(name) if name == "whoop" => Ok("whoop".to_string()),
+            ApiCommand::SelectModel(name) => Err(format!("unknown model profile '{}'", name)),
+            ApiCommand::ReloadConfig => Ok("base".to_string()),
+            ApiCommand::Disarm => Ok("disarmed".to_string()),
+        };
+        request.reply.send(reply).unwrap();
+        request.command
+    }
+
+    #[tokio::test]
+    async fn test_status_snapshots() {
+        let api = start(TOKEN).await;
+        let mut channels = [CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS];
+        channels[channels::ARM] = SWITCH_ON;
+        api.status.send_modify(|status| status.record_sent(&channels));
+        api.input.send_modify(|state| state.btn_l1 = true);
+
+        let (code, status) = http(api.address, "GET", "/api/status", None, "").await;
+        assert_eq!(code, 200);
+        assert_eq!(status["profile"], "base");
+        assert_eq!(status["armed"], true);
+        assert_eq!(status["channels"][0], 1024);
+        assert_eq!(status["channels_us"][0], 1520);
+        assert_eq!(status["controller"]["btn_l1"], true);
+        assert_eq!(status["input_running"], true);
+        assert_eq!(status["tx"]["frames_sent"], 1);
+        assert_eq!(status["tx"]["latency"], Value::Null);
+        assert_eq!(status["failsafe"], Value::Array(vec![]));
+
+        let (_, controller) = http(api.address, "GET", "/api/controller", None, "").await;
+        assert_eq!(controller["left_stick_x"], 128);
+        assert!(controller.get("last_event_time").is_none());
+
+        let (_, channels) = http(api.address, "GET", "/api/channels", None, "").await;
+        assert_eq!(channels["armed"], true);
+        assert_eq!(channels["channels"][channels::ARM], 2047);
+
+        let mut histogram = crate::latency::LatencyHistogram::new();
+        histogram.record(Duration::from_micros(2450));
+        api.status.send_modify(|status| status.latency = histogram.summary());
+        let (_, status) = http(api.address, "GET", "/api/status", None, "").await;
+        assert_eq!(status["tx"]["latency"]["samples"], 1);
+        assert_eq!(status["tx"]["latency"]["p99_ms"], 2.45);
+
+        let (code, _) = http(api.address, "GET", "/api/nothing", None, "").await;
+        assert_eq!(code, 404);
+    }
+
+    #[tokio::test]
+    async fn test_telemetry_from_virtual_module() {
+        let api = start(TOKEN).await;
+        let (mut serial, _module) = VirtualModule::duplex();
+
+        // Feed module frames to the status bus like the control loop does
+        let mut telemetry = 0;
+        while telemetry < 3 {
+            let frame = serial.recv_frame().await.unwrap();
+            if api.status.send_if_modified(|status| status.apply_telemetry(&frame, Instant::now()).unwrap()) {
+                telemetry += 1;
+            }
+        }
+
+        let (code, telemetry) = http(api.address, "GET", "/api/telemetry", None, "").await;
+        assert_eq!(code, 200);
+        assert_eq!(telemetry["link"]["uplink_lq"], 100);
+        assert_eq!(telemetry["link"]["uplink_tx_power_mw"], 100);
+        assert!(telemetry["link"]["age_ms"].as_u64().unwrap() < 1000);
+        assert!(telemetry["battery"]["voltage"].as_f64().unwrap() > 16.0);
+        assert_eq!(telemetry["gps"]["satellites"], 12);
+    }
+
+    #[tokio::test]
+    async fn test_control_needs_token() {
+        let mut api = start(TOKEN).await;
+
+        let (code, body) = http(api.address, "POST", "/api/disarm", None, "").await;
+        assert_eq!(code, 401);
+        assert_eq!(body["ok"], false);
+        let (code, _) = http(api.address, "POST", "/api/disarm", Some("wrong"), "").await;
+        assert_eq!(code, 401);
+        assert!(api.requests.try_recv().is_err());
+
+        let address = api.address;
+        let client = tokio::spawn(async move { http(address, "POST", "/api/disarm", Some(TOKEN), "").await });
+        assert_eq!(answer(&mut api.requests).await, ApiCommand::Disarm);
+        let (code, body) = client.await.unwrap();
+        assert_eq!(code, 200);
+        assert_eq!(body, json!({ "ok": true, "message": "disarmed" }));
+    }
+
+    #[tokio::test]
+    async fn test_control_disabled_without_token() {
+        let api = start("").await;
+        let (code, body) = http(api.address, "POST", "/api/reload", Some(""), "").await;
+        assert_eq!(code, 403);
+        assert!(body["error"].as_str().unwrap().contains("disabled"));
+    }
+
+    #[tokio::test]
+    async fn test_select_model_and_reload() {
+        let mut api = start(TOKEN).await;
+        let address = api.address;
+
+        let client =
+            tokio::spawn(async move { http(address, "POST", "/api/model", Some(TOKEN), r#"{"name":"whoop"}"#).await });
+        assert_eq!(answer(&mut api.requests).await, ApiCommand::SelectModel("whoop".to_string()));
+        assert_eq!(client.await.unwrap().0, 200);
+
+        let client =
+            tokio::spawn(async move { http(address, "POST", "/api/model", Some(TOKEN), r#"{"name":"nope"}"#).await });
+        answer(&mut api.requests).await;
+        let (code, body) = client.await.unwrap();
+        assert_eq!(code, 400);
+        assert_eq!(body["error"], "unknown model profile 'nope'");
+
+        let client = tokio::spawn(async move { http(address, "POST", "/api/reload", Some(TOKEN), "").await });
+        assert_eq!(answer(&mut api.requests).await, ApiCommand::ReloadConfig);
+        assert_eq!(client.await.unwrap().0, 200);
+
+        // Malformed body never reaches the control loop
+        let (code, _) = http(address, "POST", "/api/model", Some(TOKEN), "{}").await;
+        assert_eq!(code, 422);
+        assert!(api.requests.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_control_fails_when_loop_is_gone() {
+        let api = start(TOKEN).await;
+        drop(api.requests);
+        let (code, _) = http(api.address, "POST", "/api/disarm", Some(TOKEN), "").await;
+        assert_eq!(code, 503);
+    }
+
+    #[tokio::test]
+    async fn test_websocket_stream() {
+        let api = start(TOKEN).await;
+        let url = format!("ws://{}/api/stream?rate=50", api.address);
+        let (mut socket, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+
+        let first = socket.next().await.unwrap().unwrap();
+        let first: Value = serde_json::from_str(first.to_text().unwrap()).unwrap();
+        assert_eq!(first["tx"]["frames_sent"], 0);
+
+        api.status.send_modify(|status| status.frames_sent = 7);
+        let mut frames_sent = 0;
+        for _ in 0..5 {
+            let message = socket.next().await.unwrap().unwrap();
+            let snapshot: Value = serde_json::from_str(message.to_text().unwrap()).unwrap();
+            frames_sent = snapshot["tx"]["frames_sent"].as_u64().unwrap();
+            if frames_sent == 7 {
+                break;
+            }
+        }
+        assert_eq!(frames_sent, 7);
+
+        // The stream closes with the bridge
+        drop(api.status);
+        while let Some(message) = socket.next().await {
+            if message.map_or(true, |message| message.is_close()) {
+                return;
+            }
+        }
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"token", b"token"));
+        assert!(!constant_time_eq(b"token", b"tokem"));
+        assert!(!constant_time_eq(b"token", b"token2"));
+        assert!(constant_time_eq(b"", b""));
+    }
+}
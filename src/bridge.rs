@@ -0,0 +1,975 @@
+//! # Network Bridge
+//!
+//! Carries RC frames over UDP from a remote client (e.g. `fpv-bridge` on a
+//! laptop with the controller) to the ELRS module attached to this machine,
+//! and the module's telemetry back to the client.
+//!
+//! This module handles:
+//! - The bridge packet format ([`encode_bridge_frame`],
+//!   [`encode_bridge_channels`], [`decode_bridge_packet`])
+//! - The server side ([`BridgeServer`]): client tracking, sequence numbers,
+//!   dropping stale packets and the failsafe deadline
+//! - The client side ([`BridgeClient`]), a [`FrameSink`]
+//!
+//! ## Packet Format (client → server)
+//!
+//! ```text
+//! 'F' 'B' | Version (0x02) | Kind | Sequence (u32 BE) | Body | Tag (16 bytes)
+//! ```
+//!
+//! | Kind   | Body                                              |
+//! |--------|---------------------------------------------------|
+//! | `0x01` | Complete CRSF RC channels frame (0x16 or 0x17)    |
+//! | `0x02` | 16 channel values, u16 BE each (0-2047)           |
+//!
+//! The tag is the HMAC-SHA256 of everything before it, keyed with the shared
+//! `bridge.secret` and truncated to 16 bytes ([`BridgeKey`]). Packets with a
+//! wrong tag are dropped, and since the sequence number is covered by the
+//! tag, so are packets replayed while the client is connected. After the
+//! failsafe timeout without a valid packet the server forgets the sequence,
+//! so a restarted client is accepted whatever its clock says.
+//!
+//! Bare CRSF RC channels frames (no header, no tag, no sequence checks) are
+//! rejected unless the server allows them from its configured client
+//! addresses. Only RC channels frames are forwarded; commands (bind, model
+//! select) are not accepted from the network.
+//!
+//! ## Security
+//!
+//! The tag authenticates the RC frames, it does not encrypt them, and
+//! telemetry to the client is not authenticated. While no client is
+//! connected, a recorded packet stream can be replayed. Run the server on a
+//! trusted network only (a VPN or the field's own access point), never on
+//! an address reachable from the internet.
+//!
+//! ## Telemetry (server → client)
+//!
+//! Every CRSF frame received from the module is sent to the client as one
+//! datagram, unwrapped.
+
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::net::UdpSocket;
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+use crate::crsf::decoder::{decode_frame, FrameParser};
+use crate::crsf::encoder::encode_rc_channels_frame;
+use crate::crsf::protocol::{
+    RcChannels, CRSF_CHANNEL_VALUE_MAX, CRSF_FRAMETYPE_RC_CHANNELS_PACKED,
+    CRSF_FRAMETYPE_SUBSET_RC_CHANNELS_PACKED, CRSF_MAX_FRAME_SIZE, CRSF_NUM_CHANNELS,
+    CRSF_SYNC_BYTE,
+};
+use crate::error::{FpvBridgeError, Result};
+use crate::sink::FrameSink;
+
+/// First two bytes of a bridge packet
+pub const BRIDGE_MAGIC: [u8; 2] = *b"FB";
+
+/// Bridge packet format version (0x01 packets had no tag)
+pub const BRIDGE_VERSION: u8 = 0x02;
+
+/// Packet kind: body is a CRSF RC channels frame
+pub const BRIDGE_KIND_FRAME: u8 = 0x01;
+
+/// Packet kind: body is 16 big-endian channel values
+pub const BRIDGE_KIND_CHANNELS: u8 = 0x02;
+
+/// Magic + version + kind + sequence
+pub const BRIDGE_HEADER_SIZE: usize = 8;
+
+/// Truncated HMAC-SHA256 tag at the end of every bridge packet
+pub const BRIDGE_TAG_SIZE: usize = 16;
+
+/// Shortest accepted `bridge.secret`
+pub const MIN_BRIDGE_SECRET_LEN: usize = 16;
+
+/// Largest datagram either side sends
+const MAX_DATAGRAM_SIZE: usize = BRIDGE_HEADER_SIZE + CRSF_MAX_FRAME_SIZE + BRIDGE_TAG_SIZE;
+
+/// Shared secret authenticating bridge packets
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::bridge::{decode_bridge_packet, encode_bridge_frame, BridgeKey};
+/// use fpv_bridge::crsf::encoder::encode_rc_channels_frame;
+///
+/// let key = BridgeKey::new("correct horse battery staple").unwrap();
+/// let other = BridgeKey::new("a different shared secret").unwrap();
+/// let packet = encode_bridge_frame(&key, 1, &encode_rc_channels_frame(&[1024; 16]));
+///
+/// assert!(decode_bridge_packet(&packet, &key, false).is_ok());
+/// assert!(decode_bridge_packet(&packet, &other, false).is_err());
+/// ```
+#[derive(Clone)]
+pub struct BridgeKey {
+    mac: Hmac<Sha256>,
+}
+
+impl fmt::Debug for BridgeKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BridgeKey(..)")
+    }
+}
+
+impl BridgeKey {
+    /// Key for the shared `bridge.secret`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Network` error if the secret is shorter than
+    /// [`MIN_BRIDGE_SECRET_LEN`] bytes.
+    pub fn new(secret: &str) -> Result<Self> {
+        if secret.len() < MIN_BRIDGE_SECRET_LEN {
+            return Err(FpvBridgeError::Network(format!(
+                "bridge.secret must be set to at least {} characters, shared by server and client",
+                MIN_BRIDGE_SECRET_LEN
+            )));
+        }
+        let mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+        Ok(Self { mac })
+    }
+
+    /// Tag of `data`
+    fn tag(&self, data: &[u8]) -> [u8; BRIDGE_TAG_SIZE] {
+        let mut mac = self.mac.clone();
+        mac.update(data);
+        let mut tag = [0u8; BRIDGE_TAG_SIZE];
+        tag.copy_from_slice(&mac.finalize().into_bytes()[..BRIDGE_TAG_SIZE]);
+        tag
+    }
+
+    /// Whether `tag` authenticates `data` (constant time)
+    fn verify(&self, data: &[u8], tag: &[u8]) -> bool {
+        let mut mac = self.mac.clone();
+        mac.update(data);
+        mac.verify_truncated_left(tag).is_ok()
+    }
+}
+
+/// RC frame received over the network
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BridgePacket {
+    /// Sequence number (`None` for bare CRSF frames)
+    pub sequence: Option<u32>,
+    /// Complete CRSF RC channels frame to forward
+    pub frame: Vec<u8>,
+}
+
+/// Wrap a CRSF RC channels frame in a bridge packet
+///
+/// # Arguments
+///
+/// * `key` - Shared secret
+/// * `sequence` - Packet sequence number (incremented per packet, wrapping)
+/// * `frame` - Complete CRSF RC channels frame
+///
+/// # Returns
+///
+/// * `Vec<u8>` - Datagram to send to the bridge server
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::bridge::{decode_bridge_packet, encode_bridge_frame, BridgeKey};
+/// use fpv_bridge::crsf::encoder::encode_rc_channels_frame;
+/// use fpv_bridge::crsf::protocol::CRSF_CHANNEL_VALUE_CENTER;
+///
+/// let key = BridgeKey::new("correct horse battery staple").unwrap();
+/// let frame = encode_rc_channels_frame(&[CRSF_CHANNEL_VALUE_CENTER; 16]);
+/// let packet = decode_bridge_packet(&encode_bridge_frame(&key, 7, &frame), &key, false).unwrap();
+///
+/// assert_eq!(packet.sequence, Some(7));
+/// assert_eq!(packet.frame, frame);
+/// ```
+pub fn encode_bridge_frame(key: &BridgeKey, sequence: u32, frame: &[u8]) -> Vec<u8> {
+    let mut packet = bridge_header(BRIDGE_KIND_FRAME, sequence, frame.len());
+    packet.extend_from_slice(frame);
+    sign(key, packet)
+}
+
+/// Build a compact bridge packet carrying 16 channel values
+///
+/// # Arguments
+///
+/// * `key` - Shared secret
+/// * `sequence` - Packet sequence number (incremented per packet, wrapping)
+/// * `channels` - Channel values (0-2047)
+///
+/// # Returns
+///
+/// * `Vec<u8>` - 56-byte datagram to send to the bridge server
+pub fn encode_bridge_channels(key: &BridgeKey, sequence: u32, channels: &RcChannels) -> Vec<u8> {
+    let mut packet = bridge_header(BRIDGE_KIND_CHANNELS, sequence, CRSF_NUM_CHANNELS * 2);
+    for value in channels {
+        packet.extend_from_slice(&value.to_be_bytes());
+    }
+    sign(key, packet)
+}
+
+fn bridge_header(kind: u8, sequence: u32, body_len: usize) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(BRIDGE_HEADER_SIZE + body_len + BRIDGE_TAG_SIZE);
+    packet.extend_from_slice(&BRIDGE_MAGIC);
+    packet.push(BRIDGE_VERSION);
+    packet.push(kind);
+    packet.extend_from_slice(&sequence.to_be_bytes());
+    packet
+}
+
+/// Append the tag of header and body
+fn sign(key: &BridgeKey, mut packet: Vec<u8>) -> Vec<u8> {
+    let tag = key.tag(&packet);
+    packet.extend_from_slice(&tag);
+    packet
+}
+
+/// Decode a datagram received by the bridge server
+///
+/// # Arguments
+///
+/// * `data` - Bridge packet or bare CRSF RC channels frame
+/// * `key` - Shared secret the packet must be tagged with
+/// * `allow_bare` - Whether bare (untagged) CRSF frames are accepted
+///
+/// # Returns
+///
+/// * `Result<BridgePacket>` - RC frame to forward and its sequence number
+///
+/// # Errors
+///
+/// Returns `CrsfProtocol` error for unknown magic, version or kind, a wrong
+/// tag, bare frames unless `allow_bare`, channel values out of range, and
+/// frames that are malformed, fail the CRC check or are not RC channels
+/// frames.
+pub fn decode_bridge_packet(data: &[u8], key: &BridgeKey, allow_bare: bool) -> Result<BridgePacket> {
+    if data.first() == Some(&CRSF_SYNC_BYTE) {
+        if !allow_bare {
+            return Err(FpvBridgeError::CrsfProtocol(
+                "Bare CRSF frames are not accepted (bridge.allow_bare_frames)".to_string()
+            ));
+        }
+        return Ok(BridgePacket {
+            sequence: None,
+            frame: validate_rc_frame(data)?,
+        });
+    }
+
+    if data.len() < BRIDGE_HEADER_SIZE || data[..2] != BRIDGE_MAGIC {
+        return Err(FpvBridgeError::CrsfProtocol("Not a bridge packet".to_string()));
+    }
+    if data[2] != BRIDGE_VERSION {
+        return Err(FpvBridgeError::CrsfProtocol(
+            format!("Unsupported bridge packet version {}", data[2])
+        ));
+    }
+    if data.len() < BRIDGE_HEADER_SIZE + BRIDGE_TAG_SIZE {
+        return Err(FpvBridgeError::CrsfProtocol("Bridge packet has no tag".to_string()));
+    }
+    let (signed, tag) = data.split_at(data.len() - BRIDGE_TAG_SIZE);
+    if !key.verify(signed, tag) {
+        return Err(FpvBridgeError::CrsfProtocol("Bridge packet tag does not match bridge.secret".to_string()));
+    }
+    let sequence = u32::from_be_bytes([data[4], data[5], data[6], data[7]]);
+    let body = &signed[BRIDGE_HEADER_SIZE..];
+
+    let frame = match data[3] {
+        BRIDGE_KIND_FRAME => validate_rc_frame(body)?,
+        BRIDGE_KIND_CHANNELS => {
+            if body.len() != CRSF_NUM_CHANNELS * 2 {
+                return Err(FpvBridgeError::CrsfProtocol(
+                    format!("Channel packet body must be {} bytes, got {}", CRSF_NUM_CHANNELS * 2, body.len())
+                ));
+            }
+            let mut channels = [0u16; CRSF_NUM_CHANNELS];
+            for (channel, bytes) in channels.iter_mut().zip(body.chunks_exact(2)) {
+                *channel = u16::from_be_bytes([bytes[0], bytes[1]]);
+                if *channel > CRSF_CHANNEL_VALUE_MAX {
+                    return Err(FpvBridgeError::CrsfProtocol(
+                        format!("Channel value {} out of range", channel)
+                    ));
+                }
+            }
+            encode_rc_channels_frame(&channels)
+        }
+        kind => {
+            return Err(FpvBridgeError::CrsfProtocol(
+                format!("Unknown bridge packet kind 0x{:02X}", kind)
+            ))
+        }
+    };
+
+    Ok(BridgePacket {
+        sequence: Some(sequence),
+        frame,
+    })
+}
+
+/// Check that `data` is exactly one valid RC channels frame
+fn validate_rc_frame(data: &[u8]) -> Result<Vec<u8>> {
+    let frame = decode_frame(data)?;
+    if data.len() != 2 + usize::from(data[1]) {
+        return Err(FpvBridgeError::CrsfProtocol(
+            format!("Trailing bytes after CRSF frame ({} bytes)", data.len())
+        ));
+    }
+    match frame.frame_type {
+        CRSF_FRAMETYPE_RC_CHANNELS_PACKED | CRSF_FRAMETYPE_SUBSET_RC_CHANNELS_PACKED => Ok(data.to_vec()),
+        other => Err(FpvBridgeError::CrsfProtocol(
+            format!("Only RC channels frames are forwarded, got type 0x{:02X}", other)
+        )),
+    }
+}
+
+/// Bridge server counters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BridgeStats {
+    /// Datagrams received
+    pub received: u64,
+    /// RC frames forwarded to the module
+    pub forwarded: u64,
+    /// Valid frames dropped because a newer one arrived in the same burst
+    pub superseded: u64,
+    /// Packets dropped for an old or repeated sequence number
+    pub out_of_order: u64,
+    /// Packets that failed to decode or authenticate
+    pub invalid: u64,
+    /// Packets from another address while the client was active
+    pub foreign: u64,
+    /// Packets from addresses not in the allowed client list
+    pub refused: u64,
+    /// Telemetry frames sent to the client
+    pub telemetry_sent: u64,
+}
+
+impl fmt::Display for BridgeStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} received, {} forwarded, {} superseded, {} out of order, {} invalid, {} foreign, {} refused, {} telemetry sent",
+            self.received,
+            self.forwarded,
+            self.superseded,
+            self.out_of_order,
+            self.invalid,
+            self.foreign,
+            self.refused,
+            self.telemetry_sent
+        )
+    }
+}
+
+/// Receives RC frames from one client at a time
+///
+/// Only packets tagged with the shared secret are accepted, optionally only
+/// from a list of client addresses. The first client to send a valid packet
+/// owns the bridge until it has been silent for the failsafe timeout;
+/// packets from other addresses are ignored meanwhile. Sequence numbers
+/// must keep increasing while a client is connected; once it has been
+/// silent for the failsafe timeout, the next client starts a new sequence,
+/// so a restarted client whose clock went back is not locked out.
+#[derive(Debug)]
+pub struct BridgeServer {
+    socket: UdpSocket,
+    key: BridgeKey,
+    /// Client addresses allowed to send (empty: any address with the secret)
+    allowed_clients: Vec<IpAddr>,
+    /// Accept bare CRSF frames from the allowed clients
+    allow_bare_frames: bool,
+    /// Address of the active client
+    client: Option<SocketAddr>,
+    /// Highest sequence number accepted from the current client
+    last_sequence: Option<u32>,
+    /// When the last frame was accepted
+    last_packet: Option<Instant>,
+    failsafe_timeout: Duration,
+    stats: BridgeStats,
+}
+
+impl BridgeServer {
+    /// Listen for clients on `address`
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - Local address to bind (e.g. `192.168.4.1:7777` on the
+    ///   field network)
+    /// * `key` - Shared secret clients tag their packets with
+    /// * `failsafe_timeout` - Silence after which the client is considered lost
+    ///
+    /// # Errors
+    ///
+    /// Returns `Network` error if the socket cannot be bound.
+    pub async fn bind(address: &str, key: BridgeKey, failsafe_timeout: Duration) -> Result<Self> {
+        let socket = UdpSocket::bind(address)
+            .await
+            .map_err(|e| FpvBridgeError::Network(format!("Failed to bind {}: {}", address, e)))?;
+        if socket.local_addr().is_ok_and(|local| local.ip().is_unspecified()) {
+            warn!("Bridge server listens on all interfaces; only run it on a trusted network");
+        }
+
+        Ok(Self {
+            socket,
+            key,
+            allowed_clients: Vec::new(),
+            allow_bare_frames: false,
+            client: None,
+            last_sequence: None,
+            last_packet: None,
+            failsafe_timeout,
+            stats: BridgeStats::default(),
+        })
+    }
+
+    /// Only accept packets from these client addresses
+    #[must_use]
+    pub fn with_allowed_clients(mut self, allowed_clients: Vec<IpAddr>) -> Self {
+        self.allowed_clients = allowed_clients;
+        self
+    }
+
+    /// Accept bare (untagged, unsequenced) CRSF frames from the allowed
+    /// clients
+    ///
+    /// Ignored without allowed clients: a bare frame proves nothing about
+    /// its sender.
+    #[must_use]
+    pub fn with_bare_frames(mut self, allow: bool) -> Self {
+        self.allow_bare_frames = allow;
+        self
+    }
+
+    /// Address the server is listening on
+    ///
+    /// # Errors
+    ///
+    /// Returns `Io` error if the socket address cannot be read.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.socket.local_addr()?)
+    }
+
+    /// Address of the current (or last) client
+    pub fn client(&self) -> Option<SocketAddr> {
+        self.client
+    }
+
+    /// Wait for the next RC frame to forward
+    ///
+    /// Invalid, foreign and out-of-order packets are skipped. If several
+    /// packets are already queued, only the newest is returned: the others
+    /// are stale. Cancel-safe.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<u8>>` - Complete CRSF RC channels frame
+    ///
+    /// # Errors
+    ///
+    /// Returns `Network` error if the socket fails.
+    pub async fn recv_frame(&mut self) -> Result<Vec<u8>> {
+        let mut buf = [0u8; MAX_DATAGRAM_SIZE];
+        loop {
+            let (n, from) = self.socket
+                .recv_from(&mut buf)
+                .await
+                .map_err(|e| FpvBridgeError::Network(format!("Failed to receive: {}", e)))?;
+            let mut newest = self.accept(&buf[..n], from, Instant::now());
+
+            while let Ok((n, from)) = self.socket.try_recv_from(&mut buf) {
+                if let Some(frame) = self.accept(&buf[..n], from, Instant::now()) {
+                    if newest.replace(frame).is_some() {
+                        self.stats.superseded += 1;
+                    }
+                }
+            }
+
+            if let Some(frame) = newest {
+                self.stats.forwarded += 1;
+                return Ok(frame);
+            }
+        }
+    }
+
+    /// Validate one datagram and update the client state
+    fn accept(&mut self, data: &[u8], from: SocketAddr, now: Instant) -> Option<Vec<u8>> {
+        self.stats.received += 1;
+        if !self.allowed_clients.is_empty() && !self.allowed_clients.contains(&from.ip()) {
+            self.stats.refused += 1;
+            debug!("Ignoring packet from {}: not an allowed client", from);
+            return None;
+        }
+        let live = self.is_live(now);
+        if live && self.client.is_some_and(|client| client != from) {
+            self.stats.foreign += 1;
+            return None;
+        }
+
+        let allow_bare = self.allow_bare_frames && !self.allowed_clients.is_empty();
+        let packet = match decode_bridge_packet(data, &self.key, allow_bare) {
+            Ok(packet) => packet,
+            Err(e) => {
+                self.stats.invalid += 1;
+                debug!("Ignoring packet from {}: {}", from, e);
+                return None;
+            }
+        };
+
+        // A new connection starts a new sequence: a restarted client seeds
+        // its sequence from a clock that may be behind
+        if !live {
+            self.last_sequence = None;
+        }
+        if let Some(sequence) = packet.sequence {
+            if self.last_sequence.is_some_and(|last| !is_newer(sequence, last)) {
+                self.stats.out_of_order += 1;
+                return None;
+            }
+            self.last_sequence = Some(sequence);
+        }
+        if !live {
+            if self.client != Some(from) {
+                info!("Bridge client {} connected", from);
+            }
+            self.client = Some(from);
+        }
+
+        self.last_packet = Some(now);
+        Some(packet.frame)
+    }
+
+    /// When the client is considered lost unless another frame arrives
+    ///
+    /// Returns `None` before the first frame.
+    pub fn failsafe_deadline(&self) -> Option<Instant> {
+        self.last_packet.map(|last| last + self.failsafe_timeout)
+    }
+
+    /// Whether the client has sent a frame within the failsafe timeout
+    pub fn is_live(&self, now: Instant) -> bool {
+        self.failsafe_deadline().is_some_and(|deadline| now < deadline)
+    }
+
+    /// Send a telemetry frame to the client, if one is active
+    ///
+    /// # Errors
+    ///
+    /// Returns `Network` error if the datagram cannot be sent.
+    pub async fn send_telemetry(&mut self, frame: &[u8]) -> Result<()> {
+        let Some(client) = self.client.filter(|_| self.is_live(Instant::now())) else {
+            return Ok(());
+        };
+        self.socket
+            .send_to(frame, client)
+            .await
+            .map_err(|e| FpvBridgeError::Network(format!("Failed to send telemetry to {}: {}", client, e)))?;
+        self.stats.telemetry_sent += 1;
+        Ok(())
+    }
+
+    /// Counters since the server started
+    pub fn stats(&self) -> BridgeStats {
+        self.stats
+    }
+}
+
+/// Whether `sequence` comes after `last`, allowing for wrap-around
+fn is_newer(sequence: u32, last: u32) -> bool {
+    (sequence.wrapping_sub(last) as i32) > 0
+}
+
+/// Sends RC frames to a bridge server as tagged, sequenced bridge packets
+///
+/// The sequence starts from the wall clock (milliseconds, wrapping), so a
+/// client restarted within the server's failsafe timeout usually continues
+/// above the numbers the server has seen; otherwise its packets are
+/// accepted once the timeout has passed. Telemetry returned by the server is received in the background and
+/// logged at debug level.
+#[derive(Debug)]
+pub struct BridgeClient {
+    socket: Arc<UdpSocket>,
+    address: String,
+    key: BridgeKey,
+    sequence: u32,
+    telemetry_task: JoinHandle<()>,
+}
+
+impl BridgeClient {
+    /// Create a client sending to the server at `address` (`HOST:PORT`)
+    ///
+    /// Must be called from within a Tokio runtime.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - Bridge server address
+    /// * `key` - Shared secret, the server's `bridge.secret`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Network` error if the address cannot be resolved or the
+    /// socket cannot be bound.
+    pub async fn connect(address: &str, key: BridgeKey) -> Result<Self> {
+        let error = |e: std::io::Error| FpvBridgeError::Network(format!("Bridge server {}: {}", address, e));
+
+        let target = tokio::net::lookup_host(address).await.map_err(error)?.next().ok_or_else(|| {
+            FpvBridgeError::Network(format!("Bridge server {}: address did not resolve", address))
+        })?;
+        let local = if target.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+        let socket = UdpSocket::bind(local).await.map_err(error)?;
+        socket.connect(target).await.map_err(error)?;
+
+        let socket = Arc::new(socket);
+        let telemetry_task = tokio::spawn(receive_telemetry(Arc::clone(&socket)));
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        Ok(Self {
+            socket,
+            address: address.to_string(),
+            key,
+            sequence: now.as_millis() as u32,
+            telemetry_task,
+        })
+    }
+}
+
+impl Drop for BridgeClient {
+    fn drop(&mut self) {
+        self.telemetry_task.abort();
+    }
+}
+
+/// Log telemetry frames returned by the bridge server
+async fn receive_telemetry(socket: Arc<UdpSocket>) {
+    let mut buf = [0u8; MAX_DATAGRAM_SIZE];
+    let mut parser = FrameParser::new();
+    loop {
+        // Errors here are ICMP reports for our own sends (server not up)
+        let Ok(n) = socket.recv(&mut buf).await else {
+            continue;
+        };
+        parser.push(&buf[..n]);
+        while let Some(frame) = parser.next_frame() {
+            debug!("Bridge telemetry frame type 0x{:02X}", frame.frame_type);
+        }
+    }
+}
+
+#[async_trait]
+impl FrameSink for BridgeClient {
+    async fn send_frame(&mut self, frame: &[u8]) -> Result<()> {
+        self.sequence = self.sequence.wrapping_add(1);
+        self.socket
+            .send(&encode_bridge_frame(&self.key, self.sequence, frame))
+            .await
+            .map_err(|e| FpvBridgeError::Network(format!("Bridge server {}: {}", self.address, e)))?;
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        format!("bridge:{}", self.address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crsf::encoder::{encode_bind_frame, encode_subset_rc_channels_frame};
+    use crate::crsf::protocol::{SubsetRcChannels, SubsetResolution, CRSF_CHANNEL_VALUE_CENTER};
+
+    const FAILSAFE_TIMEOUT: Duration = Duration::from_millis(500);
+
+    fn rc_frame(value: u16) -> Vec<u8> {
+        encode_rc_channels_frame(&[value; CRSF_NUM_CHANNELS])
+    }
+
+    fn addr(port: u16) -> SocketAddr {
+        SocketAddr::from(([192, 168, 1, 10], port))
+    }
+
+    fn key() -> BridgeKey {
+        BridgeKey::new("correct horse battery staple").unwrap()
+    }
+
+    fn packet(sequence: u32, frame: &[u8]) -> Vec<u8> {
+        encode_bridge_frame(&key(), sequence, frame)
+    }
+
+    async fn server() -> BridgeServer {
+        BridgeServer::bind("127.0.0.1:0", key(), FAILSAFE_TIMEOUT).await.unwrap()
+    }
+
+    #[test]
+    fn test_key_requires_secret() {
+        assert!(BridgeKey::new("").unwrap_err().to_string().contains("bridge.secret"));
+        assert!(BridgeKey::new("fifteen chars!!").is_err());
+        assert!(BridgeKey::new("sixteen chars!!!").is_ok());
+    }
+
+    #[test]
+    fn test_decode_bare_frame() {
+        assert!(decode_bridge_packet(&rc_frame(1000), &key(), false).unwrap_err().to_string().contains("Bare"));
+
+        let packet = decode_bridge_packet(&rc_frame(1000), &key(), true).unwrap();
+        assert_eq!(packet.sequence, None);
+        assert_eq!(packet.frame, rc_frame(1000));
+    }
+
+    #[test]
+    fn test_decode_rejects_wrong_tag() {
+        let other = BridgeKey::new("another shared secret").unwrap();
+        let data = encode_bridge_frame(&other, 1, &rc_frame(1000));
+        assert!(decode_bridge_packet(&data, &key(), false).unwrap_err().to_string().contains("tag"));
+
+        // Any change to header or body breaks the tag
+        let mut data = packet(1, &rc_frame(1000));
+        data[7] ^= 0x01;
+        assert!(decode_bridge_packet(&data, &key(), false).is_err());
+        let mut data = packet(1, &rc_frame(1000));
+        let last = data.len() - 1;
+        data[last] ^= 0x01;
+        assert!(decode_bridge_packet(&data, &key(), false).is_err());
+
+        // Untagged header only
+        assert!(decode_bridge_packet(&packet(1, &rc_frame(1000))[..BRIDGE_HEADER_SIZE], &key(), false).is_err());
+    }
+
+    #[test]
+    fn test_decode_subset_frame() {
+        let subset = SubsetRcChannels {
+            first_channel: 0,
+            resolution: SubsetResolution::Bits12,
+            values: vec![2048; 16],
+        };
+        let frame = encode_subset_rc_channels_frame(&subset).unwrap();
+        assert_eq!(decode_bridge_packet(&packet(1, &frame), &key(), false).unwrap().frame, frame);
+    }
+
+    #[test]
+    fn test_channels_packet_round_trip() {
+        let mut channels = [CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS];
+        channels[2] = 0;
+        channels[4] = 2047;
+
+        let data = encode_bridge_channels(&key(), 42, &channels);
+        assert_eq!(data.len(), 56);
+
+        let packet = decode_bridge_packet(&data, &key(), false).unwrap();
+        assert_eq!(packet.sequence, Some(42));
+        assert_eq!(packet.frame, encode_rc_channels_frame(&channels));
+    }
+
+    #[test]
+    fn test_decode_rejects_invalid_packets() {
+        let key = key();
+        let decode = |data: &[u8]| decode_bridge_packet(data, &key, true);
+
+        // Commands are not forwarded
+        assert!(decode(&encode_bind_frame()).is_err());
+        assert!(decode(&packet(1, &encode_bind_frame())).is_err());
+
+        // Corrupted CRC
+        let mut frame = rc_frame(1000);
+        let last = frame.len() - 1;
+        frame[last] ^= 0xFF;
+        assert!(decode(&frame).is_err());
+        assert!(decode(&packet(1, &frame)).is_err());
+
+        // Trailing bytes
+        let mut frame = rc_frame(1000);
+        frame.push(0);
+        assert!(decode(&frame).is_err());
+
+        // Wrong magic, version (0x01 packets had no tag), kind
+        assert!(decode(b"XX\x02\x01\0\0\0\0").is_err());
+        let mut data = packet(1, &rc_frame(1000));
+        data[2] = 1;
+        assert!(decode(&data).unwrap_err().to_string().contains("version"));
+        let mut data = bridge_header(0x7F, 1, 0);
+        data.extend_from_slice(&rc_frame(1000));
+        assert!(decode(&sign(&key, data)).unwrap_err().to_string().contains("kind"));
+
+        // Channel value out of range, short body
+        let mut data = bridge_header(BRIDGE_KIND_CHANNELS, 1, 0);
+        data.extend_from_slice(&[0u8; CRSF_NUM_CHANNELS * 2]);
+        data[8..10].copy_from_slice(&2048u16.to_be_bytes());
+        assert!(decode(&sign(&key, data)).is_err());
+        let mut data = bridge_header(BRIDGE_KIND_CHANNELS, 1, 0);
+        data.extend_from_slice(&[0u8; 12]);
+        assert!(decode(&sign(&key, data)).is_err());
+
+        assert!(decode(&[]).is_err());
+    }
+
+    #[test]
+    fn test_is_newer_wraps() {
+        assert!(is_newer(2, 1));
+        assert!(!is_newer(1, 1));
+        assert!(!is_newer(1, 2));
+        assert!(is_newer(0, u32::MAX));
+        assert!(is_newer(5, u32::MAX - 5));
+    }
+
+    #[tokio::test]
+    async fn test_accept_drops_out_of_order_and_duplicates() {
+        let mut server = server().await;
+        let now = Instant::now();
+
+        assert!(server.accept(&packet(10, &rc_frame(1)), addr(1), now).is_some());
+        assert!(server.accept(&packet(9, &rc_frame(2)), addr(1), now).is_none());
+        assert!(server.accept(&packet(10, &rc_frame(3)), addr(1), now).is_none());
+        assert_eq!(server.accept(&packet(12, &rc_frame(4)), addr(1), now), Some(rc_frame(4)));
+
+        assert_eq!(server.stats().out_of_order, 2);
+        assert_eq!(server.client(), Some(addr(1)));
+    }
+
+    #[tokio::test]
+    async fn test_accept_rejects_untagged_and_bare_by_default() {
+        let mut server = server().await;
+        let now = Instant::now();
+        let other = BridgeKey::new("another shared secret").unwrap();
+
+        assert!(server.accept(&encode_bridge_frame(&other, 1, &rc_frame(1)), addr(1), now).is_none());
+        assert!(server.accept(&rc_frame(2), addr(1), now).is_none());
+        assert_eq!(server.stats().invalid, 2);
+        assert_eq!(server.client(), None);
+
+        // Without allowed clients, allowing bare frames has no effect
+        let mut server = server.with_bare_frames(true);
+        assert!(server.accept(&rc_frame(2), addr(1), now).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_accept_only_allowed_clients() {
+        let mut server = server()
+            .await
+            .with_allowed_clients(vec![addr(1).ip()])
+            .with_bare_frames(true);
+        let now = Instant::now();
+        let stranger = SocketAddr::from(([10, 0, 0, 5], 1));
+
+        assert!(server.accept(&packet(1, &rc_frame(1)), stranger, now).is_none());
+        assert!(server.accept(&rc_frame(1), stranger, now).is_none());
+        assert_eq!(server.stats().refused, 2);
+
+        // Bare frames carry no sequence and are always in order
+        assert!(server.accept(&packet(10, &rc_frame(2)), addr(1), now).is_some());
+        assert!(server.accept(&rc_frame(3), addr(1), now).is_some());
+        assert_eq!(server.client(), Some(addr(1)));
+    }
+
+    #[tokio::test]
+    async fn test_accept_ignores_other_clients_while_live() {
+        let mut server = server().await;
+        let start = Instant::now();
+
+        assert!(server.accept(&packet(100, &rc_frame(1)), addr(1), start).is_some());
+        assert!(server.accept(&packet(101, &rc_frame(2)), addr(2), start).is_none());
+        assert_eq!(server.stats().foreign, 1);
+
+        // Once the first client has gone quiet, another may take over
+        let later = start + FAILSAFE_TIMEOUT;
+        assert!(!server.is_live(later));
+        assert!(server.accept(&packet(200, &rc_frame(3)), addr(2), later).is_some());
+        assert_eq!(server.client(), Some(addr(2)));
+    }
+
+    #[tokio::test]
+    async fn test_replay_rejected_while_client_connected() {
+        let mut server = server().await;
+        let start = Instant::now();
+
+        let recorded = packet(500, &rc_frame(1));
+        assert!(server.accept(&recorded, addr(1), start).is_some());
+        assert!(server.accept(&packet(501, &rc_frame(2)), addr(1), start).is_some());
+
+        // Replaying the recorded packet while the client is live is dropped
+        let soon = start + FAILSAFE_TIMEOUT / 2;
+        assert!(server.accept(&recorded, addr(1), soon).is_none());
+        assert_eq!(server.stats().out_of_order, 1);
+    }
+
+    #[tokio::test]
+    async fn test_restarted_client_with_lower_sequence() {
+        let mut server = server().await;
+        let start = Instant::now();
+        assert!(server.accept(&packet(900_000, &rc_frame(1)), addr(1), start).is_some());
+
+        // Restarted within the failsafe timeout, with a clock that went back:
+        // still the same connection
+        let restart = start + FAILSAFE_TIMEOUT / 2;
+        assert!(server.accept(&packet(1_000, &rc_frame(2)), addr(1), restart).is_none());
+        assert_eq!(server.stats().out_of_order, 1);
+
+        // Once the failsafe timeout has passed with no valid packet, the
+        // lower sequence starts a new connection
+        let later = start + FAILSAFE_TIMEOUT;
+        assert!(!server.is_live(later));
+        assert_eq!(server.accept(&packet(1_000, &rc_frame(2)), addr(1), later), Some(rc_frame(2)));
+        assert!(server.accept(&packet(1_001, &rc_frame(3)), addr(1), later).is_some());
+        assert!(server.accept(&packet(999, &rc_frame(4)), addr(1), later).is_none());
+        assert_eq!(server.stats().out_of_order, 2);
+    }
+
+    #[tokio::test]
+    async fn test_failsafe_deadline() {
+        let mut server = server().await;
+        let start = Instant::now();
+        assert_eq!(server.failsafe_deadline(), None);
+        assert!(!server.is_live(start));
+
+        server.accept(&packet(1, &rc_frame(1)), addr(1), start);
+        assert_eq!(server.failsafe_deadline(), Some(start + FAILSAFE_TIMEOUT));
+        assert!(server.is_live(start + FAILSAFE_TIMEOUT / 2));
+
+        // Invalid packets do not keep the client alive
+        server.accept(b"garbage", addr(1), start + FAILSAFE_TIMEOUT / 2);
+        assert_eq!(server.failsafe_deadline(), Some(start + FAILSAFE_TIMEOUT));
+    }
+
+    #[tokio::test]
+    async fn test_client_to_server_round_trip() {
+        let mut server = server().await;
+        let address = server.local_addr().unwrap().to_string();
+        let mut client = BridgeClient::connect(&address, key()).await.unwrap();
+
+        client.send_frame(&rc_frame(1500)).await.unwrap();
+        assert_eq!(server.recv_frame().await.unwrap(), rc_frame(1500));
+        client.send_frame(&rc_frame(1600)).await.unwrap();
+        assert_eq!(server.recv_frame().await.unwrap(), rc_frame(1600));
+        assert_eq!(client.describe(), format!("bridge:{}", address));
+
+        // Telemetry goes back to the client's address
+        server.send_telemetry(&encode_bind_frame()).await.unwrap();
+        assert_eq!(server.stats().telemetry_sent, 1);
+    }
+
+    #[tokio::test]
+    async fn test_recv_frame_forwards_only_newest_of_burst() {
+        let mut server = server().await;
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        socket.connect(server.local_addr().unwrap()).await.unwrap();
+
+        for sequence in 1..=3 {
+            socket.send(&packet(sequence, &rc_frame(sequence as u16))).await.unwrap();
+        }
+        // Give the datagrams time to queue up on the loopback interface
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        assert_eq!(server.recv_frame().await.unwrap(), rc_frame(3));
+        let stats = server.stats();
+        assert_eq!(stats.received, 3);
+        assert_eq!(stats.superseded, 2);
+        assert_eq!(stats.forwarded, 1);
+    }
+
+    #[tokio::test]
+    async fn test_send_telemetry_without_client_is_noop() {
+        let mut server = server().await;
+        server.send_telemetry(&encode_bind_frame()).await.unwrap();
+        assert_eq!(server.stats().telemetry_sent, 0);
+    }
+}
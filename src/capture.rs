@@ -0,0 +1,746 @@
+//! # CRSF Traffic Capture
+//!
+//! Records every CRSF frame sent to and received from the ELRS module
+//! (`--capture FILE`), and decodes captures for `fpv-bridge dump`.
+//!
+//! This module handles:
+//! - Writing and reading capture files ([`CaptureWriter`], [`CaptureReader`])
+//! - Human-readable frame descriptions built on the
+//!   [`crsf::decoder`](crate::crsf::decoder) functions ([`describe_frame`])
+//! - Export to pcapng for Wireshark ([`write_pcapng`])
+//!
+//! ## File Format
+//!
+//! An 8 byte header: magic `"FBCP"`, version `0x01` and 3 reserved bytes.
+//! Then one record per frame:
+//!
+//! | Bytes   | Field                                              |
+//! |---------|----------------------------------------------------|
+//! | 0-7     | u64 LE timestamp, microseconds since the Unix epoch |
+//! | 8       | Direction: 0 = TX (to module), 1 = RX (from module) |
+//! | 9       | Flags: bit 0 set if the CRC did not match           |
+//! | 10      | Frame length N                                      |
+//! | 11..11+N | Frame bytes, sync byte through CRC                |
+
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::crsf::crc::crc8_dvb_s2;
+use crate::crsf::decoder::{
+    decode_attitude, decode_baud_response, decode_battery_sensor, decode_device_info, decode_flight_mode,
+    decode_frame, decode_gps, decode_link_statistics, decode_rc_channels_payload, decode_subset_rc_channels_payload,
+    decode_timing_sync,
+};
+use crate::crsf::protocol::{
+    CRSF_COMMAND_CRSF_BIND, CRSF_COMMAND_CRSF_MODEL_SELECT, CRSF_COMMAND_GENERAL,
+    CRSF_COMMAND_GENERAL_BAUD_PROPOSAL, CRSF_COMMAND_GENERAL_BAUD_RESPONSE, CRSF_COMMAND_SUBCMD_CRSF,
+    CRSF_FRAMETYPE_ATTITUDE, CRSF_FRAMETYPE_BATTERY_SENSOR, CRSF_FRAMETYPE_COMMAND, CRSF_FRAMETYPE_DEVICE_INFO,
+    CRSF_FRAMETYPE_DEVICE_PING, CRSF_FRAMETYPE_FLIGHT_MODE, CRSF_FRAMETYPE_GPS, CRSF_FRAMETYPE_LINK_STATISTICS,
+    CRSF_FRAMETYPE_RADIO_ID, CRSF_FRAMETYPE_RC_CHANNELS_PACKED, CRSF_FRAMETYPE_SUBSET_RC_CHANNELS_PACKED,
+};
+use crate::error::{FpvBridgeError, Result};
+
+/// Magic bytes opening a capture file
+pub const CAPTURE_MAGIC: [u8; 4] = *b"FBCP";
+
+/// Capture file format version
+pub const CAPTURE_VERSION: u8 = 0x01;
+
+/// Size of the file header in bytes
+pub const CAPTURE_HEADER_LEN: usize = 8;
+
+/// Size of a record header (before the frame bytes)
+pub const CAPTURE_RECORD_HEADER_LEN: usize = 11;
+
+/// pcapng link type of exported captures (`LINKTYPE_USER0`)
+///
+/// Each packet is one CRSF frame, sync byte through CRC.
+pub const PCAPNG_LINKTYPE: u16 = 147;
+
+/// Record flag: the frame's CRC did not match
+const FLAG_BAD_CRC: u8 = 0x01;
+
+/// Frame extended types (with destination and origin addresses) start here
+const CRSF_FRAMETYPE_EXTENDED_START: u8 = 0x28;
+
+/// Which way a captured frame went
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Sent to the ELRS module
+    Tx,
+    /// Received from the ELRS module
+    Rx,
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tx => write!(f, "TX"),
+            Self::Rx => write!(f, "RX"),
+        }
+    }
+}
+
+/// One captured frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptureRecord {
+    /// When the frame was sent or received
+    pub timestamp: SystemTime,
+    /// Which way it went
+    pub direction: Direction,
+    /// Whether its CRC matched
+    pub crc_ok: bool,
+    /// Frame bytes, sync byte through CRC
+    pub bytes: Vec<u8>,
+}
+
+/// Writes captured frames to a capture file
+///
+/// Records are buffered; the buffer is flushed when full and when the
+/// writer is dropped.
+#[derive(Debug)]
+pub struct CaptureWriter<W: Write = BufWriter<File>> {
+    writer: W,
+    frames: u64,
+}
+
+impl CaptureWriter {
+    /// Create (or truncate) a capture file
+    ///
+    /// # Errors
+    ///
+    /// Returns `Io` error if the file cannot be created or written.
+    pub fn create(path: &Path) -> Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> CaptureWriter<W> {
+    /// Start a capture on `writer`, writing the header
+    ///
+    /// # Errors
+    ///
+    /// Returns `Io` error if the header cannot be written.
+    pub fn new(mut writer: W) -> Result<Self> {
+        let mut header = [0u8; CAPTURE_HEADER_LEN];
+        header[..4].copy_from_slice(&CAPTURE_MAGIC);
+        header[4] = CAPTURE_VERSION;
+        writer.write_all(&header)?;
+        Ok(Self { writer, frames: 0 })
+    }
+
+    /// Append a frame captured now
+    ///
+    /// # Errors
+    ///
+    /// Returns `Io` error if the record cannot be written.
+    pub fn record(&mut self, direction: Direction, bytes: &[u8], crc_ok: bool) -> Result<()> {
+        self.write_record(&CaptureRecord {
+            timestamp: SystemTime::now(),
+            direction,
+            crc_ok,
+            bytes: bytes.to_vec(),
+        })
+    }
+
+    /// Append a record
+    ///
+    /// # Errors
+    ///
+    /// Returns `Io` error if the record cannot be written, or `CrsfProtocol`
+    /// error if the frame is longer than 255 bytes.
+    pub fn write_record(&mut self, record: &CaptureRecord) -> Result<()> {
+        let length = u8::try_from(record.bytes.len()).map_err(|_| {
+            FpvBridgeError::CrsfProtocol(format!("Frame too long to capture: {} bytes", record.bytes.len()))
+        })?;
+        let micros = record.timestamp.duration_since(UNIX_EPOCH).unwrap_or_default().as_micros();
+
+        let mut header = [0u8; CAPTURE_RECORD_HEADER_LEN];
+        header[..8].copy_from_slice(&u64::try_from(micros).unwrap_or(u64::MAX).to_le_bytes());
+        header[8] = match record.direction {
+            Direction::Tx => 0,
+            Direction::Rx => 1,
+        };
+        header[9] = if record.crc_ok { 0 } else { FLAG_BAD_CRC };
+        header[10] = length;
+        self.writer.write_all(&header)?;
+        self.writer.write_all(&record.bytes)?;
+
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Flush buffered records
+    ///
+    /// # Errors
+    ///
+    /// Returns `Io` error if the write fails.
+    pub fn flush(&mut self) -> Result<()> {
+        Ok(self.writer.flush()?)
+    }
+
+    /// Number of frames recorded
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// The underlying writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Reads the records of a capture file
+///
+/// A truncated last record (capture interrupted mid-write) ends the
+/// iteration without an error.
+#[derive(Debug)]
+pub struct CaptureReader<R: Read> {
+    reader: R,
+}
+
+impl CaptureReader<BufReader<File>> {
+    /// Open a capture file
+    ///
+    /// # Errors
+    ///
+    /// Returns `Io` error if the file cannot be opened, or `CrsfProtocol`
+    /// error if it is not a capture file.
+    pub fn open(path: &Path) -> Result<Self> {
+        Self::new(BufReader::new(File::open(path)?)).map_err(|e| match e {
+            FpvBridgeError::CrsfProtocol(reason) => {
+                FpvBridgeError::CrsfProtocol(format!("{}: {}", path.display(), reason))
+            }
+            other => other,
+        })
+    }
+}
+
+impl<R: Read> CaptureReader<R> {
+    /// Start reading a capture, checking its header
+    ///
+    /// # Errors
+    ///
+    /// Returns `Io` error if reading fails, or `CrsfProtocol` error if the
+    /// header is not a supported capture header.
+    pub fn new(mut reader: R) -> Result<Self> {
+        let mut header = [0u8; CAPTURE_HEADER_LEN];
+        reader.read_exact(&mut header).map_err(|e| match e.kind() {
+            ErrorKind::UnexpectedEof => FpvBridgeError::CrsfProtocol("not a capture file (too short)".to_string()),
+            _ => e.into(),
+        })?;
+        if header[..4] != CAPTURE_MAGIC {
+            return Err(FpvBridgeError::CrsfProtocol("not a capture file (bad magic)".to_string()));
+        }
+        if header[4] != CAPTURE_VERSION {
+            return Err(FpvBridgeError::CrsfProtocol(format!("unsupported capture version {}", header[4])));
+        }
+        Ok(Self { reader })
+    }
+
+    /// Read the next record, `None` at the end of the capture
+    fn read_record(&mut self) -> Result<Option<CaptureRecord>> {
+        let mut header = [0u8; CAPTURE_RECORD_HEADER_LEN];
+        if let Err(e) = self.reader.read_exact(&mut header) {
+            return match e.kind() {
+                ErrorKind::UnexpectedEof => Ok(None),
+                _ => Err(e.into()),
+            };
+        }
+        let mut bytes = vec![0u8; usize::from(header[10])];
+        if let Err(e) = self.reader.read_exact(&mut bytes) {
+            return match e.kind() {
+                ErrorKind::UnexpectedEof => Ok(None),
+                _ => Err(e.into()),
+            };
+        }
+
+        let micros = u64::from_le_bytes(header[..8].try_into().expect("8 byte slice"));
+        Ok(Some(CaptureRecord {
+            timestamp: UNIX_EPOCH + Duration::from_micros(micros),
+            direction: if header[8] == 0 { Direction::Tx } else { Direction::Rx },
+            crc_ok: header[9] & FLAG_BAD_CRC == 0,
+            bytes,
+        }))
+    }
+}
+
+impl<R: Read> Iterator for CaptureReader<R> {
+    type Item = Result<CaptureRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+/// Name of a CRSF frame type
+pub fn frame_type_name(frame_type: u8) -> &'static str {
+    match frame_type {
+        CRSF_FRAMETYPE_GPS => "GPS",
+        CRSF_FRAMETYPE_BATTERY_SENSOR => "BATTERY_SENSOR",
+        CRSF_FRAMETYPE_ATTITUDE => "ATTITUDE",
+        CRSF_FRAMETYPE_FLIGHT_MODE => "FLIGHT_MODE",
+        CRSF_FRAMETYPE_LINK_STATISTICS => "LINK_STATISTICS",
+        CRSF_FRAMETYPE_RC_CHANNELS_PACKED => "RC_CHANNELS_PACKED",
+        CRSF_FRAMETYPE_SUBSET_RC_CHANNELS_PACKED => "SUBSET_RC_CHANNELS",
+        CRSF_FRAMETYPE_DEVICE_PING => "DEVICE_PING",
+        CRSF_FRAMETYPE_DEVICE_INFO => "DEVICE_INFO",
+        CRSF_FRAMETYPE_COMMAND => "COMMAND",
+        CRSF_FRAMETYPE_RADIO_ID => "RADIO_ID",
+        _ => "UNKNOWN",
+    }
+}
+
+/// One-line description of a raw CRSF frame
+///
+/// Shows the sync/address byte, frame type, destination and origin of
+/// extended frames, the fields decoded with the
+/// [`crsf::decoder`](crate::crsf::decoder) functions, and the CRC status.
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::capture::describe_frame;
+/// use fpv_bridge::crsf::encoder::encode_model_select_frame;
+///
+/// let line = describe_frame(&encode_model_select_frame(3));
+/// assert_eq!(line, "C8 COMMAND(0x32) EE<-EA len=10 crc=ok model_select id=3");
+/// ```
+pub fn describe_frame(bytes: &[u8]) -> String {
+    if bytes.len() < 4 {
+        return format!("truncated frame: {}", hex(bytes));
+    }
+    let frame_type = bytes[2];
+    let mut line = format!("{:02X} {}(0x{:02X})", bytes[0], frame_type_name(frame_type), frame_type);
+    if frame_type >= CRSF_FRAMETYPE_EXTENDED_START && bytes.len() >= 6 {
+        line.push_str(&format!(" {:02X}<-{:02X}", bytes[3], bytes[4]));
+    }
+    line.push_str(&format!(" len={}", bytes.len()));
+
+    let crc = crc8_dvb_s2(&bytes[1..bytes.len() - 1]);
+    let received = bytes[bytes.len() - 1];
+    if crc != received {
+        line.push_str(&format!(" crc=BAD(expected {:02X}) {}", crc, hex(&bytes[3..bytes.len() - 1])));
+        return line;
+    }
+    line.push_str(" crc=ok");
+
+    match decode_frame(bytes).and_then(|frame| describe_payload(frame.frame_type, &frame.payload)) {
+        Ok(fields) if fields.is_empty() => {}
+        Ok(fields) => line.push_str(&format!(" {}", fields)),
+        Err(e) => line.push_str(&format!(" decode error: {}", e)),
+    }
+    line
+}
+
+/// Decoded fields of a frame payload
+fn describe_payload(frame_type: u8, payload: &[u8]) -> Result<String> {
+    Ok(match frame_type {
+        CRSF_FRAMETYPE_RC_CHANNELS_PACKED => {
+            let channels = decode_rc_channels_payload(payload)?;
+            format!("ch=[{}]", join(channels.iter()))
+        }
+        CRSF_FRAMETYPE_SUBSET_RC_CHANNELS_PACKED => {
+            let subset = decode_subset_rc_channels_payload(payload)?;
+            format!(
+                "first=ch{} bits={} values=[{}]",
+                subset.first_channel + 1,
+                subset.resolution.bits(),
+                join(subset.values.iter())
+            )
+        }
+        CRSF_FRAMETYPE_LINK_STATISTICS => {
+            let stats = decode_link_statistics(payload)?;
+            format!(
+                "up: rssi=-{}/-{}dBm lq={}% snr={}dB ant={} mode={} power={} down: rssi=-{}dBm lq={}% snr={}dB",
+                stats.uplink_rssi_1,
+                stats.uplink_rssi_2,
+                stats.uplink_lq,
+                stats.uplink_snr,
+                stats.active_antenna,
+                stats.rf_mode,
+                stats.uplink_tx_power,
+                stats.downlink_rssi,
+                stats.downlink_lq,
+                stats.downlink_snr
+            )
+        }
+        CRSF_FRAMETYPE_BATTERY_SENSOR => {
+            let battery = decode_battery_sensor(payload)?;
+            format!(
+                "{:.1}V {:.1}A {}mAh {}%",
+                battery.voltage, battery.current, battery.capacity_used, battery.remaining_percent
+            )
+        }
+        CRSF_FRAMETYPE_GPS => {
+            let gps = decode_gps(payload)?;
+            format!(
+                "lat={:.7} lon={:.7} speed={:.1}km/h heading={:.1} alt={}m sats={}",
+                gps.latitude, gps.longitude, gps.ground_speed, gps.heading, gps.altitude, gps.satellites
+            )
+        }
+        CRSF_FRAMETYPE_ATTITUDE => {
+            let attitude = decode_attitude(payload)?;
+            format!(
+                "pitch={:.1}° roll={:.1}° yaw={:.1}°",
+                attitude.pitch.to_degrees(),
+                attitude.roll.to_degrees(),
+                attitude.yaw.to_degrees()
+            )
+        }
+        CRSF_FRAMETYPE_FLIGHT_MODE => format!("mode=\"{}\"", decode_flight_mode(payload)?),
+        CRSF_FRAMETYPE_DEVICE_INFO => {
+            let device = decode_device_info(payload)?;
+            format!(
+                "name=\"{}\" serial=0x{:08X} hw=0x{:08X} sw=0x{:08X} params={}",
+                device.name,
+                device.serial_number,
+                device.hardware_version,
+                device.software_version,
+                device.parameter_count
+            )
+        }
+        CRSF_FRAMETYPE_RADIO_ID => {
+            let sync = decode_timing_sync(payload)?;
+            format!(
+                "timing_sync interval={:.1}us offset={:.1}us",
+                sync.refresh_interval_ns as f64 / 1000.0,
+                sync.offset_ns as f64 / 1000.0
+            )
+        }
+        CRSF_FRAMETYPE_COMMAND => describe_command(payload)?,
+        CRSF_FRAMETYPE_DEVICE_PING => String::new(),
+        _ => hex(payload),
+    })
+}
+
+/// Decoded fields of a COMMAND payload (dest, origin, command, subcommand, ...)
+fn describe_command(payload: &[u8]) -> Result<String> {
+    let Some(&[command, subcommand]) = payload.get(2..4).map(|bytes| <&[u8; 2]>::try_from(bytes).expect("2 bytes"))
+    else {
+        return Ok(hex(payload));
+    };
+    let data = &payload[4..payload.len().saturating_sub(1).max(4)];
+
+    Ok(match (command, subcommand) {
+        (CRSF_COMMAND_SUBCMD_CRSF, CRSF_COMMAND_CRSF_BIND) => "bind".to_string(),
+        (CRSF_COMMAND_SUBCMD_CRSF, CRSF_COMMAND_CRSF_MODEL_SELECT) if !data.is_empty() => {
+            format!("model_select id={}", data[0])
+        }
+        (CRSF_COMMAND_GENERAL, CRSF_COMMAND_GENERAL_BAUD_PROPOSAL) if data.len() >= 5 => {
+            let baud_rate = u32::from_be_bytes([data[1], data[2], data[3], data[4]]);
+            format!("baud_proposal port={} rate={}", data[0], baud_rate)
+        }
+        (CRSF_COMMAND_GENERAL, CRSF_COMMAND_GENERAL_BAUD_RESPONSE) => {
+            let response = decode_baud_response(payload)?;
+            format!("baud_response port={} accepted={}", response.port_id, response.accepted)
+        }
+        _ => format!("command=0x{:02X} sub=0x{:02X} data={}", command, subcommand, hex(data)),
+    })
+}
+
+/// Space-separated hex bytes
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(" ")
+}
+
+/// Comma-separated values
+fn join<T: fmt::Display>(values: impl Iterator<Item = T>) -> String {
+    values.map(|v| v.to_string()).collect::<Vec<_>>().join(",")
+}
+
+/// Write a human-readable dump of a capture
+///
+/// One line per frame: seconds since the first frame, direction, and
+/// [`describe_frame`].
+///
+/// # Returns
+///
+/// * `Result<u64>` - Number of frames dumped
+///
+/// # Errors
+///
+/// Returns `Io` error if reading the capture or writing the dump fails.
+pub fn write_dump<R: Read, W: Write>(capture: CaptureReader<R>, mut out: W) -> Result<u64> {
+    let mut first = None;
+    let mut frames = 0;
+    for record in capture {
+        let record = record?;
+        let start = *first.get_or_insert_with(|| {
+            let started = chrono::DateTime::<chrono::Local>::from(record.timestamp);
+            (record.timestamp, started)
+        });
+        if frames == 0 {
+            writeln!(out, "# capture started {}", start.1.format("%Y-%m-%d %H:%M:%S%.6f %:z"))?;
+        }
+
+        let elapsed = record.timestamp.duration_since(start.0).unwrap_or_default();
+        writeln!(out, "{:>12.6} {} {}", elapsed.as_secs_f64(), record.direction, describe_frame(&record.bytes))?;
+        frames += 1;
+    }
+    out.flush()?;
+    Ok(frames)
+}
+
+/// Export a capture as pcapng
+///
+/// Frames become enhanced packet blocks on one interface with link type
+/// [`PCAPNG_LINKTYPE`], with the direction (and bad CRCs, as CRC errors)
+/// in the packet flags.
+///
+/// # Returns
+///
+/// * `Result<u64>` - Number of frames exported
+///
+/// # Errors
+///
+/// Returns `Io` error if reading the capture or writing the export fails.
+pub fn write_pcapng<R: Read, W: Write>(capture: CaptureReader<R>, mut out: W) -> Result<u64> {
+    // Section header block: byte-order magic, version 1.0, unknown length
+    let mut shb = Vec::new();
+    shb.extend_from_slice(&0x1A2B_3C4Du32.to_le_bytes());
+    shb.extend_from_slice(&1u16.to_le_bytes());
+    shb.extend_from_slice(&0u16.to_le_bytes());
+    shb.extend_from_slice(&(-1i64).to_le_bytes());
+    write_pcapng_block(&mut out, 0x0A0D_0D0A, &shb)?;
+
+    // Interface description block: link type, reserved, no snap length
+    let mut idb = Vec::new();
+    idb.extend_from_slice(&PCAPNG_LINKTYPE.to_le_bytes());
+    idb.extend_from_slice(&0u16.to_le_bytes());
+    idb.extend_from_slice(&0u32.to_le_bytes());
+    write_pcapng_block(&mut out, 0x0000_0001, &idb)?;
+
+    let mut frames = 0;
+    for record in capture {
+        let record = record?;
+        let micros = u64::try_from(record.timestamp.duration_since(UNIX_EPOCH).unwrap_or_default().as_micros())
+            .unwrap_or(u64::MAX);
+        let length = record.bytes.len() as u32;
+
+        // Enhanced packet block; timestamps in the default microseconds
+        let mut epb = Vec::new();
+        epb.extend_from_slice(&0u32.to_le_bytes());
+        epb.extend_from_slice(&((micros >> 32) as u32).to_le_bytes());
+        epb.extend_from_slice(&(micros as u32).to_le_bytes());
+        epb.extend_from_slice(&length.to_le_bytes());
+        epb.extend_from_slice(&length.to_le_bytes());
+        epb.extend_from_slice(&record.bytes);
+        epb.resize(epb.len().next_multiple_of(4), 0);
+
+        // epb_flags: direction in bits 0-1, CRC error in bit 24
+        let mut flags: u32 = match record.direction {
+            Direction::Rx => 0b01,
+            Direction::Tx => 0b10,
+        };
+        if !record.crc_ok {
+            flags |= 1 << 24;
+        }
+        epb.extend_from_slice(&2u16.to_le_bytes());
+        epb.extend_from_slice(&4u16.to_le_bytes());
+        epb.extend_from_slice(&flags.to_le_bytes());
+        epb.extend_from_slice(&[0; 4]); // opt_endofopt
+        write_pcapng_block(&mut out, 0x0000_0006, &epb)?;
+        frames += 1;
+    }
+    out.flush()?;
+    Ok(frames)
+}
+
+/// Write one pcapng block: type, total length, body, total length
+fn write_pcapng_block<W: Write>(out: &mut W, block_type: u32, body: &[u8]) -> Result<()> {
+    let total = (body.len() + 12) as u32;
+    out.write_all(&block_type.to_le_bytes())?;
+    out.write_all(&total.to_le_bytes())?;
+    out.write_all(body)?;
+    out.write_all(&total.to_le_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crsf::encoder::{
+        encode_attitude_frame, encode_baud_proposal_frame, encode_baud_response_frame, encode_battery_sensor_frame,
+        encode_bind_frame, encode_device_ping_frame, encode_flight_mode_frame, encode_link_statistics_frame,
+        encode_rc_channels_frame,
+    };
+    use crate::crsf::protocol::{
+        Attitude, BatterySensor, LinkStatistics, CRSF_CHANNEL_VALUE_CENTER, CRSF_NUM_CHANNELS,
+    };
+
+    fn at(ms: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_700_000_000) + Duration::from_millis(ms)
+    }
+
+    fn record(ms: u64, direction: Direction, bytes: Vec<u8>) -> CaptureRecord {
+        let crc_ok = crc8_dvb_s2(&bytes[1..bytes.len() - 1]) == bytes[bytes.len() - 1];
+        CaptureRecord { timestamp: at(ms), direction, crc_ok, bytes }
+    }
+
+    fn capture(records: &[CaptureRecord]) -> Vec<u8> {
+        let mut writer = CaptureWriter::new(Vec::new()).unwrap();
+        for record in records {
+            writer.write_record(record).unwrap();
+        }
+        assert_eq!(writer.frames(), records.len() as u64);
+        writer.into_inner()
+    }
+
+    fn sample() -> Vec<CaptureRecord> {
+        let rc = encode_rc_channels_frame(&[CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS]);
+        let mut corrupted = rc.clone();
+        corrupted[10] ^= 0x55;
+        vec![
+            record(0, Direction::Tx, encode_device_ping_frame()),
+            record(4, Direction::Tx, rc),
+            record(5, Direction::Rx, corrupted),
+            record(8, Direction::Rx, encode_baud_response_frame(0, true)),
+        ]
+    }
+
+    #[test]
+    fn test_write_and_read_round_trip() {
+        let records = sample();
+        let bytes = capture(&records);
+        assert_eq!(&bytes[..5], b"FBCP\x01");
+
+        let read: Vec<_> = CaptureReader::new(bytes.as_slice()).unwrap().collect::<Result<_>>().unwrap();
+        assert_eq!(read, records);
+        assert!(!read[2].crc_ok);
+    }
+
+    #[test]
+    fn test_truncated_capture_ends_cleanly() {
+        let mut bytes = capture(&sample());
+        bytes.truncate(bytes.len() - 3);
+        let read: Vec<_> = CaptureReader::new(bytes.as_slice()).unwrap().collect::<Result<_>>().unwrap();
+        assert_eq!(read.len(), 3);
+    }
+
+    #[test]
+    fn test_invalid_capture_files() {
+        assert!(CaptureReader::new(&b"FB"[..]).unwrap_err().to_string().contains("too short"));
+        assert!(CaptureReader::new(&[0u8; 8][..]).unwrap_err().to_string().contains("bad magic"));
+        assert!(CaptureReader::new(&b"FBCP\x07\0\0\0"[..]).unwrap_err().to_string().contains("version 7"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("link.fbcap");
+        std::fs::write(&path, b"nonsense").unwrap();
+        let err = CaptureReader::open(&path).unwrap_err().to_string();
+        assert!(err.contains("link.fbcap: not a capture file"), "{}", err);
+    }
+
+    #[test]
+    fn test_describe_decoded_frames() {
+        let rc = describe_frame(&encode_rc_channels_frame(&[CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS]));
+        assert!(rc.starts_with("C8 RC_CHANNELS_PACKED(0x16) len=26 crc=ok ch=[1024,1024,"), "{}", rc);
+
+        let ping = describe_frame(&encode_device_ping_frame());
+        assert_eq!(ping, "C8 DEVICE_PING(0x28) 00<-EA len=6 crc=ok");
+
+        assert_eq!(
+            describe_frame(&encode_bind_frame()),
+            "C8 COMMAND(0x32) EE<-EA len=9 crc=ok bind"
+        );
+        assert!(describe_frame(&encode_baud_proposal_frame(0, 921_600)).ends_with("baud_proposal port=0 rate=921600"));
+        assert!(describe_frame(&encode_baud_response_frame(0, false)).ends_with("baud_response port=0 accepted=false"));
+
+        let stats = LinkStatistics {
+            uplink_rssi_1: 50,
+            uplink_rssi_2: 52,
+            uplink_lq: 100,
+            uplink_snr: 9,
+            active_antenna: 1,
+            rf_mode: 7,
+            uplink_tx_power: 3,
+            downlink_rssi: 60,
+            downlink_lq: 98,
+            downlink_snr: -2,
+        };
+        let line = describe_frame(&encode_link_statistics_frame(&stats));
+        assert!(line.contains("up: rssi=-50/-52dBm lq=100% snr=9dB ant=1"), "{}", line);
+        assert!(line.contains("down: rssi=-60dBm lq=98% snr=-2dB"), "{}", line);
+
+        let battery = BatterySensor { voltage: 16.8, current: 12.5, capacity_used: 420, remaining_percent: 77 };
+        assert!(describe_frame(&encode_battery_sensor_frame(&battery)).ends_with("16.8V 12.5A 420mAh 77%"));
+
+        let attitude = Attitude { pitch: 0.1, roll: -0.2, yaw: 1.5 };
+        let line = describe_frame(&encode_attitude_frame(&attitude));
+        assert!(line.starts_with("C8 ATTITUDE(0x1E) len=10"), "{}", line);
+        assert!(line.ends_with("pitch=5.7° roll=-11.5° yaw=85.9°"), "{}", line);
+        assert!(describe_frame(&encode_flight_mode_frame("ACRO*")).ends_with("mode=\"ACRO*\""));
+    }
+
+    #[test]
+    fn test_describe_bad_and_truncated_frames() {
+        let mut frame = encode_bind_frame();
+        let last = frame.len() - 1;
+        let expected = frame[last];
+        frame[last] ^= 0xFF;
+        let line = describe_frame(&frame);
+        assert!(line.contains(&format!("crc=BAD(expected {:02X})", expected)), "{}", line);
+
+        assert_eq!(describe_frame(&[0xC8, 0x02]), "truncated frame: C8 02");
+    }
+
+    #[test]
+    fn test_dump_lines() {
+        let bytes = capture(&sample());
+        let mut out = Vec::new();
+        let frames = write_dump(CaptureReader::new(bytes.as_slice()).unwrap(), &mut out).unwrap();
+        assert_eq!(frames, 4);
+
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines[0].starts_with("# capture started "));
+        assert!(lines[1].starts_with("    0.000000 TX C8 DEVICE_PING"), "{}", lines[1]);
+        assert!(lines[2].starts_with("    0.004000 TX C8 RC_CHANNELS_PACKED"), "{}", lines[2]);
+        assert!(lines[3].contains("RX C8 RC_CHANNELS_PACKED(0x16) len=26 crc=BAD"), "{}", lines[3]);
+        assert!(lines[4].ends_with("baud_response port=0 accepted=true"), "{}", lines[4]);
+    }
+
+    #[test]
+    fn test_pcapng_export() {
+        let records = sample();
+        let bytes = capture(&records);
+        let mut out = Vec::new();
+        assert_eq!(write_pcapng(CaptureReader::new(bytes.as_slice()).unwrap(), &mut out).unwrap(), 4);
+
+        let u32_at = |at: usize| u32::from_le_bytes(out[at..at + 4].try_into().unwrap());
+        // Section header, then interface description with our link type
+        assert_eq!(u32_at(0), 0x0A0D_0D0A);
+        assert_eq!(u32_at(8), 0x1A2B_3C4D);
+        let idb = u32_at(4) as usize;
+        assert_eq!(u32_at(idb), 1);
+        assert_eq!(u16::from_le_bytes([out[idb + 8], out[idb + 9]]), PCAPNG_LINKTYPE);
+
+        // Walk the enhanced packet blocks
+        let mut at_block = idb + u32_at(idb + 4) as usize;
+        let mut packets = Vec::new();
+        while at_block < out.len() {
+            let total = u32_at(at_block + 4) as usize;
+            assert_eq!(u32_at(at_block), 6);
+            assert_eq!(u32_at(at_block + total - 4) as usize, total);
+            let timestamp = (u64::from(u32_at(at_block + 12)) << 32) | u64::from(u32_at(at_block + 16));
+            let length = u32_at(at_block + 20) as usize;
+            let data = out[at_block + 28..at_block + 28 + length].to_vec();
+            let options = at_block + 28 + length.next_multiple_of(4);
+            assert_eq!(u32::from_le_bytes(out[options..options + 4].try_into().unwrap()), 0x0004_0002);
+            let flags = u32_at(options + 4);
+            packets.push((timestamp, data, flags));
+            at_block += total;
+        }
+
+        assert_eq!(packets.len(), 4);
+        let micros = |ms: u64| at(ms).duration_since(UNIX_EPOCH).unwrap().as_micros() as u64;
+        assert_eq!(packets[1].0, micros(4));
+        assert_eq!(packets[1].1, records[1].bytes);
+        assert_eq!(packets[1].2, 0b10);
+        assert_eq!(packets[2].2, 0b01 | 1 << 24);
+        assert_eq!(packets[3].2, 0b01);
+    }
+}
@@ -0,0 +1,508 @@
+//! # Command-Line Interface
+//!
+//! Parses the `fpv-bridge` command-line options documented in
+//! `docs/CONFIGURATION.md`.
+
+use std::path::PathBuf;
+
+use fpv_bridge::config::SerialConfig;
+use fpv_bridge::serial::CRSF_BAUD_RATE;
+
+/// Configuration file used when `--config` is not given
+pub const DEFAULT_CONFIG_PATH: &str = "config/default.toml";
+
+/// Log levels accepted by `--log-level`
+const LOG_LEVELS: &[&str] = &["error", "warn", "info", "debug", "trace"];
+
+/// Help text printed by `--help`
+pub const USAGE: &str = "\
+Usage: fpv-bridge [OPTIONS]
+       fpv-bridge dump <FILE> [--pcapng <OUT>]
+       fpv-bridge sniff <PORT> [--baud <RATE>]
+
+Options:
+  -c, --config <FILE>      Path to configuration file [default: config/default.toml]
+      --log-level <LEVEL>  Logging verbosity: error, warn, info, debug, trace [default: info]
+  -m, --model <NAME>       Start with the named [models.<name>] profile
+      --dry-run            Validate configuration without running
+      --bind               Put the ELRS module into bind mode after connecting
+      --latency-test       Run for 60 seconds, print the input latency report and exit
+      --serve <ADDR>       Network bridge server: forward RC frames received over UDP
+                           on ADDR to the ELRS module, no controller. Needs [bridge]
+                           secret; run on a trusted network only
+      --script <FILE>      Play controller input from a script instead of the PS5 controller
+                           (serial port 'none' or 'virtual' only)
+      --allow-live-playback
+                           Let --script or --replay drive a real ELRS module
+      --record <FILE>      Record the controller input session to FILE
+      --replay <FILE>      Replay a recorded session instead of the PS5 controller
+                           (serial port 'none' or 'virtual' only, unless --fast)
+      --fast               With --replay: map the session as fast as possible, print the
+                           channels as CSV and exit
+      --capture <FILE>     Capture all CRSF frames to and from the ELRS module to FILE
+      --tui                Show a full-screen dashboard of channels, link and telemetry
+  -V, --version            Print version and exit
+  -h, --help               Print this help message
+
+Commands:
+  dump <FILE>              Decode a --capture file into one line per frame
+      --pcapng <OUT>       Export it as pcapng (link type USER0) instead
+  sniff <PORT>             Passively decode the CRSF traffic on a serial port (read-only)
+      --baud <RATE>        Line speed [default: 420000]
+";
+
+/// Options for a normal bridge run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Args {
+    /// Path to the TOML configuration file
+    pub config: PathBuf,
+    /// Log level override (one of [`LOG_LEVELS`])
+    pub log_level: Option<String>,
+    /// Model profile to start with (base configuration if `None`)
+    pub model: Option<String>,
+    /// Validate configuration and exit
+    pub dry_run: bool,
+    /// Send a bind command to the ELRS module after connecting
+    pub bind: bool,
+    /// Run the input latency self-test and exit
+    pub latency_test: bool,
+    /// Run as network bridge server on this UDP address
+    pub serve: Option<String>,
+    /// Input script played instead of the PS5 controller
+    pub script: Option<PathBuf>,
+    /// Let scripted input reach a real ELRS module
+    pub allow_live_playback: bool,
+    /// Session file to record controller input to
+    pub record: Option<PathBuf>,
+    /// Recorded session replayed instead of the PS5 controller
+    pub replay: Option<PathBuf>,
+    /// Replay as fast as possible, printing the channels instead of running
+    pub fast: bool,
+    /// File to capture the CRSF traffic with the module to
+    pub capture: Option<PathBuf>,
+    /// Show the terminal dashboard instead of log lines
+    pub tui: bool,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Self {
+            config: PathBuf::from(DEFAULT_CONFIG_PATH),
+            log_level: None,
+            model: None,
+            dry_run: false,
+            bind: false,
+            latency_test: false,
+            serve: None,
+            script: None,
+            allow_live_playback: false,
+            record: None,
+            replay: None,
+            fast: false,
+            capture: None,
+            tui: false,
+        }
+    }
+}
+
+impl Args {
+    /// Refuse to play scripted or recorded input to a real ELRS module
+    ///
+    /// A script or a real-speed replay arms and pushes throttle with no pilot
+    /// on the sticks, so both only run against `serial.port = "none"` or
+    /// `"virtual"`, unless `--allow-live-playback` is given. `--replay --fast`
+    /// opens no module and is always allowed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message naming the option and the port if the input would
+    /// reach a real module.
+    pub fn check_playback(&self, serial: &SerialConfig) -> Result<(), String> {
+        let option = if self.script.is_some() {
+            "--script"
+        } else if self.replay.is_some() && !self.fast {
+            "--replay"
+        } else {
+            return Ok(());
+        };
+        if self.allow_live_playback || serial.is_simulated() {
+            return Ok(());
+        }
+        Err(format!(
+            "'{}' would drive the ELRS module on serial port '{}'; set serial.port to \"virtual\" or \"none\", \
+             or pass '--allow-live-playback' to fly it for real",
+            option, serial.port
+        ))
+    }
+}
+
+/// What the binary was asked to do
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Run the bridge
+    Run(Args),
+    /// Print help and exit
+    Help,
+    /// Print version and exit
+    Version,
+    /// Decode a traffic capture
+    Dump {
+        /// Capture file written with `--capture`
+        capture: PathBuf,
+        /// Export as pcapng to this file instead of printing
+        pcapng: Option<PathBuf>,
+    },
+    /// Passively watch the CRSF traffic on a serial port
+    Sniff {
+        /// Serial port device
+        port: String,
+        /// Line speed
+        baud: u32,
+    },
+}
+
+/// Parse command-line arguments (without the program name)
+///
+/// # Errors
+///
+/// Returns a human-readable message for unknown options, missing option
+/// values and invalid log levels.
+pub fn parse<I>(args: I) -> Result<Command, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut parsed = Args::default();
+    let mut args = args.into_iter().peekable();
+
+    if args.next_if(|arg| arg == "dump").is_some() {
+        return parse_dump(args);
+    }
+    if args.next_if(|arg| arg == "sniff").is_some() {
+        return parse_sniff(args);
+    }
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "-c" | "--config" => {
+                parsed.config = PathBuf::from(require_value(&arg, args.next())?);
+            }
+            "--log-level" => {
+                let level = require_value(&arg, args.next())?.to_lowercase();
+                if !LOG_LEVELS.contains(&level.as_str()) {
+                    return Err(format!(
+                        "invalid log level '{}' (expected one of: {})",
+                        level,
+                        LOG_LEVELS.join(", ")
+                    ));
+                }
+                parsed.log_level = Some(level);
+            }
+            "-m" | "--model" => parsed.model = Some(require_value(&arg, args.next())?),
+            "--dry-run" => parsed.dry_run = true,
+            "--bind" => parsed.bind = true,
+            "--latency-test" => parsed.latency_test = true,
+            "--serve" => parsed.serve = Some(require_value(&arg, args.next())?),
+            "--script" => parsed.script = Some(PathBuf::from(require_value(&arg, args.next())?)),
+            "--allow-live-playback" => parsed.allow_live_playback = true,
+            "--record" => parsed.record = Some(PathBuf::from(require_value(&arg, args.next())?)),
+            "--replay" => parsed.replay = Some(PathBuf::from(require_value(&arg, args.next())?)),
+            "--fast" => parsed.fast = true,
+            "--capture" => parsed.capture = Some(PathBuf::from(require_value(&arg, args.next())?)),
+            "--tui" => parsed.tui = true,
+            other => return Err(format!("unexpected argument '{}'", other)),
+        }
+    }
+
+    if parsed.script.is_some() && parsed.replay.is_some() {
+        return Err("'--script' and '--replay' cannot be combined".to_string());
+    }
+    if parsed.allow_live_playback && parsed.script.is_none() && parsed.replay.is_none() {
+        return Err("'--allow-live-playback' requires '--script' or '--replay'".to_string());
+    }
+    if parsed.fast && parsed.replay.is_none() {
+        return Err("'--fast' requires '--replay'".to_string());
+    }
+    if parsed.tui {
+        let conflicting = [("--serve", parsed.serve.is_some()), ("--fast", parsed.fast), ("--latency-test", parsed.latency_test)];
+        if let Some((option, _)) = conflicting.iter().find(|(_, set)| *set) {
+            return Err(format!("'--tui' cannot be combined with '{}'", option));
+        }
+    }
+
+    Ok(Command::Run(parsed))
+}
+
+/// Parse the arguments of the `dump` command
+fn parse_dump(args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut capture = None;
+    let mut pcapng = None;
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--pcapng" => pcapng = Some(PathBuf::from(require_value(&arg, args.next())?)),
+            other if other.starts_with('-') || capture.is_some() => {
+                return Err(format!("unexpected argument '{}'", other));
+            }
+            file => capture = Some(PathBuf::from(file)),
+        }
+    }
+
+    let capture = capture.ok_or_else(|| "'dump' requires a capture file".to_string())?;
+    Ok(Command::Dump { capture, pcapng })
+}
+
+/// Parse the arguments of the `sniff` command
+fn parse_sniff(args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut port = None;
+    let mut baud = CRSF_BAUD_RATE;
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--baud" => {
+                let value = require_value(&arg, args.next())?;
+                baud = match value.parse() {
+                    Ok(baud) if baud > 0 => baud,
+                    _ => return Err(format!("invalid baud rate '{}'", value)),
+                };
+            }
+            other if other.starts_with('-') || port.is_some() => {
+                return Err(format!("unexpected argument '{}'", other));
+            }
+            path => port = Some(path.to_string()),
+        }
+    }
+
+    let port = port.ok_or_else(|| "'sniff' requires a serial port".to_string())?;
+    Ok(Command::Sniff { port, baud })
+}
+
+/// Returns the value following an option, or an error naming the option
+fn require_value(option: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("option '{}' requires a value", option))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Result<Command, String> {
+        parse(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_no_arguments_uses_defaults() {
+        assert_eq!(parse_args(&[]), Ok(Command::Run(Args::default())));
+        assert_eq!(Args::default().config, PathBuf::from("config/default.toml"));
+    }
+
+    #[test]
+    fn test_config_option() {
+        let expected = Args {
+            config: PathBuf::from("/etc/fpv-bridge/custom.toml"),
+            ..Args::default()
+        };
+        assert_eq!(
+            parse_args(&["--config", "/etc/fpv-bridge/custom.toml"]),
+            Ok(Command::Run(expected.clone()))
+        );
+        assert_eq!(
+            parse_args(&["-c", "/etc/fpv-bridge/custom.toml"]),
+            Ok(Command::Run(expected))
+        );
+    }
+
+    #[test]
+    fn test_config_option_missing_value() {
+        let err = parse_args(&["--config"]).unwrap_err();
+        assert!(err.contains("--config"));
+    }
+
+    #[test]
+    fn test_model_option() {
+        match parse_args(&["--model", "whoop"]) {
+            Ok(Command::Run(args)) => assert_eq!(args.model.as_deref(), Some("whoop")),
+            other => panic!("Expected Run, got: {:?}", other),
+        }
+        match parse_args(&["-m", "cinewhoop"]) {
+            Ok(Command::Run(args)) => assert_eq!(args.model.as_deref(), Some("cinewhoop")),
+            other => panic!("Expected Run, got: {:?}", other),
+        }
+        assert!(parse_args(&["--model"]).is_err());
+    }
+
+    #[test]
+    fn test_flags() {
+        match parse_args(&["--dry-run", "--bind", "--latency-test"]) {
+            Ok(Command::Run(args)) => {
+                assert!(args.dry_run);
+                assert!(args.bind);
+                assert!(args.latency_test);
+            }
+            other => panic!("Expected Run, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_serve_option() {
+        match parse_args(&["--serve", "0.0.0.0:7777"]) {
+            Ok(Command::Run(args)) => assert_eq!(args.serve.as_deref(), Some("0.0.0.0:7777")),
+            other => panic!("Expected Run, got: {:?}", other),
+        }
+        assert!(parse_args(&["--serve"]).is_err());
+    }
+
+    #[test]
+    fn test_script_option() {
+        match parse_args(&["--script", "tests/arm.script"]) {
+            Ok(Command::Run(args)) => assert_eq!(args.script, Some(PathBuf::from("tests/arm.script"))),
+            other => panic!("Expected Run, got: {:?}", other),
+        }
+        assert!(parse_args(&["--script"]).is_err());
+        assert!(parse_args(&["--allow-live-playback"]).unwrap_err().contains("requires '--script'"));
+    }
+
+    #[test]
+    fn test_script_refused_on_live_module() {
+        let serial = |port: &str| -> SerialConfig {
+            toml::from_str(&format!("port = \"{}\"", port)).unwrap()
+        };
+        let Ok(Command::Run(args)) = parse_args(&["--script", "tests/arm.script"]) else {
+            panic!("Expected Run");
+        };
+
+        let err = args.check_playback(&serial("auto")).unwrap_err();
+        assert!(err.contains("'auto'") && err.contains("--allow-live-playback"));
+        assert!(args.check_playback(&serial("/dev/ttyACM0")).is_err());
+        assert!(args.check_playback(&serial("virtual")).is_ok());
+        assert!(args.check_playback(&serial("none")).is_ok());
+
+        let Ok(Command::Run(args)) = parse_args(&["--script", "tests/arm.script", "--allow-live-playback"]) else {
+            panic!("Expected Run");
+        };
+        assert!(args.check_playback(&serial("/dev/ttyACM0")).is_ok());
+
+        // Real-speed replay is guarded the same way, --fast opens no module
+        let Ok(Command::Run(args)) = parse_args(&["--replay", "flight.session"]) else {
+            panic!("Expected Run");
+        };
+        let err = args.check_playback(&serial("auto")).unwrap_err();
+        assert!(err.contains("'--replay'") && err.contains("--allow-live-playback"));
+        assert!(args.check_playback(&serial("virtual")).is_ok());
+        let Ok(Command::Run(args)) = parse_args(&["--replay", "flight.session", "--allow-live-playback"]) else {
+            panic!("Expected Run");
+        };
+        assert!(args.check_playback(&serial("auto")).is_ok());
+        let Ok(Command::Run(args)) = parse_args(&["--replay", "flight.session", "--fast"]) else {
+            panic!("Expected Run");
+        };
+        assert!(args.check_playback(&serial("auto")).is_ok());
+
+        // The PS5 controller is always allowed
+        assert!(Args::default().check_playback(&serial("/dev/ttyACM0")).is_ok());
+    }
+
+    #[test]
+    fn test_capture_option() {
+        match parse_args(&["--capture", "link.fbcap"]) {
+            Ok(Command::Run(args)) => assert_eq!(args.capture, Some(PathBuf::from("link.fbcap"))),
+            other => panic!("Expected Run, got: {:?}", other),
+        }
+        assert!(parse_args(&["--capture"]).is_err());
+    }
+
+    #[test]
+    fn test_tui_flag() {
+        match parse_args(&["--tui", "--script", "arm.txt"]) {
+            Ok(Command::Run(args)) => assert!(args.tui),
+            other => panic!("Expected Run, got: {:?}", other),
+        }
+        assert_eq!(
+            parse_args(&["--tui", "--serve", "0.0.0.0:7777"]),
+            Err("'--tui' cannot be combined with '--serve'".to_string())
+        );
+        assert!(parse_args(&["--tui", "--replay", "s.fbsn", "--fast"]).unwrap_err().contains("'--fast'"));
+        assert!(parse_args(&["--latency-test", "--tui"]).unwrap_err().contains("'--latency-test'"));
+    }
+
+    #[test]
+    fn test_dump_command() {
+        assert_eq!(
+            parse_args(&["dump", "link.fbcap"]),
+            Ok(Command::Dump { capture: PathBuf::from("link.fbcap"), pcapng: None })
+        );
+        assert_eq!(
+            parse_args(&["dump", "--pcapng", "link.pcapng", "link.fbcap"]),
+            Ok(Command::Dump { capture: PathBuf::from("link.fbcap"), pcapng: Some(PathBuf::from("link.pcapng")) })
+        );
+        assert_eq!(parse_args(&["dump", "--help"]), Ok(Command::Help));
+        assert!(parse_args(&["dump"]).unwrap_err().contains("requires a capture file"));
+        assert!(parse_args(&["dump", "a", "b"]).is_err());
+        assert!(parse_args(&["dump", "a", "--bind"]).is_err());
+
+        // Only as the first argument
+        assert!(parse_args(&["--bind", "dump"]).is_err());
+    }
+
+    #[test]
+    fn test_sniff_command() {
+        assert_eq!(
+            parse_args(&["sniff", "/dev/ttyUSB0"]),
+            Ok(Command::Sniff { port: "/dev/ttyUSB0".to_string(), baud: 420_000 })
+        );
+        assert_eq!(
+            parse_args(&["sniff", "--baud", "115200", "/dev/ttyUSB0"]),
+            Ok(Command::Sniff { port: "/dev/ttyUSB0".to_string(), baud: 115_200 })
+        );
+        assert!(parse_args(&["sniff"]).unwrap_err().contains("requires a serial port"));
+        assert!(parse_args(&["sniff", "a", "--baud", "fast"]).unwrap_err().contains("invalid baud rate"));
+        assert!(parse_args(&["sniff", "a", "--baud", "0"]).is_err());
+        assert!(parse_args(&["sniff", "a", "b"]).is_err());
+    }
+
+    #[test]
+    fn test_record_and_replay_options() {
+        match parse_args(&["--record", "flight.session"]) {
+            Ok(Command::Run(args)) => assert_eq!(args.record, Some(PathBuf::from("flight.session"))),
+            other => panic!("Expected Run, got: {:?}", other),
+        }
+        match parse_args(&["--replay", "flight.session", "--fast"]) {
+            Ok(Command::Run(args)) => {
+                assert_eq!(args.replay, Some(PathBuf::from("flight.session")));
+                assert!(args.fast);
+            }
+            other => panic!("Expected Run, got: {:?}", other),
+        }
+        assert!(parse_args(&["--replay"]).is_err());
+        assert!(parse_args(&["--fast"]).unwrap_err().contains("requires '--replay'"));
+        assert!(parse_args(&["--script", "a", "--replay", "b"]).unwrap_err().contains("cannot be combined"));
+    }
+
+    #[test]
+    fn test_log_level() {
+        match parse_args(&["--log-level", "DEBUG"]) {
+            Ok(Command::Run(args)) => assert_eq!(args.log_level.as_deref(), Some("debug")),
+            other => panic!("Expected Run, got: {:?}", other),
+        }
+        assert!(parse_args(&["--log-level", "verbose"]).is_err());
+    }
+
+    #[test]
+    fn test_help_and_version() {
+        assert_eq!(parse_args(&["--help"]), Ok(Command::Help));
+        assert_eq!(parse_args(&["-h"]), Ok(Command::Help));
+        assert_eq!(parse_args(&["--version"]), Ok(Command::Version));
+        assert_eq!(parse_args(&["--bind", "-V"]), Ok(Command::Version));
+    }
+
+    #[test]
+    fn test_unknown_argument() {
+        let err = parse_args(&["--turbo"]).unwrap_err();
+        assert!(err.contains("--turbo"));
+    }
+}
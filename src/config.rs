@@ -4,10 +4,18 @@
 
 use serde::Deserialize;
 use serde::de::Error;
+use std::collections::BTreeMap;
 use std::fs;
+use std::net::IpAddr;
 use std::path::Path;
 
-use crate::error::Result;
+use crate::bridge::BridgeKey;
+use crate::error::{FpvBridgeError, Result};
+use crate::crsf::protocol::SubsetResolution;
+use crate::scheduler::{SendOnChange, WaitMode};
+use crate::sink::SinkSpec;
+use crate::tracker::{TrackerProtocol, TrackerTarget};
+use std::time::Duration;
 
 /// Main configuration structure
 #[derive(Debug, Deserialize, Clone)]
@@ -18,6 +26,35 @@ pub struct Config {
     pub telemetry: TelemetryConfig,
     pub safety: SafetyConfig,
     pub crsf: CrsfConfig,
+
+    /// Extra frame outputs next to the ELRS module
+    #[serde(default)]
+    pub output: OutputConfig,
+
+    /// Local HTTP/WebSocket status and control API
+    #[serde(default)]
+    pub api: ApiConfig,
+
+    /// MAVLink telemetry output for ground control stations
+    #[serde(default)]
+    pub mavlink: MavlinkConfig,
+
+    /// LTM / MSP telemetry output for antenna trackers and goggles
+    #[serde(default)]
+    pub tracker: TrackerConfig,
+
+    /// Home position and flight statistics from GPS telemetry
+    #[serde(default)]
+    pub navigation: NavigationConfig,
+
+    /// Shared secret and client filter for the network bridge (`--serve`
+    /// and `bridge:` sinks)
+    #[serde(default)]
+    pub bridge: BridgeConfig,
+
+    /// Named model profiles (`[models.<name>]`) overriding the base settings
+    #[serde(default)]
+    pub models: BTreeMap<String, ModelProfile>,
 }
 
 /// Serial port configuration
@@ -34,6 +71,228 @@ pub struct SerialConfig {
 
     #[serde(default = "default_reconnect_interval_ms")]
     pub reconnect_interval_ms: u64,
+
+    /// With `port = "auto"`, only pick a port whose TX module answers a
+    /// CRSF device ping
+    #[serde(default)]
+    pub probe: bool,
+
+    /// Single-wire (half-duplex) UART: discard our own bytes echoed on RX
+    #[serde(default)]
+    pub half_duplex: bool,
+
+    /// Speed to propose to the TX module after connecting at `baud_rate`
+    #[serde(default)]
+    pub switch_baud_rate: Option<u32>,
+}
+
+impl SerialConfig {
+    /// Whether an ELRS module is used at all (`port` is not `"none"`)
+    pub fn is_enabled(&self) -> bool {
+        self.port != crate::serial::discovery::NO_PORT
+    }
+
+    /// Whether RC frames stay on this machine (`port` is `"none"` or
+    /// `"virtual"`), so no real module transmits them
+    pub fn is_simulated(&self) -> bool {
+        !self.is_enabled() || self.port == crate::serial::discovery::VIRTUAL_PORT
+    }
+}
+
+/// Output configuration
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct OutputConfig {
+    /// Extra sinks receiving every RC frame sent to the module
+    /// (`udp:HOST:PORT`, `tcp:HOST:PORT`, `bridge:HOST:PORT`, `file:PATH`,
+    /// `joystick` or `null`)
+    #[serde(default)]
+    pub sinks: Vec<String>,
+}
+
+impl OutputConfig {
+    /// Parse the configured sinks
+    ///
+    /// # Errors
+    ///
+    /// Returns `Output` error for the first malformed entry.
+    pub fn sink_specs(&self) -> Result<Vec<SinkSpec>> {
+        self.sinks.iter().map(|sink| sink.parse()).collect()
+    }
+}
+
+/// HTTP/WebSocket API configuration
+#[derive(Debug, Deserialize, Clone)]
+pub struct ApiConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Address to listen on (`127.0.0.1:PORT` for this machine only,
+    /// `0.0.0.0:PORT` for the LAN)
+    #[serde(default = "default_api_bind")]
+    pub bind: String,
+
+    /// Bearer token required by the control endpoints; empty disables them
+    #[serde(default)]
+    pub token: String,
+
+    /// WebSocket snapshot rate when the client does not ask for one
+    #[serde(default = "default_api_stream_rate_hz")]
+    pub stream_rate_hz: u32,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: default_api_bind(),
+            token: String::new(),
+            stream_rate_hz: default_api_stream_rate_hz(),
+        }
+    }
+}
+
+/// MAVLink telemetry output configuration
+#[derive(Debug, Deserialize, Clone)]
+pub struct MavlinkConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Ground control station address (`HOST:PORT`, GCS default port 14550)
+    #[serde(default = "default_mavlink_target")]
+    pub target: String,
+
+    /// MAVLink system ID of the drone (1-255)
+    #[serde(default = "default_mavlink_system_id")]
+    pub system_id: u8,
+}
+
+impl Default for MavlinkConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target: default_mavlink_target(),
+            system_id: default_mavlink_system_id(),
+        }
+    }
+}
+
+/// LTM / MSP tracker telemetry output configuration
+#[derive(Debug, Deserialize, Clone)]
+pub struct TrackerConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Telemetry protocol (`ltm` or `msp`)
+    #[serde(default = "default_tracker_protocol")]
+    pub protocol: String,
+
+    /// Output (`serial:PATH` or `udp:HOST:PORT`)
+    #[serde(default = "default_tracker_target")]
+    pub target: String,
+
+    /// Line speed of a serial target
+    #[serde(default = "default_tracker_baud_rate")]
+    pub baud_rate: u32,
+
+    /// Telemetry updates per second
+    #[serde(default = "default_tracker_rate_hz")]
+    pub rate_hz: u32,
+}
+
+impl Default for TrackerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            protocol: default_tracker_protocol(),
+            target: default_tracker_target(),
+            baud_rate: default_tracker_baud_rate(),
+            rate_hz: default_tracker_rate_hz(),
+        }
+    }
+}
+
+impl TrackerConfig {
+    /// Parse the configured protocol
+    ///
+    /// # Errors
+    ///
+    /// Returns `Output` error if it is neither `ltm` nor `msp`.
+    pub fn tracker_protocol(&self) -> Result<TrackerProtocol> {
+        self.protocol.parse()
+    }
+
+    /// Parse the configured target
+    ///
+    /// # Errors
+    ///
+    /// Returns `Output` error if it is malformed.
+    pub fn tracker_target(&self) -> Result<TrackerTarget> {
+        self.target.parse()
+    }
+}
+
+/// Navigation configuration
+#[derive(Debug, Deserialize, Clone)]
+pub struct NavigationConfig {
+    /// Satellites the GPS fix needs to lock the home position at arming
+    #[serde(default = "default_home_min_satellites")]
+    pub home_min_satellites: u8,
+}
+
+impl Default for NavigationConfig {
+    fn default() -> Self {
+        Self {
+            home_min_satellites: default_home_min_satellites(),
+        }
+    }
+}
+
+/// Network bridge configuration
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct BridgeConfig {
+    /// Shared secret tagging every bridge packet; the same on server and
+    /// client, at least 16 characters
+    #[serde(default)]
+    pub secret: String,
+
+    /// Client IP addresses the server accepts packets from (empty: any
+    /// address that knows the secret)
+    #[serde(default)]
+    pub allowed_clients: Vec<String>,
+
+    /// Accept bare, untagged CRSF frames from the allowed clients
+    #[serde(default)]
+    pub allow_bare_frames: bool,
+}
+
+impl BridgeConfig {
+    /// Key for the configured secret
+    ///
+    /// # Errors
+    ///
+    /// Returns `Network` error if the secret is missing or too short.
+    pub fn key(&self) -> Result<BridgeKey> {
+        BridgeKey::new(&self.secret)
+    }
+
+    /// Parse the allowed client addresses
+    ///
+    /// # Errors
+    ///
+    /// Returns `Network` error for the first entry that is not an IP address.
+    pub fn allowed_client_addrs(&self) -> Result<Vec<IpAddr>> {
+        self.allowed_clients
+            .iter()
+            .map(|client| {
+                client.parse().map_err(|_| {
+                    FpvBridgeError::Network(format!(
+                        "bridge allowed_clients entry '{}' is not an IP address",
+                        client
+                    ))
+                })
+            })
+            .collect()
+    }
 }
 
 /// Controller configuration
@@ -123,10 +382,166 @@ pub struct CrsfConfig {
 
     #[serde(default = "default_link_stats_interval_ms")]
     pub link_stats_interval_ms: u64,
+
+    /// ExpressLRS model match ID (0-63), sent to the TX module on connect.
+    /// `None` leaves model selection untouched.
+    #[serde(default)]
+    pub model_id: Option<u8>,
+
+    /// Stick channel resolution in bits: 11 sends standard RC channels
+    /// frames (0x16), 12 or 13 sends the sticks in subset RC channels frames
+    /// (0x17) and the other channels in 0x16 frames when they change.
+    #[serde(default = "default_stick_resolution")]
+    pub stick_resolution: u8,
+
+    /// How the transmit scheduler waits: "tokio" (runtime timer) or "thread"
+    /// (dedicated thread, sleep then spin)
+    #[serde(default = "default_scheduler")]
+    pub scheduler: String,
+
+    /// Busy-wait before each deadline with `scheduler = "thread"`, in µs
+    #[serde(default = "default_spin_us")]
+    pub spin_us: u64,
+
+    /// Send a frame as soon as the controller input changes instead of on
+    /// every scheduler tick, with a keep-alive frame every `keep_alive_ms`
+    #[serde(default)]
+    pub send_on_change: bool,
+
+    /// Stick/trigger movement (raw controller units, 0-255) that counts as
+    /// a change with `send_on_change`; buttons always count
+    #[serde(default = "default_change_threshold")]
+    pub change_threshold: i32,
+
+    /// Longest time between frames with `send_on_change`, in milliseconds
+    #[serde(default = "default_keep_alive_ms")]
+    pub keep_alive_ms: u64,
 }
 
+impl CrsfConfig {
+    /// Subset frame resolution to use for the sticks, or `None` for
+    /// standard 11-bit RC channels frames
+    pub fn high_resolution_sticks(&self) -> Option<SubsetResolution> {
+        SubsetResolution::from_bits(self.stick_resolution)
+            .filter(|&resolution| resolution != SubsetResolution::Bits11)
+    }
+
+    /// Wait mode for the transmit scheduler
+    pub fn wait_mode(&self) -> WaitMode {
+        match self.scheduler.as_str() {
+            "thread" => WaitMode::Thread {
+                spin: Duration::from_micros(self.spin_us),
+            },
+            _ => WaitMode::Tokio,
+        }
+    }
+
+    /// Send-on-change policy, or `None` to send on every scheduler tick
+    pub fn send_on_change(&self) -> Option<SendOnChange> {
+        self.send_on_change
+            .then(|| SendOnChange::new(self.change_threshold, Duration::from_millis(self.keep_alive_ms)))
+    }
+}
+
+/// Named model profile
+///
+/// Every field is optional; anything left out falls back to the base
+/// configuration. Resolve a profile into a flat [`Config`] with
+/// [`Config::for_model`].
+///
+/// ```toml
+/// [models.whoop]
+/// model_id = 2
+///
+/// [models.whoop.controller]
+/// expo_roll = 0.1
+///
+/// [models.whoop.safety]
+/// failsafe_timeout_ms = 300
+/// ```
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ModelProfile {
+    /// ExpressLRS model match ID, overrides `crsf.model_id`
+    #[serde(default)]
+    pub model_id: Option<u8>,
+
+    #[serde(default)]
+    pub controller: ControllerOverrides,
+
+    #[serde(default)]
+    pub channels: ChannelOverrides,
+
+    #[serde(default)]
+    pub safety: SafetyOverrides,
+}
+
+/// Per-model overrides of [`ControllerConfig`]
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ControllerOverrides {
+    pub deadzone_stick: Option<f32>,
+    pub deadzone_trigger: Option<f32>,
+    pub expo_roll: Option<f32>,
+    pub expo_pitch: Option<f32>,
+    pub expo_yaw: Option<f32>,
+    pub expo_throttle: Option<f32>,
+}
+
+/// Per-model overrides of [`ChannelConfig`]
+///
+/// Only settings the channel mapper applies can be overridden.
+/// `throttle_min`, `throttle_max` and `center` are not applied anywhere yet
+/// (channels span the full CRSF range), so profiles reject them rather
+/// than accept a setting that changes nothing.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ChannelOverrides {
+    pub channel_reverse: Option<Vec<usize>>,
+}
+
+/// Per-model overrides of [`SafetyConfig`]
+///
+/// Only settings the control loop applies can be overridden.
+/// `arm_button_hold_ms`, `auto_disarm_timeout_s` and `min_throttle_to_arm`
+/// are not applied anywhere yet (the arm channel follows L1 directly), so
+/// profiles reject them rather than accept a setting that changes nothing.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct SafetyOverrides {
+    pub failsafe_timeout_ms: Option<u64>,
+}
+
+/// Replaces `target.field` with the override value when it is set
+macro_rules! apply_overrides {
+    ($overrides:expr, $target:expr, [$($field:ident),* $(,)?]) => {
+        $(
+            if let Some(value) = &$overrides.$field {
+                $target.$field = value.clone();
+            }
+        )*
+    };
+}
+
+impl ModelProfile {
+    /// Apply this profile's overrides on top of a base configuration
+    fn apply(&self, config: &mut Config) {
+        apply_overrides!(self.controller, config.controller, [
+            deadzone_stick, deadzone_trigger, expo_roll, expo_pitch, expo_yaw, expo_throttle,
+        ]);
+        apply_overrides!(self.channels, config.channels, [channel_reverse]);
+        apply_overrides!(self.safety, config.safety, [failsafe_timeout_ms]);
+        if self.model_id.is_some() {
+            config.crsf.model_id = self.model_id;
+        }
+    }
+}
+
+/// Serial speeds supported by ELRS TX modules
+const VALID_BAUD_RATES: &[u32] = &[115200, 400000, 420000, 921600, 1870000, 3750000];
+
 // Default value functions
-fn default_serial_port() -> String { "/dev/ttyACM0".to_string() }
+fn default_serial_port() -> String { crate::serial::discovery::AUTO_PORT.to_string() }
 fn default_baud_rate() -> u32 { 420000 }
 fn default_timeout_ms() -> u64 { 100 }
 fn default_reconnect_interval_ms() -> u64 { 1000 }
@@ -149,12 +564,27 @@ fn default_max_files_to_keep() -> usize { 10 }
 fn default_log_interval_ms() -> u64 { 100 }
 fn default_log_format() -> String { "jsonl".to_string() }
 
+fn default_stick_resolution() -> u8 { 11 }
+fn default_scheduler() -> String { "tokio".to_string() }
+fn default_spin_us() -> u64 { 200 }
+fn default_change_threshold() -> i32 { 2 }
+fn default_keep_alive_ms() -> u64 { 100 }
+
 fn default_arm_button_hold_ms() -> u64 { 1000 }
 fn default_auto_disarm_timeout_s() -> u64 { 300 }
 fn default_failsafe_timeout_ms() -> u64 { 500 }
 fn default_min_throttle_to_arm() -> u16 { 1050 }
 
 fn default_packet_rate_hz() -> u32 { 250 }
+fn default_api_bind() -> String { "127.0.0.1:8080".to_string() }
+fn default_api_stream_rate_hz() -> u32 { 10 }
+fn default_mavlink_target() -> String { "127.0.0.1:14550".to_string() }
+fn default_mavlink_system_id() -> u8 { 1 }
+fn default_tracker_protocol() -> String { "ltm".to_string() }
+fn default_tracker_target() -> String { "serial:/dev/ttyUSB1".to_string() }
+fn default_tracker_baud_rate() -> u32 { 115_200 }
+fn default_tracker_rate_hz() -> u32 { 5 }
+fn default_home_min_satellites() -> u8 { crate::navigation::DEFAULT_HOME_MIN_SATELLITES }
 fn default_link_stats_interval_ms() -> u64 { 1000 }
 
 impl Config {
@@ -190,7 +620,60 @@ impl Config {
         Ok(config)
     }
 
-    /// Validate configuration values
+    /// Resolve a named model profile into a flat configuration
+    ///
+    /// Starts from the base configuration and applies the overrides from
+    /// `[models.<name>]`. The returned configuration is validated and has no
+    /// model profiles of its own.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Model profile name
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the profile does not exist or the resolved
+    /// configuration is invalid
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use fpv_bridge::config::Config;
+    ///
+    /// let config = Config::load("config/default.toml")?;
+    /// let whoop = config.for_model("whoop")?;
+    /// println!("Whoop roll expo: {}", whoop.controller.expo_roll);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn for_model(&self, name: &str) -> Result<Config> {
+        let profile = self.models.get(name).ok_or_else(|| {
+            FpvBridgeError::Config(toml::de::Error::custom(format!(
+                "unknown model profile '{}' (available: {})",
+                name,
+                self.model_names().collect::<Vec<_>>().join(", ")
+            )))
+        })?;
+
+        let mut resolved = self.clone();
+        resolved.models.clear();
+        profile.apply(&mut resolved);
+
+        resolved.validate_settings().map_err(|e| match e {
+            FpvBridgeError::Config(e) => FpvBridgeError::Config(toml::de::Error::custom(
+                format!("model profile '{}': {}", name, e.message()),
+            )),
+            other => other,
+        })?;
+
+        Ok(resolved)
+    }
+
+    /// Names of the configured model profiles, in sorted order
+    pub fn model_names(&self) -> impl Iterator<Item = &str> {
+        self.models.keys().map(String::as_str)
+    }
+
+    /// Validate configuration values, including every model profile
     ///
     /// # Returns
     ///
@@ -200,6 +683,38 @@ impl Config {
     ///
     /// Returns error if any configuration value is out of valid range
     fn validate(&self) -> Result<()> {
+        self.validate_settings()?;
+
+        for name in self.models.keys() {
+            if name.trim().is_empty() {
+                return Err(FpvBridgeError::Config(
+                    toml::de::Error::custom("model profile name cannot be empty")
+                ));
+            }
+            self.for_model(name)?;
+        }
+
+        // Switching to a profile without a model ID would leave the previous
+        // profile's ID on the module, so crsf.model_id must be the fallback
+        let with_id = self.models.values().filter(|profile| profile.model_id.is_some()).count();
+        if self.crsf.model_id.is_none() && with_id > 0 && with_id < self.models.len() {
+            let missing: Vec<&str> = self
+                .models
+                .iter()
+                .filter(|(_, profile)| profile.model_id.is_none())
+                .map(|(name, _)| name.as_str())
+                .collect();
+            return Err(FpvBridgeError::Config(toml::de::Error::custom(format!(
+                "model profiles {} have no model_id; set crsf.model_id as the default or a model_id in every profile",
+                missing.join(", ")
+            ))));
+        }
+
+        Ok(())
+    }
+
+    /// Validate the flat (non-profile) configuration values
+    fn validate_settings(&self) -> Result<()> {
         // Validate serial port configuration
         if self.serial.port.is_empty() {
             return Err(crate::error::FpvBridgeError::Config(
@@ -294,7 +809,7 @@ impl Config {
             ("expo_yaw", self.controller.expo_yaw),
             ("expo_throttle", self.controller.expo_throttle),
         ] {
-            if value < 0.0 || value > 1.0 {
+            if !(0.0..=1.0).contains(&value) {
                 return Err(crate::error::FpvBridgeError::Config(
                     toml::de::Error::custom(format!("{} must be between 0.0 and 1.0", name))
                 ));
@@ -345,11 +860,106 @@ impl Config {
         }
 
         // Validate baud rate
-        if ![115200, 400000, 420000, 921600, 1870000, 3750000].contains(&self.serial.baud_rate) {
+        if !VALID_BAUD_RATES.contains(&self.serial.baud_rate) {
             return Err(crate::error::FpvBridgeError::Config(
                 toml::de::Error::custom("baud_rate must be one of: 115200, 400000, 420000, 921600, 1870000, 3750000")
             ));
         }
+        if self.serial.switch_baud_rate.is_some_and(|rate| !VALID_BAUD_RATES.contains(&rate)) {
+            return Err(crate::error::FpvBridgeError::Config(
+                toml::de::Error::custom("switch_baud_rate must be one of: 115200, 400000, 420000, 921600, 1870000, 3750000")
+            ));
+        }
+
+        // Validate output sinks
+        if let Err(e) = self.output.sink_specs() {
+            return Err(FpvBridgeError::Config(toml::de::Error::custom(e.to_string())));
+        }
+        if !self.serial.is_enabled() && self.output.sinks.is_empty() {
+            return Err(FpvBridgeError::Config(
+                toml::de::Error::custom("serial port 'none' needs at least one output sink (e.g. \"joystick\")")
+            ));
+        }
+
+        // Validate network bridge settings
+        let bridge_sinks = self
+            .output
+            .sink_specs()
+            .unwrap_or_default()
+            .iter()
+            .any(|spec| matches!(spec, SinkSpec::Bridge(_)));
+        if bridge_sinks || !self.bridge.secret.is_empty() {
+            if let Err(e) = self.bridge.key() {
+                return Err(FpvBridgeError::Config(toml::de::Error::custom(e.to_string())));
+            }
+        }
+        if let Err(e) = self.bridge.allowed_client_addrs() {
+            return Err(FpvBridgeError::Config(toml::de::Error::custom(e.to_string())));
+        }
+        if self.bridge.allow_bare_frames && self.bridge.allowed_clients.is_empty() {
+            return Err(FpvBridgeError::Config(
+                toml::de::Error::custom("bridge allow_bare_frames needs allowed_clients")
+            ));
+        }
+
+        // Validate API settings
+        if self.api.enabled && self.api.bind.parse::<std::net::SocketAddr>().is_err() {
+            return Err(FpvBridgeError::Config(toml::de::Error::custom(format!(
+                "api bind must be an IP address and port (e.g. 127.0.0.1:8080), got '{}'",
+                self.api.bind
+            ))));
+        }
+        if self.api.stream_rate_hz == 0 || self.api.stream_rate_hz > crate::api::MAX_STREAM_RATE_HZ {
+            return Err(FpvBridgeError::Config(toml::de::Error::custom(format!(
+                "api stream_rate_hz must be between 1 and {}",
+                crate::api::MAX_STREAM_RATE_HZ
+            ))));
+        }
+
+        // Validate MAVLink settings
+        if self.mavlink.enabled {
+            let valid_target = matches!(
+                self.mavlink.target.rsplit_once(':'),
+                Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok()
+            );
+            if !valid_target {
+                return Err(FpvBridgeError::Config(toml::de::Error::custom(format!(
+                    "mavlink target must be HOST:PORT (e.g. 127.0.0.1:14550), got '{}'",
+                    self.mavlink.target
+                ))));
+            }
+        }
+        if self.mavlink.system_id == 0 {
+            return Err(FpvBridgeError::Config(
+                toml::de::Error::custom("mavlink system_id must be between 1 and 255")
+            ));
+        }
+
+        // Validate tracker telemetry settings
+        if self.tracker.enabled {
+            if let Err(e) = self.tracker.tracker_protocol().and(self.tracker.tracker_target()) {
+                return Err(FpvBridgeError::Config(toml::de::Error::custom(e.to_string())));
+            }
+            if self.tracker.baud_rate == 0 {
+                return Err(FpvBridgeError::Config(
+                    toml::de::Error::custom("tracker baud_rate must not be 0")
+                ));
+            }
+        }
+        if self.tracker.rate_hz == 0 || self.tracker.rate_hz > crate::tracker::MAX_TRACKER_RATE_HZ {
+            return Err(FpvBridgeError::Config(toml::de::Error::custom(format!(
+                "tracker rate_hz must be between 1 and {}",
+                crate::tracker::MAX_TRACKER_RATE_HZ
+            ))));
+        }
+
+        // Validate navigation settings
+        if self.navigation.home_min_satellites < crate::mavlink::GPS_MIN_SATELLITES_3D {
+            return Err(FpvBridgeError::Config(toml::de::Error::custom(format!(
+                "navigation home_min_satellites must be at least {} (a 3D fix)",
+                crate::mavlink::GPS_MIN_SATELLITES_3D
+            ))));
+        }
 
         // Validate log format
         if self.telemetry.format != "jsonl" {
@@ -358,10 +968,52 @@ impl Config {
             ));
         }
 
+        // Validate model match ID
+        if let Some(model_id) = self.crsf.model_id {
+            if model_id > crate::crsf::protocol::CRSF_MODEL_ID_MAX {
+                return Err(crate::error::FpvBridgeError::Config(
+                    toml::de::Error::custom("model_id must be between 0 and 63")
+                ));
+            }
+        }
+
+        // Validate stick resolution
+        if !(11..=13).contains(&self.crsf.stick_resolution) {
+            return Err(crate::error::FpvBridgeError::Config(
+                toml::de::Error::custom("stick_resolution must be 11, 12 or 13 bits")
+            ));
+        }
+
         // Validate packet rate
-        if ![50, 150, 250, 500].contains(&self.crsf.packet_rate_hz) {
+        if ![50, 150, 250, 500, 1000].contains(&self.crsf.packet_rate_hz) {
+            return Err(crate::error::FpvBridgeError::Config(
+                toml::de::Error::custom("packet_rate_hz must be one of: 50, 150, 250, 500, 1000")
+            ));
+        }
+
+        // Validate transmit scheduler
+        if !["tokio", "thread"].contains(&self.crsf.scheduler.as_str()) {
+            return Err(crate::error::FpvBridgeError::Config(
+                toml::de::Error::custom("scheduler must be 'tokio' or 'thread'")
+            ));
+        }
+
+        if self.crsf.spin_us > 2000 {
             return Err(crate::error::FpvBridgeError::Config(
-                toml::de::Error::custom("packet_rate_hz must be one of: 50, 150, 250, 500")
+                toml::de::Error::custom("spin_us must be between 0 and 2000")
+            ));
+        }
+
+        // Validate send-on-change settings
+        if !(0..=255).contains(&self.crsf.change_threshold) {
+            return Err(crate::error::FpvBridgeError::Config(
+                toml::de::Error::custom("change_threshold must be between 0 and 255")
+            ));
+        }
+
+        if self.crsf.keep_alive_ms == 0 || self.crsf.keep_alive_ms > 1000 {
+            return Err(crate::error::FpvBridgeError::Config(
+                toml::de::Error::custom("keep_alive_ms must be between 1 and 1000")
             ));
         }
 
@@ -381,6 +1033,9 @@ mod tests {
                 baud_rate: default_baud_rate(),
                 timeout_ms: default_timeout_ms(),
                 reconnect_interval_ms: default_reconnect_interval_ms(),
+                probe: false,
+                half_duplex: false,
+                switch_baud_rate: None,
             },
             controller: ControllerConfig {
                 device_path: String::new(),
@@ -414,7 +1069,21 @@ mod tests {
             crsf: CrsfConfig {
                 packet_rate_hz: default_packet_rate_hz(),
                 link_stats_interval_ms: default_link_stats_interval_ms(),
+                model_id: None,
+                stick_resolution: default_stick_resolution(),
+                scheduler: default_scheduler(),
+                spin_us: default_spin_us(),
+                send_on_change: false,
+                change_threshold: default_change_threshold(),
+                keep_alive_ms: default_keep_alive_ms(),
             },
+            output: OutputConfig::default(),
+            api: ApiConfig::default(),
+            mavlink: MavlinkConfig::default(),
+            tracker: TrackerConfig::default(),
+            navigation: NavigationConfig::default(),
+            bridge: BridgeConfig::default(),
+            models: BTreeMap::new(),
         };
 
         assert!(config.validate().is_ok());
@@ -428,6 +1097,9 @@ mod tests {
                 baud_rate: default_baud_rate(),
                 timeout_ms: default_timeout_ms(),
                 reconnect_interval_ms: default_reconnect_interval_ms(),
+                probe: false,
+                half_duplex: false,
+                switch_baud_rate: None,
             },
             controller: ControllerConfig {
                 device_path: String::new(),
@@ -461,7 +1133,21 @@ mod tests {
             crsf: CrsfConfig {
                 packet_rate_hz: default_packet_rate_hz(),
                 link_stats_interval_ms: default_link_stats_interval_ms(),
+                model_id: None,
+                stick_resolution: default_stick_resolution(),
+                scheduler: default_scheduler(),
+                spin_us: default_spin_us(),
+                send_on_change: false,
+                change_threshold: default_change_threshold(),
+                keep_alive_ms: default_keep_alive_ms(),
             },
+            output: OutputConfig::default(),
+            api: ApiConfig::default(),
+            mavlink: MavlinkConfig::default(),
+            tracker: TrackerConfig::default(),
+            navigation: NavigationConfig::default(),
+            bridge: BridgeConfig::default(),
+            models: BTreeMap::new(),
         };
 
         assert!(config.validate().is_err());
@@ -502,6 +1188,9 @@ port = "/dev/ttyUSB0"
                 baud_rate: default_baud_rate(),
                 timeout_ms: default_timeout_ms(),
                 reconnect_interval_ms: default_reconnect_interval_ms(),
+                probe: false,
+                half_duplex: false,
+                switch_baud_rate: None,
             },
             controller: ControllerConfig {
                 device_path: String::new(),
@@ -535,7 +1224,21 @@ port = "/dev/ttyUSB0"
             crsf: CrsfConfig {
                 packet_rate_hz: default_packet_rate_hz(),
                 link_stats_interval_ms: default_link_stats_interval_ms(),
+                model_id: None,
+                stick_resolution: default_stick_resolution(),
+                scheduler: default_scheduler(),
+                spin_us: default_spin_us(),
+                send_on_change: false,
+                change_threshold: default_change_threshold(),
+                keep_alive_ms: default_keep_alive_ms(),
             },
+            output: OutputConfig::default(),
+            api: ApiConfig::default(),
+            mavlink: MavlinkConfig::default(),
+            tracker: TrackerConfig::default(),
+            navigation: NavigationConfig::default(),
+            bridge: BridgeConfig::default(),
+            models: BTreeMap::new(),
         }
     }
 
@@ -795,6 +1498,40 @@ port = "/dev/ttyUSB0"
         assert!(config.validate().is_err());
     }
 
+    #[test]
+    fn test_output_sinks() {
+        let mut config = create_valid_config();
+        config.output.sinks = vec!["udp:127.0.0.1:7777".to_string(), "file:flight.crsf".to_string()];
+        assert!(config.validate().is_ok());
+        assert_eq!(config.output.sink_specs().unwrap().len(), 2);
+
+        config.output.sinks.push("udp:localhost".to_string());
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("udp:localhost"));
+    }
+
+    #[test]
+    fn test_serial_port_none_needs_output_sink() {
+        let mut config = create_valid_config();
+        config.serial.port = "none".to_string();
+        assert!(!config.serial.is_enabled());
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("at least one output sink"));
+
+        config.output.sinks = vec!["joystick".to_string()];
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_switch_baud_rate() {
+        let mut config = create_valid_config();
+        config.serial.switch_baud_rate = Some(1_870_000);
+        assert!(config.validate().is_ok());
+
+        config.serial.switch_baud_rate = Some(500_000);
+        assert!(config.validate().is_err());
+    }
+
     #[test]
     fn test_invalid_baud_rate() {
         let mut config = create_valid_config();
@@ -827,16 +1564,446 @@ port = "/dev/ttyUSB0"
 
     #[test]
     fn test_valid_packet_rates() {
-        for &rate in &[50, 150, 250, 500] {
+        for &rate in &[50, 150, 250, 500, 1000] {
             let mut config = create_valid_config();
             config.crsf.packet_rate_hz = rate;
             assert!(config.validate().is_ok(), "Packet rate {} should be valid", rate);
         }
     }
 
+    #[test]
+    fn test_valid_model_ids() {
+        for id in [None, Some(0), Some(1), Some(63)] {
+            let mut config = create_valid_config();
+            config.crsf.model_id = id;
+            assert!(config.validate().is_ok(), "Model ID {:?} should be valid", id);
+        }
+    }
+
+    #[test]
+    fn test_model_id_too_high() {
+        let mut config = create_valid_config();
+        config.crsf.model_id = Some(64);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_stick_resolution() {
+        let mut config = create_valid_config();
+        assert_eq!(config.crsf.stick_resolution, 11);
+        assert_eq!(config.crsf.high_resolution_sticks(), None);
+
+        config.crsf.stick_resolution = 12;
+        assert!(config.validate().is_ok());
+        assert_eq!(config.crsf.high_resolution_sticks(), Some(SubsetResolution::Bits12));
+
+        config.crsf.stick_resolution = 13;
+        assert!(config.validate().is_ok());
+        assert_eq!(config.crsf.high_resolution_sticks(), Some(SubsetResolution::Bits13));
+
+        for bits in [0, 10, 14, 16] {
+            config.crsf.stick_resolution = bits;
+            assert!(config.validate().is_err(), "stick_resolution {} should be invalid", bits);
+        }
+    }
+
+    #[test]
+    fn test_scheduler_settings() {
+        let mut config = create_valid_config();
+        assert_eq!(config.crsf.wait_mode(), WaitMode::Tokio);
+
+        config.crsf.scheduler = "thread".to_string();
+        config.crsf.spin_us = 150;
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.crsf.wait_mode(),
+            WaitMode::Thread { spin: Duration::from_micros(150) }
+        );
+
+        config.crsf.spin_us = 2001;
+        assert!(config.validate().is_err());
+
+        config.crsf.spin_us = 200;
+        config.crsf.scheduler = "realtime".to_string();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_send_on_change_settings() {
+        let mut config = create_valid_config();
+        assert!(!config.crsf.send_on_change);
+        assert_eq!(config.crsf.change_threshold, 2);
+        assert_eq!(config.crsf.keep_alive_ms, 100);
+        assert!(config.crsf.send_on_change().is_none());
+
+        config.crsf.send_on_change = true;
+        assert!(config.validate().is_ok());
+        assert!(config.crsf.send_on_change().is_some());
+
+        for (threshold, valid) in [(-1, false), (0, true), (255, true), (256, false)] {
+            config.crsf.change_threshold = threshold;
+            assert_eq!(config.validate().is_ok(), valid, "change_threshold {}", threshold);
+        }
+        config.crsf.change_threshold = 2;
+
+        for (keep_alive_ms, valid) in [(0, false), (1, true), (1000, true), (1001, false)] {
+            config.crsf.keep_alive_ms = keep_alive_ms;
+            assert_eq!(config.validate().is_ok(), valid, "keep_alive_ms {}", keep_alive_ms);
+        }
+    }
+
+    #[test]
+    fn test_load_config_with_model_id() {
+        use std::io::Write;
+        use tempfile::NamedTempFile;
+
+        let toml_content = r#"
+[serial]
+[controller]
+[channels]
+[telemetry]
+[safety]
+
+[crsf]
+model_id = 3
+"#;
+
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(toml_content.as_bytes()).unwrap();
+        temp_file.flush().unwrap();
+
+        let config = Config::load(temp_file.path()).unwrap();
+        assert_eq!(config.crsf.model_id, Some(3));
+    }
+
+    fn load_from_str(toml_content: &str) -> Result<Config> {
+        use std::io::Write;
+        use tempfile::NamedTempFile;
+
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(toml_content.as_bytes()).unwrap();
+        temp_file.flush().unwrap();
+
+        Config::load(temp_file.path())
+    }
+
+    #[test]
+    fn test_load_config_with_output_sinks() {
+        let config = load_from_str(r#"
+[serial]
+[controller]
+[channels]
+[telemetry]
+[safety]
+[crsf]
+
+[output]
+sinks = ["udp:127.0.0.1:7777", "null"]
+"#).unwrap();
+        assert_eq!(config.output.sink_specs().unwrap(), vec![
+            SinkSpec::Udp("127.0.0.1:7777".to_string()),
+            SinkSpec::Null,
+        ]);
+
+        // The section is optional
+        let config = load_from_str("[serial]\n[controller]\n[channels]\n[telemetry]\n[safety]\n[crsf]\n").unwrap();
+        assert!(config.output.sinks.is_empty());
+    }
+
+    #[test]
+    fn test_load_config_with_api() {
+        let config = load_from_str(r#"
+[serial]
+[controller]
+[channels]
+[telemetry]
+[safety]
+[crsf]
+
+[api]
+enabled = true
+bind = "0.0.0.0:8080"
+token = "secret"
+"#).unwrap();
+        assert!(config.api.enabled);
+        assert_eq!(config.api.bind, "0.0.0.0:8080");
+        assert_eq!(config.api.token, "secret");
+        assert_eq!(config.api.stream_rate_hz, 10);
+
+        // The section is optional and off by default
+        let config = load_from_str("[serial]\n[controller]\n[channels]\n[telemetry]\n[safety]\n[crsf]\n").unwrap();
+        assert!(!config.api.enabled);
+        assert_eq!(config.api.bind, "127.0.0.1:8080");
+    }
+
+    #[test]
+    fn test_load_config_with_mavlink() {
+        let config = load_from_str(r#"
+[serial]
+[controller]
+[channels]
+[telemetry]
+[safety]
+[crsf]
+
+[mavlink]
+enabled = true
+target = "192.168.1.50:14550"
+"#).unwrap();
+        assert!(config.mavlink.enabled);
+        assert_eq!(config.mavlink.target, "192.168.1.50:14550");
+        assert_eq!(config.mavlink.system_id, 1);
+
+        let config = load_from_str("[serial]\n[controller]\n[channels]\n[telemetry]\n[safety]\n[crsf]\n").unwrap();
+        assert!(!config.mavlink.enabled);
+        assert_eq!(config.mavlink.target, "127.0.0.1:14550");
+    }
+
+    #[test]
+    fn test_mavlink_validation() {
+        let mut config = create_valid_config();
+        config.mavlink.enabled = true;
+        config.mavlink.target = "gcs.local:14550".to_string();
+        assert!(config.validate().is_ok());
+
+        config.mavlink.target = "gcs.local".to_string();
+        assert!(config.validate().unwrap_err().to_string().contains("mavlink target"));
+
+        // Only checked when enabled
+        config.mavlink.enabled = false;
+        assert!(config.validate().is_ok());
+
+        config.mavlink.system_id = 0;
+        assert!(config.validate().unwrap_err().to_string().contains("system_id"));
+    }
+
+    #[test]
+    fn test_load_config_with_tracker() {
+        let config = load_from_str(r#"
+[serial]
+[controller]
+[channels]
+[telemetry]
+[safety]
+[crsf]
+
+[tracker]
+enabled = true
+protocol = "msp"
+target = "udp:192.168.4.1:5760"
+"#).unwrap();
+        assert!(config.tracker.enabled);
+        assert_eq!(config.tracker.tracker_protocol().unwrap(), TrackerProtocol::Msp);
+        assert_eq!(config.tracker.tracker_target().unwrap(), TrackerTarget::Udp("192.168.4.1:5760".to_string()));
+        assert_eq!(config.tracker.rate_hz, 5);
+
+        let config = load_from_str("[serial]\n[controller]\n[channels]\n[telemetry]\n[safety]\n[crsf]\n").unwrap();
+        assert!(!config.tracker.enabled);
+        assert_eq!(config.tracker.protocol, "ltm");
+        assert_eq!(config.tracker.baud_rate, 115_200);
+    }
+
+    #[test]
+    fn test_tracker_validation() {
+        let mut config = create_valid_config();
+        config.tracker.enabled = true;
+        assert!(config.validate().is_ok());
+
+        config.tracker.protocol = "frsky".to_string();
+        assert!(config.validate().unwrap_err().to_string().contains("tracker protocol"));
+        config.tracker.protocol = "msp".to_string();
+
+        config.tracker.target = "/dev/ttyUSB1".to_string();
+        assert!(config.validate().unwrap_err().to_string().contains("tracker target"));
+        config.tracker.target = "serial:/dev/ttyUSB1".to_string();
+
+        config.tracker.baud_rate = 0;
+        assert!(config.validate().unwrap_err().to_string().contains("baud_rate"));
+
+        // Only checked when enabled
+        config.tracker.enabled = false;
+        assert!(config.validate().is_ok());
+
+        config.tracker.rate_hz = 0;
+        assert!(config.validate().unwrap_err().to_string().contains("rate_hz"));
+        config.tracker.rate_hz = 51;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_navigation_config() {
+        let config = load_from_str("[serial]\n[controller]\n[channels]\n[telemetry]\n[safety]\n[crsf]\n").unwrap();
+        assert_eq!(config.navigation.home_min_satellites, 6);
+
+        let mut config = load_from_str(
+            "[serial]\n[controller]\n[channels]\n[telemetry]\n[safety]\n[crsf]\n[navigation]\nhome_min_satellites = 8\n",
+        )
+        .unwrap();
+        assert_eq!(config.navigation.home_min_satellites, 8);
+        assert!(config.validate().is_ok());
+
+        config.navigation.home_min_satellites = 3;
+        assert!(config.validate().unwrap_err().to_string().contains("home_min_satellites"));
+    }
+
+    #[test]
+    fn test_bridge_validation() {
+        let mut config = create_valid_config();
+        assert!(config.validate().is_ok());
+
+        config.output.sinks = vec!["bridge:192.168.4.1:7777".to_string()];
+        assert!(config.validate().unwrap_err().to_string().contains("bridge.secret"));
+        config.bridge.secret = "too short".to_string();
+        assert!(config.validate().unwrap_err().to_string().contains("bridge.secret"));
+        config.bridge.secret = "correct horse battery staple".to_string();
+        assert!(config.validate().is_ok());
+
+        config.bridge.allow_bare_frames = true;
+        assert!(config.validate().unwrap_err().to_string().contains("allowed_clients"));
+        config.bridge.allowed_clients = vec!["192.168.4.2".to_string()];
+        assert!(config.validate().is_ok());
+        assert_eq!(config.bridge.allowed_client_addrs().unwrap(), vec!["192.168.4.2".parse::<IpAddr>().unwrap()]);
+
+        config.bridge.allowed_clients.push("laptop.local".to_string());
+        assert!(config.validate().unwrap_err().to_string().contains("laptop.local"));
+    }
+
+    #[test]
+    fn test_api_validation() {
+        let mut config = create_valid_config();
+        config.api.enabled = true;
+        config.api.bind = "localhost:8080".to_string();
+        assert!(config.validate().unwrap_err().to_string().contains("api bind"));
+
+        // Only checked when enabled
+        config.api.enabled = false;
+        assert!(config.validate().is_ok());
+
+        config.api.stream_rate_hz = 0;
+        assert!(config.validate().unwrap_err().to_string().contains("stream_rate_hz"));
+        config.api.stream_rate_hz = 51;
+        assert!(config.validate().is_err());
+    }
+
+    const MODELS_TOML: &str = r#"
+[serial]
+[controller]
+expo_roll = 0.3
+[channels]
+[telemetry]
+[safety]
+[crsf]
+model_id = 1
+
+[models.freestyle]
+
+[models.whoop]
+model_id = 2
+
+[models.whoop.controller]
+expo_roll = 0.1
+deadzone_stick = 0.02
+
+[models.whoop.channels]
+channel_reverse = [3]
+
+[models.whoop.safety]
+failsafe_timeout_ms = 300
+"#;
+
+    #[test]
+    fn test_load_config_with_models() {
+        let config = load_from_str(MODELS_TOML).unwrap();
+        assert_eq!(config.model_names().collect::<Vec<_>>(), vec!["freestyle", "whoop"]);
+    }
+
+    #[test]
+    fn test_for_model_applies_overrides() {
+        let config = load_from_str(MODELS_TOML).unwrap();
+        let whoop = config.for_model("whoop").unwrap();
+
+        assert_eq!(whoop.controller.expo_roll, 0.1);
+        assert_eq!(whoop.controller.deadzone_stick, 0.02);
+        assert_eq!(whoop.controller.expo_pitch, config.controller.expo_pitch);
+        assert_eq!(whoop.channels.channel_reverse, vec![3]);
+        assert_eq!(whoop.safety.failsafe_timeout_ms, 300);
+        assert_eq!(whoop.safety.min_throttle_to_arm, config.safety.min_throttle_to_arm);
+        assert_eq!(whoop.crsf.model_id, Some(2));
+        assert!(whoop.models.is_empty());
+    }
+
+    #[test]
+    fn test_for_model_without_overrides_keeps_base() {
+        let config = load_from_str(MODELS_TOML).unwrap();
+        let freestyle = config.for_model("freestyle").unwrap();
+
+        assert_eq!(freestyle.controller.expo_roll, 0.3);
+        assert_eq!(freestyle.crsf.model_id, Some(1));
+    }
+
+    #[test]
+    fn test_for_model_unknown_name() {
+        let config = load_from_str(MODELS_TOML).unwrap();
+        let err = config.for_model("cinewhoop").unwrap_err().to_string();
+
+        assert!(err.contains("cinewhoop"));
+        assert!(err.contains("freestyle, whoop"));
+    }
+
+    #[test]
+    fn test_invalid_model_override_rejected() {
+        let toml_content = MODELS_TOML.replace("expo_roll = 0.1", "expo_roll = 1.5");
+        let err = load_from_str(&toml_content).unwrap_err().to_string();
+
+        assert!(err.contains("whoop"));
+        assert!(err.contains("expo_roll"));
+    }
+
+    #[test]
+    fn test_model_override_unknown_field_rejected() {
+        let toml_content = MODELS_TOML.replace("expo_roll = 0.1", "expo_rol = 0.1");
+        assert!(load_from_str(&toml_content).is_err());
+    }
+
+    #[test]
+    fn test_model_override_unused_settings_rejected() {
+        for (section, setting) in [
+            ("channels", "throttle_min = 1100"),
+            ("channels", "throttle_max = 1900"),
+            ("channels", "center = 1520"),
+            ("safety", "arm_button_hold_ms = 500"),
+            ("safety", "auto_disarm_timeout_s = 10"),
+            ("safety", "min_throttle_to_arm = 1020"),
+        ] {
+            let toml_content = format!("{}\n[models.freestyle.{}]\n{}\n", MODELS_TOML, section, setting);
+            assert!(load_from_str(&toml_content).is_err(), "{} should be rejected", setting);
+        }
+    }
+
+    #[test]
+    fn test_models_without_model_id_need_default() {
+        // freestyle falls back to crsf.model_id = 1
+        let toml_content = MODELS_TOML.replace("[crsf]\nmodel_id = 1\n", "[crsf]\n");
+        let err = load_from_str(&toml_content).unwrap_err().to_string();
+        assert!(err.contains("freestyle"));
+
+        let toml_content = toml_content.replace("[models.freestyle]\n", "[models.freestyle]\nmodel_id = 1\n");
+        assert!(load_from_str(&toml_content).is_ok());
+
+        // No profile selects a model: model match stays untouched
+        let toml_content = MODELS_TOML.replace("[crsf]\nmodel_id = 1\n", "[crsf]\n").replace("model_id = 2\n", "");
+        assert!(load_from_str(&toml_content).is_ok());
+    }
+
+    #[test]
+    fn test_model_override_invalid_model_id_rejected() {
+        let toml_content = MODELS_TOML.replace("model_id = 2", "model_id = 99");
+        assert!(load_from_str(&toml_content).is_err());
+    }
+
     #[test]
     fn test_default_functions() {
-        assert_eq!(default_serial_port(), "/dev/ttyACM0");
+        assert_eq!(default_serial_port(), "auto");
         assert_eq!(default_baud_rate(), 420000);
         assert_eq!(default_timeout_ms(), 100);
         assert_eq!(default_reconnect_interval_ms(), 1000);
@@ -849,7 +2016,7 @@ port = "/dev/ttyUSB0"
         assert_eq!(default_throttle_min(), 1000);
         assert_eq!(default_throttle_max(), 2000);
         assert_eq!(default_center(), 1500);
-        assert_eq!(default_telemetry_enabled(), true);
+        assert!(default_telemetry_enabled());
         assert_eq!(default_log_dir(), "./logs");
         assert_eq!(default_max_records_per_file(), 10000);
         assert_eq!(default_max_files_to_keep(), 10);
@@ -0,0 +1,743 @@
+//! # Control Loop
+//!
+//! Turns controller input into RC frames for the ELRS module and the
+//! output sinks, at the configured packet rate.
+//!
+//! [`ControlLoop`] owns the serial connection, the output sinks and the
+//! model profiles. It reads the controller state from a watch channel (fed
+//! by [`spawn_controller_reader`]), publishes a [`BridgeStatus`] on the
+//! status bus and serves control requests from the status API, until the
+//! shutdown future completes.
+//!
+//! Each iteration it:
+//! - Maps the controller state through the active profile and sends it as
+//!   an RC channels frame (0x16), locked to the module's RF timing once it
+//!   reports timing sync. With high-resolution sticks, the sticks go in a
+//!   0x17 subset frame every time and the 0x16 frame carries the other
+//!   channels when they change (see [`AuxChannels`])
+//! - With `crsf.send_on_change`, sends on input change and keep-alive
+//!   frames only while the input does not change
+//! - Applies telemetry from the module to the status bus
+//! - Switches model profiles (Options + D-Pad, or the API) while disarmed
+//! - Tracks consecutive transmission failures and serial output stalls
+
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use tokio::sync::{mpsc, watch};
+use tokio::time::{sleep_until, Instant};
+use tracing::{debug, error, info, warn};
+
+use crate::api::{ApiCommand, ApiRequest};
+use crate::config::Config;
+use crate::controller::channel_mapper::STICK_CHANNELS;
+use crate::controller::disarm::DisarmLatch;
+use crate::controller::input::InputSource;
+use crate::controller::mapper::{ControllerState, EventMapper};
+use crate::controller::profile::{ProfileGesture, ProfileManager, BASE_PROFILE_NAME};
+use crate::crsf::decoder::decode_timing_sync;
+use crate::crsf::encoder::{encode_rc_channels_frame_into, encode_subset_rc_channels_frame_into};
+use crate::crsf::protocol::{
+    CrsfFrame, RcChannels, CRSF_FRAMETYPE_RADIO_ID, CRSF_MAX_FRAME_SIZE, CRSF_RC_CHANNELS_FRAME_SIZE,
+};
+use crate::dashboard::BridgeStatus;
+use crate::error::{FpvBridgeError, Result};
+use crate::latency::{LatencySummary, LatencyTracker};
+use crate::navigation::Navigation;
+use crate::scheduler::TxScheduler;
+use crate::serial::ElrsSerial;
+use crate::sink::{FrameSink, TeeSink};
+
+/// Default packet transmission rate in Hz (ELRS standard)
+///
+/// ExpressLRS uses 250Hz packet rate for control commands, resulting in
+/// a 4ms period between packets. This ensures responsive control with
+/// low latency suitable for FPV drone racing and freestyle.
+pub const PACKET_RATE_HZ: u32 = 250;
+
+/// Consecutive failure threshold before escalating to warning level
+///
+/// When packet transmission fails 10 times consecutively, logging
+/// escalates from debug to warning level to alert of persistent
+/// connectivity issues that may require intervention.
+pub const FAILURE_WARNING_THRESHOLD: u32 = 10;
+
+/// With high-resolution sticks, the standard RC channels frame carrying
+/// the other channels is resent at least every this many frames (10 times
+/// a second at 250Hz), in case the module restarted and lost them
+pub const AUX_REFRESH_FRAMES: u32 = 25;
+
+/// Status bus contents before the first frame: the active profile, its
+/// failsafe timeout and home lock settings
+///
+/// # Arguments
+///
+/// * `profiles` - Model profiles, with the one to start with selected
+///
+/// # Returns
+///
+/// * `BridgeStatus` - Initial status for the status bus
+pub fn initial_status(profiles: &ProfileManager) -> BridgeStatus {
+    let active = profiles.active();
+    let failsafe_timeout = Duration::from_millis(active.config.safety.failsafe_timeout_ms);
+    let mut status = BridgeStatus::new(&active.name, failsafe_timeout);
+    status.navigation = Navigation::new(active.config.navigation.home_min_satellites);
+    status
+}
+
+/// Reads controller events on a dedicated thread and publishes the latest state
+///
+/// `fetch_events` blocks until the controller reports input, so it cannot run
+/// on the async runtime. If the controller disconnects, the state is reset to
+/// default (sticks centered, all buttons released, ARM off). When an input
+/// script ends, its last state is held.
+///
+/// # Arguments
+///
+/// * `controller` - PS5 controller, input script or recorded session
+/// * `state_tx` - Controller state bus read by the control loop
+pub fn spawn_controller_reader(mut controller: Box<dyn InputSource>, state_tx: watch::Sender<ControllerState>) {
+    std::thread::spawn(move || {
+        let mut mapper = EventMapper::new();
+
+        loop {
+            match controller.fetch_events() {
+                Ok(Some(events)) => {
+                    for event in events {
+                        mapper.process_event(&event);
+                    }
+                    state_tx.send_replace(mapper.state_snapshot());
+                }
+                Ok(None) => {
+                    info!("Input {} finished, holding its last state", controller.describe());
+                    break;
+                }
+                Err(e) => {
+                    warn!("Controller read failed, releasing all inputs: {}", e);
+                    state_tx.send_replace(ControllerState::default());
+                    break;
+                }
+            }
+        }
+    });
+}
+
+/// Ongoing serial output stall
+struct SerialStall {
+    /// First failed write of the stall
+    since: Instant,
+    /// Whether the stall outlasted the failsafe timeout and was logged
+    reported: bool,
+}
+
+/// Tracks the non-stick channels while the sticks go in subset frames
+///
+/// Subset frames carry only the sticks, so the other channels (arm, modes,
+/// buttons) are sent in a standard RC channels frame: when they change,
+/// and every [`AUX_REFRESH_FRAMES`] frames.
+#[derive(Debug, Default)]
+struct AuxChannels {
+    /// Channels of the last standard frame sent
+    sent: Option<RcChannels>,
+    /// Subset frames since then
+    frames_since: u32,
+}
+
+impl AuxChannels {
+    /// Whether `channels` need a standard RC channels frame before the
+    /// stick subset frame
+    fn due(&mut self, channels: &RcChannels) -> bool {
+        self.frames_since += 1;
+        self.frames_since >= AUX_REFRESH_FRAMES
+            || self.sent.is_none_or(|sent| sent[STICK_CHANNELS..] != channels[STICK_CHANNELS..])
+    }
+
+    /// Record a standard RC channels frame as sent
+    fn sent(&mut self, channels: &RcChannels) {
+        self.sent = Some(*channels);
+        self.frames_since = 0;
+    }
+}
+
+/// Sends RC frames from the controller state to the ELRS module and the
+/// output sinks
+///
+/// # Examples
+///
+/// ```no_run
+/// use fpv_bridge::config::Config;
+/// use fpv_bridge::control::{initial_status, ControlLoop};
+/// use fpv_bridge::controller::mapper::ControllerState;
+/// use fpv_bridge::controller::profile::ProfileManager;
+/// use fpv_bridge::serial::ElrsSerial;
+/// use fpv_bridge::sink::TeeSink;
+/// use tokio::sync::watch;
+///
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     let profiles = ProfileManager::new(Config::load("config/default.toml")?, None)?;
+///     let serial = ElrsSerial::connect(&profiles.active().config.serial).await?;
+///     let (_state_tx, state_rx) = watch::channel(ControllerState::default());
+///     let (status_tx, _status_rx) = watch::channel(initial_status(&profiles));
+///
+///     ControlLoop::new(profiles, Some(serial), TeeSink::new(), state_rx, status_tx)
+///         .run(async { tokio::signal::ctrl_c().await.unwrap() })
+///         .await?;
+///     Ok(())
+/// }
+/// ```
+pub struct ControlLoop {
+    profiles: ProfileManager,
+    /// ELRS module (`None` with `serial.port = "none"`)
+    serial: Option<ElrsSerial>,
+    /// Extra outputs getting the same RC frames as the module
+    outputs: TeeSink,
+    state_rx: watch::Receiver<ControllerState>,
+    status_tx: watch::Sender<BridgeStatus>,
+    /// Control requests from the status API
+    api_requests: Option<mpsc::Receiver<ApiRequest>>,
+    /// Configuration file reloaded by the API
+    config_path: Option<PathBuf>,
+    /// How long the latency self-test runs
+    latency_test: Option<Duration>,
+}
+
+impl ControlLoop {
+    /// Create a control loop
+    ///
+    /// # Arguments
+    ///
+    /// * `profiles` - Model profiles, with the one to start with selected
+    /// * `serial` - Connected ELRS module, `None` to send to the outputs only
+    /// * `outputs` - Extra sinks receiving every RC frame
+    /// * `state_rx` - Controller state bus
+    /// * `status_tx` - Status bus (see [`initial_status`])
+    pub fn new(
+        profiles: ProfileManager,
+        serial: Option<ElrsSerial>,
+        outputs: TeeSink,
+        state_rx: watch::Receiver<ControllerState>,
+        status_tx: watch::Sender<BridgeStatus>,
+    ) -> Self {
+        Self {
+            profiles,
+            serial,
+            outputs,
+            state_rx,
+            status_tx,
+            api_requests: None,
+            config_path: None,
+            latency_test: None,
+        }
+    }
+
+    /// Serve control requests from the status API; config reloads read
+    /// `config_path`
+    #[must_use]
+    pub fn with_api(mut self, requests: mpsc::Receiver<ApiRequest>, config_path: &Path) -> Self {
+        self.api_requests = Some(requests);
+        self.config_path = Some(config_path.to_path_buf());
+        self
+    }
+
+    /// Stop after `duration` (`--latency-test`)
+    #[must_use]
+    pub fn with_latency_test(mut self, duration: Duration) -> Self {
+        self.latency_test = Some(duration);
+        self
+    }
+
+    /// Run until `shutdown` completes or the latency self-test ends
+    ///
+    /// Transmit, serial, latency and output statistics are logged on the
+    /// way out.
+    ///
+    /// # Arguments
+    ///
+    /// * `shutdown` - Completes when the loop should stop (e.g. Ctrl+C)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<LatencySummary>>` - Input latency over the whole
+    ///   run, `None` without controller input
+    ///
+    /// # Errors
+    ///
+    /// Returns `Controller` error if the latency self-test saw no
+    /// controller input. Send failures are logged and counted on the status
+    /// bus, they do not end the loop.
+    pub async fn run<F>(mut self, shutdown: F) -> Result<Option<LatencySummary>>
+    where
+        F: Future<Output = ()>,
+    {
+        tokio::pin!(shutdown);
+        let mut gesture = ProfileGesture::new();
+        let mut failsafe_timeout = Duration::from_millis(self.profiles.active().config.safety.failsafe_timeout_ms);
+
+        // Free-run at the configured rate until the module reports its RF timing
+        let crsf = &self.profiles.active().config.crsf;
+        let packet_rate_hz = crsf.packet_rate_hz;
+        let mut scheduler = TxScheduler::with_mode(packet_rate_hz, crsf.wait_mode());
+        let mut rx_enabled = self.serial.is_some();
+
+        info!("Starting CRSF packet transmission loop at {}Hz ({:?})", packet_rate_hz, crsf.wait_mode());
+        if packet_rate_hz != PACKET_RATE_HZ {
+            info!("Non-default packet rate, the ELRS module must run at {}Hz too", packet_rate_hz);
+        }
+        if let Some(resolution) = crsf.high_resolution_sticks() {
+            info!("Sending {}-bit sticks in subset RC channels frames", resolution.bits());
+        }
+        if crsf.send_on_change {
+            info!("Sending on input change, keep-alive every {}ms", crsf.keep_alive_ms);
+        }
+        let latency_test_end = self.latency_test.map(|duration| Instant::now() + duration);
+
+        let mut consecutive_failures: u32 = 0;
+        let mut stall: Option<SerialStall> = None;
+        let mut packet = [0u8; CRSF_RC_CHANNELS_FRAME_SIZE];
+        let mut subset_packet = Vec::with_capacity(CRSF_MAX_FRAME_SIZE);
+        let mut aux_channels = AuxChannels::default();
+        let mut latency = LatencyTracker::new();
+        let mut send_on_change = crsf.send_on_change();
+        let mut watch_input = send_on_change.is_some();
+        let mut disarm = DisarmLatch::new();
+
+        loop {
+            let mut state = tokio::select! {
+                // Scheduler tick: send every time, or only a keep-alive in send-on-change mode
+                _ = scheduler.tick() => {
+                    // Per-second timing statistics
+                    if let Some(report) = scheduler.take_report() {
+                        info!("TX {} (model profile: {})", report, self.profiles.active().name);
+                        let latency_window = latency.take_window();
+                        self.status_tx.send_modify(|status| {
+                            status.tx = Some(report);
+                            status.serial = self.serial.as_ref().map(ElrsSerial::tx_stats);
+                            status.latency = latency_window;
+                        });
+                        if let Some(policy) = &mut send_on_change {
+                            info!("Frames sent {}", policy.take_counts());
+                        }
+                        if let Some(summary) = latency_window {
+                            info!("Input latency {}", summary);
+                        }
+                    }
+
+                    let state = self.state_rx.borrow().clone();
+
+                    // Model profile switching (Options + D-Pad, only while the
+                    // arm channel last sent is off)
+                    let armed = self.status_tx.borrow().armed();
+                    if let Some(step) = gesture.update(&state, armed) {
+                        match self.profiles.step(step) {
+                            Ok(Some(_)) => failsafe_timeout = self.activate_profile().await,
+                            Ok(None) => debug!("No model profiles configured"),
+                            Err(e) => warn!("Failed to switch model profile: {}", e),
+                        }
+                    }
+
+                    if let Some(policy) = &mut send_on_change {
+                        if policy.on_tick(&state, Instant::now(), scheduler.period()).is_none() {
+                            continue;
+                        }
+                    }
+                    state
+                }
+
+                // Controller input changed (send-on-change mode only)
+                changed = self.state_rx.changed(), if watch_input => {
+                    if changed.is_err() {
+                        // Reader thread ended; keep-alive ticks carry the released state
+                        watch_input = false;
+                        continue;
+                    }
+
+                    let state = self.state_rx.borrow_and_update().clone();
+                    let send = send_on_change
+                        .as_mut()
+                        .is_some_and(|policy| policy.on_input(&state, Instant::now(), scheduler.period()));
+                    if !send {
+                        continue;
+                    }
+                    state
+                }
+
+                // Frames from the module (timing sync, telemetry)
+                frame = recv_module_frame(self.serial.as_mut()), if rx_enabled => {
+                    match frame {
+                        Ok(frame) if frame.frame_type == CRSF_FRAMETYPE_RADIO_ID => {
+                            match decode_timing_sync(&frame.payload) {
+                                Ok(sync) => {
+                                    scheduler.apply_sync(&sync, Instant::now());
+                                }
+                                Err(e) => debug!("Ignoring RADIO_ID frame: {}", e),
+                            }
+                        }
+                        Ok(frame) => {
+                            debug!("Received CRSF frame type 0x{:02X}", frame.frame_type);
+                            self.status_tx.send_if_modified(|status| {
+                                status.apply_telemetry(&frame, Instant::now()).unwrap_or_else(|e| {
+                                    debug!("Ignoring telemetry frame type 0x{:02X}: {}", frame.frame_type, e);
+                                    false
+                                })
+                            });
+                        }
+                        Err(e) => {
+                            warn!("Stopped reading from ELRS module, timing sync disabled: {}", e);
+                            rx_enabled = false;
+                        }
+                    }
+                    continue;
+                }
+
+                // Control requests from the status API
+                Some(request) = recv_api_request(self.api_requests.as_mut()) => {
+                    // Like the Options + D-Pad gesture, profiles only change while disarmed
+                    let armed = self.status_tx.borrow().armed();
+                    let reply = match request.command {
+                        ApiCommand::SelectModel(_) | ApiCommand::ReloadConfig if armed => {
+                            Err("refusing to change the model profile while armed".to_string())
+                        }
+                        ApiCommand::SelectModel(name) => match self.profiles.select(&name) {
+                            Ok(_) => {
+                                failsafe_timeout = self.activate_profile().await;
+                                Ok(format!("switched to model profile {}", name))
+                            }
+                            Err(e) => Err(e.to_string()),
+                        },
+                        ApiCommand::ReloadConfig => match self.config_path.clone() {
+                            Some(path) => match reload_profiles(&path, &self.profiles) {
+                                Ok(reloaded) => {
+                                    self.profiles = reloaded;
+                                    failsafe_timeout = self.activate_profile().await;
+                                    Ok(format!("reloaded {}, model profile {}", path.display(), self.profiles.active().name))
+                                }
+                                Err(e) => Err(format!("Failed to load configuration from {}: {}", path.display(), e)),
+                            },
+                            None => Err("no configuration file to reload".to_string()),
+                        },
+                        ApiCommand::Disarm => {
+                            warn!("Disarm requested over the API, hold until L1 is released");
+                            disarm.engage();
+                            self.status_tx.send_modify(|status| status.disarm_latched = true);
+                            Ok("disarmed until L1 is released".to_string())
+                        }
+                    };
+                    if let Err(e) = &reply {
+                        warn!("API request failed: {}", e);
+                    }
+                    // The client may have gone away meanwhile
+                    let _ = request.reply.send(reply);
+                    continue;
+                }
+
+                // End of the latency self-test
+                _ = sleep_until(latency_test_end.unwrap_or_else(Instant::now)), if latency_test_end.is_some() => {
+                    if latency.total().summary().is_none() {
+                        return Err(FpvBridgeError::Controller(
+                            "Latency self-test received no controller input".to_string()
+                        ));
+                    }
+                    break;
+                }
+
+                _ = &mut shutdown => break,
+            };
+
+            // Remote disarm holds the arm button off until it is released
+            disarm.apply(&mut state);
+            self.status_tx.send_if_modified(|status| {
+                std::mem::replace(&mut status.disarm_latched, disarm.is_engaged()) != disarm.is_engaged()
+            });
+
+            // Encode and send CRSF packet from controller input
+            let active = self.profiles.active();
+            let write_start = Instant::now();
+            let channels = active.channel_mapper.map_to_channels(&state);
+            let subset = active
+                .config
+                .crsf
+                .high_resolution_sticks()
+                .map(|resolution| active.channel_mapper.map_to_subset_channels(&state, resolution));
+            encode_rc_channels_frame_into(&channels, &mut packet);
+            let result = match subset {
+                Some(subset) => match encode_subset_rc_channels_frame_into(&subset, &mut subset_packet) {
+                    Ok(()) => {
+                        // The standard frame goes first, so the module ends
+                        // up with the high-resolution sticks
+                        let mut result = Ok(());
+                        if aux_channels.due(&channels) {
+                            result = self.send_rc_frame(&packet).await;
+                            if result.is_ok() {
+                                aux_channels.sent(&channels);
+                            }
+                        }
+                        result.and(self.send_rc_frame(&subset_packet).await)
+                    }
+                    Err(e) => Err(e),
+                },
+                None => self.send_rc_frame(&packet).await,
+            };
+
+            if let Err(e) = result {
+                consecutive_failures += 1;
+
+                if let FpvBridgeError::SerialStall(_) = e {
+                    let stall = stall.get_or_insert(SerialStall { since: write_start, reported: false });
+                    let stalled_for = stall.since.elapsed();
+                    if stalled_for >= failsafe_timeout && !stall.reported {
+                        error!(
+                            "Serial output stalled for {}ms (failsafe timeout {}ms), ELRS module is not receiving RC frames: {}",
+                            stalled_for.as_millis(), failsafe_timeout.as_millis(), e
+                        );
+                        stall.reported = true;
+                    }
+                }
+
+                let stalled = stall.as_ref().is_some_and(|stall| stall.reported);
+                self.status_tx.send_modify(|status| status.record_error(&e.to_string(), stalled));
+
+                if consecutive_failures >= FAILURE_WARNING_THRESHOLD {
+                    warn!("Failed to send packet (consecutive failures: {}): {}", consecutive_failures, e);
+                } else {
+                    debug!("Failed to send packet: {}", e);
+                }
+                continue;
+            }
+
+            // Reset failure counter on successful transmission
+            consecutive_failures = 0;
+            self.status_tx.send_modify(|status| status.record_sent(&channels));
+            if let Some(stall) = stall.take().filter(|stall| stall.reported) {
+                if let Some(serial) = &self.serial {
+                    info!("Serial output recovered after {}ms ({})", stall.since.elapsed().as_millis(), serial.tx_stats());
+                }
+            }
+            scheduler.record_write(write_start.elapsed());
+            let flushed = match &self.serial {
+                Some(serial) => serial.last_flush_time(),
+                None => Some(SystemTime::now()),
+            };
+            if let Some(flushed) = flushed {
+                latency.record_flush(state.last_event_time, flushed);
+            }
+        }
+
+        info!("Total transmit ticks: {}", scheduler.total_ticks());
+        if let Some(serial) = &self.serial {
+            info!("Serial output: {}", serial.tx_stats());
+            if let Some(echo) = serial.echo_stats() {
+                info!("Half-duplex echo: {}", echo);
+            }
+            if let Some(frames) = serial.captured_frames() {
+                info!("Captured {} frames", frames);
+            }
+            if let Some(module) = serial.virtual_module() {
+                info!("Virtual module: {}", module.stats());
+            }
+        }
+        let summary = latency.total().summary();
+        if let Some(summary) = summary {
+            info!("Input latency {}", summary);
+        }
+        if let Err(e) = self.outputs.flush().await {
+            warn!("Failed to flush outputs: {}", e);
+        }
+        for (output, stats) in self.outputs.stats() {
+            info!("Output {}: {}", output, stats);
+        }
+        Ok(summary)
+    }
+
+    /// Sends one RC frame to the module and the output sinks, or to the
+    /// sinks only without a module
+    ///
+    /// # Errors
+    ///
+    /// Returns the module's send error; output sink failures are logged by
+    /// the tee, once per outage.
+    async fn send_rc_frame(&mut self, frame: &[u8]) -> Result<()> {
+        match &mut self.serial {
+            Some(serial) => {
+                let result = serial.send_packet(frame).await;
+                let _ = self.outputs.send_frame(frame).await;
+                result
+            }
+            None => self.outputs.send_frame(frame).await,
+        }
+    }
+
+    /// Applies a newly selected model profile: sends its model ID to the
+    /// module, logs the model ID now active and publishes the profile on the
+    /// status bus
+    ///
+    /// # Returns
+    ///
+    /// * `Duration` - Failsafe timeout of the profile
+    async fn activate_profile(&mut self) -> Duration {
+        let active = self.profiles.active();
+        if let Some(serial) = &mut self.serial {
+            if let Some(model_id) = active.model_id().filter(|&id| serial.model_id() != Some(id)) {
+                if let Err(e) = serial.select_model(model_id).await {
+                    warn!("Failed to select model ID {}: {}", model_id, e);
+                }
+            }
+            match serial.model_id() {
+                Some(model_id) => info!("Model ID {} active on the ELRS module", model_id),
+                None => info!("No model ID selected on the ELRS module"),
+            }
+        }
+
+        let failsafe_timeout = Duration::from_millis(active.config.safety.failsafe_timeout_ms);
+        self.status_tx.send_modify(|status| {
+            status.profile = active.name.clone();
+            status.failsafe_timeout = failsafe_timeout;
+            // A reloaded configuration may change it; home and flight stats are kept
+            status.navigation.home_min_satellites = active.config.navigation.home_min_satellites;
+        });
+        failsafe_timeout
+    }
+}
+
+/// Loads the configuration file again, keeping the active model profile
+///
+/// Serial, CRSF timing, output and API settings only take effect on restart.
+///
+/// # Errors
+///
+/// Returns error if the file is invalid or no longer has the active profile
+fn reload_profiles(path: &Path, profiles: &ProfileManager) -> Result<ProfileManager> {
+    let config = Config::load(path)?;
+    let active = Some(profiles.active().name.as_str()).filter(|&name| name != BASE_PROFILE_NAME);
+    ProfileManager::new(config, active)
+}
+
+/// Next control request from the status API, if it is enabled
+///
+/// Without the API the returned future never completes.
+async fn recv_api_request(requests: Option<&mut mpsc::Receiver<ApiRequest>>) -> Option<ApiRequest> {
+    match requests {
+        Some(requests) => requests.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Next frame from the ELRS module, if there is one
+///
+/// Without a module the returned future never completes.
+///
+/// # Errors
+///
+/// Returns error if reading from the module fails
+async fn recv_module_frame(serial: Option<&mut ElrsSerial>) -> Result<CrsfFrame> {
+    match serial {
+        Some(serial) => serial.recv_frame().await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::channel_mapper::{channels, SWITCH_ON};
+    use crate::crsf::protocol::{CRSF_CHANNEL_VALUE_CENTER, CRSF_NUM_CHANNELS};
+
+    #[test]
+    fn test_packet_rate_constant() {
+        // Verify ELRS standard packet rate
+        assert_eq!(PACKET_RATE_HZ, 250, "Packet rate should be 250Hz (ELRS standard)");
+    }
+
+    #[test]
+    fn test_default_packet_rate_matches_config_default() {
+        let config: Config = toml::from_str("[serial]\n[controller]\n[channels]\n[telemetry]\n[safety]\n[crsf]\n").unwrap();
+        assert_eq!(config.crsf.packet_rate_hz, PACKET_RATE_HZ);
+    }
+
+    #[test]
+    fn test_packet_period_calculation() {
+        // Verify period calculation is correct
+        let period_ms = 1000 / PACKET_RATE_HZ;
+        assert_eq!(period_ms, 4, "Period should be 4ms at 250Hz");
+    }
+
+    #[test]
+    fn test_failure_warning_threshold() {
+        // Verify failure threshold is reasonable
+        assert_eq!(FAILURE_WARNING_THRESHOLD, 10);
+
+        // At 250Hz, 10 failures = 40ms of consecutive failures
+        // This is a reasonable threshold before escalating to warnings
+        let failure_duration_ms = FAILURE_WARNING_THRESHOLD * 4; // 4ms per packet at 250Hz
+        assert_eq!(failure_duration_ms, 40, "Should tolerate 40ms of failures before warning");
+    }
+
+    #[test]
+    fn test_constants_are_consistent() {
+        // Verify that constants work together logically
+
+        // Packet rate and period
+        let period_ms = 1000 / PACKET_RATE_HZ;
+        assert_eq!(period_ms, 4, "250Hz rate should result in 4ms period");
+
+        // Failure threshold timing
+        let failure_threshold_ms = FAILURE_WARNING_THRESHOLD * period_ms;
+        assert_eq!(failure_threshold_ms, 40, "Should warn after 40ms of failures");
+
+        // Sanity checks
+        const { assert!(PACKET_RATE_HZ > 0, "Packet rate must be positive") };
+        const { assert!(FAILURE_WARNING_THRESHOLD > 0, "Failure threshold must be positive") };
+    }
+
+    #[test]
+    fn test_elrs_standard_packet_rate() {
+        // ExpressLRS standard specifies 250Hz for RC channels
+        // This is critical for proper operation
+        assert_eq!(PACKET_RATE_HZ, 250,
+            "ELRS requires 250Hz packet rate for RC channels");
+
+        // Verify period calculation
+        let period_ms = 1000 / PACKET_RATE_HZ;
+        assert_eq!(period_ms, 4,
+            "250Hz should result in exactly 4ms period per packet");
+    }
+
+    #[test]
+    fn test_aux_channels_sent_on_change_and_refresh() {
+        let mut aux = AuxChannels::default();
+        let mut rc = [CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS];
+
+        // Nothing sent yet
+        assert!(aux.due(&rc));
+        aux.sent(&rc);
+
+        // Stick movement alone goes in the subset frames
+        rc[channels::THROTTLE] = 1800;
+        assert!(!aux.due(&rc));
+
+        // A switch change needs a standard frame at once
+        rc[channels::ARM] = SWITCH_ON;
+        assert!(aux.due(&rc));
+        aux.sent(&rc);
+
+        // Unchanged channels are refreshed every AUX_REFRESH_FRAMES frames
+        for _ in 1..AUX_REFRESH_FRAMES {
+            assert!(!aux.due(&rc));
+        }
+        assert!(aux.due(&rc));
+    }
+
+    #[test]
+    fn test_initial_status_follows_active_profile() {
+        let config: Config = toml::from_str(
+            "[serial]\n[controller]\n[channels]\n[telemetry]\n[safety]\nfailsafe_timeout_ms = 300\n[crsf]\n\
+             [navigation]\nhome_min_satellites = 8\n",
+        )
+        .unwrap();
+        let status = initial_status(&ProfileManager::new(config, None).unwrap());
+        assert_eq!(status.profile, BASE_PROFILE_NAME);
+        assert_eq!(status.failsafe_timeout, Duration::from_millis(300));
+        assert_eq!(status.navigation.home_min_satellites, 8);
+    }
+}
@@ -340,10 +340,33 @@ pub fn normalize_trigger(raw: i32) -> f32 {
 /// ```
 #[must_use]
 pub fn to_crsf_channel(normalized: f32) -> u16 {
-    // Map -1.0..1.0 to 0..2047
+    to_channel_value(normalized, 2047)
+}
+
+/// Converts normalized value (-1.0 to 1.0) to a channel value (0 to `max`).
+///
+/// Generalization of [`to_crsf_channel`] for higher-resolution channel
+/// frames (e.g. `max` = 4095 for 12-bit sticks).
+///
+/// # Arguments
+///
+/// * `normalized` - Normalized value (-1.0 to 1.0)
+/// * `max` - Channel value at full positive deflection
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::controller::calibration::to_channel_value;
+///
+/// assert_eq!(to_channel_value(-1.0, 4095), 0);
+/// assert_eq!(to_channel_value(1.0, 4095), 4095);
+/// ```
+#[must_use]
+pub fn to_channel_value(normalized: f32, max: u16) -> u16 {
+    // Map -1.0..1.0 to 0..max
     let clamped = normalized.clamp(-1.0, 1.0);
-    let scaled = (clamped + 1.0) * 1023.5;
-    (scaled as u16).min(2047)
+    let scaled = (clamped + 1.0) * (f32::from(max) / 2.0);
+    (scaled as u16).min(max)
 }
 
 /// Converts normalized trigger value (0.0 to 1.0) to CRSF channel value (0-2047).
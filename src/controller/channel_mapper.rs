@@ -35,10 +35,13 @@
 //! assert!((channels[0] as i32 - 1024).abs() <= 5);
 //! ```
 
+use super::calibration::{
+    normalize_axis, normalize_trigger, to_channel_value, trigger_to_crsf_channel, AxisCalibration,
+};
 use super::mapper::{ControllerState, AXIS_MAX, AXIS_MIN};
 use crate::crsf::protocol::{
-    RcChannels, CRSF_CHANNEL_VALUE_CENTER, CRSF_CHANNEL_VALUE_MAX, CRSF_CHANNEL_VALUE_MIN,
-    CRSF_NUM_CHANNELS,
+    RcChannels, SubsetRcChannels, SubsetResolution, CRSF_CHANNEL_VALUE_CENTER,
+    CRSF_CHANNEL_VALUE_MAX, CRSF_CHANNEL_VALUE_MIN, CRSF_NUM_CHANNELS,
 };
 
 /// CRSF value for switch OFF state.
@@ -47,6 +50,10 @@ pub const SWITCH_OFF: u16 = CRSF_CHANNEL_VALUE_MIN;
 /// CRSF value for switch ON state.
 pub const SWITCH_ON: u16 = CRSF_CHANNEL_VALUE_MAX;
 
+/// Number of stick channels (roll, pitch, throttle, yaw), which lead the
+/// channel order.
+pub const STICK_CHANNELS: usize = channels::YAW + 1;
+
 /// Channel indices for semantic access.
 pub mod channels {
     /// Roll - Right Stick X
@@ -89,6 +96,8 @@ pub mod channels {
 pub struct ChannelMapper {
     /// Channels to reverse (invert direction).
     reversed_channels: [bool; CRSF_NUM_CHANNELS],
+    /// Deadzone/expo applied to sticks and triggers (linear if `None`).
+    calibration: Option<AxisCalibration>,
 }
 
 impl Default for ChannelMapper {
@@ -103,6 +112,7 @@ impl ChannelMapper {
     pub fn new() -> Self {
         Self {
             reversed_channels: [false; CRSF_NUM_CHANNELS],
+            calibration: None,
         }
     }
 
@@ -128,7 +138,41 @@ impl ChannelMapper {
                 reversed_channels[ch - 1] = true;
             }
         }
-        Self { reversed_channels }
+        Self {
+            reversed_channels,
+            calibration: None,
+        }
+    }
+
+    /// Applies deadzones and expo curves to sticks and triggers.
+    ///
+    /// Without calibration, axes are mapped linearly from the raw 0-255 range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fpv_bridge::controller::calibration::AxisCalibration;
+    /// use fpv_bridge::controller::channel_mapper::{ChannelMapper, channels};
+    /// use fpv_bridge::controller::mapper::ControllerState;
+    ///
+    /// let mapper = ChannelMapper::new().with_calibration(AxisCalibration::default());
+    ///
+    /// let mut state = ControllerState::default();
+    /// state.right_stick_x = 130; // Slight drift within deadzone
+    ///
+    /// let channels = mapper.map_to_channels(&state);
+    /// assert!((channels[channels::ROLL] as i32 - 1024).abs() <= 1);
+    /// ```
+    #[must_use]
+    pub fn with_calibration(mut self, calibration: AxisCalibration) -> Self {
+        self.calibration = Some(calibration);
+        self
+    }
+
+    /// Returns the calibration applied to sticks and triggers, if any.
+    #[must_use]
+    pub fn calibration(&self) -> Option<&AxisCalibration> {
+        self.calibration.as_ref()
     }
 
     /// Maps controller state to 16 RC channels.
@@ -158,20 +202,71 @@ impl ChannelMapper {
     /// ```
     #[must_use]
     pub fn map_to_channels(&self, state: &ControllerState) -> RcChannels {
+        self.map_channels(state, CRSF_CHANNEL_VALUE_MAX)
+    }
+
+    /// Maps controller state to a subset RC channels frame carrying only
+    /// the sticks (channels 1-4), scaled at `resolution`.
+    ///
+    /// Sticks are computed directly at the higher resolution rather than
+    /// upscaled from 11 bits. The other channels are switches and gain
+    /// nothing from more bits: they go in standard RC channels frames
+    /// ([`map_to_channels`](Self::map_to_channels)), which keeps the subset
+    /// frame short.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - Current controller state
+    /// * `resolution` - Channel resolution of the frame
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fpv_bridge::controller::mapper::ControllerState;
+    /// use fpv_bridge::controller::channel_mapper::{ChannelMapper, channels};
+    /// use fpv_bridge::crsf::protocol::SubsetResolution;
+    ///
+    /// let mut state = ControllerState::default();
+    /// state.left_stick_y = 0; // Full throttle
+    ///
+    /// let subset = ChannelMapper::new().map_to_subset_channels(&state, SubsetResolution::Bits12);
+    /// assert_eq!(subset.first_channel, 0);
+    /// assert_eq!(subset.values.len(), 4);
+    /// assert_eq!(subset.values[channels::THROTTLE], 4095);
+    /// ```
+    #[must_use]
+    pub fn map_to_subset_channels(
+        &self,
+        state: &ControllerState,
+        resolution: SubsetResolution,
+    ) -> SubsetRcChannels {
+        let values = self.map_channels(state, resolution.max_value());
+
+        SubsetRcChannels {
+            first_channel: 0,
+            resolution,
+            values: values[..STICK_CHANNELS].to_vec(),
+        }
+    }
+
+    /// Maps controller state to 16 channels, scaling the sticks to `stick_max`
+    /// and everything else to the 11-bit CRSF range.
+    fn map_channels(&self, state: &ControllerState, stick_max: u16) -> RcChannels {
         let mut channels = [CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS];
 
         // CH1: Roll (Right Stick X)
-        channels[channels::ROLL] = self.map_axis(state.right_stick_x, channels::ROLL);
+        channels[channels::ROLL] = self.map_axis(state.right_stick_x, channels::ROLL, stick_max);
 
         // CH2: Pitch (Right Stick Y) - inverted (up = forward = high value)
-        channels[channels::PITCH] = self.map_axis_inverted(state.right_stick_y, channels::PITCH);
+        channels[channels::PITCH] =
+            self.map_axis_inverted(state.right_stick_y, channels::PITCH, stick_max);
 
         // CH3: Throttle (Left Stick Y) - inverted (up = high throttle)
         channels[channels::THROTTLE] =
-            self.map_axis_inverted(state.left_stick_y, channels::THROTTLE);
+            self.map_axis_inverted(state.left_stick_y, channels::THROTTLE, stick_max);
 
         // CH4: Yaw (Left Stick X)
-        channels[channels::YAW] = self.map_axis(state.left_stick_x, channels::YAW);
+        channels[channels::YAW] = self.map_axis(state.left_stick_x, channels::YAW, stick_max);
 
         // CH5: ARM (L1 button)
         channels[channels::ARM] = self.map_button(state.btn_l1, channels::ARM);
@@ -188,52 +283,79 @@ impl ChannelMapper {
         channels
     }
 
-    /// Maps an axis value (0-255) to CRSF range (0-2047).
-    fn map_axis(&self, value: i32, channel: usize) -> u16 {
-        let mapped = Self::scale_axis_to_crsf(value);
-        self.apply_reverse(mapped, channel)
+    /// Maps an axis value (0-255) to channel range (0-`max`).
+    fn map_axis(&self, value: i32, channel: usize, max: u16) -> u16 {
+        let mapped = self.scale_stick(value, channel, max);
+        self.apply_reverse(mapped, channel, max)
     }
 
-    /// Maps an inverted axis value (0-255) to CRSF range (0-2047).
-    /// Inverted means 0 -> 2047 and 255 -> 0.
-    fn map_axis_inverted(&self, value: i32, channel: usize) -> u16 {
+    /// Maps an inverted axis value (0-255) to channel range (0-`max`).
+    /// Inverted means 0 -> `max` and 255 -> 0.
+    fn map_axis_inverted(&self, value: i32, channel: usize, max: u16) -> u16 {
         // Clamp before subtraction to prevent integer overflow on invalid inputs
         let clamped = value.clamp(AXIS_MIN, AXIS_MAX);
         let inverted = AXIS_MAX - clamped;
-        let mapped = Self::scale_axis_to_crsf(inverted);
-        self.apply_reverse(mapped, channel)
+        let mapped = self.scale_stick(inverted, channel, max);
+        self.apply_reverse(mapped, channel, max)
     }
 
     /// Maps a trigger value (0-255) to CRSF range (0-2047).
     fn map_trigger(&self, value: i32, channel: usize) -> u16 {
-        let mapped = Self::scale_axis_to_crsf(value);
-        self.apply_reverse(mapped, channel)
+        let mapped = match &self.calibration {
+            Some(cal) => trigger_to_crsf_channel(cal.apply_trigger(normalize_trigger(
+                value.clamp(AXIS_MIN, AXIS_MAX),
+            ))),
+            None => Self::scale_axis_to_crsf(value),
+        };
+        self.apply_reverse(mapped, channel, CRSF_CHANNEL_VALUE_MAX)
+    }
+
+    /// Scales a stick value (0-255) to channel range (0-`max`), applying the
+    /// axis calibration if set.
+    fn scale_stick(&self, value: i32, channel: usize, max: u16) -> u16 {
+        let Some(cal) = &self.calibration else {
+            return Self::scale_axis(value, max);
+        };
+
+        let axis = match channel {
+            channels::ROLL => &cal.roll,
+            channels::PITCH => &cal.pitch,
+            channels::THROTTLE => &cal.throttle,
+            _ => &cal.yaw,
+        };
+        to_channel_value(axis.apply(normalize_axis(value)), max)
     }
 
     /// Maps a button state to switch value.
     fn map_button(&self, pressed: bool, channel: usize) -> u16 {
         let value = if pressed { SWITCH_ON } else { SWITCH_OFF };
-        self.apply_reverse(value, channel)
+        self.apply_reverse(value, channel, CRSF_CHANNEL_VALUE_MAX)
     }
 
     /// Scales raw axis value (0-255) to CRSF range (0-2047).
     #[inline]
     fn scale_axis_to_crsf(value: i32) -> u16 {
+        Self::scale_axis(value, CRSF_CHANNEL_VALUE_MAX)
+    }
+
+    /// Scales raw axis value (0-255) to channel range (0-`max`).
+    #[inline]
+    fn scale_axis(value: i32, max: u16) -> u16 {
         // Clamp input to valid range
         let clamped = value.clamp(AXIS_MIN, AXIS_MAX);
 
-        // Scale: (value / 255) * 2047
-        // Using integer math: (value * 2047 + 127) / 255 for rounding
-        let scaled = ((clamped as u32 * CRSF_CHANNEL_VALUE_MAX as u32) + 127) / 255;
+        // Scale: (value / 255) * max
+        // Using integer math: (value * max + 127) / 255 for rounding
+        let scaled = ((clamped as u32 * max as u32) + 127) / 255;
 
         scaled as u16
     }
 
     /// Applies channel reversal if configured.
     #[inline]
-    fn apply_reverse(&self, value: u16, channel: usize) -> u16 {
+    fn apply_reverse(&self, value: u16, channel: usize, max: u16) -> u16 {
         if self.reversed_channels[channel] {
-            CRSF_CHANNEL_VALUE_MAX - value
+            max - value
         } else {
             value
         }
@@ -241,6 +363,7 @@ impl ChannelMapper {
 }
 
 #[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
 mod tests {
     use super::*;
     use crate::controller::mapper::AXIS_CENTER;
@@ -518,6 +641,153 @@ mod tests {
         assert_eq!(channels[channels::TURTLE], CRSF_CHANNEL_VALUE_MAX);
     }
 
+    #[test]
+    fn test_mapped_channels_survive_wire_encoding() {
+        use crate::crsf::decoder::decode_rc_channels_frame;
+        use crate::crsf::encoder::encode_rc_channels_frame;
+
+        let mapper = ChannelMapper::with_reversed(&[2]);
+        let mut state = ControllerState::default();
+        state.left_stick_y = 40;
+        state.right_stick_x = 200;
+        state.btn_l1 = true;
+        state.trigger_r2 = 128;
+
+        let channels = mapper.map_to_channels(&state);
+        let frame = encode_rc_channels_frame(&channels);
+
+        assert_eq!(decode_rc_channels_frame(&frame).unwrap(), channels);
+    }
+
+    // ==================== Calibration Tests ====================
+
+    #[test]
+    fn test_calibration_deadzone_centers_small_deflection() {
+        let mapper = ChannelMapper::new().with_calibration(AxisCalibration::default());
+        let mut state = ControllerState::default();
+        state.right_stick_x = 131;
+        state.left_stick_x = 125;
+
+        let channels = mapper.map_to_channels(&state);
+
+        assert!((channels[channels::ROLL] as i32 - 1024).abs() <= 1);
+        assert!((channels[channels::YAW] as i32 - 1024).abs() <= 1);
+    }
+
+    #[test]
+    fn test_calibration_preserves_endpoints() {
+        let mapper = ChannelMapper::new().with_calibration(AxisCalibration::default());
+        let mut state = ControllerState::default();
+        state.right_stick_x = AXIS_MAX;
+        state.right_stick_y = AXIS_MIN;
+        state.left_stick_y = AXIS_MAX;
+        state.trigger_l2 = AXIS_MAX;
+
+        let channels = mapper.map_to_channels(&state);
+
+        assert_eq!(channels[channels::ROLL], CRSF_CHANNEL_VALUE_MAX);
+        assert_eq!(channels[channels::PITCH], CRSF_CHANNEL_VALUE_MAX);
+        assert_eq!(channels[channels::THROTTLE], CRSF_CHANNEL_VALUE_MIN);
+        assert_eq!(channels[channels::BEEPER], CRSF_CHANNEL_VALUE_MAX);
+    }
+
+    #[test]
+    fn test_calibration_expo_softens_roll() {
+        let linear = ChannelMapper::new();
+        let expo = ChannelMapper::new()
+            .with_calibration(AxisCalibration::from_config(0.0, 0.0, 0.7, 0.0, 0.0, 0.0));
+        let mut state = ControllerState::default();
+        state.right_stick_x = 192; // Half deflection right
+
+        let linear_roll = linear.map_to_channels(&state)[channels::ROLL];
+        let expo_roll = expo.map_to_channels(&state)[channels::ROLL];
+
+        assert!(expo_roll < linear_roll, "expo {} should be below linear {}", expo_roll, linear_roll);
+        assert!(expo_roll > CRSF_CHANNEL_VALUE_CENTER);
+    }
+
+    #[test]
+    fn test_calibration_trigger_deadzone() {
+        let mapper = ChannelMapper::new().with_calibration(AxisCalibration::default());
+        let mut state = ControllerState::default();
+        state.trigger_r2 = 20; // Below 10% deadzone
+
+        let channels = mapper.map_to_channels(&state);
+
+        assert_eq!(channels[channels::TURTLE], CRSF_CHANNEL_VALUE_MIN);
+    }
+
+    #[test]
+    fn test_calibration_with_reverse() {
+        let mapper = ChannelMapper::with_reversed(&[1]).with_calibration(AxisCalibration::default());
+        let mut state = ControllerState::default();
+        state.right_stick_x = AXIS_MAX;
+
+        let channels = mapper.map_to_channels(&state);
+
+        assert_eq!(channels[channels::ROLL], CRSF_CHANNEL_VALUE_MIN);
+        assert!(mapper.calibration().is_some());
+        assert!(ChannelMapper::new().calibration().is_none());
+    }
+
+    // ==================== High-Resolution Tests ====================
+
+    #[test]
+    fn test_subset_channels_11_bit_matches_map_to_channels() {
+        let mapper = ChannelMapper::with_reversed(&[1]).with_calibration(AxisCalibration::default());
+        let mut state = ControllerState::default();
+        state.right_stick_x = 30;
+        state.left_stick_y = 77;
+        state.btn_r1 = true;
+        state.trigger_l2 = 200;
+
+        let subset = mapper.map_to_subset_channels(&state, SubsetResolution::Bits11);
+
+        assert_eq!(subset.first_channel, 0);
+        assert_eq!(subset.values, mapper.map_to_channels(&state)[..STICK_CHANNELS].to_vec());
+    }
+
+    #[test]
+    fn test_subset_channels_12_bit_sticks() {
+        let mapper = ChannelMapper::with_reversed(&[4]);
+        let mut state = ControllerState::default();
+        state.left_stick_y = AXIS_MIN; // Full throttle
+        state.right_stick_x = AXIS_MIN; // Full left roll
+        state.left_stick_x = AXIS_MAX; // Full right yaw, reversed
+        state.btn_l1 = true;
+
+        let subset = mapper.map_to_subset_channels(&state, SubsetResolution::Bits12);
+
+        assert_eq!(subset.values[channels::THROTTLE], 4095);
+        assert_eq!(subset.values[channels::ROLL], 0);
+        assert_eq!(subset.values[channels::YAW], 0);
+        // Only the sticks: switches go in standard RC channels frames
+        assert_eq!(subset.values.len(), STICK_CHANNELS);
+    }
+
+    #[test]
+    fn test_subset_channels_finer_than_11_bit() {
+        // Expo flattens the curve near center, where 11-bit output steps
+        let cal = AxisCalibration::from_config(0.0, 0.0, 0.0, 0.0, 0.0, 1.0);
+        let mapper = ChannelMapper::new().with_calibration(cal);
+        let mut state = ControllerState::default();
+
+        let mut coarse_values = std::collections::BTreeSet::new();
+        let mut fine_values = std::collections::BTreeSet::new();
+        for raw in AXIS_MIN..=AXIS_MAX {
+            state.left_stick_y = raw;
+            let coarse = mapper.map_to_channels(&state)[channels::THROTTLE];
+            let fine = mapper.map_to_subset_channels(&state, SubsetResolution::Bits12).values
+                [channels::THROTTLE];
+
+            // Same stick position, within one 11-bit step
+            assert!((i32::from(fine >> 1) - i32::from(coarse)).abs() <= 1);
+            coarse_values.insert(coarse);
+            fine_values.insert(fine);
+        }
+        assert!(fine_values.len() > coarse_values.len());
+    }
+
     // ==================== Constants Tests ====================
 
     #[test]
@@ -0,0 +1,110 @@
+//! # Remote Disarm
+//!
+//! Disarming from outside the controller (the status API) must not be undone
+//! by the pilot still holding L1: [`DisarmLatch`] keeps the arm button
+//! released in the controller state until it is physically released, so
+//! re-arming takes a deliberate new press.
+
+use super::mapper::ControllerState;
+
+/// Forces the arm button off after a remote disarm until it is released
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::controller::disarm::DisarmLatch;
+/// use fpv_bridge::controller::mapper::ControllerState;
+///
+/// let mut latch = DisarmLatch::new();
+/// let mut state = ControllerState::default();
+/// state.btn_l1 = true;
+///
+/// latch.engage();
+/// let mut disarmed = state.clone();
+/// latch.apply(&mut disarmed);
+/// assert!(!disarmed.btn_l1);
+///
+/// // Released and pressed again: armed
+/// let mut released = ControllerState::default();
+/// latch.apply(&mut released);
+/// latch.apply(&mut state);
+/// assert!(state.btn_l1);
+/// ```
+#[derive(Debug, Default)]
+pub struct DisarmLatch {
+    engaged: bool,
+}
+
+impl DisarmLatch {
+    /// Latch that is not engaged
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Disarm now and until the arm button is released
+    pub fn engage(&mut self) {
+        self.engaged = true;
+    }
+
+    /// Whether the arm button is being held off
+    pub fn is_engaged(&self) -> bool {
+        self.engaged
+    }
+
+    /// Apply the latch to a controller state
+    ///
+    /// Releases the latch once the state shows the arm button released.
+    pub fn apply(&mut self, state: &mut ControllerState) {
+        if !self.engaged {
+            return;
+        }
+        if state.btn_l1 {
+            state.btn_l1 = false;
+        } else {
+            self.engaged = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn armed() -> ControllerState {
+        ControllerState { btn_l1: true, ..ControllerState::default() }
+    }
+
+    #[test]
+    fn test_not_engaged_passes_state_through() {
+        let mut latch = DisarmLatch::new();
+        let mut state = armed();
+        latch.apply(&mut state);
+        assert!(state.btn_l1);
+    }
+
+    #[test]
+    fn test_holds_while_arm_button_held() {
+        let mut latch = DisarmLatch::new();
+        latch.engage();
+        for _ in 0..3 {
+            let mut state = armed();
+            latch.apply(&mut state);
+            assert!(!state.btn_l1);
+            assert!(latch.is_engaged());
+        }
+
+        latch.apply(&mut ControllerState::default());
+        assert!(!latch.is_engaged());
+        let mut state = armed();
+        latch.apply(&mut state);
+        assert!(state.btn_l1);
+    }
+
+    #[test]
+    fn test_disarmed_state_releases_at_once() {
+        let mut latch = DisarmLatch::new();
+        latch.engage();
+        latch.apply(&mut ControllerState::default());
+        assert!(!latch.is_engaged());
+    }
+}
@@ -0,0 +1,41 @@
+//! # Controller Input Sources
+//!
+//! Where controller events come from: the PS5 controller
+//! ([`DualSenseController`]) or a replayed input script
+//! ([`ScriptedController`](super::script::ScriptedController)). Both yield
+//! raw evdev [`InputEvent`]s for the [`EventMapper`](super::mapper::EventMapper),
+//! so everything above it runs the same way in tests as in flight.
+
+use evdev::InputEvent;
+
+use super::ps5::DualSenseController;
+use crate::error::Result;
+
+/// Blocking source of controller input events
+pub trait InputSource: Send {
+    /// Wait for the next batch of input events
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Vec<InputEvent>>>` - The events, or `None` once the
+    ///   source has no more input (end of a script)
+    ///
+    /// # Errors
+    ///
+    /// Returns `Controller` error if reading fails (e.g. controller
+    /// disconnected).
+    fn fetch_events(&mut self) -> Result<Option<Vec<InputEvent>>>;
+
+    /// Short description for logs (device path or script name)
+    fn describe(&self) -> String;
+}
+
+impl InputSource for DualSenseController {
+    fn fetch_events(&mut self) -> Result<Option<Vec<InputEvent>>> {
+        Ok(Some(DualSenseController::fetch_events(self)?.collect()))
+    }
+
+    fn describe(&self) -> String {
+        self.device_path().to_string()
+    }
+}
@@ -62,6 +62,8 @@
 //! ```
 
 use evdev::{AbsoluteAxisType, InputEvent, Key};
+use serde::Serialize;
+use std::time::SystemTime;
 
 /// Raw axis value range from DualSense controller.
 pub const AXIS_MIN: i32 = 0;
@@ -92,7 +94,7 @@ pub const DPAD_POSITIVE: i32 = 1;
 /// assert_eq!(state.left_stick_x, 128);  // Centered
 /// assert!(!state.btn_l1);               // Not pressed
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ControllerState {
     // Analog sticks (0-255, 128 = center)
     /// Left stick X axis (Yaw). 0 = full left, 255 = full right.
@@ -153,6 +155,12 @@ pub struct ControllerState {
     // Touchpad
     /// Touchpad click (calibration).
     pub btn_touchpad: bool,
+
+    // Timing
+    /// Kernel timestamp of the last event that updated this state
+    /// (`None` until the first one). Used to measure input latency.
+    #[serde(skip)]
+    pub last_event_time: Option<SystemTime>,
 }
 
 impl Default for ControllerState {
@@ -188,6 +196,8 @@ impl Default for ControllerState {
             btn_l3: false,
             btn_r3: false,
             btn_touchpad: false,
+
+            last_event_time: None,
         }
     }
 }
@@ -209,6 +219,28 @@ impl ControllerState {
         Self::default()
     }
 
+    /// Creates the state sent when control input is lost: sticks centered,
+    /// throttle fully down, all buttons and triggers released (disarmed).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fpv_bridge::controller::channel_mapper::{ChannelMapper, channels, SWITCH_OFF};
+    /// use fpv_bridge::controller::mapper::ControllerState;
+    /// use fpv_bridge::crsf::protocol::CRSF_CHANNEL_VALUE_MIN;
+    ///
+    /// let channels = ChannelMapper::new().map_to_channels(&ControllerState::failsafe());
+    /// assert_eq!(channels[channels::THROTTLE], CRSF_CHANNEL_VALUE_MIN);
+    /// assert_eq!(channels[channels::ARM], SWITCH_OFF);
+    /// ```
+    #[must_use]
+    pub fn failsafe() -> Self {
+        Self {
+            left_stick_y: AXIS_MAX,
+            ..Self::default()
+        }
+    }
+
     /// Checks if any stick has moved from center position.
     ///
     /// Useful for detecting controller activity for auto-disarm timeout.
@@ -309,6 +341,73 @@ impl ControllerState {
         );
         self.trigger_l2 > threshold || self.trigger_r2 > threshold
     }
+
+    /// Checks if this state differs from `other` enough to send a new frame.
+    ///
+    /// Sticks and triggers count as changed when they moved **strictly more
+    /// than** `threshold`; any D-Pad or button change counts. The event
+    /// timestamp is ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - State to compare against (usually the last one sent)
+    /// * `threshold` - Analog movement that must be exceeded (valid range: 0-255)
+    ///
+    /// # Panics
+    ///
+    /// Debug builds will panic if `threshold` is outside the valid range 0-255.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fpv_bridge::controller::mapper::ControllerState;
+    ///
+    /// let sent = ControllerState::new();
+    /// let mut state = sent.clone();
+    ///
+    /// state.right_stick_x += 1; // Sensor noise
+    /// assert!(!state.changed_beyond(&sent, 2));
+    ///
+    /// state.btn_l1 = true;
+    /// assert!(state.changed_beyond(&sent, 2));
+    /// ```
+    #[must_use]
+    pub fn changed_beyond(&self, other: &Self, threshold: i32) -> bool {
+        debug_assert!(
+            (0..=255).contains(&threshold),
+            "threshold must be in range 0-255, got {}",
+            threshold
+        );
+        let moved = |a: i32, b: i32| (a - b).abs() > threshold;
+
+        moved(self.left_stick_x, other.left_stick_x)
+            || moved(self.left_stick_y, other.left_stick_y)
+            || moved(self.right_stick_x, other.right_stick_x)
+            || moved(self.right_stick_y, other.right_stick_y)
+            || moved(self.trigger_l2, other.trigger_l2)
+            || moved(self.trigger_r2, other.trigger_r2)
+            || self.dpad_x != other.dpad_x
+            || self.dpad_y != other.dpad_y
+            || self.buttons_differ(other)
+    }
+
+    /// Checks if any button state differs from `other`.
+    fn buttons_differ(&self, other: &Self) -> bool {
+        self.btn_cross != other.btn_cross
+            || self.btn_circle != other.btn_circle
+            || self.btn_square != other.btn_square
+            || self.btn_triangle != other.btn_triangle
+            || self.btn_l1 != other.btn_l1
+            || self.btn_r1 != other.btn_r1
+            || self.btn_l2 != other.btn_l2
+            || self.btn_r2 != other.btn_r2
+            || self.btn_share != other.btn_share
+            || self.btn_options != other.btn_options
+            || self.btn_ps != other.btn_ps
+            || self.btn_l3 != other.btn_l3
+            || self.btn_r3 != other.btn_r3
+            || self.btn_touchpad != other.btn_touchpad
+    }
 }
 
 /// Parses raw evdev events and maintains controller state.
@@ -410,21 +509,20 @@ impl EventMapper {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn process_event(&mut self, event: &InputEvent) {
-        match event.kind() {
-            evdev::InputEventKind::AbsAxis(axis) => {
-                self.process_axis_event(axis, event.value());
-            }
-            evdev::InputEventKind::Key(key) => {
-                self.process_key_event(key, event.value() != 0);
-            }
-            _ => {
-                // Ignore sync events and other event types
-            }
+        let mapped = match event.kind() {
+            evdev::InputEventKind::AbsAxis(axis) => self.process_axis_event(axis, event.value()),
+            evdev::InputEventKind::Key(key) => self.process_key_event(key, event.value() != 0),
+            // Ignore sync events and other event types
+            _ => false,
+        };
+
+        if mapped {
+            self.state.last_event_time = Some(event.timestamp());
         }
     }
 
-    /// Processes an absolute axis event.
-    fn process_axis_event(&mut self, axis: AbsoluteAxisType, value: i32) {
+    /// Processes an absolute axis event. Returns `false` for unmapped axes.
+    fn process_axis_event(&mut self, axis: AbsoluteAxisType, value: i32) -> bool {
         match axis {
             // Left stick
             AbsoluteAxisType::ABS_X => self.state.left_stick_x = value,
@@ -442,14 +540,14 @@ impl EventMapper {
             AbsoluteAxisType::ABS_HAT0X => self.state.dpad_x = value,
             AbsoluteAxisType::ABS_HAT0Y => self.state.dpad_y = value,
 
-            _ => {
-                // Ignore other axes (gyro, accelerometer, etc.)
-            }
+            // Ignore other axes (gyro, accelerometer, etc.)
+            _ => return false,
         }
+        true
     }
 
-    /// Processes a key/button event.
-    fn process_key_event(&mut self, key: Key, pressed: bool) {
+    /// Processes a key/button event. Returns `false` for unmapped buttons.
+    fn process_key_event(&mut self, key: Key, pressed: bool) -> bool {
         match key {
             // Face buttons
             Key::BTN_SOUTH => self.state.btn_cross = pressed,
@@ -475,10 +573,10 @@ impl EventMapper {
             // Touchpad (BTN_TOUCH for finger contact, we use click)
             Key::BTN_TOUCH => self.state.btn_touchpad = pressed,
 
-            _ => {
-                // Ignore unknown buttons
-            }
+            // Ignore unknown buttons
+            _ => return false,
         }
+        true
     }
 
     /// Resets all state to default (centered sticks, released buttons).
@@ -501,6 +599,7 @@ impl EventMapper {
 }
 
 #[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
 mod tests {
     use super::*;
     use evdev::EventType;
@@ -1054,4 +1153,89 @@ mod tests {
         state3.btn_l1 = true;
         assert_ne!(state1, state3);
     }
+
+    // ==================== Event Timestamp Tests ====================
+
+    #[test]
+    fn test_event_time_starts_unset() {
+        assert_eq!(ControllerState::default().last_event_time, None);
+    }
+
+    #[test]
+    fn test_event_time_taken_from_kernel_timestamp() {
+        let mut mapper = EventMapper::new();
+
+        let event = InputEvent::new_now(EventType::ABSOLUTE, AbsoluteAxisType::ABS_X.0, 200);
+        mapper.process_event(&event);
+        assert_eq!(mapper.state().last_event_time, Some(event.timestamp()));
+
+        let button = InputEvent::new_now(EventType::KEY, Key::BTN_TL.code(), 1);
+        mapper.process_event(&button);
+        assert_eq!(mapper.state().last_event_time, Some(button.timestamp()));
+    }
+
+    #[test]
+    fn test_event_time_ignores_unmapped_events() {
+        let mut mapper = EventMapper::new();
+        let event = InputEvent::new_now(EventType::ABSOLUTE, AbsoluteAxisType::ABS_X.0, 200);
+        mapper.process_event(&event);
+
+        mapper.process_event(&InputEvent::new_now(EventType::ABSOLUTE, AbsoluteAxisType::ABS_MISC.0, 1));
+        mapper.process_event(&InputEvent::new_now(EventType::SYNCHRONIZATION, 0, 0));
+        mapper.process_event(&InputEvent::new_now(EventType::KEY, Key::KEY_A.code(), 1));
+
+        assert_eq!(mapper.state().last_event_time, Some(event.timestamp()));
+    }
+
+    #[test]
+    fn test_reset_clears_event_time() {
+        let mut mapper = EventMapper::new();
+        mapper.process_event(&InputEvent::new_now(EventType::KEY, Key::BTN_TL.code(), 1));
+        mapper.reset();
+        assert_eq!(mapper.state().last_event_time, None);
+    }
+
+    // ==================== Change Detection Tests ====================
+
+    #[test]
+    fn test_changed_beyond_identical_states() {
+        let state = ControllerState::default();
+        assert!(!state.changed_beyond(&state.clone(), 0));
+    }
+
+    #[test]
+    fn test_changed_beyond_analog_threshold() {
+        let sent = ControllerState::default();
+        let mut state = sent.clone();
+
+        state.left_stick_y = sent.left_stick_y + 3;
+        assert!(!state.changed_beyond(&sent, 3), "Movement equal to threshold is not a change");
+        assert!(state.changed_beyond(&sent, 2));
+
+        let mut state = sent.clone();
+        state.trigger_r2 = 5;
+        assert!(state.changed_beyond(&sent, 4));
+        assert!(!state.changed_beyond(&sent, 5));
+    }
+
+    #[test]
+    fn test_changed_beyond_digital_inputs_ignore_threshold() {
+        let sent = ControllerState::default();
+
+        let mut state = sent.clone();
+        state.dpad_x = DPAD_POSITIVE;
+        assert!(state.changed_beyond(&sent, 255));
+
+        let mut state = sent.clone();
+        state.btn_touchpad = true;
+        assert!(state.changed_beyond(&sent, 255));
+    }
+
+    #[test]
+    fn test_changed_beyond_ignores_event_time() {
+        let sent = ControllerState::default();
+        let mut state = sent.clone();
+        state.last_event_time = Some(SystemTime::now());
+        assert!(!state.changed_beyond(&sent, 0));
+    }
 }
@@ -8,8 +8,17 @@
 //! - Applying deadzones and exponential curves
 //! - Mapping inputs to RC channels
 //! - Calibration and safety checks
+//! - Named model profiles with runtime switching
+//! - Scripted input for tests and hardware-free runs
+//! - Recording and replay of input sessions
+//! - Remote disarm that holds until the arm button is released
 
 pub mod calibration;
 pub mod channel_mapper;
+pub mod disarm;
+pub mod input;
 pub mod mapper;
+pub mod profile;
 pub mod ps5;
+pub mod script;
+pub mod session;
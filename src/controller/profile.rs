@@ -0,0 +1,412 @@
+//! # Model Profile Module
+//!
+//! Runtime selection of the named `[models.<name>]` profiles from the
+//! configuration.
+//!
+//! Each profile resolves into a flat [`Config`] plus the [`ChannelMapper`]
+//! built from it (deadzones, expo and channel reverse). Switching resolves and
+//! validates the new profile completely before replacing the active one, so
+//! the control loop never sees a half-applied profile.
+//!
+//! ## Switching Gesture
+//!
+//! While disarmed (arm channel off), hold **Options** and press:
+//!
+//! | Button | Action |
+//! |--------|--------|
+//! | D-Pad Right (→) | Next model profile |
+//! | D-Pad Left (←) | Previous model profile |
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use fpv_bridge::config::Config;
+//! use fpv_bridge::controller::profile::{ProfileManager, ProfileStep};
+//!
+//! let config = Config::load("config/default.toml")?;
+//! let mut profiles = ProfileManager::new(config, Some("whoop"))?;
+//! profiles.step(ProfileStep::Next)?;
+//! println!("Flying: {}", profiles.active().name);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use tracing::info;
+
+use super::calibration::AxisCalibration;
+use super::channel_mapper::ChannelMapper;
+use super::mapper::{ControllerState, DPAD_NEGATIVE, DPAD_POSITIVE, DPAD_RELEASED};
+use crate::config::Config;
+use crate::error::Result;
+
+/// Name reported for the base configuration when no model profile is selected.
+pub const BASE_PROFILE_NAME: &str = "base";
+
+/// Direction to move through the model profiles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileStep {
+    /// Next profile in sorted name order (wraps around).
+    Next,
+    /// Previous profile in sorted name order (wraps around).
+    Previous,
+}
+
+/// A fully resolved model profile, ready for the control loop.
+#[derive(Debug, Clone)]
+pub struct ActiveProfile {
+    /// Profile name ([`BASE_PROFILE_NAME`] for the base configuration).
+    pub name: String,
+    /// Flat configuration with the profile's overrides applied.
+    pub config: Config,
+    /// Channel mapper built from the profile's controller and channel settings.
+    pub channel_mapper: ChannelMapper,
+}
+
+impl ActiveProfile {
+    /// Builds the runtime profile from a resolved configuration.
+    #[must_use]
+    pub fn from_config(name: &str, config: Config) -> Self {
+        let controller = &config.controller;
+        let calibration = AxisCalibration::from_config(
+            controller.deadzone_stick,
+            controller.deadzone_trigger,
+            controller.expo_roll,
+            controller.expo_pitch,
+            controller.expo_yaw,
+            controller.expo_throttle,
+        );
+        let channel_mapper =
+            ChannelMapper::with_reversed(&config.channels.channel_reverse).with_calibration(calibration);
+
+        Self {
+            name: name.to_string(),
+            config,
+            channel_mapper,
+        }
+    }
+
+    /// ExpressLRS model match ID of this profile, if configured.
+    #[must_use]
+    pub fn model_id(&self) -> Option<u8> {
+        self.config.crsf.model_id
+    }
+}
+
+/// Holds the configured model profiles and the currently active one.
+#[derive(Debug)]
+pub struct ProfileManager {
+    /// Base configuration including all `[models.<name>]` profiles.
+    config: Config,
+    /// Currently active profile.
+    active: ActiveProfile,
+}
+
+impl ProfileManager {
+    /// Creates a profile manager, optionally starting with a named profile.
+    ///
+    /// Without `initial`, the base configuration is active until a profile is
+    /// selected.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if `initial` names an unknown or invalid profile.
+    pub fn new(config: Config, initial: Option<&str>) -> Result<Self> {
+        let active = match initial {
+            Some(name) => ActiveProfile::from_config(name, config.for_model(name)?),
+            None => {
+                let mut base = config.clone();
+                base.models.clear();
+                ActiveProfile::from_config(BASE_PROFILE_NAME, base)
+            }
+        };
+
+        info!("Active model profile: {}", active.name);
+        Ok(Self { config, active })
+    }
+
+    /// Returns the active profile.
+    #[must_use]
+    pub fn active(&self) -> &ActiveProfile {
+        &self.active
+    }
+
+    /// Returns `true` if any `[models.<name>]` profiles are configured.
+    #[must_use]
+    pub fn has_profiles(&self) -> bool {
+        !self.config.models.is_empty()
+    }
+
+    /// Switches to the named profile.
+    ///
+    /// The new profile is resolved and validated before it replaces the active
+    /// one; on error the active profile is unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the profile is unknown or invalid.
+    pub fn select(&mut self, name: &str) -> Result<&ActiveProfile> {
+        let next = ActiveProfile::from_config(name, self.config.for_model(name)?);
+        let previous = std::mem::replace(&mut self.active, next);
+
+        info!(
+            "Switched model profile: {} -> {} (model ID: {:?})",
+            previous.name,
+            self.active.name,
+            self.active.model_id()
+        );
+        Ok(&self.active)
+    }
+
+    /// Switches to the next or previous profile in sorted name order.
+    ///
+    /// From the base configuration, `Next` selects the first profile and
+    /// `Previous` the last one.
+    ///
+    /// # Returns
+    ///
+    /// The new active profile, or `None` if no profiles are configured.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the target profile is invalid.
+    pub fn step(&mut self, step: ProfileStep) -> Result<Option<&ActiveProfile>> {
+        let names: Vec<&str> = self.config.model_names().collect();
+        if names.is_empty() {
+            return Ok(None);
+        }
+
+        let current = names.iter().position(|&name| name == self.active.name);
+        let index = match (current, step) {
+            (Some(i), ProfileStep::Next) => (i + 1) % names.len(),
+            (Some(i), ProfileStep::Previous) => (i + names.len() - 1) % names.len(),
+            (None, ProfileStep::Next) => 0,
+            (None, ProfileStep::Previous) => names.len() - 1,
+        };
+
+        let name = names[index].to_string();
+        self.select(&name).map(Some)
+    }
+}
+
+/// Detects the profile switching gesture (Options + D-Pad Left/Right).
+///
+/// Only fires on the D-Pad press edge, and only while disarmed. Arming is
+/// judged from the arm channel as sent, not from L1, so a reversed arm
+/// channel or a latched remote disarm is taken into account.
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::controller::mapper::ControllerState;
+/// use fpv_bridge::controller::profile::{ProfileGesture, ProfileStep};
+///
+/// let mut gesture = ProfileGesture::new();
+/// let mut state = ControllerState::default();
+/// state.btn_options = true;
+/// state.dpad_x = 1;
+///
+/// assert_eq!(gesture.update(&state, false), Some(ProfileStep::Next));
+/// assert_eq!(gesture.update(&state, false), None); // Still held, no repeat
+/// ```
+#[derive(Debug, Default)]
+pub struct ProfileGesture {
+    last_dpad_x: i32,
+}
+
+impl ProfileGesture {
+    /// Creates a new gesture detector.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds the latest controller state, returning a step when the gesture fires.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - Latest controller state
+    /// * `armed` - Whether the arm channel last sent to the drone is on
+    pub fn update(&mut self, state: &ControllerState, armed: bool) -> Option<ProfileStep> {
+        let previous = std::mem::replace(&mut self.last_dpad_x, state.dpad_x);
+
+        if previous != DPAD_RELEASED || !state.btn_options || armed {
+            return None;
+        }
+
+        match state.dpad_x {
+            DPAD_POSITIVE => Some(ProfileStep::Next),
+            DPAD_NEGATIVE => Some(ProfileStep::Previous),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use super::*;
+    use crate::controller::channel_mapper::channels;
+    use crate::controller::mapper::AXIS_MAX;
+    use crate::crsf::protocol::CRSF_CHANNEL_VALUE_CENTER;
+
+    fn config_with_models() -> Config {
+        let toml_content = r#"
+[serial]
+[controller]
+[channels]
+[telemetry]
+[safety]
+[crsf]
+
+[models.cinewhoop]
+model_id = 3
+
+[models.freestyle]
+model_id = 1
+
+[models.whoop]
+model_id = 2
+[models.whoop.channels]
+channel_reverse = [1]
+"#;
+        toml::from_str(toml_content).unwrap()
+    }
+
+    fn gesture_state(dpad_x: i32) -> ControllerState {
+        let mut state = ControllerState::default();
+        state.btn_options = true;
+        state.dpad_x = dpad_x;
+        state
+    }
+
+    #[test]
+    fn test_new_without_initial_uses_base() {
+        let profiles = ProfileManager::new(config_with_models(), None).unwrap();
+
+        assert_eq!(profiles.active().name, BASE_PROFILE_NAME);
+        assert_eq!(profiles.active().model_id(), None);
+        assert!(profiles.has_profiles());
+    }
+
+    #[test]
+    fn test_new_with_initial_profile() {
+        let profiles = ProfileManager::new(config_with_models(), Some("whoop")).unwrap();
+
+        assert_eq!(profiles.active().name, "whoop");
+        assert_eq!(profiles.active().model_id(), Some(2));
+    }
+
+    #[test]
+    fn test_new_with_unknown_profile_fails() {
+        assert!(ProfileManager::new(config_with_models(), Some("tinywhoop")).is_err());
+    }
+
+    #[test]
+    fn test_select_applies_channel_mapper() {
+        let mut profiles = ProfileManager::new(config_with_models(), None).unwrap();
+        let mut state = ControllerState::default();
+        state.right_stick_x = AXIS_MAX;
+
+        assert_eq!(profiles.active().channel_mapper.map_to_channels(&state)[channels::ROLL], 2047);
+
+        profiles.select("whoop").unwrap();
+        assert_eq!(profiles.active().channel_mapper.map_to_channels(&state)[channels::ROLL], 0);
+    }
+
+    #[test]
+    fn test_select_unknown_keeps_active() {
+        let mut profiles = ProfileManager::new(config_with_models(), Some("freestyle")).unwrap();
+
+        assert!(profiles.select("tinywhoop").is_err());
+        assert_eq!(profiles.active().name, "freestyle");
+    }
+
+    #[test]
+    fn test_step_cycles_in_sorted_order() {
+        let mut profiles = ProfileManager::new(config_with_models(), None).unwrap();
+
+        let names: Vec<String> = (0..4)
+            .map(|_| profiles.step(ProfileStep::Next).unwrap().unwrap().name.clone())
+            .collect();
+        assert_eq!(names, vec!["cinewhoop", "freestyle", "whoop", "cinewhoop"]);
+
+        let previous = profiles.step(ProfileStep::Previous).unwrap().unwrap();
+        assert_eq!(previous.name, "whoop");
+    }
+
+    #[test]
+    fn test_step_previous_from_base_selects_last() {
+        let mut profiles = ProfileManager::new(config_with_models(), None).unwrap();
+
+        let profile = profiles.step(ProfileStep::Previous).unwrap().unwrap();
+        assert_eq!(profile.name, "whoop");
+    }
+
+    #[test]
+    fn test_step_without_profiles() {
+        let mut config = config_with_models();
+        config.models.clear();
+        let mut profiles = ProfileManager::new(config, None).unwrap();
+
+        assert!(!profiles.has_profiles());
+        assert!(profiles.step(ProfileStep::Next).unwrap().is_none());
+        assert_eq!(profiles.active().name, BASE_PROFILE_NAME);
+    }
+
+    #[test]
+    fn test_gesture_next_and_previous() {
+        let mut gesture = ProfileGesture::new();
+
+        assert_eq!(gesture.update(&gesture_state(DPAD_POSITIVE), false), Some(ProfileStep::Next));
+        assert_eq!(gesture.update(&gesture_state(DPAD_RELEASED), false), None);
+        assert_eq!(gesture.update(&gesture_state(DPAD_NEGATIVE), false), Some(ProfileStep::Previous));
+    }
+
+    #[test]
+    fn test_gesture_fires_once_per_press() {
+        let mut gesture = ProfileGesture::new();
+
+        assert!(gesture.update(&gesture_state(DPAD_POSITIVE), false).is_some());
+        assert!(gesture.update(&gesture_state(DPAD_POSITIVE), false).is_none());
+        assert!(gesture.update(&gesture_state(DPAD_POSITIVE), false).is_none());
+    }
+
+    #[test]
+    fn test_gesture_requires_options() {
+        let mut gesture = ProfileGesture::new();
+        let mut state = gesture_state(DPAD_POSITIVE);
+        state.btn_options = false;
+
+        assert_eq!(gesture.update(&state, false), None);
+    }
+
+    #[test]
+    fn test_gesture_ignored_while_armed() {
+        let mut gesture = ProfileGesture::new();
+
+        assert_eq!(gesture.update(&gesture_state(DPAD_POSITIVE), true), None);
+    }
+
+    #[test]
+    fn test_gesture_follows_arm_channel_not_l1() {
+        // Reversed arm channel: L1 released means armed
+        let mapper = ChannelMapper::with_reversed(&[channels::ARM + 1]);
+        let mut gesture = ProfileGesture::new();
+        let state = gesture_state(DPAD_POSITIVE);
+        let armed = mapper.map_to_channels(&state)[channels::ARM] > CRSF_CHANNEL_VALUE_CENTER;
+
+        assert!(!state.btn_l1);
+        assert_eq!(gesture.update(&state, armed), None);
+    }
+
+    #[test]
+    fn test_gesture_dpad_held_before_options() {
+        let mut gesture = ProfileGesture::new();
+        let mut state = gesture_state(DPAD_POSITIVE);
+        state.btn_options = false;
+        gesture.update(&state, false);
+
+        // Pressing Options while D-Pad is already held must not trigger
+        state.btn_options = true;
+        assert_eq!(gesture.update(&state, false), None);
+    }
+}
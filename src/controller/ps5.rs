@@ -50,6 +50,61 @@ const DUALSENSE_VENDOR_ID: u16 = 0x054c;
 /// PS5 DualSense product ID (wired and Bluetooth)
 const DUALSENSE_PRODUCT_ID: u16 = 0x0ce6;
 
+/// Sysfs directory the kernel's `hid-playstation` driver reports the
+/// controller battery in
+const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+
+/// Power supply name prefix of DualSense batteries
+/// (`ps-controller-battery-<MAC>`)
+const BATTERY_PREFIX: &str = "ps-controller-battery-";
+
+/// DualSense battery state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ControllerBattery {
+    /// Charge level (0-100%)
+    pub percent: u8,
+    /// Whether the controller is charging over USB
+    pub charging: bool,
+}
+
+impl std::fmt::Display for ControllerBattery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}%", self.percent)?;
+        if self.charging {
+            write!(f, " (charging)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Read the battery of the first connected DualSense
+///
+/// # Returns
+///
+/// * `Option<ControllerBattery>` - Battery state, or `None` if no DualSense
+///   battery is reported (no controller, or a kernel without
+///   `hid-playstation`)
+pub fn read_battery() -> Option<ControllerBattery> {
+    read_battery_from(Path::new(POWER_SUPPLY_DIR))
+}
+
+/// Read the battery from a `power_supply` class directory
+fn read_battery_from(dir: &Path) -> Option<ControllerBattery> {
+    let mut batteries: Vec<_> = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(BATTERY_PREFIX))
+        .map(|entry| entry.path())
+        .collect();
+    batteries.sort();
+
+    let battery = batteries.first()?;
+    let percent = std::fs::read_to_string(battery.join("capacity")).ok()?.trim().parse().ok()?;
+    let charging = std::fs::read_to_string(battery.join("status"))
+        .is_ok_and(|status| status.trim() == "Charging");
+    Some(ControllerBattery { percent, charging })
+}
+
 /// PS5 DualSense controller handle
 ///
 /// Represents an active connection to a PS5 DualSense controller via evdev.
@@ -159,11 +214,10 @@ impl DualSenseController {
         // Provide helpful error message if all failures were permission denied
         if event_devices_checked > 0 && permission_denied_count == event_devices_checked {
             return Err(FpvBridgeError::Controller(
-                format!(
-                    "Permission denied accessing /dev/input/event* devices. \
-                    See module documentation for setup instructions: \
-                    add user to 'input' group or configure udev rules."
-                )
+                "Permission denied accessing /dev/input/event* devices. \
+                See module documentation for setup instructions: \
+                add user to 'input' group or configure udev rules."
+                    .to_string(),
             ));
         }
 
@@ -374,8 +428,31 @@ mod tests {
             "DualSense product ID must be 0x0ce6 for both wired and Bluetooth");
 
         // Verify IDs are non-zero (sanity check)
-        assert!(DUALSENSE_VENDOR_ID > 0, "Vendor ID must be non-zero");
-        assert!(DUALSENSE_PRODUCT_ID > 0, "Product ID must be non-zero");
+        const { assert!(DUALSENSE_VENDOR_ID > 0, "Vendor ID must be non-zero") };
+        const { assert!(DUALSENSE_PRODUCT_ID > 0, "Product ID must be non-zero") };
+    }
+
+    #[test]
+    fn test_read_battery_from_sysfs() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(read_battery_from(dir.path()), None);
+
+        std::fs::create_dir(dir.path().join("BAT0")).unwrap();
+        let battery = dir.path().join("ps-controller-battery-a0:ab:51:12:34:56");
+        std::fs::create_dir(&battery).unwrap();
+        std::fs::write(battery.join("capacity"), "70\n").unwrap();
+        std::fs::write(battery.join("status"), "Discharging\n").unwrap();
+        assert_eq!(read_battery_from(dir.path()), Some(ControllerBattery { percent: 70, charging: false }));
+
+        std::fs::write(battery.join("status"), "Charging\n").unwrap();
+        let state = read_battery_from(dir.path()).unwrap();
+        assert!(state.charging);
+        assert_eq!(state.to_string(), "70% (charging)");
+    }
+
+    #[test]
+    fn test_read_battery_missing_dir() {
+        assert_eq!(read_battery_from(Path::new("/nonexistent/power_supply")), None);
     }
 
     // Integration test - only runs with real hardware
@@ -403,8 +480,8 @@ mod tests {
         // Try to read events over 5 seconds (100 iterations * 50ms)
         for _ in 0..100 {
             match controller.fetch_events() {
-                Ok(events) => {
-                    for event in events {
+                Ok(mut events) => {
+                    if let Some(event) = events.next() {
                         println!("Received event: {:?}", event);
                         return; // Test passed if we got at least one event
                     }
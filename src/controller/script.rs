@@ -0,0 +1,445 @@
+//! # Scripted Controller Input
+//!
+//! Replays a timeline of controller events, for integration tests and
+//! hardware-free runs (`--script FILE`).
+//!
+//! ## Script Format
+//!
+//! One event per line: time, evdev code and value. Times are milliseconds
+//! from the start of playback, or `+N` for N milliseconds after the previous
+//! line. Codes are the evdev names listed in [`mapper`](super::mapper)
+//! (`ABS_Y` is the throttle stick, `BTN_TL` is L1). `#` starts a comment.
+//!
+//! ```text
+//! # Hold L1 for 1.2s with throttle down, then push throttle
+//! 0      ABS_Y   255    # throttle stick fully down
+//! 0      BTN_TL  1      # L1 pressed (arm)
+//! +1200  ABS_Y   0      # full throttle
+//! +500   ABS_Y   255
+//! +0     BTN_TL  0
+//! ```
+//!
+//! Events with the same time are delivered together. After the last one
+//! the input is held as it is.
+//!
+//! [`VirtualDualSense`] plays a script through a `uinput` device that looks
+//! like a DualSense, for exercising the real evdev input path.
+
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use evdev::uinput::{VirtualDevice, VirtualDeviceBuilder};
+use evdev::{AbsInfo, AbsoluteAxisType, AttributeSet, BusType, EventType, InputEvent, InputId, Key, UinputAbsSetup};
+
+use super::input::InputSource;
+use super::mapper::{AXIS_CENTER, AXIS_MAX, AXIS_MIN, DPAD_NEGATIVE, DPAD_POSITIVE, DPAD_RELEASED};
+use crate::error::{FpvBridgeError, Result};
+
+/// One scheduled input event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScriptStep {
+    /// Time from the start of playback
+    pub at: Duration,
+    /// Event type (`EventType::ABSOLUTE` or `EventType::KEY`)
+    pub event_type: EventType,
+    /// Axis or key code
+    pub code: u16,
+    /// Axis value, or 1/0 for pressed/released
+    pub value: i32,
+}
+
+/// Timeline of controller events
+///
+/// # Examples
+///
+/// ```
+/// use evdev::{AbsoluteAxisType, Key};
+/// use fpv_bridge::controller::script::InputScript;
+/// use std::time::Duration;
+///
+/// let script = InputScript::new()
+///     .axis(Duration::ZERO, AbsoluteAxisType::ABS_Y, 255)
+///     .button(Duration::ZERO, Key::BTN_TL, true)
+///     .axis(Duration::from_millis(1200), AbsoluteAxisType::ABS_Y, 0);
+///
+/// let parsed: InputScript = "0 ABS_Y 255\n0 BTN_TL 1\n+1200 ABS_Y 0".parse().unwrap();
+/// assert_eq!(parsed, script);
+/// assert_eq!(script.duration(), Duration::from_millis(1200));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InputScript {
+    /// Steps in playback order
+    steps: Vec<ScriptStep>,
+}
+
+impl InputScript {
+    /// Create an empty script
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a script file
+    ///
+    /// # Errors
+    ///
+    /// Returns `Io` error if the file cannot be read, or `Controller` error
+    /// naming the first malformed line.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Self::parse(&text).map_err(|e| FpvBridgeError::Controller(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Add an axis movement at `at`
+    #[must_use]
+    pub fn axis(self, at: Duration, axis: AbsoluteAxisType, value: i32) -> Self {
+        self.step(ScriptStep { at, event_type: EventType::ABSOLUTE, code: axis.0, value })
+    }
+
+    /// Add a button press (`true`) or release at `at`
+    #[must_use]
+    pub fn button(self, at: Duration, key: Key, pressed: bool) -> Self {
+        self.step(ScriptStep { at, event_type: EventType::KEY, code: key.code(), value: i32::from(pressed) })
+    }
+
+    /// Add a step, after any other steps at the same time
+    #[must_use]
+    pub fn step(mut self, step: ScriptStep) -> Self {
+        let index = self.steps.partition_point(|other| other.at <= step.at);
+        self.steps.insert(index, step);
+        self
+    }
+
+    /// Steps in playback order
+    pub fn steps(&self) -> &[ScriptStep] {
+        &self.steps
+    }
+
+    /// Time of the last step
+    pub fn duration(&self) -> Duration {
+        self.steps.last().map_or(Duration::ZERO, |step| step.at)
+    }
+
+    /// Parse script text, naming the first malformed line in the error
+    fn parse(text: &str) -> std::result::Result<Self, String> {
+        let mut script = Self::new();
+        let mut previous = Duration::ZERO;
+
+        for (number, line) in text.lines().enumerate() {
+            let invalid = |reason: String| format!("line {}: {}", number + 1, reason);
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [time, code, value] = fields[..] else {
+                return Err(invalid(format!("expected 'TIME CODE VALUE', got '{}'", line)));
+            };
+
+            let at = match time.strip_prefix('+') {
+                Some(delay) => delay.parse().map(|ms| previous + Duration::from_millis(ms)),
+                None => time.parse().map(Duration::from_millis),
+            }
+            .map_err(|_| invalid(format!("invalid time '{}'", time)))?;
+            if at < previous {
+                return Err(invalid(format!("time {}ms is before the previous line", at.as_millis())));
+            }
+            let value: i32 = value.parse().map_err(|_| invalid(format!("invalid value '{}'", value)))?;
+
+            script = if let Ok(axis) = AbsoluteAxisType::from_str(code) {
+                script.axis(at, axis, value)
+            } else if let Ok(key) = Key::from_str(code) {
+                if value != 0 && value != 1 {
+                    return Err(invalid(format!("button value must be 0 or 1, got {}", value)));
+                }
+                script.button(at, key, value == 1)
+            } else {
+                return Err(invalid(format!("unknown evdev code '{}' (e.g. ABS_Y, BTN_TL)", code)));
+            };
+            previous = at;
+        }
+
+        Ok(script)
+    }
+}
+
+impl FromStr for InputScript {
+    type Err = FpvBridgeError;
+
+    fn from_str(text: &str) -> Result<Self> {
+        Self::parse(text).map_err(FpvBridgeError::Controller)
+    }
+}
+
+/// Input source replaying an [`InputScript`] in real time
+///
+/// Playback starts at the first [`fetch_events`](InputSource::fetch_events)
+/// call. Events are timestamped when they are delivered.
+#[derive(Debug)]
+pub struct ScriptedController {
+    script: InputScript,
+    name: String,
+    /// Index of the next step to deliver
+    next: usize,
+    start: Option<Instant>,
+}
+
+impl ScriptedController {
+    /// Create a player for `script`, named `name` in logs
+    pub fn new(script: InputScript, name: &str) -> Self {
+        Self {
+            script,
+            name: name.to_string(),
+            next: 0,
+            start: None,
+        }
+    }
+
+    /// Take the steps due `elapsed` after the start of playback
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<InputEvent>` - Events not delivered before, in script order
+    pub fn due_events(&mut self, elapsed: Duration) -> Vec<InputEvent> {
+        let due = self.script.steps[self.next..].iter().take_while(|step| step.at <= elapsed).count();
+        let events = self.script.steps[self.next..self.next + due]
+            .iter()
+            .map(|step| InputEvent::new_now(step.event_type, step.code, step.value))
+            .collect();
+        self.next += due;
+        events
+    }
+
+    /// Time of the next undelivered step, `None` at the end of the script
+    pub fn next_due(&self) -> Option<Duration> {
+        self.script.steps.get(self.next).map(|step| step.at)
+    }
+}
+
+impl InputSource for ScriptedController {
+    fn fetch_events(&mut self) -> Result<Option<Vec<InputEvent>>> {
+        let start = *self.start.get_or_insert_with(Instant::now);
+        let Some(at) = self.next_due() else {
+            return Ok(None);
+        };
+
+        if let Some(wait) = at.checked_sub(start.elapsed()) {
+            std::thread::sleep(wait);
+        }
+        Ok(Some(self.due_events(start.elapsed())))
+    }
+
+    fn describe(&self) -> String {
+        format!("script:{}", self.name)
+    }
+}
+
+/// `uinput` device posing as a PS5 DualSense controller
+///
+/// It has the DualSense vendor and product IDs, axes and buttons, so
+/// [`DualSenseController::open`](super::ps5::DualSenseController::open)
+/// finds it like a real controller.
+pub struct VirtualDualSense {
+    device: VirtualDevice,
+}
+
+impl VirtualDualSense {
+    /// Create the virtual controller
+    ///
+    /// # Errors
+    ///
+    /// Returns `Controller` error if `/dev/uinput` cannot be opened or the
+    /// device cannot be created.
+    pub fn create() -> Result<Self> {
+        let error = |e: std::io::Error| FpvBridgeError::Controller(format!("Virtual DualSense (/dev/uinput): {}", e));
+
+        let stick = AbsInfo::new(AXIS_CENTER, AXIS_MIN, AXIS_MAX, 0, 0, 0);
+        let trigger = AbsInfo::new(AXIS_MIN, AXIS_MIN, AXIS_MAX, 0, 0, 0);
+        let dpad = AbsInfo::new(DPAD_RELEASED, DPAD_NEGATIVE, DPAD_POSITIVE, 0, 0, 0);
+        let axes = [
+            (AbsoluteAxisType::ABS_X, stick),
+            (AbsoluteAxisType::ABS_Y, stick),
+            (AbsoluteAxisType::ABS_Z, stick),
+            (AbsoluteAxisType::ABS_RZ, stick),
+            (AbsoluteAxisType::ABS_RX, trigger),
+            (AbsoluteAxisType::ABS_RY, trigger),
+            (AbsoluteAxisType::ABS_HAT0X, dpad),
+            (AbsoluteAxisType::ABS_HAT0Y, dpad),
+        ];
+        let mut buttons = AttributeSet::<Key>::new();
+        for button in [
+            Key::BTN_SOUTH, Key::BTN_EAST, Key::BTN_WEST, Key::BTN_NORTH,
+            Key::BTN_TL, Key::BTN_TR, Key::BTN_TL2, Key::BTN_TR2,
+            Key::BTN_SELECT, Key::BTN_START, Key::BTN_MODE,
+            Key::BTN_THUMBL, Key::BTN_THUMBR, Key::BTN_TOUCH,
+        ] {
+            buttons.insert(button);
+        }
+
+        let mut builder = VirtualDeviceBuilder::new()
+            .map_err(error)?
+            .name("FPV Bridge Virtual DualSense")
+            .input_id(InputId::new(BusType::BUS_USB, 0x054c, 0x0ce6, 0x8111));
+        for (axis, info) in axes {
+            builder = builder.with_absolute_axis(&UinputAbsSetup::new(axis, info)).map_err(error)?;
+        }
+        let device = builder.with_keys(&buttons).map_err(error)?.build().map_err(error)?;
+        Ok(Self { device })
+    }
+
+    /// Play `script` through the device in real time, blocking until its
+    /// last step
+    ///
+    /// # Errors
+    ///
+    /// Returns `Controller` error if an event cannot be written.
+    pub fn play(&mut self, script: InputScript) -> Result<()> {
+        let mut player = ScriptedController::new(script, "uinput");
+        while let Some(events) = player.fetch_events()? {
+            self.device
+                .emit(&events)
+                .map_err(|e| FpvBridgeError::Controller(format!("Virtual DualSense: {}", e)))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::channel_mapper::{channels, ChannelMapper, SWITCH_OFF, SWITCH_ON};
+    use crate::controller::mapper::EventMapper;
+    use crate::crsf::decoder::decode_rc_channels_frame;
+    use crate::crsf::encoder::encode_rc_channels_frame;
+
+    const ARM_SCRIPT: &str = "\
+# Hold L1 for 1.2s with throttle down, then push throttle
+0      ABS_Y   255    # throttle stick fully down
+0      BTN_TL  1
++1200  ABS_Y   0      # full throttle
++500   ABS_Y   255
++0     BTN_TL  0
+";
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_parse_script() {
+        let script: InputScript = ARM_SCRIPT.parse().unwrap();
+        let steps = script.steps();
+
+        assert_eq!(steps.len(), 5);
+        assert_eq!(steps[0], ScriptStep { at: ms(0), event_type: EventType::ABSOLUTE, code: 1, value: 255 });
+        assert_eq!(steps[1].code, Key::BTN_TL.code());
+        assert_eq!(steps[2].at, ms(1200));
+        assert_eq!(steps[4].at, ms(1700));
+        assert_eq!(script.duration(), ms(1700));
+    }
+
+    #[test]
+    fn test_parse_invalid_scripts() {
+        for (text, expected) in [
+            ("0 ABS_Y", "line 1: expected 'TIME CODE VALUE'"),
+            ("# comment\nsoon ABS_Y 0", "line 2: invalid time"),
+            ("0 ABS_NOPE 0", "unknown evdev code 'ABS_NOPE'"),
+            ("0 BTN_TL 2", "button value must be 0 or 1"),
+            ("0 ABS_Y x", "invalid value 'x'"),
+            ("500 ABS_Y 0\n100 ABS_Y 255", "line 2: time 100ms is before"),
+        ] {
+            let err = text.parse::<InputScript>().unwrap_err().to_string();
+            assert!(err.contains(expected), "{}: {}", text, err);
+        }
+    }
+
+    #[test]
+    fn test_load_script_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("arm.script");
+        std::fs::write(&path, ARM_SCRIPT).unwrap();
+        assert_eq!(InputScript::load(&path).unwrap().steps().len(), 5);
+
+        std::fs::write(&path, "0 ABS_Y\n").unwrap();
+        let err = InputScript::load(&path).unwrap_err().to_string();
+        assert!(err.contains("arm.script: line 1"), "{}", err);
+    }
+
+    #[test]
+    fn test_builder_keeps_time_order() {
+        let script = InputScript::new()
+            .axis(ms(100), AbsoluteAxisType::ABS_X, 0)
+            .button(ms(0), Key::BTN_TR, true)
+            .axis(ms(100), AbsoluteAxisType::ABS_X, 255);
+
+        let values: Vec<_> = script.steps().iter().map(|step| (step.at, step.value)).collect();
+        assert_eq!(values, [(ms(0), 1), (ms(100), 0), (ms(100), 255)]);
+    }
+
+    #[test]
+    fn test_due_events_delivers_each_step_once() {
+        let mut player = ScriptedController::new(ARM_SCRIPT.parse().unwrap(), "arm");
+        assert_eq!(player.describe(), "script:arm");
+
+        assert_eq!(player.due_events(ms(0)).len(), 2);
+        assert!(player.due_events(ms(1000)).is_empty());
+        assert_eq!(player.next_due(), Some(ms(1200)));
+
+        // Late polls get everything that became due in between
+        assert_eq!(player.due_events(ms(5000)).len(), 3);
+        assert_eq!(player.next_due(), None);
+    }
+
+    #[test]
+    fn test_fetch_events_plays_in_real_time() {
+        let script = InputScript::new()
+            .button(ms(0), Key::BTN_TL, true)
+            .button(ms(20), Key::BTN_TL, false);
+        let mut player = ScriptedController::new(script, "short");
+
+        let start = Instant::now();
+        assert_eq!(player.fetch_events().unwrap().unwrap().len(), 1);
+        assert_eq!(player.fetch_events().unwrap().unwrap()[0].value(), 0);
+        assert!(start.elapsed() >= ms(20));
+        assert!(player.fetch_events().unwrap().is_none());
+    }
+
+    /// Channels sent at each 4ms tick while the script plays
+    fn frame_stream(script: InputScript, until: Duration) -> Vec<(Duration, [u16; 16])> {
+        let mut player = ScriptedController::new(script, "test");
+        let mut mapper = EventMapper::new();
+        let channel_mapper = ChannelMapper::new();
+
+        let mut frames = Vec::new();
+        let mut now = Duration::ZERO;
+        while now <= until {
+            for event in player.due_events(now) {
+                mapper.process_event(&event);
+            }
+            let frame = encode_rc_channels_frame(&channel_mapper.map_to_channels(mapper.state()));
+            frames.push((now, decode_rc_channels_frame(&frame).unwrap()));
+            now += ms(4);
+        }
+        frames
+    }
+
+    #[test]
+    fn test_script_drives_crsf_frame_stream() {
+        let frames = frame_stream(ARM_SCRIPT.parse().unwrap(), ms(2000));
+        let at = |t: u64| frames.iter().find(|(time, _)| *time >= ms(t)).unwrap().1;
+
+        // Armed with throttle low while L1 is held
+        assert_eq!(at(0)[channels::ARM], SWITCH_ON);
+        assert!(at(1000)[channels::THROTTLE] < 200);
+
+        // Throttle pushed after 1.2s, still armed
+        assert!(at(1200)[channels::THROTTLE] > 1800);
+        assert_eq!(at(1200)[channels::ARM], SWITCH_ON);
+
+        // Throttle down and disarmed at the end, and held
+        assert!(at(1700)[channels::THROTTLE] < 200);
+        assert_eq!(at(1700)[channels::ARM], SWITCH_OFF);
+        assert_eq!(at(2000), at(1700));
+    }
+}
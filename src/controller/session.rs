@@ -0,0 +1,467 @@
+//! # Input Session Recording
+//!
+//! Records every controller event with its kernel timestamp while flying
+//! (`--record FILE`), and replays recorded sessions through the
+//! [`EventMapper`] and [`ChannelMapper`] (`--replay FILE`), either in real
+//! time in place of the controller, or as fast as possible to check what a
+//! mapping configuration makes of the same input.
+//!
+//! ## File Format
+//!
+//! A 16 byte header: magic `"FBSN"`, version `0x01`, 3 reserved bytes and
+//! the session start as little-endian u64 microseconds since the Unix
+//! epoch. Then one 12 byte record per event, little-endian:
+//!
+//! | Bytes | Field                                                   |
+//! |-------|---------------------------------------------------------|
+//! | 0-3   | u32 microseconds since the previous event (or start)    |
+//! | 4-5   | u16 event type                                          |
+//! | 6-7   | u16 event code                                          |
+//! | 8-11  | i32 event value                                         |
+//!
+//! Gaps longer than `u32::MAX` microseconds (about 71 minutes) are stored
+//! as that maximum.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use evdev::{EventType, InputEvent};
+
+use super::channel_mapper::ChannelMapper;
+use super::input::InputSource;
+use super::mapper::EventMapper;
+use super::script::{InputScript, ScriptStep};
+use crate::crsf::protocol::RcChannels;
+use crate::error::{FpvBridgeError, Result};
+
+/// Magic bytes opening a session file
+pub const SESSION_MAGIC: [u8; 4] = *b"FBSN";
+
+/// Session file format version
+pub const SESSION_VERSION: u8 = 0x01;
+
+/// Size of the file header in bytes
+pub const SESSION_HEADER_LEN: usize = 16;
+
+/// Size of one event record in bytes
+pub const SESSION_RECORD_LEN: usize = 12;
+
+/// Writes controller events to a session file
+#[derive(Debug)]
+pub struct SessionWriter<W: Write> {
+    writer: W,
+    /// Time of the last recorded event (session start before the first)
+    last: SystemTime,
+    events: u64,
+}
+
+impl SessionWriter<BufWriter<File>> {
+    /// Create a session file starting now
+    ///
+    /// # Errors
+    ///
+    /// Returns `Io` error if the file cannot be created or written.
+    pub fn create(path: &Path) -> Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?), SystemTime::now())
+    }
+}
+
+impl<W: Write> SessionWriter<W> {
+    /// Start a session on `writer`, writing the header
+    ///
+    /// # Errors
+    ///
+    /// Returns `Io` error if the header cannot be written.
+    pub fn new(mut writer: W, started: SystemTime) -> Result<Self> {
+        let mut header = [0u8; SESSION_HEADER_LEN];
+        header[..4].copy_from_slice(&SESSION_MAGIC);
+        header[4] = SESSION_VERSION;
+        header[8..].copy_from_slice(&micros(started.duration_since(UNIX_EPOCH).unwrap_or_default()).to_le_bytes());
+        writer.write_all(&header)?;
+
+        Ok(Self { writer, last: started, events: 0 })
+    }
+
+    /// Append an event, timed by its kernel timestamp
+    ///
+    /// # Errors
+    ///
+    /// Returns `Io` error if the record cannot be written.
+    pub fn record(&mut self, event: &InputEvent) -> Result<()> {
+        self.record_at(event.timestamp(), event)
+    }
+
+    /// Append an event that happened at `timestamp`
+    ///
+    /// Events timestamped before the previous one are recorded at the same
+    /// time as it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Io` error if the record cannot be written.
+    pub fn record_at(&mut self, timestamp: SystemTime, event: &InputEvent) -> Result<()> {
+        let timestamp = timestamp.max(self.last);
+        let delay = timestamp.duration_since(self.last).unwrap_or_default();
+        let delay = u32::try_from(micros(delay)).unwrap_or(u32::MAX);
+
+        let mut record = [0u8; SESSION_RECORD_LEN];
+        record[..4].copy_from_slice(&delay.to_le_bytes());
+        record[4..6].copy_from_slice(&event.event_type().0.to_le_bytes());
+        record[6..8].copy_from_slice(&event.code().to_le_bytes());
+        record[8..].copy_from_slice(&event.value().to_le_bytes());
+        self.writer.write_all(&record)?;
+
+        self.last = timestamp;
+        self.events += 1;
+        Ok(())
+    }
+
+    /// Flush buffered records to the file
+    ///
+    /// # Errors
+    ///
+    /// Returns `Io` error if the write fails.
+    pub fn flush(&mut self) -> Result<()> {
+        Ok(self.writer.flush()?)
+    }
+
+    /// Number of events recorded
+    pub fn events(&self) -> u64 {
+        self.events
+    }
+}
+
+/// Recorded controller session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    /// Wall-clock time the recording started
+    pub started: SystemTime,
+    /// Events, timed from the session start
+    pub events: Vec<ScriptStep>,
+}
+
+impl Session {
+    /// Load a session file
+    ///
+    /// # Errors
+    ///
+    /// Returns `Io` error if the file cannot be read, or `Controller` error
+    /// if it is not a session file.
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::read(BufReader::new(File::open(path)?))
+            .map_err(|e| match e {
+                FpvBridgeError::Controller(reason) => {
+                    FpvBridgeError::Controller(format!("{}: {}", path.display(), reason))
+                }
+                other => other,
+            })
+    }
+
+    /// Read a session from `reader`
+    ///
+    /// A truncated last record (recording interrupted mid-write) is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Io` error if reading fails, or `Controller` error if the
+    /// header is not a supported session header.
+    pub fn read<R: Read>(mut reader: R) -> Result<Self> {
+        let mut header = [0u8; SESSION_HEADER_LEN];
+        reader.read_exact(&mut header).map_err(|e| match e.kind() {
+            ErrorKind::UnexpectedEof => FpvBridgeError::Controller("not a session file (too short)".to_string()),
+            _ => e.into(),
+        })?;
+        if header[..4] != SESSION_MAGIC {
+            return Err(FpvBridgeError::Controller("not a session file (bad magic)".to_string()));
+        }
+        if header[4] != SESSION_VERSION {
+            return Err(FpvBridgeError::Controller(format!("unsupported session version {}", header[4])));
+        }
+        let start_us = u64::from_le_bytes(header[8..].try_into().expect("8 byte slice"));
+        let started = UNIX_EPOCH + Duration::from_micros(start_us);
+
+        let mut events = Vec::new();
+        let mut at = Duration::ZERO;
+        let mut record = [0u8; SESSION_RECORD_LEN];
+        loop {
+            match reader.read_exact(&mut record) {
+                Ok(()) => {}
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e.into()),
+            }
+            at += Duration::from_micros(u64::from(u32::from_le_bytes(record[..4].try_into().expect("4 byte slice"))));
+            events.push(ScriptStep {
+                at,
+                event_type: EventType(u16::from_le_bytes([record[4], record[5]])),
+                code: u16::from_le_bytes([record[6], record[7]]),
+                value: i32::from_le_bytes(record[8..].try_into().expect("4 byte slice")),
+            });
+        }
+
+        Ok(Self { started, events })
+    }
+
+    /// Length of the session (time of its last event)
+    pub fn duration(&self) -> Duration {
+        self.events.last().map_or(Duration::ZERO, |event| event.at)
+    }
+
+    /// The session as an input script, for real-time replay with
+    /// [`ScriptedController`](super::script::ScriptedController)
+    pub fn to_script(&self) -> InputScript {
+        self.events.iter().fold(InputScript::new(), |script, event| script.step(*event))
+    }
+
+    /// Replay the session as fast as possible through `channel_mapper`
+    ///
+    /// Channels are sampled every `period`, as the transmit loop would send
+    /// them, from the start of the session to its last event.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<(Duration, RcChannels)>` - Time from the session start and the
+    ///   channels sent at that time
+    pub fn replay_channels(&self, channel_mapper: &ChannelMapper, period: Duration) -> Vec<(Duration, RcChannels)> {
+        let mut mapper = EventMapper::new();
+        let mut events = self.events.iter().peekable();
+        let mut frames = Vec::new();
+        let mut now = Duration::ZERO;
+
+        loop {
+            while let Some(event) = events.next_if(|event| event.at <= now) {
+                mapper.process_event(&InputEvent::new(event.event_type, event.code, event.value));
+            }
+            frames.push((now, channel_mapper.map_to_channels(mapper.state())));
+            if events.peek().is_none() || period.is_zero() {
+                break;
+            }
+            now += period;
+        }
+        frames
+    }
+}
+
+/// Microseconds in `duration`, saturating
+fn micros(duration: Duration) -> u64 {
+    u64::try_from(duration.as_micros()).unwrap_or(u64::MAX)
+}
+
+/// Input source that records everything another source delivers
+///
+/// Records are flushed after every batch, so the file is complete up to
+/// the last input even if the bridge is killed.
+pub struct RecordingSource {
+    inner: Box<dyn InputSource>,
+    writer: SessionWriter<BufWriter<File>>,
+    /// Whether a write failure was already logged
+    failed: bool,
+}
+
+impl RecordingSource {
+    /// Record `inner` into a new session file at `path`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Io` error if the file cannot be created.
+    pub fn new(inner: Box<dyn InputSource>, path: &Path) -> Result<Self> {
+        Ok(Self {
+            inner,
+            writer: SessionWriter::create(path)?,
+            failed: false,
+        })
+    }
+}
+
+impl InputSource for RecordingSource {
+    fn fetch_events(&mut self) -> Result<Option<Vec<InputEvent>>> {
+        let events = self.inner.fetch_events()?;
+
+        // A full disk must not take the controller away
+        if let Some(events) = &events {
+            let written = events
+                .iter()
+                .try_for_each(|event| self.writer.record(event))
+                .and_then(|()| self.writer.flush());
+            if let Err(e) = written {
+                if !self.failed {
+                    tracing::warn!("Session recording failed, input continues unrecorded: {}", e);
+                    self.failed = true;
+                }
+            }
+        }
+        Ok(events)
+    }
+
+    fn describe(&self) -> String {
+        self.inner.describe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::channel_mapper::{channels, SWITCH_OFF, SWITCH_ON};
+    use evdev::{AbsoluteAxisType, Key};
+
+    type TimedEvent = (SystemTime, InputEvent);
+
+    fn event_at(started: SystemTime, ms: u64, event_type: EventType, code: u16, value: i32) -> TimedEvent {
+        (started + Duration::from_millis(ms), InputEvent::new(event_type, code, value))
+    }
+
+    fn record(started: SystemTime, events: &[TimedEvent]) -> Vec<u8> {
+        let mut writer = SessionWriter::new(Vec::new(), started).unwrap();
+        for (timestamp, event) in events {
+            writer.record_at(*timestamp, event).unwrap();
+        }
+        assert_eq!(writer.events(), events.len() as u64);
+        writer.writer
+    }
+
+    fn arm_session(started: SystemTime) -> Vec<TimedEvent> {
+        let abs = EventType::ABSOLUTE;
+        let key = EventType::KEY;
+        vec![
+            event_at(started, 10, abs, AbsoluteAxisType::ABS_Y.0, 255),
+            event_at(started, 10, key, Key::BTN_TL.code(), 1),
+            event_at(started, 1210, abs, AbsoluteAxisType::ABS_Y.0, 0),
+            event_at(started, 1710, key, Key::BTN_TL.code(), 0),
+        ]
+    }
+
+    #[test]
+    fn test_record_and_read_round_trip() {
+        let started = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let bytes = record(started, &arm_session(started));
+        assert_eq!(bytes.len(), SESSION_HEADER_LEN + 4 * SESSION_RECORD_LEN);
+        assert_eq!(&bytes[..5], b"FBSN\x01");
+
+        let session = Session::read(bytes.as_slice()).unwrap();
+        assert_eq!(session.started, started);
+        assert_eq!(session.events.len(), 4);
+        assert_eq!(
+            session.events[0],
+            ScriptStep { at: Duration::from_millis(10), event_type: EventType::ABSOLUTE, code: 1, value: 255 }
+        );
+        assert_eq!(session.events[2].at, Duration::from_millis(1210));
+        assert_eq!(session.duration(), Duration::from_millis(1710));
+    }
+
+    #[test]
+    fn test_out_of_order_timestamps_are_clamped() {
+        let started = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let events = [
+            event_at(started, 100, EventType::KEY, Key::BTN_TR.code(), 1),
+            event_at(started, 50, EventType::KEY, Key::BTN_TR.code(), 0),
+        ];
+        let session = Session::read(record(started, &events).as_slice()).unwrap();
+        assert_eq!(session.events[1].at, Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_truncated_record_is_dropped() {
+        let started = UNIX_EPOCH;
+        let mut bytes = record(started, &arm_session(started));
+        bytes.truncate(bytes.len() - 5);
+        assert_eq!(Session::read(bytes.as_slice()).unwrap().events.len(), 3);
+    }
+
+    #[test]
+    fn test_invalid_session_files() {
+        assert!(Session::read(&b"FBSN"[..]).unwrap_err().to_string().contains("too short"));
+        assert!(Session::read(&[0u8; 16][..]).unwrap_err().to_string().contains("bad magic"));
+
+        let mut header = [0u8; SESSION_HEADER_LEN];
+        header[..4].copy_from_slice(&SESSION_MAGIC);
+        header[4] = 9;
+        assert!(Session::read(&header[..]).unwrap_err().to_string().contains("version 9"));
+    }
+
+    #[test]
+    fn test_load_names_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("flight.session");
+        std::fs::write(&path, b"nope").unwrap();
+        let err = Session::load(&path).unwrap_err().to_string();
+        assert!(err.contains("flight.session: not a session file"), "{}", err);
+    }
+
+    #[test]
+    fn test_replay_channels_as_fast_as_possible() {
+        let started = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let session = Session::read(record(started, &arm_session(started)).as_slice()).unwrap();
+        let frames = session.replay_channels(&ChannelMapper::new(), Duration::from_millis(4));
+
+        // One frame per 4ms up to the last event
+        assert_eq!(frames.first().unwrap().0, Duration::ZERO);
+        assert_eq!(frames.last().unwrap().0, Duration::from_millis(1712));
+        assert_eq!(frames.len(), 1712 / 4 + 1);
+
+        let at = |ms: u64| frames.iter().find(|(time, _)| *time >= Duration::from_millis(ms)).unwrap().1;
+        assert_eq!(at(0)[channels::ARM], SWITCH_OFF);
+        assert_eq!(at(12)[channels::ARM], SWITCH_ON);
+        assert!(at(12)[channels::THROTTLE] < 200);
+        assert!(at(1212)[channels::THROTTLE] > 1800);
+        assert_eq!(at(1712)[channels::ARM], SWITCH_OFF);
+    }
+
+    #[test]
+    fn test_replay_with_reversed_channel_differs() {
+        let started = UNIX_EPOCH;
+        let session = Session::read(record(started, &arm_session(started)).as_slice()).unwrap();
+        let period = Duration::from_millis(4);
+
+        let normal = session.replay_channels(&ChannelMapper::new(), period);
+        let reversed = session.replay_channels(&ChannelMapper::with_reversed(&[channels::THROTTLE + 1]), period);
+        let last = normal.len() - 1;
+        assert_eq!(normal[last].1[channels::ARM], reversed[last].1[channels::ARM]);
+        assert_ne!(normal[300].1[channels::THROTTLE], reversed[300].1[channels::THROTTLE]);
+    }
+
+    #[test]
+    fn test_session_to_script() {
+        let started = UNIX_EPOCH;
+        let session = Session::read(record(started, &arm_session(started)).as_slice()).unwrap();
+        assert_eq!(session.to_script().steps(), session.events.as_slice());
+    }
+
+    /// Source replaying fixed batches, then ending
+    struct Batches(Vec<Vec<InputEvent>>);
+
+    impl InputSource for Batches {
+        fn fetch_events(&mut self) -> Result<Option<Vec<InputEvent>>> {
+            Ok((!self.0.is_empty()).then(|| self.0.remove(0)))
+        }
+
+        fn describe(&self) -> String {
+            "batches".to_string()
+        }
+    }
+
+    #[test]
+    fn test_recording_source_tees_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("flight.session");
+        let events: Vec<InputEvent> = arm_session(UNIX_EPOCH)
+            .into_iter()
+            .map(|(_, event)| InputEvent::new_now(event.event_type(), event.code(), event.value()))
+            .collect();
+
+        let inner = Batches(vec![events[..2].to_vec(), events[2..].to_vec()]);
+        let mut source = RecordingSource::new(Box::new(inner), &path).unwrap();
+        assert_eq!(source.describe(), "batches");
+        assert_eq!(source.fetch_events().unwrap().unwrap().len(), 2);
+
+        // Flushed after every batch
+        assert_eq!(Session::load(&path).unwrap().events.len(), 2);
+        assert_eq!(source.fetch_events().unwrap().unwrap().len(), 2);
+        assert!(source.fetch_events().unwrap().is_none());
+
+        let session = Session::load(&path).unwrap();
+        let values: Vec<i32> = session.events.iter().map(|event| event.value).collect();
+        assert_eq!(values, [255, 1, 0, 0]);
+        assert_eq!(session.events[3].code, Key::BTN_TL.code());
+    }
+}
@@ -4,15 +4,24 @@
 //!
 //! **Polynomial**: 0xD5 (x^8 + x^7 + x^6 + x^4 + x^2 + 1)
 //! **Initial Value**: 0x00
+//!
+//! Command frames (0x32) carry an additional inner checksum using the same
+//! algorithm with polynomial 0xBA, see [`crc8_ba`].
 
 /// CRC-8-DVB-S2 polynomial
 const CRC8_POLY: u8 = 0xD5;
 
+/// CRC8 polynomial used for the inner checksum of CRSF command frames
+const CRC8_BA_POLY: u8 = 0xBA;
+
 /// Precomputed CRC8 lookup table for fast calculation
-const CRC8_TABLE: [u8; 256] = generate_crc8_table();
+const CRC8_TABLE: [u8; 256] = generate_crc8_table(CRC8_POLY);
+
+/// Precomputed lookup table for the command frame checksum
+const CRC8_BA_TABLE: [u8; 256] = generate_crc8_table(CRC8_BA_POLY);
 
-/// Generate CRC8 lookup table at compile time
-const fn generate_crc8_table() -> [u8; 256] {
+/// Generate CRC8 lookup table for the given polynomial at compile time
+const fn generate_crc8_table(poly: u8) -> [u8; 256] {
     let mut table = [0u8; 256];
     let mut i = 0;
 
@@ -22,7 +31,7 @@ const fn generate_crc8_table() -> [u8; 256] {
 
         while j < 8 {
             if (crc & 0x80) != 0 {
-                crc = (crc << 1) ^ CRC8_POLY;
+                crc = (crc << 1) ^ poly;
             } else {
                 crc <<= 1;
             }
@@ -64,6 +73,29 @@ pub fn crc8_dvb_s2(data: &[u8]) -> u8 {
     crc
 }
 
+/// Calculate the CRC8 (polynomial 0xBA) used inside CRSF command frames
+///
+/// Command frames (type 0x32) protect their command payload with this checksum
+/// in addition to the regular DVB-S2 frame CRC. It is calculated over
+/// Type + Destination + Origin + Command + Payload.
+///
+/// # Arguments
+///
+/// * `data` - Byte slice to calculate CRC for
+///
+/// # Returns
+///
+/// * `u8` - Calculated CRC8 checksum
+pub fn crc8_ba(data: &[u8]) -> u8 {
+    let mut crc: u8 = 0;
+
+    for &byte in data {
+        crc = CRC8_BA_TABLE[(crc ^ byte) as usize];
+    }
+
+    crc
+}
+
 /// Calculate CRC8-DVB-S2 checksum using direct algorithm (slow, for verification)
 ///
 /// This implementation is slower but easier to verify against the specification.
@@ -229,6 +261,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_crc8_ba_matches_bitwise_algorithm() {
+        fn crc8_ba_slow(data: &[u8]) -> u8 {
+            let mut crc: u8 = 0;
+            for &byte in data {
+                crc ^= byte;
+                for _ in 0..8 {
+                    if (crc & 0x80) != 0 {
+                        crc = (crc << 1) ^ CRC8_BA_POLY;
+                    } else {
+                        crc <<= 1;
+                    }
+                }
+            }
+            crc
+        }
+
+        let test_data = [
+            vec![0x32, 0xEE, 0xEA, 0x10, 0x05, 0x01],
+            vec![0x32, 0xEE, 0xEA, 0x10, 0x01],
+            vec![0xFF; 10],
+        ];
+
+        for data in &test_data {
+            assert_eq!(crc8_ba(data), crc8_ba_slow(data), "Mismatch for data: {:?}", data);
+        }
+        assert_ne!(crc8_ba(&test_data[0]), crc8_dvb_s2(&test_data[0]));
+    }
+
     #[test]
     fn test_crc8_byte_boundaries() {
         // Test with byte boundary values
@@ -1,8 +1,10 @@
 //! # CRSF Packet Decoder
 //!
-//! Decodes CRSF telemetry packets (Link Statistics, Battery, GPS).
+//! Decodes CRSF telemetry packets (Link Statistics, Battery, GPS, Attitude,
+//! Flight Mode), RC channels frames, timing sync, device info and baud rate
+//! replies, and splits a received byte stream into frames ([`FrameParser`]).
 
-use super::crc::crc8_dvb_s2;
+use super::crc::{crc8_ba, crc8_dvb_s2};
 use super::protocol::*;
 use crate::error::{FpvBridgeError, Result};
 
@@ -20,7 +22,8 @@ use crate::error::{FpvBridgeError, Result};
 ///
 /// Returns error if:
 /// - Frame is too short
-/// - Sync byte is incorrect
+/// - Sync byte is incorrect (0xC8, or the 0xEA/0xEE addresses accepted by
+///   [`FrameParser`])
 /// - CRC check fails
 pub fn decode_frame(frame: &[u8]) -> Result<CrsfFrame> {
     // Minimum frame size: sync(1) + length(1) + type(1) + crc(1) = 4 bytes
@@ -30,8 +33,8 @@ pub fn decode_frame(frame: &[u8]) -> Result<CrsfFrame> {
         ));
     }
 
-    // Check sync byte
-    if frame[0] != CRSF_SYNC_BYTE {
+    // Check sync byte (or the address byte frames from a module may start with)
+    if !FrameParser::is_sync_byte(frame[0]) {
         return Err(FpvBridgeError::CrsfProtocol(
             format!("Invalid sync byte: 0x{:02X}", frame[0])
         ));
@@ -76,6 +79,448 @@ pub fn decode_frame(frame: &[u8]) -> Result<CrsfFrame> {
     CrsfFrame::new(frame_type, payload)
 }
 
+/// Unpack a fixed-size RC channels payload into 16 channel values
+///
+/// Inverse of [`crate::crsf::encoder::pack_rc_channels`]: each group of 11
+/// bytes is loaded into a single `u128` and split into 8 11-bit channels.
+///
+/// # Arguments
+///
+/// * `payload` - Packed RC channels payload (22 bytes)
+///
+/// # Returns
+///
+/// * `RcChannels` - Channel values (0-2047)
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::crsf::decoder::unpack_rc_channels;
+/// use fpv_bridge::crsf::encoder::pack_rc_channels;
+///
+/// let channels = [172, 992, 1811, 1024, 0, 2047, 1500, 500, 1, 2, 3, 4, 5, 6, 7, 8];
+/// let mut payload = [0u8; 22];
+/// pack_rc_channels(&channels, &mut payload);
+/// assert_eq!(unpack_rc_channels(&payload), channels);
+/// ```
+pub fn unpack_rc_channels(payload: &RcChannelsPayload) -> RcChannels {
+    const CHANNELS_PER_WORD: usize = 8;
+    const BYTES_PER_WORD: usize = CHANNELS_PER_WORD * 11 / 8;
+
+    let mut channels = [0u16; CRSF_NUM_CHANNELS];
+    for (bytes, group) in payload
+        .chunks_exact(BYTES_PER_WORD)
+        .zip(channels.chunks_exact_mut(CHANNELS_PER_WORD))
+    {
+        let mut word_bytes = [0u8; 16];
+        word_bytes[..BYTES_PER_WORD].copy_from_slice(bytes);
+        let word = u128::from_le_bytes(word_bytes);
+
+        for (i, channel) in group.iter_mut().enumerate() {
+            *channel = ((word >> (i * 11)) as u16) & CRSF_CHANNEL_VALUE_MAX;
+        }
+    }
+
+    channels
+}
+
+/// Decode an RC channels payload into 16 channel values
+///
+/// Inverse of [`crate::crsf::encoder::encode_rc_channels_payload`] for
+/// sniffed or replayed traffic.
+///
+/// # Arguments
+///
+/// * `payload` - RC channels payload (22 bytes)
+///
+/// # Returns
+///
+/// * `Result<RcChannels>` - Channel values (0-2047)
+///
+/// # Errors
+///
+/// Returns error if the payload is not exactly 22 bytes.
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::crsf::decoder::decode_rc_channels_payload;
+/// use fpv_bridge::crsf::encoder::encode_rc_channels_payload;
+///
+/// let channels = [992u16; 16];
+/// let payload = encode_rc_channels_payload(&channels);
+/// assert_eq!(decode_rc_channels_payload(&payload).unwrap(), channels);
+/// ```
+pub fn decode_rc_channels_payload(payload: &[u8]) -> Result<RcChannels> {
+    let payload: &RcChannelsPayload = payload.try_into().map_err(|_| {
+        FpvBridgeError::CrsfProtocol(format!(
+            "RC channels payload must be {} bytes, got {}",
+            CRSF_RC_CHANNELS_PAYLOAD_SIZE,
+            payload.len()
+        ))
+    })?;
+
+    Ok(unpack_rc_channels(payload))
+}
+
+/// Decode a complete RC channels frame into 16 channel values
+///
+/// # Arguments
+///
+/// * `frame` - Complete CRSF frame bytes (sync, length, type, payload, crc)
+///
+/// # Returns
+///
+/// * `Result<RcChannels>` - Channel values (0-2047)
+///
+/// # Errors
+///
+/// Returns error if the frame is invalid (see [`decode_frame`]), is not an
+/// RC channels frame (0x16), or has the wrong payload size.
+pub fn decode_rc_channels_frame(frame: &[u8]) -> Result<RcChannels> {
+    let frame = decode_frame(frame)?;
+
+    if frame.frame_type != CRSF_FRAMETYPE_RC_CHANNELS_PACKED {
+        return Err(FpvBridgeError::CrsfProtocol(
+            format!("Not an RC channels frame: type 0x{:02X}", frame.frame_type)
+        ));
+    }
+
+    decode_rc_channels_payload(&frame.payload)
+}
+
+/// Decode a subset RC channels payload (0x17)
+///
+/// The channel count is derived from the payload length; trailing padding
+/// bits are ignored.
+///
+/// # Arguments
+///
+/// * `payload` - Subset RC channels payload (header byte + packed channels)
+///
+/// # Returns
+///
+/// * `Result<SubsetRcChannels>` - Starting channel, resolution and values
+///
+/// # Errors
+///
+/// Returns error if the payload is too short to hold a single channel.
+pub fn decode_subset_rc_channels_payload(payload: &[u8]) -> Result<SubsetRcChannels> {
+    let Some((&header, data)) = payload.split_first() else {
+        return Err(FpvBridgeError::CrsfProtocol(
+            "Subset RC payload is empty".to_string()
+        ));
+    };
+
+    let resolution = SubsetResolution::from_header(header >> 5);
+    let bits = resolution.bits();
+    let count = data.len() * 8 / bits as usize;
+    if count == 0 {
+        return Err(FpvBridgeError::CrsfProtocol(
+            format!("Subset RC payload too short: {} bytes", payload.len())
+        ));
+    }
+
+    let mut values = Vec::with_capacity(count);
+    let mut acc: u32 = 0;
+    let mut acc_bits: u8 = 0;
+    for &byte in data {
+        acc |= u32::from(byte) << acc_bits;
+        acc_bits += 8;
+        while acc_bits >= bits && values.len() < count {
+            values.push((acc as u16) & resolution.max_value());
+            acc >>= bits;
+            acc_bits -= bits;
+        }
+    }
+
+    Ok(SubsetRcChannels {
+        first_channel: header & CRSF_SUBSET_RC_MAX_FIRST_CHANNEL,
+        resolution,
+        values,
+    })
+}
+
+/// Decode a RADIO_ID timing sync payload
+///
+/// # Arguments
+///
+/// * `payload` - RADIO_ID payload (dest, origin, sub-type, interval, offset)
+///
+/// # Returns
+///
+/// * `Result<TimingSync>` - Refresh interval and phase offset
+///
+/// # Errors
+///
+/// Returns error if the payload is too short or is not a timing sync.
+///
+/// # Payload Layout
+///
+/// ```text
+/// Dest(0xEA) | Origin(0xEE) | 0x10 | Interval (u32 BE, 0.1µs) | Offset (i32 BE, 0.1µs)
+/// ```
+pub fn decode_timing_sync(payload: &[u8]) -> Result<TimingSync> {
+    if payload.len() < CRSF_TIMING_SYNC_PAYLOAD_SIZE {
+        return Err(FpvBridgeError::CrsfProtocol(
+            format!("Timing sync payload too short: {} bytes", payload.len())
+        ));
+    }
+
+    if payload[2] != CRSF_RADIO_ID_TIMING_SYNC {
+        return Err(FpvBridgeError::CrsfProtocol(
+            format!("Unknown RADIO_ID sub-type: 0x{:02X}", payload[2])
+        ));
+    }
+
+    let interval = u32::from_be_bytes([payload[3], payload[4], payload[5], payload[6]]);
+    let offset = i32::from_be_bytes([payload[7], payload[8], payload[9], payload[10]]);
+
+    Ok(TimingSync {
+        refresh_interval_ns: u64::from(interval) * 100,
+        offset_ns: i64::from(offset) * 100,
+    })
+}
+
+/// Decode a device info payload (reply to a device ping)
+///
+/// # Arguments
+///
+/// * `payload` - DEVICE_INFO payload (dest, origin, name, versions, ...)
+///
+/// # Returns
+///
+/// * `Result<DeviceInfo>` - Decoded device info
+///
+/// # Errors
+///
+/// Returns error if the name is not NUL-terminated or the payload is too
+/// short for the fields after it.
+///
+/// # Payload Layout
+///
+/// ```text
+/// Dest | Origin | Name (NUL-terminated) | Serial (u32 BE) | HW ver (u32 BE) |
+/// SW ver (u32 BE) | Parameter count | Parameter protocol version
+/// ```
+pub fn decode_device_info(payload: &[u8]) -> Result<DeviceInfo> {
+    let body = payload.get(2..).unwrap_or_default();
+    let name_len = body.iter().position(|&b| b == 0).ok_or_else(|| {
+        FpvBridgeError::CrsfProtocol("Device info name is not NUL-terminated".to_string())
+    })?;
+
+    let fields = &body[name_len + 1..];
+    if fields.len() < 13 {
+        return Err(FpvBridgeError::CrsfProtocol(
+            format!("Device info payload too short: {} bytes", payload.len())
+        ));
+    }
+    let be_u32 = |at: usize| u32::from_be_bytes([fields[at], fields[at + 1], fields[at + 2], fields[at + 3]]);
+
+    Ok(DeviceInfo {
+        origin: payload[1],
+        name: String::from_utf8_lossy(&body[..name_len]).into_owned(),
+        serial_number: be_u32(0),
+        hardware_version: be_u32(4),
+        software_version: be_u32(8),
+        parameter_count: fields[12],
+    })
+}
+
+/// Decode the reply to a port speed proposal
+///
+/// # Arguments
+///
+/// * `payload` - COMMAND (0x32) payload
+///
+/// # Returns
+///
+/// * `Result<BaudResponse>` - Port and whether the speed was accepted
+///
+/// # Errors
+///
+/// Returns error if the payload is not a general 0x71 command, is too short,
+/// or its command CRC does not match.
+///
+/// # Payload Layout
+///
+/// ```text
+/// Dest | Origin | 0x0A | 0x71 | Port ID | Accepted (0/1) | CRC8-BA
+/// ```
+pub fn decode_baud_response(payload: &[u8]) -> Result<BaudResponse> {
+    if payload.len() < 7 {
+        return Err(FpvBridgeError::CrsfProtocol(
+            format!("Baud rate response too short: {} bytes", payload.len())
+        ));
+    }
+    if payload[2] != CRSF_COMMAND_GENERAL || payload[3] != CRSF_COMMAND_GENERAL_BAUD_RESPONSE {
+        return Err(FpvBridgeError::CrsfProtocol(format!(
+            "Not a baud rate response: command 0x{:02X} 0x{:02X}",
+            payload[2], payload[3]
+        )));
+    }
+
+    // Command CRC covers Type + Payload up to the CRC itself
+    let mut crc_data = vec![CRSF_FRAMETYPE_COMMAND];
+    crc_data.extend_from_slice(&payload[..6]);
+    if crc8_ba(&crc_data) != payload[6] {
+        return Err(FpvBridgeError::CrsfProtocol(
+            "Baud rate response command CRC mismatch".to_string()
+        ));
+    }
+
+    Ok(BaudResponse {
+        port_id: payload[4],
+        accepted: payload[5] != 0,
+    })
+}
+
+/// Frame bytes as received, from [`FrameParser::next_raw_frame`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawFrame {
+    /// Sync/address byte through CRC
+    pub bytes: Vec<u8>,
+    /// Whether the CRC matched
+    pub crc_ok: bool,
+}
+
+/// Splits a received byte stream into CRSF frames
+///
+/// Bytes are buffered until a complete frame is available. Frames from the
+/// TX module may start with the handset address (0xEA) or module address
+/// (0xEE) instead of the 0xC8 sync byte; all three are accepted. Garbage and
+/// frames with a bad CRC are skipped by resynchronizing on the next sync byte.
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::crsf::decoder::FrameParser;
+/// use fpv_bridge::crsf::encoder::encode_bind_frame;
+///
+/// let frame = encode_bind_frame();
+/// let mut parser = FrameParser::new();
+///
+/// parser.push(&[0x00, 0x42]); // Line noise
+/// parser.push(&frame[..4]);
+/// assert!(parser.next_frame().is_none());
+///
+/// parser.push(&frame[4..]);
+/// assert_eq!(parser.next_frame().unwrap().frame_type, 0x32);
+/// ```
+#[derive(Debug, Default)]
+pub struct FrameParser {
+    /// Bytes received but not yet consumed
+    buffer: Vec<u8>,
+    /// Frames dropped because of a CRC mismatch
+    crc_errors: u64,
+}
+
+impl FrameParser {
+    /// Create an empty parser
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append received bytes
+    pub fn push(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// Number of frames dropped because of a CRC mismatch
+    pub fn crc_errors(&self) -> u64 {
+        self.crc_errors
+    }
+
+    /// Take the next complete frame, if one has been received
+    pub fn next_frame(&mut self) -> Option<CrsfFrame> {
+        loop {
+            let (frame_size, crc_ok) = self.next_candidate()?;
+            if !crc_ok {
+                self.crc_errors += 1;
+                self.buffer.remove(0);
+                continue;
+            }
+
+            let frame = CrsfFrame {
+                frame_type: self.buffer[2],
+                payload: self.buffer[3..frame_size - 1].to_vec(),
+            };
+            self.buffer.drain(..frame_size);
+            return Some(frame);
+        }
+    }
+
+    /// Take the next complete frame as raw bytes, including frames with a
+    /// bad CRC
+    ///
+    /// For traffic capture. A frame with a bad CRC is counted and returned
+    /// once, then only its first byte is skipped, as in
+    /// [`next_frame`](Self::next_frame).
+    ///
+    /// # Returns
+    ///
+    /// * `Option<RawFrame>` - The frame bytes from sync byte to CRC, or
+    ///   `None` if no complete frame has been received
+    pub fn next_raw_frame(&mut self) -> Option<RawFrame> {
+        let (frame_size, crc_ok) = self.next_candidate()?;
+        let bytes = self.buffer[..frame_size].to_vec();
+        if crc_ok {
+            self.buffer.drain(..frame_size);
+        } else {
+            self.crc_errors += 1;
+            self.buffer.remove(0);
+        }
+        Some(RawFrame { bytes, crc_ok })
+    }
+
+    /// Size and CRC status of the complete frame at the start of the buffer
+    ///
+    /// Skips garbage and impossible lengths; returns `None` until a whole
+    /// frame has been received.
+    fn next_candidate(&mut self) -> Option<(usize, bool)> {
+        loop {
+            // Discard everything before the next sync byte
+            let start = self.buffer.iter().position(|&b| Self::is_sync_byte(b));
+            match start {
+                Some(start) => {
+                    self.buffer.drain(..start);
+                }
+                None => {
+                    self.buffer.clear();
+                    return None;
+                }
+            }
+
+            if self.buffer.len() < 2 {
+                return None;
+            }
+
+            // Length covers type + payload + crc
+            let length = self.buffer[1] as usize;
+            if !(2..=CRSF_MAX_PAYLOAD_SIZE + 2).contains(&length) {
+                self.buffer.remove(0);
+                continue;
+            }
+
+            let frame_size = length + 2;
+            if self.buffer.len() < frame_size {
+                return None;
+            }
+
+            let crc = crc8_dvb_s2(&self.buffer[1..frame_size - 1]);
+            return Some((frame_size, crc == self.buffer[frame_size - 1]));
+        }
+    }
+
+    /// Returns true for bytes that can start a frame
+    fn is_sync_byte(byte: u8) -> bool {
+        matches!(
+            byte,
+            CRSF_SYNC_BYTE | CRSF_ADDRESS_RADIO_TRANSMITTER | CRSF_ADDRESS_CRSF_TRANSMITTER
+        )
+    }
+}
+
 /// Decode Link Statistics telemetry packet
 ///
 /// # Arguments
@@ -193,10 +638,55 @@ pub fn decode_gps(payload: &[u8]) -> Result<GpsData> {
     })
 }
 
+/// Decode Attitude telemetry packet
+///
+/// # Arguments
+///
+/// * `payload` - Attitude payload (6 bytes)
+///
+/// # Returns
+///
+/// * `Result<Attitude>` - Decoded attitude
+pub fn decode_attitude(payload: &[u8]) -> Result<Attitude> {
+    if payload.len() < CRSF_ATTITUDE_PAYLOAD_SIZE {
+        return Err(FpvBridgeError::CrsfProtocol(
+            format!("Attitude payload too short: {} bytes", payload.len())
+        ));
+    }
+
+    // Pitch, roll, yaw: 2 bytes each, big-endian, radians × 10000
+    let angle = |offset: usize| i16::from_be_bytes([payload[offset], payload[offset + 1]]) as f32 / 10_000.0;
+
+    Ok(Attitude {
+        pitch: angle(0),
+        roll: angle(2),
+        yaw: angle(4),
+    })
+}
+
+/// Decode Flight Mode telemetry packet
+///
+/// # Arguments
+///
+/// * `payload` - Null-terminated flight mode name (e.g. `ACRO`, `ANGL*`)
+///
+/// # Returns
+///
+/// * `Result<String>` - Flight mode name without the terminator
+pub fn decode_flight_mode(payload: &[u8]) -> Result<String> {
+    let name = payload.split(|&byte| byte == 0).next().unwrap_or_default();
+    if name.is_empty() {
+        return Err(FpvBridgeError::CrsfProtocol("Empty flight mode".to_string()));
+    }
+    Ok(String::from_utf8_lossy(name).into_owned())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::crsf::encoder::encode_rc_channels_frame;
+    use crate::crsf::encoder::{
+        encode_baud_proposal_frame, encode_model_select_frame, encode_rc_channels_frame, encode_rc_channels_payload,
+    };
 
     #[test]
     fn test_decode_frame_too_short() {
@@ -212,6 +702,16 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_decode_frame_address_sync_bytes() {
+        let mut frame = encode_model_select_frame(3);
+        for address in [CRSF_ADDRESS_RADIO_TRANSMITTER, CRSF_ADDRESS_CRSF_TRANSMITTER] {
+            // The sync byte is not covered by the CRC
+            frame[0] = address;
+            assert_eq!(decode_frame(&frame).unwrap().frame_type, CRSF_FRAMETYPE_COMMAND);
+        }
+    }
+
     #[test]
     fn test_decode_frame_length_too_small() {
         // Test with length = 0 (corrupted)
@@ -365,4 +865,363 @@ mod tests {
         let result = decode_gps(&payload);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_decode_attitude() {
+        // Pitch 0.1 rad, roll -0.5 rad, yaw 3.0 rad
+        let payload = [0x03, 0xE8, 0xEC, 0x78, 0x75, 0x30];
+        let attitude = decode_attitude(&payload).unwrap();
+        assert!((attitude.pitch - 0.1).abs() < 1e-4);
+        assert!((attitude.roll + 0.5).abs() < 1e-4);
+        assert!((attitude.yaw - 3.0).abs() < 1e-4);
+
+        assert!(decode_attitude(&payload[..4]).is_err());
+    }
+
+    #[test]
+    fn test_decode_flight_mode() {
+        assert_eq!(decode_flight_mode(b"ANGL*\0").unwrap(), "ANGL*");
+        // Terminator missing
+        assert_eq!(decode_flight_mode(b"ACRO").unwrap(), "ACRO");
+        assert!(decode_flight_mode(b"\0").is_err());
+        assert!(decode_flight_mode(b"").is_err());
+    }
+
+    #[test]
+    fn test_unpack_rc_channels_extremes() {
+        assert_eq!(unpack_rc_channels(&[0u8; 22]), [0u16; CRSF_NUM_CHANNELS]);
+        assert_eq!(
+            unpack_rc_channels(&[0xFFu8; 22]),
+            [CRSF_CHANNEL_VALUE_MAX; CRSF_NUM_CHANNELS]
+        );
+    }
+
+    #[test]
+    fn test_unpack_rc_channels_from_encoded_frame() {
+        let channels = [100, 200, 300, 400, 500, 600, 700, 800, 900, 1000, 1100, 1200, 1300, 1400, 1500, 1600];
+        let frame = decode_frame(&encode_rc_channels_frame(&channels)).unwrap();
+        let payload: RcChannelsPayload = frame.payload.as_slice().try_into().unwrap();
+
+        assert_eq!(unpack_rc_channels(&payload), channels);
+    }
+
+    #[test]
+    fn test_decode_rc_channels_payload_wrong_length() {
+        assert!(decode_rc_channels_payload(&[0u8; 21]).is_err());
+        assert!(decode_rc_channels_payload(&[0u8; 23]).is_err());
+        assert!(decode_rc_channels_payload(&[]).is_err());
+    }
+
+    #[test]
+    fn test_decode_rc_channels_payload_known_bytes() {
+        // Channel 1 = 2047 (bits 0-10), channel 2 = 1 (bit 11), rest zero
+        let mut payload = [0u8; 22];
+        payload[0] = 0xFF;
+        payload[1] = 0x0F;
+
+        let channels = decode_rc_channels_payload(&payload).unwrap();
+        assert_eq!(channels[0], 2047);
+        assert_eq!(channels[1], 1);
+        assert!(channels[2..].iter().all(|&c| c == 0));
+    }
+
+    #[test]
+    fn test_decode_rc_channels_round_trip_all_values_every_channel() {
+        // Exhaustive: every 11-bit value in every channel position, with the
+        // other channels holding the bitwise complement to catch bleed-over.
+        for value in CRSF_CHANNEL_VALUE_MIN..=CRSF_CHANNEL_VALUE_MAX {
+            for position in 0..CRSF_NUM_CHANNELS {
+                let mut channels = [!value & CRSF_CHANNEL_VALUE_MAX; CRSF_NUM_CHANNELS];
+                channels[position] = value;
+
+                let payload = encode_rc_channels_payload(&channels);
+                assert_eq!(
+                    decode_rc_channels_payload(&payload).unwrap(),
+                    channels,
+                    "value {} at channel {}",
+                    value,
+                    position + 1
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_rc_channels_frame() {
+        let channels = [172, 992, 1811, 1024, 0, 2047, 1500, 500, 1, 2, 3, 4, 5, 6, 7, 8];
+        let frame = encode_rc_channels_frame(&channels);
+
+        assert_eq!(decode_rc_channels_frame(&frame).unwrap(), channels);
+    }
+
+    #[test]
+    fn test_decode_rc_channels_frame_wrong_type() {
+        let frame = encode_model_select_frame(1);
+        let result = decode_rc_channels_frame(&frame);
+
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("0x32"));
+    }
+
+    #[test]
+    fn test_decode_rc_channels_frame_crc_error() {
+        let mut frame = encode_rc_channels_frame(&[CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS]);
+        frame[10] ^= 0x01;
+
+        assert!(decode_rc_channels_frame(&frame).is_err());
+    }
+
+    #[test]
+    fn test_decode_subset_rc_channels() {
+        use crate::crsf::encoder::encode_subset_rc_channels_frame;
+
+        let subset = SubsetRcChannels {
+            first_channel: 2,
+            resolution: SubsetResolution::Bits12,
+            values: vec![0, 4095, 2048],
+        };
+        let frame = decode_frame(&encode_subset_rc_channels_frame(&subset).unwrap()).unwrap();
+
+        assert_eq!(frame.frame_type, CRSF_FRAMETYPE_SUBSET_RC_CHANNELS_PACKED);
+        assert_eq!(decode_subset_rc_channels_payload(&frame.payload).unwrap(), subset);
+    }
+
+    #[test]
+    fn test_decode_subset_rc_channels_too_short() {
+        assert!(decode_subset_rc_channels_payload(&[]).is_err());
+        assert!(decode_subset_rc_channels_payload(&[0x00]).is_err());
+        // 13-bit channels need two data bytes
+        assert!(decode_subset_rc_channels_payload(&[3 << 5, 0xFF]).is_err());
+    }
+
+    /// RADIO_ID timing sync frame as sent by an ELRS TX module
+    fn timing_sync_frame(interval: u32, offset: i32) -> Vec<u8> {
+        let mut frame = vec![CRSF_ADDRESS_RADIO_TRANSMITTER, 13, CRSF_FRAMETYPE_RADIO_ID];
+        frame.extend_from_slice(&[
+            CRSF_ADDRESS_RADIO_TRANSMITTER,
+            CRSF_ADDRESS_CRSF_TRANSMITTER,
+            CRSF_RADIO_ID_TIMING_SYNC,
+        ]);
+        frame.extend_from_slice(&interval.to_be_bytes());
+        frame.extend_from_slice(&offset.to_be_bytes());
+        frame.push(crc8_dvb_s2(&frame[1..]));
+        frame
+    }
+
+    #[test]
+    fn test_decode_timing_sync() {
+        let frame = timing_sync_frame(40_000, -1_234);
+        let mut parser = FrameParser::new();
+        parser.push(&frame);
+
+        let frame = parser.next_frame().unwrap();
+        assert_eq!(frame.frame_type, CRSF_FRAMETYPE_RADIO_ID);
+
+        let sync = decode_timing_sync(&frame.payload).unwrap();
+        assert_eq!(sync.refresh_interval_ns, 4_000_000); // 4ms (250Hz)
+        assert_eq!(sync.offset_ns, -123_400);
+    }
+
+    #[test]
+    fn test_decode_timing_sync_invalid() {
+        assert!(decode_timing_sync(&[0u8; 10]).is_err());
+
+        let mut payload = [0u8; CRSF_TIMING_SYNC_PAYLOAD_SIZE];
+        payload[2] = 0x11;
+        assert!(decode_timing_sync(&payload).is_err());
+    }
+
+    fn device_info_payload(name: &str) -> Vec<u8> {
+        let mut payload = vec![CRSF_ADDRESS_RADIO_TRANSMITTER, CRSF_ADDRESS_CRSF_TRANSMITTER];
+        payload.extend_from_slice(name.as_bytes());
+        payload.push(0);
+        payload.extend_from_slice(b"ELRS");
+        payload.extend_from_slice(&0x0000_0001u32.to_be_bytes());
+        payload.extend_from_slice(&0x0003_0400u32.to_be_bytes());
+        payload.extend_from_slice(&[21, 0]);
+        payload
+    }
+
+    #[test]
+    fn test_decode_device_info() {
+        let info = decode_device_info(&device_info_payload("ELRS TX")).unwrap();
+
+        assert_eq!(info.origin, CRSF_ADDRESS_CRSF_TRANSMITTER);
+        assert_eq!(info.name, "ELRS TX");
+        assert_eq!(info.serial_number, u32::from_be_bytes(*b"ELRS"));
+        assert_eq!(info.hardware_version, 1);
+        assert_eq!(info.software_version, 0x0003_0400);
+        assert_eq!(info.parameter_count, 21);
+    }
+
+    #[test]
+    fn test_decode_device_info_invalid() {
+        assert!(decode_device_info(&[]).is_err());
+
+        // Name without terminator
+        assert!(decode_device_info(&[0xEA, 0xEE, b'E', b'L']).is_err());
+
+        // Truncated version fields
+        let payload = device_info_payload("ELRS TX");
+        assert!(decode_device_info(&payload[..payload.len() - 2]).is_err());
+    }
+
+    fn baud_response_payload(accepted: bool) -> Vec<u8> {
+        let mut payload = vec![
+            CRSF_ADDRESS_RADIO_TRANSMITTER,
+            CRSF_ADDRESS_CRSF_TRANSMITTER,
+            CRSF_COMMAND_GENERAL,
+            CRSF_COMMAND_GENERAL_BAUD_RESPONSE,
+            0,
+            u8::from(accepted),
+        ];
+        let mut crc_data = vec![CRSF_FRAMETYPE_COMMAND];
+        crc_data.extend_from_slice(&payload);
+        payload.push(crc8_ba(&crc_data));
+        payload
+    }
+
+    #[test]
+    fn test_decode_baud_response() {
+        let response = decode_baud_response(&baud_response_payload(true)).unwrap();
+        assert_eq!(response, BaudResponse { port_id: 0, accepted: true });
+
+        let response = decode_baud_response(&baud_response_payload(false)).unwrap();
+        assert!(!response.accepted);
+    }
+
+    #[test]
+    fn test_decode_baud_response_invalid() {
+        let payload = baud_response_payload(true);
+        assert!(decode_baud_response(&payload[..6]).is_err());
+
+        let mut corrupt = payload.clone();
+        corrupt[5] = 0;
+        assert!(decode_baud_response(&corrupt).unwrap_err().to_string().contains("CRC"));
+
+        // Our own proposal echoed back is not a response
+        let proposal = encode_baud_proposal_frame(0, 921_600);
+        let frame = decode_frame(&proposal).unwrap();
+        assert!(decode_baud_response(&frame.payload).is_err());
+    }
+
+    #[test]
+    fn test_frame_parser_byte_at_a_time() {
+        let rc = encode_rc_channels_frame(&[CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS]);
+        let sync = timing_sync_frame(20_000, 50);
+        let mut parser = FrameParser::new();
+        let mut frames = Vec::new();
+
+        for &byte in rc.iter().chain(&sync) {
+            parser.push(&[byte]);
+            frames.extend(std::iter::from_fn(|| parser.next_frame()));
+        }
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].frame_type, CRSF_FRAMETYPE_RC_CHANNELS_PACKED);
+        assert_eq!(frames[1].frame_type, CRSF_FRAMETYPE_RADIO_ID);
+    }
+
+    #[test]
+    fn test_frame_parser_resyncs_after_garbage_and_bad_crc() {
+        let good = encode_rc_channels_frame(&[CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS]);
+        let mut bad = good.clone();
+        bad[25] ^= 0xFF;
+
+        let mut parser = FrameParser::new();
+        parser.push(&[0x00, 0x13, 0x37]);
+        parser.push(&bad);
+        parser.push(&[CRSF_SYNC_BYTE, 0xFF]); // Impossible length
+        parser.push(&good);
+
+        let frame = parser.next_frame().unwrap();
+        assert_eq!(frame.payload, good[3..25]);
+        assert!(parser.next_frame().is_none());
+        assert_eq!(parser.crc_errors(), 1);
+    }
+
+    #[test]
+    fn test_frame_parser_raw_frames_include_bad_crc() {
+        let good = encode_rc_channels_frame(&[CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS]);
+        let mut bad = good.clone();
+        bad[25] ^= 0xFF;
+
+        let mut parser = FrameParser::new();
+        parser.push(&[0x00]);
+        parser.push(&bad);
+        parser.push(&good);
+
+        let first = parser.next_raw_frame().unwrap();
+        assert_eq!(first, RawFrame { bytes: bad, crc_ok: false });
+        let second = parser.next_raw_frame().unwrap();
+        assert_eq!(second, RawFrame { bytes: good, crc_ok: true });
+        assert!(parser.next_raw_frame().is_none());
+        assert_eq!(parser.crc_errors(), 1);
+    }
+
+    mod proptests {
+        use super::*;
+        use crate::crsf::encoder::pack_rc_channels;
+        use proptest::prelude::*;
+
+        proptest! {
+            #[test]
+            fn unpack_inverts_pack(channels in any::<[u16; CRSF_NUM_CHANNELS]>()) {
+                let mut payload = [0u8; CRSF_RC_CHANNELS_PAYLOAD_SIZE];
+                pack_rc_channels(&channels, &mut payload);
+                prop_assert_eq!(unpack_rc_channels(&payload), channels.map(|c| c.min(CRSF_CHANNEL_VALUE_MAX)));
+            }
+
+            #[test]
+            fn pack_inverts_unpack(payload in any::<[u8; CRSF_RC_CHANNELS_PAYLOAD_SIZE]>()) {
+                let mut repacked = [0u8; CRSF_RC_CHANNELS_PAYLOAD_SIZE];
+                pack_rc_channels(&unpack_rc_channels(&payload), &mut repacked);
+                prop_assert_eq!(repacked, payload);
+            }
+
+            #[test]
+            fn decode_inverts_encode(channels in prop::array::uniform16(0..=CRSF_CHANNEL_VALUE_MAX)) {
+                let frame = encode_rc_channels_frame(&channels);
+                prop_assert_eq!(decode_rc_channels_frame(&frame).unwrap(), channels);
+            }
+
+            #[test]
+            fn subset_decode_inverts_encode(
+                first_channel in 0..=CRSF_SUBSET_RC_MAX_FIRST_CHANNEL,
+                bits in 10u8..=13,
+                raw in prop::collection::vec(any::<u16>(), 1..=CRSF_NUM_CHANNELS),
+            ) {
+                use crate::crsf::encoder::encode_subset_rc_channels_payload;
+
+                let resolution = SubsetResolution::from_bits(bits).unwrap();
+                let subset = SubsetRcChannels {
+                    first_channel,
+                    resolution,
+                    values: raw.iter().map(|&v| v & resolution.max_value()).collect(),
+                };
+                let payload = encode_subset_rc_channels_payload(&subset).unwrap();
+                prop_assert_eq!(decode_subset_rc_channels_payload(&payload).unwrap(), subset);
+            }
+
+            #[test]
+            fn frame_parser_survives_noise(
+                noise in prop::collection::vec(any::<u8>(), 0..64),
+                channels in prop::array::uniform16(0..=CRSF_CHANNEL_VALUE_MAX),
+                split in 0usize..26,
+            ) {
+                let frame = encode_rc_channels_frame(&channels);
+                let mut parser = FrameParser::new();
+                parser.push(&noise);
+                parser.push(&frame[..split]);
+                parser.push(&frame[split..]);
+                // A bogus header in the noise holds frames back until enough
+                // bytes arrive to reject it, so keep the stream going.
+                parser.push(&frame);
+                parser.push(&frame);
+                let found = std::iter::from_fn(|| parser.next_frame())
+                    .any(|f| f.frame_type == CRSF_FRAMETYPE_RC_CHANNELS_PACKED && f.payload == frame[3..25]);
+                prop_assert!(found);
+            }
+        }
+    }
 }
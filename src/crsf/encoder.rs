@@ -1,9 +1,11 @@
 //! # CRSF Packet Encoder
 //!
-//! Encodes RC channels into CRSF protocol packets.
+//! Encodes RC channels and commands into CRSF protocol packets, and the
+//! telemetry and replies a TX module sends (for the virtual module).
 
-use super::crc::crc8_dvb_s2;
+use super::crc::{crc8_ba, crc8_dvb_s2};
 use super::protocol::*;
+use crate::error::{FpvBridgeError, Result};
 
 /// Encode RC channels into a complete CRSF frame
 ///
@@ -25,24 +27,45 @@ use super::protocol::*;
 /// assert_eq!(frame.len(), 26);
 /// ```
 pub fn encode_rc_channels_frame(channels: &RcChannels) -> Vec<u8> {
-    let payload = encode_rc_channels_payload(channels);
-
-    // Build frame: Length + Type + Payload
-    let mut frame_data = Vec::with_capacity(1 + 1 + payload.len());
-    frame_data.push(CRSF_RC_CHANNELS_FRAME_LENGTH); // Length
-    frame_data.push(CRSF_FRAMETYPE_RC_CHANNELS_PACKED); // Type
-    frame_data.extend_from_slice(&payload); // Payload
+    let mut frame = [0u8; CRSF_RC_CHANNELS_FRAME_SIZE];
+    encode_rc_channels_frame_into(channels, &mut frame);
+    frame.to_vec()
+}
 
-    // Calculate CRC over Length + Type + Payload
-    let crc = crc8_dvb_s2(&frame_data);
+/// Encode RC channels into a caller-supplied frame buffer
+///
+/// Allocation-free variant of [`encode_rc_channels_frame`] for the packet
+/// loop: the same buffer can be reused for every packet.
+///
+/// # Arguments
+///
+/// * `channels` - Array of 16 channel values (11-bit: 0-2047)
+/// * `frame` - Destination buffer, fully overwritten
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::crsf::encoder::{encode_rc_channels_frame, encode_rc_channels_frame_into};
+/// use fpv_bridge::crsf::protocol::RcChannelsFrame;
+///
+/// let channels = [1024u16; 16];
+/// let mut frame: RcChannelsFrame = [0; 26];
+/// encode_rc_channels_frame_into(&channels, &mut frame);
+/// assert_eq!(frame.to_vec(), encode_rc_channels_frame(&channels));
+/// ```
+pub fn encode_rc_channels_frame_into(channels: &RcChannels, frame: &mut RcChannelsFrame) {
+    frame[0] = CRSF_SYNC_BYTE;
+    frame[1] = CRSF_RC_CHANNELS_FRAME_LENGTH;
+    frame[2] = CRSF_FRAMETYPE_RC_CHANNELS_PACKED;
 
-    // Build complete frame: Sync + Length + Type + Payload + CRC
-    let mut complete_frame = Vec::with_capacity(26);
-    complete_frame.push(CRSF_SYNC_BYTE); // Sync byte
-    complete_frame.extend_from_slice(&frame_data); // Length + Type + Payload
-    complete_frame.push(crc); // CRC
+    let payload: &mut RcChannelsPayload = (&mut frame[3..3 + CRSF_RC_CHANNELS_PAYLOAD_SIZE])
+        .try_into()
+        .expect("payload slice has fixed size");
+    pack_rc_channels(channels, payload);
 
-    complete_frame
+    // CRC covers Length + Type + Payload
+    frame[CRSF_RC_CHANNELS_FRAME_SIZE - 1] =
+        crc8_dvb_s2(&frame[1..CRSF_RC_CHANNELS_FRAME_SIZE - 1]);
 }
 
 /// Encode RC channels into payload (22 bytes)
@@ -68,25 +91,549 @@ pub fn encode_rc_channels_frame(channels: &RcChannels) -> Vec<u8> {
 /// ...
 /// ```
 pub fn encode_rc_channels_payload(channels: &RcChannels) -> Vec<u8> {
-    let mut payload = vec![0u8; CRSF_RC_CHANNELS_PAYLOAD_SIZE];
-    let mut bit_index = 0;
-
-    for &channel in channels.iter() {
-        // Clamp channel value to 11-bit range
-        let value = channel.min(CRSF_CHANNEL_VALUE_MAX);
-
-        // Pack 11 bits
-        for bit in 0..11 {
-            if (value >> bit) & 1 == 1 {
-                let byte_index = bit_index / 8;
-                let bit_offset = bit_index % 8;
-                payload[byte_index] |= 1 << bit_offset;
-            }
-            bit_index += 1;
+    let mut payload = [0u8; CRSF_RC_CHANNELS_PAYLOAD_SIZE];
+    pack_rc_channels(channels, &mut payload);
+    payload.to_vec()
+}
+
+/// Number of channels packed per 88-bit word (8 × 11 bits = 11 bytes)
+const CHANNELS_PER_WORD: usize = 8;
+
+/// Bytes produced per packed word
+const BYTES_PER_WORD: usize = CHANNELS_PER_WORD * 11 / 8;
+
+/// Pack RC channels into a caller-supplied payload buffer
+///
+/// Word-wise equivalent of the bitstream described in
+/// [`encode_rc_channels_payload`]: each group of 8 channels is shifted into a
+/// single `u128` and written out as 11 little-endian bytes, instead of setting
+/// the 176 payload bits one at a time.
+///
+/// # Arguments
+///
+/// * `channels` - Array of 16 channel values (values above 2047 are clamped)
+/// * `payload` - Destination buffer, fully overwritten
+pub fn pack_rc_channels(channels: &RcChannels, payload: &mut RcChannelsPayload) {
+    for (group, out) in channels
+        .chunks_exact(CHANNELS_PER_WORD)
+        .zip(payload.chunks_exact_mut(BYTES_PER_WORD))
+    {
+        let mut word: u128 = 0;
+        for (i, &channel) in group.iter().enumerate() {
+            word |= u128::from(channel.min(CRSF_CHANNEL_VALUE_MAX)) << (i * 11);
+        }
+        out.copy_from_slice(&word.to_le_bytes()[..BYTES_PER_WORD]);
+    }
+}
+
+/// Check a subset against the frame limits
+///
+/// # Returns
+///
+/// * `Result<usize>` - Payload size in bytes
+fn validate_subset_rc_channels(subset: &SubsetRcChannels) -> Result<usize> {
+    if subset.first_channel > CRSF_SUBSET_RC_MAX_FIRST_CHANNEL {
+        return Err(FpvBridgeError::CrsfProtocol(format!(
+            "Subset RC first channel {} exceeds {}",
+            subset.first_channel, CRSF_SUBSET_RC_MAX_FIRST_CHANNEL
+        )));
+    }
+    if subset.values.is_empty() {
+        return Err(FpvBridgeError::CrsfProtocol(
+            "Subset RC frame needs at least one channel".to_string()
+        ));
+    }
+    let size = subset.payload_size();
+    if size > CRSF_MAX_PAYLOAD_SIZE {
+        return Err(FpvBridgeError::CrsfProtocol(format!(
+            "Subset RC payload too large: {} bytes (max {})",
+            size, CRSF_MAX_PAYLOAD_SIZE
+        )));
+    }
+
+    Ok(size)
+}
+
+/// Encode a subset RC channels payload (0x17)
+///
+/// # Arguments
+///
+/// * `subset` - Starting channel, resolution and channel values (values above
+///   the resolution's maximum are clamped)
+///
+/// # Returns
+///
+/// * `Result<Vec<u8>>` - Header byte followed by the packed channel bits
+///
+/// # Errors
+///
+/// Returns error if the starting channel is above 31, no values are given, or
+/// the payload would exceed the 60-byte CRSF limit.
+///
+/// # Payload Layout
+///
+/// ```text
+/// Byte 0: first channel [0:4] | resolution [5:6] (0=10 .. 3=13 bits) | reserved [7]
+/// Byte 1..: channel values, packed LSB-first like the 0x16 payload
+/// ```
+pub fn encode_subset_rc_channels_payload(subset: &SubsetRcChannels) -> Result<Vec<u8>> {
+    let size = validate_subset_rc_channels(subset)?;
+    let mut payload = Vec::with_capacity(size);
+    pack_subset_rc_channels(subset, &mut payload);
+
+    Ok(payload)
+}
+
+/// Append the header byte and packed channel bits of a validated subset
+fn pack_subset_rc_channels(subset: &SubsetRcChannels, payload: &mut Vec<u8>) {
+    payload.push(subset.first_channel | (subset.resolution.header() << 5));
+
+    let bits = subset.resolution.bits();
+    let max = subset.resolution.max_value();
+    let mut acc: u32 = 0;
+    let mut acc_bits: u8 = 0;
+    for &value in &subset.values {
+        acc |= u32::from(value.min(max)) << acc_bits;
+        acc_bits += bits;
+        while acc_bits >= 8 {
+            payload.push(acc as u8);
+            acc >>= 8;
+            acc_bits -= 8;
         }
     }
+    if acc_bits > 0 {
+        payload.push(acc as u8);
+    }
+}
+
+/// Encode a subset RC channels frame (0x17)
+///
+/// # Arguments
+///
+/// * `subset` - Starting channel, resolution and channel values
+///
+/// # Returns
+///
+/// * `Result<Vec<u8>>` - Complete CRSF frame
+///
+/// # Errors
+///
+/// See [`encode_subset_rc_channels_payload`].
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::crsf::encoder::encode_subset_rc_channels_frame;
+/// use fpv_bridge::crsf::protocol::{SubsetRcChannels, SubsetResolution};
+///
+/// // Sticks only, at 12-bit resolution
+/// let subset = SubsetRcChannels {
+///     first_channel: 0,
+///     resolution: SubsetResolution::Bits12,
+///     values: vec![2048, 2048, 0, 2048],
+/// };
+/// let frame = encode_subset_rc_channels_frame(&subset).unwrap();
+/// assert_eq!(frame.len(), 4 + 1 + 6);
+/// ```
+pub fn encode_subset_rc_channels_frame(subset: &SubsetRcChannels) -> Result<Vec<u8>> {
+    let payload = encode_subset_rc_channels_payload(subset)?;
+    Ok(encode_frame(&CrsfFrame {
+        frame_type: CRSF_FRAMETYPE_SUBSET_RC_CHANNELS_PACKED,
+        payload,
+    }))
+}
+
+/// Encode a subset RC channels frame (0x17) into a caller-supplied buffer
+///
+/// Allocation-free variant of [`encode_subset_rc_channels_frame`] for the
+/// packet loop: the buffer is cleared and refilled, so its capacity is reused
+/// for every packet.
+///
+/// # Arguments
+///
+/// * `subset` - Starting channel, resolution and channel values
+/// * `frame` - Destination buffer, replaced by the complete CRSF frame
+///
+/// # Errors
+///
+/// See [`encode_subset_rc_channels_payload`]. The buffer is left empty.
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::crsf::encoder::{encode_subset_rc_channels_frame, encode_subset_rc_channels_frame_into};
+/// use fpv_bridge::crsf::protocol::{SubsetRcChannels, SubsetResolution};
+///
+/// let subset = SubsetRcChannels {
+///     first_channel: 0,
+///     resolution: SubsetResolution::Bits12,
+///     values: vec![2048, 2048, 0, 2048],
+/// };
+/// let mut frame = Vec::new();
+/// encode_subset_rc_channels_frame_into(&subset, &mut frame).unwrap();
+/// assert_eq!(frame, encode_subset_rc_channels_frame(&subset).unwrap());
+/// ```
+pub fn encode_subset_rc_channels_frame_into(subset: &SubsetRcChannels, frame: &mut Vec<u8>) -> Result<()> {
+    frame.clear();
+    let size = validate_subset_rc_channels(subset)?;
+
+    // Length covers Type + Payload + CRC
+    frame.extend_from_slice(&[CRSF_SYNC_BYTE, (size + 2) as u8, CRSF_FRAMETYPE_SUBSET_RC_CHANNELS_PACKED]);
+    pack_subset_rc_channels(subset, frame);
 
-    payload
+    // CRC covers Length + Type + Payload
+    let crc = crc8_dvb_s2(&frame[1..]);
+    frame.push(crc);
+
+    Ok(())
+}
+
+/// Encode an arbitrary CRSF frame
+///
+/// # Arguments
+///
+/// * `frame` - Frame type and payload
+///
+/// # Returns
+///
+/// * `Vec<u8>` - Complete CRSF frame (sync + length + type + payload + crc)
+pub fn encode_frame(frame: &CrsfFrame) -> Vec<u8> {
+    let mut complete_frame = Vec::with_capacity(frame.payload.len() + 4);
+    complete_frame.push(CRSF_SYNC_BYTE);
+    complete_frame.push(frame.length());
+    complete_frame.push(frame.frame_type);
+    complete_frame.extend_from_slice(&frame.payload);
+
+    // CRC covers Length + Type + Payload
+    let crc = crc8_dvb_s2(&complete_frame[1..]);
+    complete_frame.push(crc);
+
+    complete_frame
+}
+
+/// Encode a CRSF command frame (type 0x32) addressed to the TX module
+///
+/// Command frames use the extended header (destination + origin) and carry an
+/// inner CRC8 (polynomial 0xBA) after the command data, followed by the usual
+/// frame CRC.
+///
+/// # Arguments
+///
+/// * `command` - Command ID (e.g. `CRSF_COMMAND_SUBCMD_CRSF`)
+/// * `subcommand` - Sub-command ID (e.g. `CRSF_COMMAND_CRSF_BIND`)
+/// * `data` - Sub-command arguments
+///
+/// # Returns
+///
+/// * `Vec<u8>` - Complete CRSF frame
+///
+/// # Frame Layout
+///
+/// ```text
+/// Sync | Len | 0x32 | Dest(0xEE) | Origin(0xEA) | Cmd | SubCmd | Data... | CRC8-BA | CRC8
+/// ```
+pub fn encode_command_frame(command: u8, subcommand: u8, data: &[u8]) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(data.len() + 5);
+    payload.push(CRSF_ADDRESS_CRSF_TRANSMITTER);
+    payload.push(CRSF_ADDRESS_RADIO_TRANSMITTER);
+    payload.push(command);
+    payload.push(subcommand);
+    payload.extend_from_slice(data);
+
+    // Inner command CRC covers Type + Payload
+    let mut crc_data = Vec::with_capacity(payload.len() + 1);
+    crc_data.push(CRSF_FRAMETYPE_COMMAND);
+    crc_data.extend_from_slice(&payload);
+    payload.push(crc8_ba(&crc_data));
+
+    debug_assert!(payload.len() <= CRSF_MAX_PAYLOAD_SIZE);
+    encode_frame(&CrsfFrame {
+        frame_type: CRSF_FRAMETYPE_COMMAND,
+        payload,
+    })
+}
+
+/// Encode a model select command for ExpressLRS model match
+///
+/// The TX module forwards the model ID to the receiver, which only accepts
+/// control from a handset whose model ID matches the one it was bound with.
+///
+/// # Arguments
+///
+/// * `model_id` - Model (receiver) ID (0-63)
+///
+/// # Returns
+///
+/// * `Vec<u8>` - Complete 10-byte CRSF command frame
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::crsf::encoder::encode_model_select_frame;
+///
+/// let frame = encode_model_select_frame(3);
+/// assert_eq!(frame.len(), 10);
+/// assert_eq!(frame[7], 3);
+/// ```
+pub fn encode_model_select_frame(model_id: u8) -> Vec<u8> {
+    encode_command_frame(
+        CRSF_COMMAND_SUBCMD_CRSF,
+        CRSF_COMMAND_CRSF_MODEL_SELECT,
+        &[model_id],
+    )
+}
+
+/// Encode a bind command that puts the TX module into bind mode
+///
+/// # Returns
+///
+/// * `Vec<u8>` - Complete 9-byte CRSF command frame
+pub fn encode_bind_frame() -> Vec<u8> {
+    encode_command_frame(CRSF_COMMAND_SUBCMD_CRSF, CRSF_COMMAND_CRSF_BIND, &[])
+}
+
+/// Encode a port speed proposal for the TX module's CRSF port
+///
+/// If the module accepts (general sub-command 0x71 reply), both sides switch
+/// to the new speed; the proposal itself is sent at the current speed.
+///
+/// # Arguments
+///
+/// * `port_id` - CRSF port on the module (0 for the handset UART)
+/// * `baud_rate` - Proposed speed in baud
+///
+/// # Returns
+///
+/// * `Vec<u8>` - Complete 14-byte CRSF command frame
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::crsf::encoder::encode_baud_proposal_frame;
+///
+/// let frame = encode_baud_proposal_frame(0, 1_870_000);
+/// assert_eq!(&frame[5..7], &[0x0A, 0x70]);
+/// assert_eq!(&frame[8..12], &1_870_000u32.to_be_bytes());
+/// ```
+pub fn encode_baud_proposal_frame(port_id: u8, baud_rate: u32) -> Vec<u8> {
+    let mut data = [0u8; 5];
+    data[0] = port_id;
+    data[1..].copy_from_slice(&baud_rate.to_be_bytes());
+    encode_command_frame(CRSF_COMMAND_GENERAL, CRSF_COMMAND_GENERAL_BAUD_PROPOSAL, &data)
+}
+
+/// Encode a device ping broadcast
+///
+/// Every CRSF device that receives it answers with a device info frame
+/// (type 0x29), which identifies an ELRS TX module on an unknown port.
+///
+/// # Returns
+///
+/// * `Vec<u8>` - Complete 6-byte CRSF frame
+pub fn encode_device_ping_frame() -> Vec<u8> {
+    encode_frame(&CrsfFrame {
+        frame_type: CRSF_FRAMETYPE_DEVICE_PING,
+        payload: vec![CRSF_ADDRESS_BROADCAST, CRSF_ADDRESS_RADIO_TRANSMITTER],
+    })
+}
+
+/// Encode the TX module's reply to a device ping
+///
+/// # Arguments
+///
+/// * `device` - Device info; `origin` is the answering device's address
+///
+/// # Returns
+///
+/// * `Vec<u8>` - Complete CRSF frame addressed to the handset
+///
+/// # Payload Layout
+///
+/// ```text
+/// Dest | Origin | Name (NUL-terminated) | Serial (u32 BE) | HW ver (u32 BE) |
+/// SW ver (u32 BE) | Parameter count | Parameter protocol version
+/// ```
+pub fn encode_device_info_frame(device: &DeviceInfo) -> Vec<u8> {
+    let mut payload = vec![CRSF_ADDRESS_RADIO_TRANSMITTER, device.origin];
+    payload.extend_from_slice(device.name.as_bytes());
+    payload.push(0);
+    payload.extend_from_slice(&device.serial_number.to_be_bytes());
+    payload.extend_from_slice(&device.hardware_version.to_be_bytes());
+    payload.extend_from_slice(&device.software_version.to_be_bytes());
+    payload.push(device.parameter_count);
+    payload.push(0);
+
+    encode_frame(&CrsfFrame {
+        frame_type: CRSF_FRAMETYPE_DEVICE_INFO,
+        payload,
+    })
+}
+
+/// Encode the TX module's reply to a port speed proposal
+///
+/// # Arguments
+///
+/// * `port_id` - Port the proposal was for
+/// * `accepted` - Whether the module switches to the proposed speed
+///
+/// # Returns
+///
+/// * `Vec<u8>` - Complete CRSF frame addressed to the handset
+///
+/// # Frame Layout
+///
+/// ```text
+/// Sync | Len | 0x32 | Dest(0xEA) | Origin(0xEE) | 0x0A | 0x71 | Port ID | Accepted | CRC8-BA | CRC8
+/// ```
+pub fn encode_baud_response_frame(port_id: u8, accepted: bool) -> Vec<u8> {
+    let mut payload = vec![
+        CRSF_ADDRESS_RADIO_TRANSMITTER,
+        CRSF_ADDRESS_CRSF_TRANSMITTER,
+        CRSF_COMMAND_GENERAL,
+        CRSF_COMMAND_GENERAL_BAUD_RESPONSE,
+        port_id,
+        u8::from(accepted),
+    ];
+
+    // Inner CRC covers the frame type and the payload so far
+    let mut crc_data = Vec::with_capacity(payload.len() + 1);
+    crc_data.push(CRSF_FRAMETYPE_COMMAND);
+    crc_data.extend_from_slice(&payload);
+    payload.push(crc8_ba(&crc_data));
+
+    encode_frame(&CrsfFrame {
+        frame_type: CRSF_FRAMETYPE_COMMAND,
+        payload,
+    })
+}
+
+/// Encode a Link Statistics telemetry frame
+///
+/// # Arguments
+///
+/// * `stats` - Link statistics
+///
+/// # Returns
+///
+/// * `Vec<u8>` - Complete 14-byte CRSF frame
+pub fn encode_link_statistics_frame(stats: &LinkStatistics) -> Vec<u8> {
+    encode_frame(&CrsfFrame {
+        frame_type: CRSF_FRAMETYPE_LINK_STATISTICS,
+        payload: vec![
+            stats.uplink_rssi_1,
+            stats.uplink_rssi_2,
+            stats.uplink_lq,
+            stats.uplink_snr as u8,
+            stats.active_antenna,
+            stats.rf_mode,
+            stats.uplink_tx_power,
+            stats.downlink_rssi,
+            stats.downlink_lq,
+            stats.downlink_snr as u8,
+        ],
+    })
+}
+
+/// Encode a Battery Sensor telemetry frame
+///
+/// Values are rounded to the wire resolution (0.01V, 0.1A) and saturate at
+/// the field limits.
+///
+/// # Arguments
+///
+/// * `battery` - Battery sensor data
+///
+/// # Returns
+///
+/// * `Vec<u8>` - Complete 12-byte CRSF frame
+pub fn encode_battery_sensor_frame(battery: &BatterySensor) -> Vec<u8> {
+    let voltage_cv = (battery.voltage * 100.0).round() as u16;
+    let current_da = (battery.current * 10.0).round() as u16;
+    let capacity = battery.capacity_used.min(0x00FF_FFFF).to_be_bytes();
+
+    let mut payload = Vec::with_capacity(CRSF_BATTERY_SENSOR_PAYLOAD_SIZE);
+    payload.extend_from_slice(&voltage_cv.to_be_bytes());
+    payload.extend_from_slice(&current_da.to_be_bytes());
+    payload.extend_from_slice(&capacity[1..]);
+    payload.push(battery.remaining_percent);
+
+    encode_frame(&CrsfFrame {
+        frame_type: CRSF_FRAMETYPE_BATTERY_SENSOR,
+        payload,
+    })
+}
+
+/// Encode a GPS telemetry frame
+///
+/// # Arguments
+///
+/// * `gps` - GPS data
+///
+/// # Returns
+///
+/// * `Vec<u8>` - Complete 19-byte CRSF frame
+pub fn encode_gps_frame(gps: &GpsData) -> Vec<u8> {
+    let latitude = (gps.latitude * 10_000_000.0).round() as i32;
+    let longitude = (gps.longitude * 10_000_000.0).round() as i32;
+    let ground_speed = (gps.ground_speed * 10.0).round() as u16;
+    let heading = (gps.heading * 100.0).round() as u16;
+    let altitude = (i32::from(gps.altitude) + 1000).clamp(0, i32::from(u16::MAX)) as u16;
+
+    let mut payload = Vec::with_capacity(CRSF_GPS_PAYLOAD_SIZE);
+    payload.extend_from_slice(&latitude.to_be_bytes());
+    payload.extend_from_slice(&longitude.to_be_bytes());
+    payload.extend_from_slice(&ground_speed.to_be_bytes());
+    payload.extend_from_slice(&heading.to_be_bytes());
+    payload.extend_from_slice(&altitude.to_be_bytes());
+    payload.push(gps.satellites);
+
+    encode_frame(&CrsfFrame {
+        frame_type: CRSF_FRAMETYPE_GPS,
+        payload,
+    })
+}
+
+/// Encode an Attitude telemetry frame
+///
+/// Angles are rounded to the wire resolution (0.0001 rad) and saturate at
+/// the field limits.
+///
+/// # Arguments
+///
+/// * `attitude` - Attitude in radians
+///
+/// # Returns
+///
+/// * `Vec<u8>` - Complete 10-byte CRSF frame
+pub fn encode_attitude_frame(attitude: &Attitude) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(CRSF_ATTITUDE_PAYLOAD_SIZE);
+    for angle in [attitude.pitch, attitude.roll, attitude.yaw] {
+        payload.extend_from_slice(&((angle * 10_000.0).round() as i16).to_be_bytes());
+    }
+
+    encode_frame(&CrsfFrame {
+        frame_type: CRSF_FRAMETYPE_ATTITUDE,
+        payload,
+    })
+}
+
+/// Encode a Flight Mode telemetry frame
+///
+/// # Arguments
+///
+/// * `mode` - Flight mode name, cut to fit the frame
+///
+/// # Returns
+///
+/// * `Vec<u8>` - Complete CRSF frame with the null-terminated name
+pub fn encode_flight_mode_frame(mode: &str) -> Vec<u8> {
+    let mut payload: Vec<u8> = mode.bytes().take(CRSF_MAX_PAYLOAD_SIZE - 1).collect();
+    payload.push(0);
+
+    encode_frame(&CrsfFrame {
+        frame_type: CRSF_FRAMETYPE_FLIGHT_MODE,
+        payload,
+    })
 }
 
 /// Clamp a channel value to valid CRSF range (0-2047)
@@ -106,6 +653,32 @@ pub fn clamp_channel_value(value: u16) -> u16 {
 mod tests {
     use super::*;
 
+    /// Reference bit-by-bit packer (slow, for verification)
+    ///
+    /// This is the original payload encoder, kept to check that
+    /// [`pack_rc_channels`] produces bit-identical output.
+    fn encode_rc_channels_payload_bitwise(channels: &RcChannels) -> Vec<u8> {
+        let mut payload = vec![0u8; CRSF_RC_CHANNELS_PAYLOAD_SIZE];
+        let mut bit_index = 0;
+
+        for &channel in channels.iter() {
+            // Clamp channel value to 11-bit range
+            let value = channel.min(CRSF_CHANNEL_VALUE_MAX);
+
+            // Pack 11 bits
+            for bit in 0..11 {
+                if (value >> bit) & 1 == 1 {
+                    let byte_index = bit_index / 8;
+                    let bit_offset = bit_index % 8;
+                    payload[byte_index] |= 1 << bit_offset;
+                }
+                bit_index += 1;
+            }
+        }
+
+        payload
+    }
+
     #[test]
     fn test_encode_rc_channels_frame_length() {
         let channels = [CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS];
@@ -220,6 +793,255 @@ mod tests {
         assert_eq!(payload[1] & 0x07, 0x07);
     }
 
+    #[test]
+    fn test_encode_frame_matches_rc_channels_encoder() {
+        let channels = [100, 200, 300, 400, 500, 600, 700, 800, 900, 1000, 1100, 1200, 1300, 1400, 1500, 1600];
+        let frame = CrsfFrame::new(
+            CRSF_FRAMETYPE_RC_CHANNELS_PACKED,
+            encode_rc_channels_payload(&channels),
+        )
+        .unwrap();
+
+        assert_eq!(encode_frame(&frame), encode_rc_channels_frame(&channels));
+    }
+
+    #[test]
+    fn test_encode_model_select_frame_structure() {
+        let frame = encode_model_select_frame(7);
+
+        assert_eq!(frame.len(), 10);
+        assert_eq!(frame[0], CRSF_SYNC_BYTE);
+        assert_eq!(frame[1], 8); // type + dest + origin + cmd + subcmd + id + crc_ba + crc
+        assert_eq!(frame[2], CRSF_FRAMETYPE_COMMAND);
+        assert_eq!(frame[3], CRSF_ADDRESS_CRSF_TRANSMITTER);
+        assert_eq!(frame[4], CRSF_ADDRESS_RADIO_TRANSMITTER);
+        assert_eq!(frame[5], CRSF_COMMAND_SUBCMD_CRSF);
+        assert_eq!(frame[6], CRSF_COMMAND_CRSF_MODEL_SELECT);
+        assert_eq!(frame[7], 7);
+        assert_eq!(frame[8], crc8_ba(&frame[2..8]));
+        assert_eq!(frame[9], crc8_dvb_s2(&frame[1..9]));
+    }
+
+    #[test]
+    fn test_encode_model_select_frame_decodes() {
+        use crate::crsf::decoder::decode_frame;
+
+        let frame = encode_model_select_frame(42);
+        let decoded = decode_frame(&frame).unwrap();
+
+        assert_eq!(decoded.frame_type, CRSF_FRAMETYPE_COMMAND);
+        assert_eq!(decoded.payload[4], 42);
+    }
+
+    #[test]
+    fn test_encode_model_select_different_ids_differ() {
+        assert_ne!(encode_model_select_frame(1), encode_model_select_frame(2));
+    }
+
+    #[test]
+    fn test_encode_bind_frame_structure() {
+        let frame = encode_bind_frame();
+
+        assert_eq!(frame.len(), 9);
+        assert_eq!(frame[1], 7);
+        assert_eq!(frame[2], CRSF_FRAMETYPE_COMMAND);
+        assert_eq!(frame[5], CRSF_COMMAND_SUBCMD_CRSF);
+        assert_eq!(frame[6], CRSF_COMMAND_CRSF_BIND);
+        assert_eq!(frame[7], crc8_ba(&frame[2..7]));
+        assert_eq!(frame[8], crc8_dvb_s2(&frame[1..8]));
+    }
+
+    #[test]
+    fn test_encode_device_ping_frame() {
+        let frame = encode_device_ping_frame();
+
+        assert_eq!(frame, vec![
+            CRSF_SYNC_BYTE, 0x04, CRSF_FRAMETYPE_DEVICE_PING,
+            CRSF_ADDRESS_BROADCAST, CRSF_ADDRESS_RADIO_TRANSMITTER,
+            crc8_dvb_s2(&frame[1..5]),
+        ]);
+    }
+
+    #[test]
+    fn test_encode_baud_proposal_frame() {
+        let frame = encode_baud_proposal_frame(0, 3_750_000);
+
+        assert_eq!(frame.len(), 14);
+        assert_eq!(frame[2], CRSF_FRAMETYPE_COMMAND);
+        assert_eq!(frame[3], CRSF_ADDRESS_CRSF_TRANSMITTER);
+        assert_eq!(frame[4], CRSF_ADDRESS_RADIO_TRANSMITTER);
+        assert_eq!(frame[5], CRSF_COMMAND_GENERAL);
+        assert_eq!(frame[6], CRSF_COMMAND_GENERAL_BAUD_PROPOSAL);
+        assert_eq!(frame[7], 0);
+        assert_eq!(&frame[8..12], &[0x00, 0x39, 0x38, 0x70]);
+        assert_eq!(frame[12], crc8_ba(&frame[2..12]));
+        assert_eq!(frame[13], crc8_dvb_s2(&frame[1..13]));
+    }
+
+    #[test]
+    fn test_pack_rc_channels_matches_bitwise_reference() {
+        let channels = [0, 1, 2, 4, 8, 16, 32, 64, 128, 256, 512, 1024, 2047, 1500, 172, 1811];
+        let mut payload = [0u8; CRSF_RC_CHANNELS_PAYLOAD_SIZE];
+        pack_rc_channels(&channels, &mut payload);
+
+        assert_eq!(payload.to_vec(), encode_rc_channels_payload_bitwise(&channels));
+    }
+
+    #[test]
+    fn test_pack_rc_channels_overwrites_buffer() {
+        let mut payload = [0xAAu8; CRSF_RC_CHANNELS_PAYLOAD_SIZE];
+        pack_rc_channels(&[0u16; CRSF_NUM_CHANNELS], &mut payload);
+
+        assert_eq!(payload, [0u8; CRSF_RC_CHANNELS_PAYLOAD_SIZE]);
+    }
+
+    #[test]
+    fn test_encode_rc_channels_frame_into_reused_buffer() {
+        let mut frame = [0u8; CRSF_RC_CHANNELS_FRAME_SIZE];
+
+        encode_rc_channels_frame_into(&[CRSF_CHANNEL_VALUE_MAX; CRSF_NUM_CHANNELS], &mut frame);
+        encode_rc_channels_frame_into(&[CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS], &mut frame);
+
+        assert_eq!(
+            frame.to_vec(),
+            encode_rc_channels_frame(&[CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS])
+        );
+    }
+
+    #[test]
+    fn test_encode_rc_channels_frame_into_decodes() {
+        use crate::crsf::decoder::decode_frame;
+
+        let channels = [100, 200, 300, 400, 500, 600, 700, 800, 900, 1000, 1100, 1200, 1300, 1400, 1500, 1600];
+        let mut frame = [0u8; CRSF_RC_CHANNELS_FRAME_SIZE];
+        encode_rc_channels_frame_into(&channels, &mut frame);
+
+        let decoded = decode_frame(&frame).unwrap();
+        assert_eq!(decoded.frame_type, CRSF_FRAMETYPE_RC_CHANNELS_PACKED);
+        assert_eq!(decoded.payload, encode_rc_channels_payload_bitwise(&channels));
+    }
+
+    #[test]
+    fn test_encode_subset_header_byte() {
+        let subset = SubsetRcChannels {
+            first_channel: 4,
+            resolution: SubsetResolution::Bits12,
+            values: vec![0],
+        };
+        let payload = encode_subset_rc_channels_payload(&subset).unwrap();
+
+        assert_eq!(payload[0], 4 | (2 << 5));
+        assert_eq!(payload.len(), 3); // header + ceil(12 / 8)
+    }
+
+    #[test]
+    fn test_encode_subset_11_bit_matches_rc_channels_payload() {
+        let channels = [100, 200, 300, 400, 500, 600, 700, 800, 900, 1000, 1100, 1200, 1300, 1400, 1500, 1600];
+        let subset = SubsetRcChannels {
+            first_channel: 0,
+            resolution: SubsetResolution::Bits11,
+            values: channels.to_vec(),
+        };
+        let payload = encode_subset_rc_channels_payload(&subset).unwrap();
+
+        assert_eq!(payload[1..], encode_rc_channels_payload(&channels)[..]);
+    }
+
+    #[test]
+    fn test_encode_subset_packs_lsb_first() {
+        let subset = SubsetRcChannels {
+            first_channel: 0,
+            resolution: SubsetResolution::Bits13,
+            values: vec![0x1FFF, 0x0001],
+        };
+        let payload = encode_subset_rc_channels_payload(&subset).unwrap();
+
+        // 26 bits: 13 ones, then a single one at bit 13
+        assert_eq!(payload[1..], [0xFF, 0x3F, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn test_encode_subset_rejects_invalid() {
+        let mut subset = SubsetRcChannels {
+            first_channel: 32,
+            resolution: SubsetResolution::Bits10,
+            values: vec![0],
+        };
+        assert!(encode_subset_rc_channels_frame(&subset).is_err());
+
+        subset.first_channel = 0;
+        subset.values.clear();
+        assert!(encode_subset_rc_channels_frame(&subset).is_err());
+
+        subset.resolution = SubsetResolution::Bits13;
+        subset.values = vec![0; 37]; // 1 + ceil(481 / 8) = 62 bytes
+        assert!(encode_subset_rc_channels_frame(&subset).is_err());
+
+        subset.values = vec![0; 32]; // 1 + 52 bytes
+        assert!(encode_subset_rc_channels_frame(&subset).is_ok());
+    }
+
+    #[test]
+    fn test_encode_subset_frame_decodes() {
+        use crate::crsf::decoder::decode_frame;
+
+        let subset = SubsetRcChannels {
+            first_channel: 0,
+            resolution: SubsetResolution::Bits12,
+            values: vec![4095; CRSF_NUM_CHANNELS],
+        };
+        let frame = encode_subset_rc_channels_frame(&subset).unwrap();
+        let decoded = decode_frame(&frame).unwrap();
+
+        assert_eq!(decoded.frame_type, CRSF_FRAMETYPE_SUBSET_RC_CHANNELS_PACKED);
+        assert_eq!(decoded.payload.len(), 1 + 24);
+        assert!(decoded.payload[1..].iter().all(|&b| b == 0xFF));
+    }
+
+    #[test]
+    fn test_subset_frame_into_reuses_buffer() {
+        let mut frame = Vec::new();
+        for (first_channel, resolution, len) in [
+            (0, SubsetResolution::Bits12, 4),
+            (4, SubsetResolution::Bits10, 12),
+            (0, SubsetResolution::Bits13, 16),
+        ] {
+            let subset = SubsetRcChannels { first_channel, resolution, values: vec![1234; len] };
+            encode_subset_rc_channels_frame_into(&subset, &mut frame).unwrap();
+            assert_eq!(frame, encode_subset_rc_channels_frame(&subset).unwrap());
+        }
+
+        let invalid = SubsetRcChannels { first_channel: 32, resolution: SubsetResolution::Bits11, values: vec![0] };
+        assert!(encode_subset_rc_channels_frame_into(&invalid, &mut frame).is_err());
+        assert!(frame.is_empty());
+    }
+
+    mod proptests {
+        use super::*;
+        use proptest::prelude::*;
+
+        proptest! {
+            #[test]
+            fn pack_matches_bitwise_reference(channels in any::<[u16; CRSF_NUM_CHANNELS]>()) {
+                let mut payload = [0u8; CRSF_RC_CHANNELS_PAYLOAD_SIZE];
+                pack_rc_channels(&channels, &mut payload);
+                prop_assert_eq!(payload.to_vec(), encode_rc_channels_payload_bitwise(&channels));
+            }
+
+            #[test]
+            fn frame_into_matches_vec_encoder(channels in any::<[u16; CRSF_NUM_CHANNELS]>()) {
+                let mut frame = [0u8; CRSF_RC_CHANNELS_FRAME_SIZE];
+                encode_rc_channels_frame_into(&channels, &mut frame);
+
+                let payload = encode_rc_channels_payload_bitwise(&channels);
+                let expected = encode_frame(
+                    &CrsfFrame::new(CRSF_FRAMETYPE_RC_CHANNELS_PACKED, payload).unwrap(),
+                );
+                prop_assert_eq!(frame.to_vec(), expected);
+            }
+        }
+    }
+
     #[test]
     fn test_encode_frame_different_data_different_crc() {
         let channels1 = [1000u16; CRSF_NUM_CHANNELS];
@@ -231,4 +1053,98 @@ mod tests {
         // Frames should have different CRCs
         assert_ne!(frame1[25], frame2[25]);
     }
+
+    #[test]
+    fn test_encode_device_info_frame_round_trip() {
+        use crate::crsf::decoder::{decode_device_info, decode_frame};
+
+        let device = DeviceInfo {
+            origin: CRSF_ADDRESS_CRSF_TRANSMITTER,
+            name: "ELRS TX".to_string(),
+            serial_number: u32::from_be_bytes(*b"ELRS"),
+            hardware_version: 1,
+            software_version: 0x0003_0400,
+            parameter_count: 0,
+        };
+        let frame = decode_frame(&encode_device_info_frame(&device)).unwrap();
+
+        assert_eq!(frame.frame_type, CRSF_FRAMETYPE_DEVICE_INFO);
+        assert_eq!(frame.payload[0], CRSF_ADDRESS_RADIO_TRANSMITTER);
+        assert_eq!(decode_device_info(&frame.payload).unwrap(), device);
+    }
+
+    #[test]
+    fn test_encode_baud_response_frame_round_trip() {
+        use crate::crsf::decoder::{decode_baud_response, decode_frame};
+
+        for accepted in [true, false] {
+            let frame = decode_frame(&encode_baud_response_frame(0, accepted)).unwrap();
+            let response = decode_baud_response(&frame.payload).unwrap();
+            assert_eq!(response, BaudResponse { port_id: 0, accepted });
+        }
+    }
+
+    #[test]
+    fn test_encode_telemetry_frames_round_trip() {
+        use crate::crsf::decoder::{
+            decode_attitude, decode_battery_sensor, decode_flight_mode, decode_frame, decode_gps,
+            decode_link_statistics,
+        };
+
+        let stats = LinkStatistics {
+            uplink_rssi_1: 45,
+            uplink_rssi_2: 60,
+            uplink_lq: 98,
+            uplink_snr: -3,
+            active_antenna: 1,
+            rf_mode: 7,
+            uplink_tx_power: 3,
+            downlink_rssi: 70,
+            downlink_lq: 100,
+            downlink_snr: 5,
+        };
+        let frame = decode_frame(&encode_link_statistics_frame(&stats)).unwrap();
+        assert_eq!(frame.frame_type, CRSF_FRAMETYPE_LINK_STATISTICS);
+        assert_eq!(decode_link_statistics(&frame.payload).unwrap(), stats);
+
+        let battery = BatterySensor { voltage: 16.42, current: 12.5, capacity_used: 1250, remaining_percent: 64 };
+        let frame = decode_frame(&encode_battery_sensor_frame(&battery)).unwrap();
+        assert_eq!(frame.frame_type, CRSF_FRAMETYPE_BATTERY_SENSOR);
+        let decoded = decode_battery_sensor(&frame.payload).unwrap();
+        assert!((decoded.voltage - 16.42).abs() < 0.001);
+        assert!((decoded.current - 12.5).abs() < 0.001);
+        assert_eq!(decoded.capacity_used, 1250);
+        assert_eq!(decoded.remaining_percent, 64);
+
+        let gps = GpsData {
+            latitude: 50.450_001_2,
+            longitude: -30.523_333_3,
+            ground_speed: 42.5,
+            heading: 270.25,
+            altitude: -12,
+            satellites: 11,
+        };
+        let frame = decode_frame(&encode_gps_frame(&gps)).unwrap();
+        assert_eq!(frame.frame_type, CRSF_FRAMETYPE_GPS);
+        let decoded = decode_gps(&frame.payload).unwrap();
+        assert!((decoded.latitude - gps.latitude).abs() < 1e-7);
+        assert!((decoded.longitude - gps.longitude).abs() < 1e-7);
+        assert!((decoded.ground_speed - 42.5).abs() < 0.01);
+        assert!((decoded.heading - 270.25).abs() < 0.01);
+        assert_eq!(decoded.altitude, -12);
+        assert_eq!(decoded.satellites, 11);
+
+        let attitude = Attitude { pitch: -0.25, roll: 1.5, yaw: 3.0 };
+        let frame = decode_frame(&encode_attitude_frame(&attitude)).unwrap();
+        assert_eq!(frame.frame_type, CRSF_FRAMETYPE_ATTITUDE);
+        let decoded = decode_attitude(&frame.payload).unwrap();
+        assert!((decoded.pitch - attitude.pitch).abs() < 1e-4);
+        assert!((decoded.roll - attitude.roll).abs() < 1e-4);
+        assert!((decoded.yaw - attitude.yaw).abs() < 1e-4);
+
+        let frame = decode_frame(&encode_flight_mode_frame("ANGL")).unwrap();
+        assert_eq!(frame.frame_type, CRSF_FRAMETYPE_FLIGHT_MODE);
+        assert_eq!(frame.payload, b"ANGL\0");
+        assert_eq!(decode_flight_mode(&frame.payload).unwrap(), "ANGL");
+    }
 }
@@ -2,6 +2,8 @@
 //!
 //! Core protocol definitions for CRSF (Crossfire) communication.
 
+use serde::Serialize;
+
 use crate::error::{FpvBridgeError, Result};
 
 /// CRSF frame sync byte (always 0xC8)
@@ -10,20 +12,95 @@ pub const CRSF_SYNC_BYTE: u8 = 0xC8;
 /// RC Channels packet type
 pub const CRSF_FRAMETYPE_RC_CHANNELS_PACKED: u8 = 0x16;
 
+/// Subset RC Channels packet type (starting channel + variable resolution)
+pub const CRSF_FRAMETYPE_SUBSET_RC_CHANNELS_PACKED: u8 = 0x17;
+
 /// Link Statistics packet type
 pub const CRSF_FRAMETYPE_LINK_STATISTICS: u8 = 0x14;
 
+/// GPS telemetry packet type
+pub const CRSF_FRAMETYPE_GPS: u8 = 0x02;
+
+/// Battery Sensor telemetry packet type
+pub const CRSF_FRAMETYPE_BATTERY_SENSOR: u8 = 0x08;
+
+/// Attitude telemetry packet type
+pub const CRSF_FRAMETYPE_ATTITUDE: u8 = 0x1E;
+
+/// Flight mode telemetry packet type (null-terminated string)
+pub const CRSF_FRAMETYPE_FLIGHT_MODE: u8 = 0x21;
+
+/// Radio ID packet type (extended header), carries timing sync from the TX module
+pub const CRSF_FRAMETYPE_RADIO_ID: u8 = 0x3A;
+
+/// Command packet type (extended header with destination and origin)
+pub const CRSF_FRAMETYPE_COMMAND: u8 = 0x32;
+
+/// Device ping packet type (extended header), answered with device info
+pub const CRSF_FRAMETYPE_DEVICE_PING: u8 = 0x28;
+
+/// Device info packet type (extended header), reply to a device ping
+pub const CRSF_FRAMETYPE_DEVICE_INFO: u8 = 0x29;
+
+/// Broadcast device address
+pub const CRSF_ADDRESS_BROADCAST: u8 = 0x00;
+
+/// Flight controller device address
+pub const CRSF_ADDRESS_FLIGHT_CONTROLLER: u8 = 0xC8;
+
+/// Radio transmitter (handset) device address - the role FPV Bridge plays
+pub const CRSF_ADDRESS_RADIO_TRANSMITTER: u8 = 0xEA;
+
+/// CRSF receiver device address
+pub const CRSF_ADDRESS_CRSF_RECEIVER: u8 = 0xEC;
+
+/// CRSF transmitter module device address (the ELRS TX module)
+pub const CRSF_ADDRESS_CRSF_TRANSMITTER: u8 = 0xEE;
+
+/// Command ID for CRSF sub-commands (bind, model select, ...)
+pub const CRSF_COMMAND_SUBCMD_CRSF: u8 = 0x10;
+
+/// CRSF sub-command: enter bind mode
+pub const CRSF_COMMAND_CRSF_BIND: u8 = 0x01;
+
+/// CRSF sub-command: select model (receiver) ID for model match
+pub const CRSF_COMMAND_CRSF_MODEL_SELECT: u8 = 0x05;
+
+/// Command ID for general commands (baud rate negotiation)
+pub const CRSF_COMMAND_GENERAL: u8 = 0x0A;
+
+/// General sub-command: propose a new CRSF port speed
+pub const CRSF_COMMAND_GENERAL_BAUD_PROPOSAL: u8 = 0x70;
+
+/// General sub-command: reply to a port speed proposal
+pub const CRSF_COMMAND_GENERAL_BAUD_RESPONSE: u8 = 0x71;
+
+/// Highest model ID accepted by ExpressLRS model match
+pub const CRSF_MODEL_ID_MAX: u8 = 63;
+
+/// Radio ID sub-type: timing sync (a.k.a. OpenTX sync)
+pub const CRSF_RADIO_ID_TIMING_SYNC: u8 = 0x10;
+
+/// Timing sync payload size (dest + origin + sub-type + interval(4) + offset(4))
+pub const CRSF_TIMING_SYNC_PAYLOAD_SIZE: usize = 11;
+
 /// Maximum CRSF payload size
 /// Frame structure: sync(1) + length(1) + type(1) + payload(N) + crc(1)
 /// Maximum frame size is 64 bytes, so max payload = 64 - 4 = 60 bytes
 pub const CRSF_MAX_PAYLOAD_SIZE: usize = 60;
 
+/// Maximum complete frame size (sync + length + type + payload + crc)
+pub const CRSF_MAX_FRAME_SIZE: usize = CRSF_MAX_PAYLOAD_SIZE + 4;
+
 /// RC channels payload size (22 bytes for 16 channels × 11 bits)
 pub const CRSF_RC_CHANNELS_PAYLOAD_SIZE: usize = 22;
 
 /// RC channels frame length (type + payload + crc)
 pub const CRSF_RC_CHANNELS_FRAME_LENGTH: u8 = 0x18; // 24 bytes
 
+/// Complete RC channels frame size (sync + length + type + payload + crc)
+pub const CRSF_RC_CHANNELS_FRAME_SIZE: usize = 26;
+
 /// Number of RC channels
 pub const CRSF_NUM_CHANNELS: usize = 16;
 
@@ -32,6 +109,23 @@ pub const CRSF_CHANNEL_VALUE_MIN: u16 = 0;
 pub const CRSF_CHANNEL_VALUE_MAX: u16 = 2047;
 pub const CRSF_CHANNEL_VALUE_CENTER: u16 = 1024;
 
+/// Pulse width a flight controller reads for a channel value, in µs
+///
+/// Uses the standard CRSF scaling: 992 is 1500µs, 5/8µs per step.
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::crsf::protocol::crsf_value_to_us;
+///
+/// assert_eq!(crsf_value_to_us(172), 988);
+/// assert_eq!(crsf_value_to_us(992), 1500);
+/// assert_eq!(crsf_value_to_us(1811), 2011);
+/// ```
+pub fn crsf_value_to_us(value: u16) -> i32 {
+    1500 + (i32::from(value) - 992) * 5 / 8
+}
+
 /// Link Statistics payload size
 pub const CRSF_LINK_STATS_PAYLOAD_SIZE: usize = 10;
 
@@ -41,11 +135,178 @@ pub const CRSF_BATTERY_SENSOR_PAYLOAD_SIZE: usize = 8;
 /// GPS payload size
 pub const CRSF_GPS_PAYLOAD_SIZE: usize = 15;
 
+/// Attitude payload size
+pub const CRSF_ATTITUDE_PAYLOAD_SIZE: usize = 6;
+
 /// RC channels array type (16 channels, 11-bit values)
 pub type RcChannels = [u16; CRSF_NUM_CHANNELS];
 
-/// Link statistics telemetry data
+/// Packed RC channels payload (16 × 11 bits)
+pub type RcChannelsPayload = [u8; CRSF_RC_CHANNELS_PAYLOAD_SIZE];
+
+/// Complete RC channels frame, ready to write to the serial port
+pub type RcChannelsFrame = [u8; CRSF_RC_CHANNELS_FRAME_SIZE];
+
+/// Highest starting channel a subset RC channels frame can address (5 bits)
+pub const CRSF_SUBSET_RC_MAX_FIRST_CHANNEL: u8 = 31;
+
+/// Channel resolution of a subset RC channels frame (0x17)
+///
+/// Values share the scale of the 11-bit RC channels frame (0x16), with one
+/// bit fewer or up to two extra fractional bits: a 12-bit value of 2048 is
+/// the same stick position as an 11-bit value of 1024.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubsetResolution {
+    /// 10 bits per channel (0-1023)
+    Bits10,
+    /// 11 bits per channel (0-2047), same as the 0x16 frame
+    Bits11,
+    /// 12 bits per channel (0-4095)
+    Bits12,
+    /// 13 bits per channel (0-8191)
+    Bits13,
+}
+
+impl SubsetResolution {
+    /// Resolution for a bit count (10-13)
+    pub fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            10 => Some(Self::Bits10),
+            11 => Some(Self::Bits11),
+            12 => Some(Self::Bits12),
+            13 => Some(Self::Bits13),
+            _ => None,
+        }
+    }
+
+    /// Resolution from the 2-bit configuration field of the frame header
+    pub fn from_header(config: u8) -> Self {
+        match config & 0x03 {
+            0 => Self::Bits10,
+            1 => Self::Bits11,
+            2 => Self::Bits12,
+            _ => Self::Bits13,
+        }
+    }
+
+    /// 2-bit configuration field for the frame header
+    pub fn header(self) -> u8 {
+        self.bits() - 10
+    }
+
+    /// Bits per channel
+    pub fn bits(self) -> u8 {
+        match self {
+            Self::Bits10 => 10,
+            Self::Bits11 => 11,
+            Self::Bits12 => 12,
+            Self::Bits13 => 13,
+        }
+    }
+
+    /// Largest channel value at this resolution
+    pub fn max_value(self) -> u16 {
+        (1 << self.bits()) - 1
+    }
+
+    /// Converts an 11-bit channel value (0-2047) to this resolution
+    pub fn from_crsf_value(self, value: u16) -> u16 {
+        let value = value.min(CRSF_CHANNEL_VALUE_MAX);
+        match self {
+            Self::Bits10 => value >> 1,
+            _ => value << (self.bits() - 11),
+        }
+    }
+
+    /// Converts a value at this resolution to an 11-bit channel value (0-2047)
+    pub fn to_crsf_value(self, value: u16) -> u16 {
+        let value = value.min(self.max_value());
+        match self {
+            Self::Bits10 => value << 1,
+            _ => value >> (self.bits() - 11),
+        }
+    }
+}
+
+/// Decoded subset RC channels frame (0x17)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubsetRcChannels {
+    /// Index of the first channel carried (0-based, 0-31)
+    pub first_channel: u8,
+
+    /// Bits per channel value
+    pub resolution: SubsetResolution,
+
+    /// Consecutive channel values starting at `first_channel`
+    pub values: Vec<u16>,
+}
+
+impl SubsetRcChannels {
+    /// Payload size in bytes: header byte plus the packed channel bits
+    pub fn payload_size(&self) -> usize {
+        1 + (self.values.len() * self.resolution.bits() as usize).div_ceil(8)
+    }
+
+    /// Writes the carried channels into a 16-channel array as 11-bit values
+    ///
+    /// Channels beyond channel 16 are ignored.
+    pub fn apply_to(&self, channels: &mut RcChannels) {
+        let first = self.first_channel as usize;
+        for (channel, &value) in channels.iter_mut().skip(first).zip(&self.values) {
+            *channel = self.resolution.to_crsf_value(value);
+        }
+    }
+}
+
+/// Timing sync reported by the TX module in a RADIO_ID frame
+///
+/// The module measures when RC frames arrive relative to its RF packets and
+/// asks the handset to send at `refresh_interval_ns`, shifted by `offset_ns`.
+/// A positive offset means frames arrive earlier than needed, so the next
+/// frames should be sent later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimingSync {
+    /// RF packet interval the handset should match, in nanoseconds
+    pub refresh_interval_ns: u64,
+
+    /// Phase offset of the handset's frames, in nanoseconds
+    pub offset_ns: i64,
+}
+
+/// Device info reported in reply to a device ping
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceInfo {
+    /// Address of the device that answered (0xEE for the TX module)
+    pub origin: u8,
+
+    /// Device name (e.g. "ELRS TX")
+    pub name: String,
+
+    /// Serial number (ExpressLRS reports the "ELRS" magic here)
+    pub serial_number: u32,
+
+    /// Hardware version
+    pub hardware_version: u32,
+
+    /// Firmware version
+    pub software_version: u32,
+
+    /// Number of configurable parameters
+    pub parameter_count: u8,
+}
+
+/// Reply to a CRSF port speed proposal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BaudResponse {
+    /// Port the proposal was for
+    pub port_id: u8,
+
+    /// Whether the device switches to the proposed speed
+    pub accepted: bool,
+}
+
+/// Link statistics telemetry data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub struct LinkStatistics {
     /// Uplink RSSI (antenna 1) in -dBm
     pub uplink_rssi_1: u8,
@@ -78,8 +339,30 @@ pub struct LinkStatistics {
     pub downlink_snr: i8,
 }
 
+/// TX power levels in mW, indexed by the encoded `uplink_tx_power`
+const TX_POWER_MW: [u16; 9] = [0, 10, 25, 100, 500, 1000, 2000, 250, 50];
+
+impl LinkStatistics {
+    /// Uplink TX power in mW, or `None` for an unknown encoding
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use fpv_bridge::crsf::protocol::LinkStatistics;
+    /// # let mut stats = LinkStatistics {
+    /// #     uplink_rssi_1: 50, uplink_rssi_2: 50, uplink_lq: 100, uplink_snr: 9, active_antenna: 0,
+    /// #     rf_mode: 7, uplink_tx_power: 0, downlink_rssi: 45, downlink_lq: 100, downlink_snr: 6,
+    /// # };
+    /// stats.uplink_tx_power = 3;
+    /// assert_eq!(stats.uplink_tx_power_mw(), Some(100));
+    /// ```
+    pub fn uplink_tx_power_mw(&self) -> Option<u16> {
+        TX_POWER_MW.get(usize::from(self.uplink_tx_power)).copied()
+    }
+}
+
 /// Battery sensor telemetry data
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
 pub struct BatterySensor {
     /// Battery voltage in volts
     pub voltage: f32,
@@ -95,7 +378,7 @@ pub struct BatterySensor {
 }
 
 /// GPS telemetry data
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
 pub struct GpsData {
     /// Latitude in degrees
     pub latitude: f64,
@@ -116,6 +399,19 @@ pub struct GpsData {
     pub satellites: u8,
 }
 
+/// Attitude telemetry data
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Attitude {
+    /// Pitch in radians
+    pub pitch: f32,
+
+    /// Roll in radians
+    pub roll: f32,
+
+    /// Yaw in radians
+    pub yaw: f32,
+}
+
 /// CRSF frame structure
 #[derive(Debug, Clone)]
 pub struct CrsfFrame {
@@ -178,9 +474,105 @@ mod tests {
         assert_eq!(CRSF_SYNC_BYTE, 0xC8);
         assert_eq!(CRSF_FRAMETYPE_RC_CHANNELS_PACKED, 0x16);
         assert_eq!(CRSF_FRAMETYPE_LINK_STATISTICS, 0x14);
+        assert_eq!(CRSF_FRAMETYPE_GPS, 0x02);
+        assert_eq!(CRSF_FRAMETYPE_BATTERY_SENSOR, 0x08);
         assert_eq!(CRSF_NUM_CHANNELS, 16);
     }
 
+    #[test]
+    fn test_uplink_tx_power_mw() {
+        let mut stats = LinkStatistics {
+            uplink_rssi_1: 50,
+            uplink_rssi_2: 50,
+            uplink_lq: 100,
+            uplink_snr: 9,
+            active_antenna: 0,
+            rf_mode: 7,
+            uplink_tx_power: 7,
+            downlink_rssi: 45,
+            downlink_lq: 100,
+            downlink_snr: 6,
+        };
+        assert_eq!(stats.uplink_tx_power_mw(), Some(250));
+        stats.uplink_tx_power = 8;
+        assert_eq!(stats.uplink_tx_power_mw(), Some(50));
+        stats.uplink_tx_power = 9;
+        assert_eq!(stats.uplink_tx_power_mw(), None);
+    }
+
+    #[test]
+    fn test_rc_channels_frame_size() {
+        assert_eq!(
+            CRSF_RC_CHANNELS_FRAME_SIZE,
+            2 + CRSF_RC_CHANNELS_FRAME_LENGTH as usize
+        );
+        assert_eq!(CRSF_RC_CHANNELS_PAYLOAD_SIZE * 8, CRSF_NUM_CHANNELS * 11);
+    }
+
+    #[test]
+    fn test_subset_resolution_header_round_trip() {
+        for bits in 10..=13 {
+            let resolution = SubsetResolution::from_bits(bits).unwrap();
+            assert_eq!(resolution.bits(), bits);
+            assert_eq!(SubsetResolution::from_header(resolution.header()), resolution);
+        }
+        assert_eq!(SubsetResolution::from_bits(9), None);
+        assert_eq!(SubsetResolution::from_bits(14), None);
+    }
+
+    #[test]
+    fn test_subset_resolution_scaling() {
+        assert_eq!(SubsetResolution::Bits12.max_value(), 4095);
+        assert_eq!(SubsetResolution::Bits12.from_crsf_value(1024), 2048);
+        assert_eq!(SubsetResolution::Bits13.from_crsf_value(2047), 8188);
+        assert_eq!(SubsetResolution::Bits10.from_crsf_value(1024), 512);
+        assert_eq!(SubsetResolution::Bits11.from_crsf_value(5000), 2047);
+
+        assert_eq!(SubsetResolution::Bits12.to_crsf_value(4095), 2047);
+        assert_eq!(SubsetResolution::Bits13.to_crsf_value(4100), 1025);
+        assert_eq!(SubsetResolution::Bits10.to_crsf_value(1023), 2046);
+        assert_eq!(SubsetResolution::Bits11.to_crsf_value(u16::MAX), 2047);
+
+        for value in CRSF_CHANNEL_VALUE_MIN..=CRSF_CHANNEL_VALUE_MAX {
+            for resolution in [SubsetResolution::Bits11, SubsetResolution::Bits12, SubsetResolution::Bits13] {
+                assert_eq!(resolution.to_crsf_value(resolution.from_crsf_value(value)), value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_subset_rc_channels_apply_to() {
+        let subset = SubsetRcChannels {
+            first_channel: 14,
+            resolution: SubsetResolution::Bits12,
+            values: vec![4095, 0, 2048],
+        };
+        let mut channels = [CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS];
+        subset.apply_to(&mut channels);
+
+        assert_eq!(subset.payload_size(), 1 + 5);
+        assert_eq!(channels[13], CRSF_CHANNEL_VALUE_CENTER);
+        assert_eq!(channels[14], 2047);
+        assert_eq!(channels[15], 0);
+    }
+
+    #[test]
+    fn test_radio_id_constants() {
+        assert_eq!(CRSF_FRAMETYPE_RADIO_ID, 0x3A);
+        assert_eq!(CRSF_RADIO_ID_TIMING_SYNC, 0x10);
+        assert_eq!(CRSF_TIMING_SYNC_PAYLOAD_SIZE, 11);
+    }
+
+    #[test]
+    fn test_command_constants() {
+        assert_eq!(CRSF_FRAMETYPE_COMMAND, 0x32);
+        assert_eq!(CRSF_ADDRESS_CRSF_TRANSMITTER, 0xEE);
+        assert_eq!(CRSF_ADDRESS_RADIO_TRANSMITTER, 0xEA);
+        assert_eq!(CRSF_COMMAND_SUBCMD_CRSF, 0x10);
+        assert_eq!(CRSF_COMMAND_CRSF_BIND, 0x01);
+        assert_eq!(CRSF_COMMAND_CRSF_MODEL_SELECT, 0x05);
+    }
+
     #[test]
     fn test_crsf_frame() {
         let frame = CrsfFrame::new(CRSF_FRAMETYPE_RC_CHANNELS_PACKED, vec![0u8; 22]).unwrap();
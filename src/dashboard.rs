@@ -0,0 +1,788 @@
+//! # Terminal Dashboard
+//!
+//! Full-screen live view of a bridge run (`fpv-bridge --tui`): the 16
+//! channels as bars in µs, arm and failsafe state, the input and the
+//! controller battery, link statistics, flight-pack battery, GPS, attitude
+//! and flight mode telemetry, transmit timing and errors, and the latest
+//! log lines.
+//!
+//! The control loop publishes a [`BridgeStatus`] on a `watch` channel next
+//! to the controller state it reads. [`run_dashboard`] subscribes to both
+//! and redraws every [`REFRESH_INTERVAL`], so drawing never blocks the
+//! control loop. While the dashboard is up, log lines go to a [`LogTail`]
+//! instead of the terminal.
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::watch;
+use tokio::time::{interval, Duration, Instant, MissedTickBehavior};
+
+use crate::controller::channel_mapper::channels;
+use crate::controller::mapper::ControllerState;
+use crate::controller::ps5::{read_battery, ControllerBattery};
+use crate::crsf::decoder::{
+    decode_attitude, decode_battery_sensor, decode_flight_mode, decode_gps, decode_link_statistics,
+};
+use crate::crsf::protocol::{
+    crsf_value_to_us, Attitude, BatterySensor, CrsfFrame, GpsData, LinkStatistics, RcChannels,
+    CRSF_CHANNEL_VALUE_CENTER, CRSF_FRAMETYPE_ATTITUDE, CRSF_FRAMETYPE_BATTERY_SENSOR, CRSF_FRAMETYPE_FLIGHT_MODE,
+    CRSF_FRAMETYPE_GPS, CRSF_FRAMETYPE_LINK_STATISTICS, CRSF_NUM_CHANNELS,
+};
+use crate::error::Result;
+use crate::latency::LatencySummary;
+use crate::navigation::{Navigation, DEFAULT_HOME_MIN_SATELLITES};
+use crate::scheduler::TickReport;
+use crate::serial::TxQueueStats;
+
+/// How often the dashboard is redrawn (~20Hz)
+pub const REFRESH_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How often the controller battery is read
+const BATTERY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Log lines kept by [`LogTail`]
+const LOG_TAIL_LINES: usize = 200;
+
+/// Log lines shown at the bottom of the dashboard
+const LOG_LINES_SHOWN: usize = 6;
+
+/// Characters of a log line shown before it is cut off
+const LOG_LINE_WIDTH: usize = 100;
+
+/// Width of a channel bar in characters
+const BAR_WIDTH: usize = 20;
+
+/// Channel bar range in µs
+const BAR_MIN_US: i32 = 988;
+const BAR_MAX_US: i32 = 2012;
+
+/// Switch to the alternate screen and hide the cursor
+const ENTER_SCREEN: &str = "\x1b[?1049h\x1b[?25l";
+
+/// Show the cursor and return to the normal screen
+const LEAVE_SCREEN: &str = "\x1b[?25h\x1b[?1049l";
+
+/// State of a bridge run, published by the control loop
+#[derive(Debug, Clone)]
+pub struct BridgeStatus {
+    /// Active model profile
+    pub profile: String,
+    /// Failsafe timeout of the active profile
+    pub failsafe_timeout: Duration,
+    /// Channels of the last frame sent
+    pub channels: RcChannels,
+    /// RC frames sent since start
+    pub frames_sent: u64,
+    /// Failed sends since start
+    pub send_errors: u64,
+    /// Failed sends since the last successful one
+    pub consecutive_failures: u32,
+    /// Serial output stalled for longer than the failsafe timeout
+    pub stalled: bool,
+    /// Last error message of a failed send
+    pub last_error: Option<String>,
+    /// Transmit timing of the last complete second
+    pub tx: Option<TickReport>,
+    /// Serial output queue counters (`None` without a module)
+    pub serial: Option<TxQueueStats>,
+    /// Input-to-flush latency of the last complete second (`None` while
+    /// no input events arrive)
+    pub latency: Option<LatencySummary>,
+    /// Latest link statistics and when they arrived
+    pub link: Option<(LinkStatistics, Instant)>,
+    /// Latest flight-pack battery telemetry
+    pub battery: Option<BatterySensor>,
+    /// Latest GPS telemetry
+    pub gps: Option<GpsData>,
+    /// Latest attitude telemetry
+    pub attitude: Option<Attitude>,
+    /// Latest flight mode reported by the flight controller
+    pub flight_mode: Option<String>,
+    /// Home position and flight statistics from the GPS telemetry
+    pub navigation: Navigation,
+    /// Arm button held off after a remote disarm
+    pub disarm_latched: bool,
+}
+
+impl BridgeStatus {
+    /// Status before the first frame is sent
+    pub fn new(profile: &str, failsafe_timeout: Duration) -> Self {
+        Self {
+            profile: profile.to_string(),
+            failsafe_timeout,
+            channels: [CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS],
+            frames_sent: 0,
+            send_errors: 0,
+            consecutive_failures: 0,
+            stalled: false,
+            last_error: None,
+            tx: None,
+            serial: None,
+            latency: None,
+            link: None,
+            battery: None,
+            gps: None,
+            attitude: None,
+            flight_mode: None,
+            navigation: Navigation::new(DEFAULT_HOME_MIN_SATELLITES),
+            disarm_latched: false,
+        }
+    }
+
+    /// Take link statistics, battery, GPS, attitude or flight mode
+    /// telemetry from a module frame
+    ///
+    /// GPS updates also go to [`navigation`](Self::navigation).
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool>` - Whether the frame was telemetry shown here
+    ///
+    /// # Errors
+    ///
+    /// Returns `CrsfProtocol` error if a telemetry payload does not decode.
+    pub fn apply_telemetry(&mut self, frame: &CrsfFrame, now: Instant) -> Result<bool> {
+        match frame.frame_type {
+            CRSF_FRAMETYPE_LINK_STATISTICS => self.link = Some((decode_link_statistics(&frame.payload)?, now)),
+            CRSF_FRAMETYPE_BATTERY_SENSOR => self.battery = Some(decode_battery_sensor(&frame.payload)?),
+            CRSF_FRAMETYPE_GPS => {
+                let gps = decode_gps(&frame.payload)?;
+                self.gps = Some(gps);
+                self.navigation.update(&gps, now);
+            }
+            CRSF_FRAMETYPE_ATTITUDE => self.attitude = Some(decode_attitude(&frame.payload)?),
+            CRSF_FRAMETYPE_FLIGHT_MODE => self.flight_mode = Some(decode_flight_mode(&frame.payload)?),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Record a successfully sent frame
+    pub fn record_sent(&mut self, channels: &RcChannels) {
+        self.channels = *channels;
+        self.frames_sent += 1;
+        self.consecutive_failures = 0;
+        self.stalled = false;
+        // Flights follow the arm switch: the flight mode telemetry lags
+        // behind it, and would briefly report the disarmed `*` after arming
+        let armed = self.armed();
+        self.navigation.set_armed(armed, self.gps.as_ref(), Instant::now());
+    }
+
+    /// Record a failed send
+    pub fn record_error(&mut self, error: &str, stalled: bool) {
+        self.send_errors += 1;
+        self.consecutive_failures += 1;
+        self.stalled = stalled;
+        self.last_error = Some(error.to_string());
+    }
+
+    /// Whether the arm channel is on
+    pub fn armed(&self) -> bool {
+        self.channels[channels::ARM] > CRSF_CHANNEL_VALUE_CENTER
+    }
+
+    /// Reasons the drone is, or is about to be, in failsafe
+    pub fn failsafe_reasons(&self, now: Instant) -> Vec<&'static str> {
+        let mut reasons = Vec::new();
+        if self.stalled {
+            reasons.push("serial output stalled");
+        }
+        match self.link {
+            Some((stats, _)) if stats.uplink_lq == 0 => reasons.push("link lost"),
+            Some((_, at)) if now.duration_since(at) > self.failsafe_timeout => reasons.push("no link telemetry"),
+            _ => {}
+        }
+        reasons
+    }
+}
+
+/// Input side of the dashboard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputStatus {
+    /// Input description (controller path, script or session)
+    pub name: String,
+    /// Whether the input is still delivering events (a finished script or
+    /// a failed controller is not; the control loop holds its last state)
+    pub connected: bool,
+    /// DualSense battery, if reported
+    pub battery: Option<ControllerBattery>,
+}
+
+/// Shared buffer of the latest log lines
+///
+/// Used as the `tracing` writer while the dashboard is up. After
+/// [`LogTail::detach`] it prints the kept lines to stderr and forwards
+/// everything written later.
+#[derive(Debug, Clone, Default)]
+pub struct LogTail {
+    inner: Arc<Mutex<LogTailInner>>,
+}
+
+#[derive(Debug, Default)]
+struct LogTailInner {
+    lines: VecDeque<String>,
+    partial: String,
+    detached: bool,
+}
+
+impl LogTail {
+    /// Empty log tail
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The last `count` complete lines
+    pub fn lines(&self, count: usize) -> Vec<String> {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.lines.iter().skip(inner.lines.len().saturating_sub(count)).cloned().collect()
+    }
+
+    /// Print the kept lines to stderr and forward later writes there
+    pub fn detach(&self) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if inner.detached {
+            return;
+        }
+        inner.detached = true;
+        let mut stderr = std::io::stderr().lock();
+        for line in inner.lines.drain(..) {
+            let _ = writeln!(stderr, "{}", line);
+        }
+        if !inner.partial.is_empty() {
+            let _ = write!(stderr, "{}", std::mem::take(&mut inner.partial));
+        }
+    }
+}
+
+impl Write for LogTail {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if inner.detached {
+            return std::io::stderr().write(buf);
+        }
+
+        inner.partial.push_str(&String::from_utf8_lossy(buf));
+        while let Some(end) = inner.partial.find('\n') {
+            let line = inner.partial[..end].trim_end().to_string();
+            inner.partial.drain(..=end);
+            if inner.lines.len() == LOG_TAIL_LINES {
+                inner.lines.pop_front();
+            }
+            inner.lines.push_back(line);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Bar of `us` µs between [`BAR_MIN_US`] and [`BAR_MAX_US`]
+fn bar(us: i32) -> String {
+    let clamped = us.clamp(BAR_MIN_US, BAR_MAX_US);
+    let filled = ((clamped - BAR_MIN_US) as usize * BAR_WIDTH + (BAR_MAX_US - BAR_MIN_US) as usize / 2)
+        / (BAR_MAX_US - BAR_MIN_US) as usize;
+    format!("{}{}", "#".repeat(filled), ".".repeat(BAR_WIDTH - filled))
+}
+
+/// Render one dashboard frame
+///
+/// # Arguments
+///
+/// * `status` - Latest bridge status
+/// * `input` - Input state
+/// * `log` - Log lines to show at the bottom
+/// * `now` - Current time, for telemetry age
+pub fn render(status: &BridgeStatus, input: &InputStatus, log: &[String], now: Instant) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(out, "FPV Bridge v{}   profile: {}", env!("CARGO_PKG_VERSION"), status.profile);
+    let battery = input.battery.map_or_else(|| "-".to_string(), |battery| battery.to_string());
+    let _ = writeln!(
+        out,
+        "Input: {} ({})   controller battery: {}",
+        input.name,
+        if input.connected { "running" } else { "stopped" },
+        battery
+    );
+    let reasons = status.failsafe_reasons(now);
+    let failsafe = if reasons.is_empty() { "ok".to_string() } else { format!("FAILSAFE: {}", reasons.join(", ")) };
+    let armed = match (status.armed(), status.disarm_latched) {
+        (true, _) => "ARMED",
+        (false, true) => "disarmed (remote disarm, release L1 to re-arm)",
+        (false, false) => "disarmed",
+    };
+    let _ = writeln!(out, "State: {}   {}", armed, failsafe);
+
+    let _ = writeln!(out, "\nChannels");
+    let half = CRSF_NUM_CHANNELS / 2;
+    for row in 0..half {
+        let cell = |channel: usize| {
+            let us = crsf_value_to_us(status.channels[channel]);
+            format!("CH{:<2} {:>4}us [{}]", channel + 1, us, bar(us))
+        };
+        let _ = writeln!(out, "  {}   {}", cell(row), cell(row + half));
+    }
+
+    let _ = writeln!(out, "\nLink");
+    match status.link {
+        Some((stats, at)) => {
+            let power = stats
+                .uplink_tx_power_mw()
+                .map_or_else(|| format!("#{}", stats.uplink_tx_power), |mw| format!("{}mW", mw));
+            let _ = writeln!(
+                out,
+                "  up:   RSSI -{}/-{}dBm (ant {})  LQ {}%  SNR {}dB  RF mode {}  TX power {}",
+                stats.uplink_rssi_1,
+                stats.uplink_rssi_2,
+                stats.active_antenna + 1,
+                stats.uplink_lq,
+                stats.uplink_snr,
+                stats.rf_mode,
+                power
+            );
+            let _ = writeln!(
+                out,
+                "  down: RSSI -{}dBm  LQ {}%  SNR {}dB   ({:.1}s ago)",
+                stats.downlink_rssi,
+                stats.downlink_lq,
+                stats.downlink_snr,
+                now.duration_since(at).as_secs_f64()
+            );
+        }
+        None => {
+            let _ = writeln!(out, "  no link statistics");
+        }
+    }
+
+    let _ = writeln!(out, "\nTelemetry");
+    match status.battery {
+        Some(battery) => {
+            let _ = writeln!(
+                out,
+                "  battery: {:.1}V  {:.1}A  {}mAh  {}%",
+                battery.voltage, battery.current, battery.capacity_used, battery.remaining_percent
+            );
+        }
+        None => {
+            let _ = writeln!(out, "  battery: -");
+        }
+    }
+    match status.gps {
+        Some(gps) => {
+            let _ = writeln!(
+                out,
+                "  GPS: {:.6}, {:.6}  alt {}m  {:.1}km/h  heading {:.0}°  {} sats",
+                gps.latitude, gps.longitude, gps.altitude, gps.ground_speed, gps.heading, gps.satellites
+            );
+        }
+        None => {
+            let _ = writeln!(out, "  GPS: -");
+        }
+    }
+    let navigation = &status.navigation;
+    match (navigation.to_home, navigation.armed()) {
+        (Some(to_home), _) => {
+            let _ = writeln!(
+                out,
+                "  home: {:.0}m  bearing {:.0}°  {:+}m",
+                to_home.distance_m, to_home.bearing, to_home.relative_altitude_m
+            );
+        }
+        (None, true) => {
+            let _ = writeln!(out, "  home: waiting for {} satellites", navigation.home_min_satellites);
+        }
+        (None, false) => {
+            let _ = writeln!(out, "  home: -");
+        }
+    }
+    if let Some(flight) = navigation.flight {
+        let _ = writeln!(out, "  flight: {}", flight);
+    }
+    match status.attitude {
+        Some(attitude) => {
+            let _ = writeln!(
+                out,
+                "  attitude: roll {:.1}°  pitch {:.1}°  yaw {:.1}°",
+                attitude.roll.to_degrees(),
+                attitude.pitch.to_degrees(),
+                attitude.yaw.to_degrees()
+            );
+        }
+        None => {
+            let _ = writeln!(out, "  attitude: -");
+        }
+    }
+    let _ = writeln!(out, "  flight mode: {}", status.flight_mode.as_deref().unwrap_or("-"));
+
+    let _ = writeln!(out, "\nTransmit");
+    match &status.tx {
+        Some(tx) => {
+            let _ = writeln!(
+                out,
+                "  {} ticks/s  period {:.1}µs  jitter p99 {:.1}µs (max {:.1}µs)  missed {}  late {}",
+                tx.ticks, tx.mean_period_us, tx.p99_jitter_us, tx.max_jitter_us, tx.missed, tx.late
+            );
+            let _ = writeln!(out, "  write {:.1}µs (max {:.1}µs)  timing: {}", tx.mean_write_us, tx.max_write_us, tx.sync);
+        }
+        None => {
+            let _ = writeln!(out, "  waiting for the first report");
+        }
+    }
+    let _ = writeln!(
+        out,
+        "  {} frames sent, {} errors ({} in a row){}",
+        status.frames_sent,
+        status.send_errors,
+        status.consecutive_failures,
+        if status.stalled { ", STALLED" } else { "" }
+    );
+    if let Some(serial) = &status.serial {
+        let _ = writeln!(out, "  serial: {}", serial);
+    }
+    if let Some(latency) = &status.latency {
+        let _ = writeln!(out, "  input latency: {}", latency);
+    }
+    if let Some(error) = &status.last_error {
+        let _ = writeln!(out, "  last error: {}", error);
+    }
+
+    let _ = writeln!(out, "\nLog");
+    for line in log {
+        let line = match line.char_indices().nth(LOG_LINE_WIDTH) {
+            Some((end, _)) => &line[..end],
+            None => line,
+        };
+        let _ = writeln!(out, "  {}", line);
+    }
+    out
+}
+
+/// Leaves the alternate screen when dropped, also when the dashboard task
+/// is cancelled
+struct Screen<W: Write> {
+    out: W,
+}
+
+impl<W: Write> Drop for Screen<W> {
+    fn drop(&mut self) {
+        let _ = write!(self.out, "{}", LEAVE_SCREEN);
+        let _ = self.out.flush();
+    }
+}
+
+/// Draw the dashboard on `out` until the status sender is dropped
+///
+/// Reads the controller battery every few seconds and treats the input as
+/// disconnected once its state sender is gone. On exit the terminal is
+/// restored and `log` is detached, printing the kept lines to stderr.
+///
+/// # Arguments
+///
+/// * `status` - Bridge status published by the control loop
+/// * `input` - Controller state bus of the control loop
+/// * `input_name` - Input description
+/// * `log` - Log tail shown at the bottom
+/// * `out` - Terminal to draw on
+///
+/// # Errors
+///
+/// Returns `Io` error if the terminal cannot be written.
+pub async fn run_dashboard<W: Write>(
+    mut status: watch::Receiver<BridgeStatus>,
+    input: watch::Receiver<ControllerState>,
+    input_name: String,
+    log: LogTail,
+    out: W,
+) -> Result<()> {
+    let result = draw_until_closed(&mut status, &input, input_name, &log, out).await;
+    log.detach();
+    result
+}
+
+async fn draw_until_closed<W: Write>(
+    status: &mut watch::Receiver<BridgeStatus>,
+    input: &watch::Receiver<ControllerState>,
+    input_name: String,
+    log: &LogTail,
+    out: W,
+) -> Result<()> {
+    let mut screen = Screen { out };
+    write!(screen.out, "{}", ENTER_SCREEN)?;
+
+    let mut refresh = interval(REFRESH_INTERVAL);
+    refresh.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let mut input_status = InputStatus { name: input_name, connected: true, battery: None };
+    let mut battery_read: Option<Instant> = None;
+
+    loop {
+        refresh.tick().await;
+        if status.has_changed().is_err() {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        if battery_read.is_none_or(|at| now.duration_since(at) >= BATTERY_INTERVAL) {
+            input_status.battery = read_battery();
+            battery_read = Some(now);
+        }
+        input_status.connected = input.has_changed().is_ok();
+
+        let frame = render(&status.borrow_and_update(), &input_status, &log.lines(LOG_LINES_SHOWN), now);
+        // Home, each line cleared to its end, then the rest of the screen
+        write!(screen.out, "\x1b[H")?;
+        for line in frame.lines() {
+            write!(screen.out, "{}\x1b[K\r\n", line)?;
+        }
+        write!(screen.out, "\x1b[J")?;
+        screen.out.flush()?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::channel_mapper::{SWITCH_OFF, SWITCH_ON};
+
+    fn link_stats(lq: u8) -> LinkStatistics {
+        LinkStatistics {
+            uplink_rssi_1: 50,
+            uplink_rssi_2: 52,
+            uplink_lq: lq,
+            uplink_snr: 9,
+            active_antenna: 0,
+            rf_mode: 7,
+            uplink_tx_power: 3,
+            downlink_rssi: 45,
+            downlink_lq: 100,
+            downlink_snr: 6,
+        }
+    }
+
+    fn input(connected: bool) -> InputStatus {
+        InputStatus { name: "/dev/input/event5".to_string(), connected, battery: None }
+    }
+
+    #[test]
+    fn test_bar() {
+        assert_eq!(bar(1500), format!("{}{}", "#".repeat(10), ".".repeat(10)));
+        assert_eq!(bar(860), ".".repeat(BAR_WIDTH));
+        assert_eq!(bar(2140), "#".repeat(BAR_WIDTH));
+    }
+
+    #[test]
+    fn test_record_sent_and_errors() {
+        let mut status = BridgeStatus::new("default", Duration::from_millis(500));
+        status.record_error("stalled", true);
+        status.record_error("stalled", true);
+        assert_eq!((status.send_errors, status.consecutive_failures, status.stalled), (2, 2, true));
+
+        let mut channels = [CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS];
+        channels[channels::ARM] = SWITCH_ON;
+        status.record_sent(&channels);
+        assert_eq!((status.frames_sent, status.consecutive_failures, status.stalled), (1, 0, false));
+        assert!(status.armed());
+        assert_eq!(status.send_errors, 2);
+
+        channels[channels::ARM] = SWITCH_OFF;
+        status.record_sent(&channels);
+        assert!(!status.armed());
+    }
+
+    #[test]
+    fn test_failsafe_reasons() {
+        let now = Instant::now();
+        let mut status = BridgeStatus::new("default", Duration::from_millis(500));
+        assert!(status.failsafe_reasons(now).is_empty());
+
+        status.link = Some((link_stats(0), now));
+        status.stalled = true;
+        assert_eq!(status.failsafe_reasons(now), vec!["serial output stalled", "link lost"]);
+
+        status.stalled = false;
+        status.link = Some((link_stats(100), now));
+        assert!(status.failsafe_reasons(now + Duration::from_millis(400)).is_empty());
+        assert_eq!(
+            status.failsafe_reasons(now + Duration::from_millis(600)),
+            vec!["no link telemetry"]
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let now = Instant::now();
+        let mut status = BridgeStatus::new("race", Duration::from_millis(500));
+        let mut channels = [CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS];
+        channels[channels::THROTTLE] = 0;
+        channels[channels::ARM] = SWITCH_ON;
+        status.record_sent(&channels);
+        status.link = Some((link_stats(99), now));
+        status.battery = Some(BatterySensor { voltage: 16.8, current: 12.3, capacity_used: 450, remaining_percent: 80 });
+
+        let mut input = input(true);
+        input.battery = Some(ControllerBattery { percent: 70, charging: false });
+        let text = render(&status, &input, &["INFO started".to_string()], now + Duration::from_millis(200));
+
+        assert!(text.contains("profile: race"), "{}", text);
+        assert!(text.contains("Input: /dev/input/event5 (running)   controller battery: 70%"), "{}", text);
+        assert!(text.contains("State: ARMED   ok"), "{}", text);
+        assert!(text.contains(&format!("CH3   880us [{}]", ".".repeat(BAR_WIDTH))), "{}", text);
+        assert!(text.contains("CH16 1520us"), "{}", text);
+        assert!(text.contains("up:   RSSI -50/-52dBm (ant 1)  LQ 99%  SNR 9dB  RF mode 7  TX power 100mW"), "{}", text);
+        assert!(text.contains("(0.2s ago)"), "{}", text);
+        assert!(text.contains("battery: 16.8V  12.3A  450mAh  80%"), "{}", text);
+        assert!(text.contains("GPS: -"), "{}", text);
+        assert!(text.contains("home: waiting for 6 satellites"), "{}", text);
+        assert!(text.contains("waiting for the first report"), "{}", text);
+        assert!(text.contains("1 frames sent, 0 errors (0 in a row)"), "{}", text);
+        assert!(!text.contains("input latency"), "{}", text);
+        assert!(text.ends_with("Log\n  INFO started\n"), "{}", text);
+
+        let mut histogram = crate::latency::LatencyHistogram::new();
+        histogram.record(Duration::from_micros(2450));
+        status.latency = histogram.summary();
+        let text = render(&status, &input, &[], now);
+        assert!(text.contains("  input latency: 1 samples, p50 2.45ms, p95 2.45ms, p99 2.45ms"), "{}", text);
+    }
+
+    #[test]
+    fn test_apply_telemetry() {
+        use crate::crsf::decoder::decode_frame;
+        use crate::crsf::encoder::{
+            encode_attitude_frame, encode_battery_sensor_frame, encode_flight_mode_frame, encode_link_statistics_frame,
+            encode_rc_channels_frame,
+        };
+
+        let now = Instant::now();
+        let mut status = BridgeStatus::new("default", Duration::from_millis(500));
+        let link = decode_frame(&encode_link_statistics_frame(&link_stats(90))).unwrap();
+        assert!(status.apply_telemetry(&link, now).unwrap());
+        assert_eq!(status.link, Some((link_stats(90), now)));
+
+        let battery = BatterySensor { voltage: 16.8, current: 1.5, capacity_used: 10, remaining_percent: 99 };
+        let frame = decode_frame(&encode_battery_sensor_frame(&battery)).unwrap();
+        assert!(status.apply_telemetry(&frame, now).unwrap());
+        assert_eq!(status.battery.unwrap().remaining_percent, 99);
+
+        let rc = decode_frame(&encode_rc_channels_frame(&[CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS])).unwrap();
+        assert!(!status.apply_telemetry(&rc, now).unwrap());
+
+        let frame = decode_frame(&encode_attitude_frame(&Attitude { pitch: 0.0, roll: 0.5, yaw: 1.0 })).unwrap();
+        assert!(status.apply_telemetry(&frame, now).unwrap());
+        assert!((status.attitude.unwrap().roll - 0.5).abs() < 1e-4);
+
+        let frame = decode_frame(&encode_flight_mode_frame("ANGL")).unwrap();
+        assert!(status.apply_telemetry(&frame, now).unwrap());
+        assert_eq!(status.flight_mode.as_deref(), Some("ANGL"));
+
+        let text = render(&status, &input(true), &[], now);
+        assert!(text.contains("  attitude: roll 28.6°  pitch 0.0°  yaw 57.3°\n  flight mode: ANGL\n"), "{}", text);
+
+        let short = CrsfFrame::new(CRSF_FRAMETYPE_GPS, vec![0; 3]).unwrap();
+        assert!(status.apply_telemetry(&short, now).is_err());
+        assert!(status.gps.is_none());
+    }
+
+    #[test]
+    fn test_navigation_follows_gps_and_arming() {
+        use crate::crsf::decoder::decode_frame;
+        use crate::crsf::encoder::{encode_flight_mode_frame, encode_gps_frame};
+
+        let now = Instant::now();
+        let gps = |latitude: f64, altitude: i16| {
+            let gps = GpsData { latitude, longitude: 30.5, ground_speed: 36.0, heading: 0.0, altitude, satellites: 9 };
+            decode_frame(&encode_gps_frame(&gps)).unwrap()
+        };
+        let mut status = BridgeStatus::new("default", Duration::from_millis(500));
+        status.apply_telemetry(&gps(50.45, 120), now).unwrap();
+        assert_eq!(status.navigation.home, None);
+        assert!(render(&status, &input(true), &[], now).contains("  home: -
+"));
+
+        // Arming locks home at the latest position
+        let mut channels = [CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS];
+        channels[channels::ARM] = SWITCH_ON;
+        status.record_sent(&channels);
+        assert!(status.navigation.armed());
+        assert_eq!(status.navigation.home.unwrap().altitude, 120);
+
+        status.apply_telemetry(&gps(50.46, 150), now).unwrap();
+        let text = render(&status, &input(true), &[], now);
+        assert!(text.contains("  home: 1112m  bearing 180°  +30m
+"), "{}", text);
+        assert!(text.contains("  flight: 0m00s, max distance 1112m, max altitude 30m"), "{}", text);
+
+        // A lagging disarmed marker does not end the flight, the arm switch does
+        let frame = decode_frame(&encode_flight_mode_frame("ACRO*")).unwrap();
+        status.apply_telemetry(&frame, now).unwrap();
+        assert!(status.navigation.armed());
+        channels[channels::ARM] = SWITCH_OFF;
+        status.record_sent(&channels);
+        assert!(!status.navigation.armed());
+        assert_eq!(status.navigation.flight.unwrap().max_altitude_m, 30);
+    }
+
+    #[test]
+    fn test_render_failsafe() {
+        let now = Instant::now();
+        let mut status = BridgeStatus::new("default", Duration::from_millis(500));
+        status.record_error("Serial output stalled", true);
+        let text = render(&status, &input(false), &["x".repeat(150)], now);
+        assert!(text.contains("(stopped)"), "{}", text);
+        assert!(text.contains("State: disarmed   FAILSAFE: serial output stalled"), "{}", text);
+        assert!(text.ends_with(&format!("  {}\n", "x".repeat(LOG_LINE_WIDTH))), "{}", text);
+        assert!(text.contains("0 frames sent, 1 errors (1 in a row), STALLED"), "{}", text);
+        assert!(text.contains("last error: Serial output stalled"), "{}", text);
+
+        status.disarm_latched = true;
+        let text = render(&status, &input(true), &[], now);
+        assert!(text.contains("State: disarmed (remote disarm, release L1 to re-arm)"), "{}", text);
+    }
+
+    #[test]
+    fn test_log_tail() {
+        let mut log = LogTail::new();
+        write!(log, "first line\nsecond ").unwrap();
+        assert_eq!(log.lines(5), vec!["first line"]);
+        writeln!(log, "line").unwrap();
+        assert_eq!(log.lines(5), vec!["first line", "second line"]);
+        assert_eq!(log.lines(1), vec!["second line"]);
+
+        for i in 0..LOG_TAIL_LINES {
+            writeln!(log, "line {}", i).unwrap();
+        }
+        assert_eq!(log.lines(LOG_TAIL_LINES + 10).len(), LOG_TAIL_LINES);
+        assert_eq!(log.lines(1), vec![format!("line {}", LOG_TAIL_LINES - 1)]);
+    }
+
+    #[tokio::test]
+    async fn test_run_until_status_closes() {
+        let (status_tx, status_rx) = watch::channel(BridgeStatus::new("default", Duration::from_millis(500)));
+        let (input_tx, input_rx) = watch::channel(ControllerState::default());
+        drop(input_tx);
+        let mut log = LogTail::new();
+        writeln!(log, "INFO hello").unwrap();
+
+        let task = tokio::spawn(async move {
+            let mut out = Vec::new();
+            run_dashboard(status_rx, input_rx, "script:arm".to_string(), log, &mut out).await.unwrap();
+            String::from_utf8(out).unwrap()
+        });
+        tokio::time::sleep(REFRESH_INTERVAL * 3).await;
+        status_tx.send_modify(|status| status.frames_sent = 42);
+        tokio::time::sleep(REFRESH_INTERVAL * 2).await;
+        drop(status_tx);
+
+        let text = task.await.unwrap();
+        assert!(text.starts_with(ENTER_SCREEN));
+        assert!(text.ends_with(LEAVE_SCREEN));
+        assert!(text.contains("Input: script:arm (stopped)"));
+        assert!(text.contains("42 frames sent"));
+        assert!(text.contains("INFO hello"));
+    }
+}
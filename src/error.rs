@@ -23,10 +23,27 @@ pub enum FpvBridgeError {
     #[error("Serial port error: {0}")]
     Serial(String),
 
+    /// Serial output stalled: a write timed out or the port is not
+    /// draining, so the packet was dropped
+    #[error("Serial write stalled: {0}")]
+    SerialStall(String),
+
     /// Serial port not found
     #[error("No ELRS device found. Tried: {0}")]
     SerialPortNotFound(String),
 
+    /// Output sink errors (UDP, TCP, file)
+    #[error("Output sink error: {0}")]
+    Output(String),
+
+    /// Network bridge errors
+    #[error("Network bridge error: {0}")]
+    Network(String),
+
+    /// Status and control API errors
+    #[error("API error: {0}")]
+    Api(String),
+
     /// Controller errors
     #[error("Controller error: {0}")]
     Controller(String),
@@ -60,6 +77,38 @@ mod tests {
         assert!(message.contains("write failed"));
     }
 
+    #[test]
+    fn test_serial_stall_message() {
+        let error = FpvBridgeError::SerialStall("write did not complete within 100ms".to_string());
+        let message = error.to_string();
+        assert!(message.contains("Serial write stalled"));
+        assert!(message.contains("100ms"));
+    }
+
+    #[test]
+    fn test_output_error_message() {
+        let error = FpvBridgeError::Output("UDP sink 127.0.0.1:7777: connection refused".to_string());
+        let message = error.to_string();
+        assert!(message.contains("Output sink error"));
+        assert!(message.contains("connection refused"));
+    }
+
+    #[test]
+    fn test_api_error_message() {
+        let error = FpvBridgeError::Api("Failed to bind 127.0.0.1:8080: address in use".to_string());
+        let message = error.to_string();
+        assert!(message.contains("API error"));
+        assert!(message.contains("address in use"));
+    }
+
+    #[test]
+    fn test_network_error_message() {
+        let error = FpvBridgeError::Network("Failed to bind 0.0.0.0:7777: address in use".to_string());
+        let message = error.to_string();
+        assert!(message.contains("Network bridge error"));
+        assert!(message.contains("address in use"));
+    }
+
     #[test]
     fn test_serial_port_not_found_message() {
         let error = FpvBridgeError::SerialPortNotFound("/dev/ttyACM0, /dev/ttyUSB0".to_string());
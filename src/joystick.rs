@@ -0,0 +1,292 @@
+//! # Virtual Joystick
+//!
+//! Simulator output through Linux `uinput`.
+//!
+//! [`VirtualJoystick`] creates a virtual gamepad and feeds it the RC
+//! channels of every frame it is sent, so simulators (Liftoff, Velocidrone,
+//! Uncrashed) see the same mapped, expo'd and calibrated sticks the model
+//! gets. It is an [`FrameSink`], configured as the `joystick` output sink.
+//!
+//! | Channels | Joystick                                                        |
+//! |----------|-----------------------------------------------------------------|
+//! | 1-8      | Axes X, Y, Z, RX, RY, RZ, THROTTLE, RUDDER (range 0-2047)       |
+//! | 9-16     | Buttons TRIGGER, THUMB, THUMB2, TOP, TOP2, PINKIE, BASE, BASE2  |
+//!
+//! Buttons are pressed while their channel is above center. Creating the
+//! device needs write access to `/dev/uinput` (e.g. membership of the
+//! `input` group with a udev rule, see TROUBLESHOOTING.md).
+
+use async_trait::async_trait;
+use evdev::uinput::{VirtualDevice, VirtualDeviceBuilder};
+use evdev::{AbsInfo, AbsoluteAxisType, AttributeSet, EventType, InputEvent, Key, UinputAbsSetup};
+
+use crate::crsf::decoder::{decode_frame, decode_rc_channels_payload, decode_subset_rc_channels_payload};
+use crate::crsf::protocol::{
+    RcChannels, CRSF_CHANNEL_VALUE_CENTER, CRSF_CHANNEL_VALUE_MAX, CRSF_CHANNEL_VALUE_MIN,
+    CRSF_FRAMETYPE_RC_CHANNELS_PACKED, CRSF_FRAMETYPE_SUBSET_RC_CHANNELS_PACKED, CRSF_NUM_CHANNELS,
+};
+use crate::error::{FpvBridgeError, Result};
+use crate::sink::FrameSink;
+
+/// Name of the virtual device, as shown by simulators
+pub const JOYSTICK_NAME: &str = "FPV Bridge Virtual Joystick";
+
+/// Axes carrying channels 1-8
+pub const JOYSTICK_AXES: [AbsoluteAxisType; 8] = [
+    AbsoluteAxisType::ABS_X,
+    AbsoluteAxisType::ABS_Y,
+    AbsoluteAxisType::ABS_Z,
+    AbsoluteAxisType::ABS_RX,
+    AbsoluteAxisType::ABS_RY,
+    AbsoluteAxisType::ABS_RZ,
+    AbsoluteAxisType::ABS_THROTTLE,
+    AbsoluteAxisType::ABS_RUDDER,
+];
+
+/// Buttons carrying channels 9-16
+///
+/// Taken from the joystick button range so udev tags the device as a
+/// joystick.
+pub const JOYSTICK_BUTTONS: [Key; 8] = [
+    Key::BTN_TRIGGER,
+    Key::BTN_THUMB,
+    Key::BTN_THUMB2,
+    Key::BTN_TOP,
+    Key::BTN_TOP2,
+    Key::BTN_PINKIE,
+    Key::BTN_BASE,
+    Key::BTN_BASE2,
+];
+
+/// Joystick events for the channels that differ from `previous`
+///
+/// Axes carry the channel value unchanged; buttons are pressed (1) while
+/// their channel is above center. The SYN_REPORT closing the batch is
+/// added by [`VirtualDevice::emit`].
+///
+/// # Arguments
+///
+/// * `channels` - New channel values
+/// * `previous` - Channel values last reported to the device, or `None`
+///   to report every axis and button
+///
+/// # Returns
+///
+/// * `Vec<InputEvent>` - Events to emit
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::crsf::protocol::CRSF_CHANNEL_VALUE_CENTER;
+/// use fpv_bridge::joystick::joystick_events;
+///
+/// let previous = [CRSF_CHANNEL_VALUE_CENTER; 16];
+/// let mut channels = previous;
+/// channels[2] = 1500;
+/// let events = joystick_events(&channels, Some(&previous));
+/// assert_eq!(events.len(), 1); // ABS_Z
+/// assert_eq!(events[0].value(), 1500);
+/// ```
+pub fn joystick_events(channels: &RcChannels, previous: Option<&RcChannels>) -> Vec<InputEvent> {
+    let pressed = |value: u16| i32::from(value > CRSF_CHANNEL_VALUE_CENTER);
+
+    let mut events = Vec::new();
+    for (i, axis) in JOYSTICK_AXES.iter().enumerate() {
+        let value = i32::from(channels[i]);
+        if previous.is_none_or(|previous| i32::from(previous[i]) != value) {
+            events.push(InputEvent::new(EventType::ABSOLUTE, axis.0, value));
+        }
+    }
+    for (i, button) in JOYSTICK_BUTTONS.iter().enumerate() {
+        let channel = JOYSTICK_AXES.len() + i;
+        let value = pressed(channels[channel]);
+        if previous.is_none_or(|previous| pressed(previous[channel]) != value) {
+            events.push(InputEvent::new(EventType::KEY, button.code(), value));
+        }
+    }
+
+    events
+}
+
+/// Channel values carried by an RC frame
+///
+/// Subset frames only update the channels they carry, on top of `current`.
+///
+/// # Returns
+///
+/// * `Result<Option<RcChannels>>` - New channel values, or `None` for frames
+///   that are not RC channels frames
+///
+/// # Errors
+///
+/// Returns error if the frame or its payload is malformed.
+fn frame_channels(frame: &[u8], current: &RcChannels) -> Result<Option<RcChannels>> {
+    let frame = decode_frame(frame)?;
+    match frame.frame_type {
+        CRSF_FRAMETYPE_RC_CHANNELS_PACKED => decode_rc_channels_payload(&frame.payload).map(Some),
+        CRSF_FRAMETYPE_SUBSET_RC_CHANNELS_PACKED => {
+            let subset = decode_subset_rc_channels_payload(&frame.payload)?;
+            let mut channels = *current;
+            subset.apply_to(&mut channels);
+            Ok(Some(channels))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Virtual gamepad fed with the RC channels of each frame
+pub struct VirtualJoystick {
+    device: VirtualDevice,
+    /// Channels last reported, `None` before the first frame
+    channels: Option<RcChannels>,
+}
+
+impl VirtualJoystick {
+    /// Create the virtual device
+    ///
+    /// # Errors
+    ///
+    /// Returns `Output` error if `/dev/uinput` cannot be opened (missing
+    /// `uinput` kernel module or permissions) or the device cannot be created.
+    pub fn create() -> Result<Self> {
+        let error = |e: std::io::Error| FpvBridgeError::Output(format!("Virtual joystick (/dev/uinput): {}", e));
+
+        let range = AbsInfo::new(
+            i32::from(CRSF_CHANNEL_VALUE_CENTER),
+            i32::from(CRSF_CHANNEL_VALUE_MIN),
+            i32::from(CRSF_CHANNEL_VALUE_MAX),
+            0,
+            0,
+            0,
+        );
+        let mut buttons = AttributeSet::<Key>::new();
+        for button in JOYSTICK_BUTTONS {
+            buttons.insert(button);
+        }
+
+        let mut builder = VirtualDeviceBuilder::new().map_err(error)?.name(JOYSTICK_NAME);
+        for axis in JOYSTICK_AXES {
+            builder = builder.with_absolute_axis(&UinputAbsSetup::new(axis, range)).map_err(error)?;
+        }
+        let mut device = builder.with_keys(&buttons).map_err(error)?.build().map_err(error)?;
+
+        if let Ok(path) = device.get_syspath() {
+            tracing::info!("Virtual joystick created at {}", path.display());
+        }
+        Ok(Self { device, channels: None })
+    }
+}
+
+#[async_trait]
+impl FrameSink for VirtualJoystick {
+    async fn send_frame(&mut self, frame: &[u8]) -> Result<()> {
+        let current = self.channels.unwrap_or([CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS]);
+        let Some(channels) = frame_channels(frame, &current)? else {
+            return Ok(());
+        };
+
+        let events = joystick_events(&channels, self.channels.as_ref());
+        if !events.is_empty() {
+            // uinput writes go to a kernel buffer and do not block
+            self.device
+                .emit(&events)
+                .map_err(|e| FpvBridgeError::Output(format!("Virtual joystick: {}", e)))?;
+        }
+        self.channels = Some(channels);
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        "joystick".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crsf::encoder::{encode_rc_channels_frame, encode_subset_rc_channels_frame, encode_bind_frame};
+    use crate::crsf::protocol::{SubsetResolution, SubsetRcChannels};
+
+    fn centered() -> RcChannels {
+        [CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS]
+    }
+
+    #[test]
+    fn test_first_report_has_every_axis_and_button() {
+        let events = joystick_events(&centered(), None);
+
+        // 8 axes, 8 buttons
+        assert_eq!(events.len(), 16);
+        assert_eq!(events[0].event_type(), EventType::ABSOLUTE);
+        assert_eq!(events[0].code(), AbsoluteAxisType::ABS_X.0);
+        assert_eq!(events[0].value(), 1024);
+        assert_eq!(events[8].event_type(), EventType::KEY);
+        assert_eq!(events[8].code(), Key::BTN_TRIGGER.code());
+        assert_eq!(events[8].value(), 0);
+    }
+
+    #[test]
+    fn test_only_changes_are_reported() {
+        let previous = centered();
+        assert!(joystick_events(&previous, Some(&previous)).is_empty());
+
+        let mut channels = previous;
+        channels[6] = CRSF_CHANNEL_VALUE_MAX;
+        channels[15] = 1800;
+        let events = joystick_events(&channels, Some(&previous));
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].code(), AbsoluteAxisType::ABS_THROTTLE.0);
+        assert_eq!(events[0].value(), 2047);
+        assert_eq!(events[1].code(), Key::BTN_BASE2.code());
+        assert_eq!(events[1].value(), 1);
+    }
+
+    #[test]
+    fn test_button_follows_center_threshold() {
+        let previous = centered();
+        let mut channels = previous;
+
+        // Moving within one side of center does not toggle the button
+        channels[8] = 200;
+        assert!(joystick_events(&channels, Some(&previous)).is_empty());
+
+        channels[8] = CRSF_CHANNEL_VALUE_CENTER + 1;
+        let events = joystick_events(&channels, Some(&previous));
+        assert_eq!(events[0].code(), Key::BTN_TRIGGER.code());
+        assert_eq!(events[0].value(), 1);
+    }
+
+    #[test]
+    fn test_frame_channels_from_rc_frame() {
+        let mut channels = centered();
+        channels[0] = 172;
+        channels[11] = 1811;
+
+        let frame = encode_rc_channels_frame(&channels);
+        assert_eq!(frame_channels(&frame, &centered()).unwrap(), Some(channels));
+    }
+
+    #[test]
+    fn test_frame_channels_from_subset_frame_keeps_other_channels() {
+        let mut current = centered();
+        current[10] = 1811;
+
+        let subset = SubsetRcChannels {
+            first_channel: 0,
+            resolution: SubsetResolution::Bits11,
+            values: vec![100, 200, 300, 400],
+        };
+        let frame = encode_subset_rc_channels_frame(&subset).unwrap();
+        let channels = frame_channels(&frame, &current).unwrap().unwrap();
+
+        assert_eq!(&channels[..4], &[100, 200, 300, 400]);
+        assert_eq!(channels[10], 1811);
+    }
+
+    #[test]
+    fn test_frame_channels_ignores_other_frames() {
+        assert_eq!(frame_channels(&encode_bind_frame(), &centered()).unwrap(), None);
+        assert!(frame_channels(&[0xC8, 0x02], &centered()).is_err());
+    }
+}
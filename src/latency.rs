@@ -0,0 +1,378 @@
+//! # Input Latency
+//!
+//! Measures stick-to-wire latency: the time from the kernel timestamp of a
+//! controller input event to the flush of the first CRSF frame carrying it.
+//!
+//! Both ends use the system clock, since evdev stamps events with
+//! `CLOCK_REALTIME`. Samples go into a fixed-size [`LatencyHistogram`], so a
+//! long run costs no more memory than a short one.
+
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+/// Width of a histogram bucket
+pub const BUCKET_WIDTH: Duration = Duration::from_micros(100);
+
+/// Number of buckets (100ms range); percentiles beyond it report the largest sample
+const BUCKET_COUNT: usize = 1000;
+
+/// Fixed-bucket latency histogram
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use fpv_bridge::latency::LatencyHistogram;
+///
+/// let mut histogram = LatencyHistogram::new();
+/// for ms in 1..=100 {
+///     histogram.record(Duration::from_millis(ms));
+/// }
+///
+/// let summary = histogram.summary().unwrap();
+/// assert_eq!(summary.samples, 100);
+/// assert_eq!(summary.p50, Duration::from_millis(50));
+/// ```
+#[derive(Debug, Clone)]
+pub struct LatencyHistogram {
+    buckets: Vec<u32>,
+    samples: u64,
+    sum: Duration,
+    max: Duration,
+}
+
+/// Percentiles of a [`LatencyHistogram`]
+///
+/// Percentiles are bucket upper bounds (at most [`BUCKET_WIDTH`] above the
+/// true value), capped at the largest sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencySummary {
+    /// Number of samples
+    pub samples: u64,
+    /// Mean latency
+    pub mean: Duration,
+    /// Median latency
+    pub p50: Duration,
+    /// 95th percentile latency
+    pub p95: Duration,
+    /// 99th percentile latency
+    pub p99: Duration,
+    /// Largest sample
+    pub max: Duration,
+}
+
+impl fmt::Display for LatencySummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} samples, p50 {:.2}ms, p95 {:.2}ms, p99 {:.2}ms (mean {:.2}ms, max {:.2}ms)",
+            self.samples,
+            as_ms(self.p50),
+            as_ms(self.p95),
+            as_ms(self.p99),
+            as_ms(self.mean),
+            as_ms(self.max)
+        )
+    }
+}
+
+fn as_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LatencyHistogram {
+    /// Creates an empty histogram
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            buckets: vec![0; BUCKET_COUNT],
+            samples: 0,
+            sum: Duration::ZERO,
+            max: Duration::ZERO,
+        }
+    }
+
+    /// Adds a sample
+    pub fn record(&mut self, latency: Duration) {
+        // Bucket `i` holds (i * width, (i + 1) * width]
+        let index = (latency.as_nanos().saturating_sub(1) / BUCKET_WIDTH.as_nanos()) as usize;
+        if let Some(bucket) = self.buckets.get_mut(index) {
+            *bucket += 1;
+        }
+        self.samples += 1;
+        self.sum += latency;
+        self.max = self.max.max(latency);
+    }
+
+    /// Number of samples recorded
+    #[must_use]
+    pub fn len(&self) -> u64 {
+        self.samples
+    }
+
+    /// Returns `true` if no samples were recorded
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.samples == 0
+    }
+
+    /// Latency below which `pct` percent of the samples fall
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Duration>` - Percentile, or `None` if the histogram is empty
+    #[must_use]
+    pub fn percentile(&self, pct: u64) -> Option<Duration> {
+        if self.samples == 0 {
+            return None;
+        }
+
+        let rank = (self.samples * pct.min(100)).div_ceil(100).max(1);
+        let mut seen = 0u64;
+        for (index, &count) in self.buckets.iter().enumerate() {
+            seen += u64::from(count);
+            if seen >= rank {
+                let upper = BUCKET_WIDTH * (index as u32 + 1);
+                return Some(upper.min(self.max));
+            }
+        }
+        Some(self.max)
+    }
+
+    /// Summarizes the histogram
+    ///
+    /// # Returns
+    ///
+    /// * `Option<LatencySummary>` - Percentiles, or `None` if empty
+    #[must_use]
+    pub fn summary(&self) -> Option<LatencySummary> {
+        Some(LatencySummary {
+            samples: self.samples,
+            mean: self.sum / u32::try_from(self.samples).unwrap_or(u32::MAX).max(1),
+            p50: self.percentile(50)?,
+            p95: self.percentile(95)?,
+            p99: self.percentile(99)?,
+            max: self.max,
+        })
+    }
+
+    /// Removes all samples
+    pub fn clear(&mut self) {
+        self.buckets.fill(0);
+        self.samples = 0;
+        self.sum = Duration::ZERO;
+        self.max = Duration::ZERO;
+    }
+}
+
+/// Pairs controller input timestamps with frame flush times
+///
+/// Each input timestamp is measured once, against the first frame flushed
+/// after it; frames re-sending an unchanged state are not counted. Samples go
+/// into a per-window histogram (for periodic logs) and a histogram covering
+/// the whole run.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, SystemTime};
+/// use fpv_bridge::latency::LatencyTracker;
+///
+/// let mut tracker = LatencyTracker::new();
+/// let input = SystemTime::now();
+/// let flushed = input + Duration::from_millis(3);
+///
+/// assert_eq!(tracker.record_flush(Some(input), flushed), Some(Duration::from_millis(3)));
+/// // The next frame carries the same input: not a new sample
+/// assert_eq!(tracker.record_flush(Some(input), flushed + Duration::from_millis(4)), None);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LatencyTracker {
+    window: LatencyHistogram,
+    total: LatencyHistogram,
+    last_input: Option<SystemTime>,
+}
+
+impl LatencyTracker {
+    /// Creates a tracker with no samples
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records that a frame built from input stamped `input` was flushed
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Kernel timestamp of the newest input event in the frame
+    /// * `flushed` - When the frame was flushed to the serial port
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Duration>` - The new sample, or `None` if there was no new
+    ///   input or the clock went backwards
+    pub fn record_flush(&mut self, input: Option<SystemTime>, flushed: SystemTime) -> Option<Duration> {
+        let input = input?;
+        if self.last_input == Some(input) {
+            return None;
+        }
+        self.last_input = Some(input);
+
+        let latency = flushed.duration_since(input).ok()?;
+        self.window.record(latency);
+        self.total.record(latency);
+        Some(latency)
+    }
+
+    /// Summarizes and clears the current window
+    ///
+    /// # Returns
+    ///
+    /// * `Option<LatencySummary>` - Window percentiles, or `None` if no input
+    ///   arrived since the last call
+    pub fn take_window(&mut self) -> Option<LatencySummary> {
+        let summary = self.window.summary();
+        self.window.clear();
+        summary
+    }
+
+    /// Histogram of every sample since the tracker was created
+    #[must_use]
+    pub fn total(&self) -> &LatencyHistogram {
+        &self.total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(value: u64) -> Duration {
+        Duration::from_millis(value)
+    }
+
+    #[test]
+    fn test_empty_histogram() {
+        let histogram = LatencyHistogram::new();
+        assert!(histogram.is_empty());
+        assert_eq!(histogram.percentile(50), None);
+        assert_eq!(histogram.summary(), None);
+    }
+
+    #[test]
+    fn test_percentiles() {
+        let mut histogram = LatencyHistogram::new();
+        for value in 1..=100 {
+            histogram.record(ms(value));
+        }
+
+        let summary = histogram.summary().unwrap();
+        assert_eq!(summary.samples, 100);
+        assert_eq!(summary.p50, ms(50));
+        assert_eq!(summary.p95, ms(95));
+        assert_eq!(summary.p99, ms(99));
+        assert_eq!(summary.max, ms(100));
+        assert_eq!(summary.mean, Duration::from_micros(50_500));
+    }
+
+    #[test]
+    fn test_percentile_is_bucket_upper_bound() {
+        let mut histogram = LatencyHistogram::new();
+        histogram.record(Duration::from_micros(1_234));
+        histogram.record(Duration::from_micros(5_000));
+
+        // 1.234ms falls in the 1.2-1.3ms bucket
+        assert_eq!(histogram.percentile(50), Some(Duration::from_micros(1_300)));
+        // Capped at the largest sample
+        assert_eq!(histogram.percentile(100), Some(Duration::from_micros(5_000)));
+    }
+
+    #[test]
+    fn test_single_sample() {
+        let mut histogram = LatencyHistogram::new();
+        histogram.record(Duration::from_micros(250));
+
+        let summary = histogram.summary().unwrap();
+        assert_eq!(summary.p50, Duration::from_micros(250));
+        assert_eq!(summary.p99, Duration::from_micros(250));
+    }
+
+    #[test]
+    fn test_overflow_samples() {
+        let mut histogram = LatencyHistogram::new();
+        histogram.record(ms(1));
+        histogram.record(ms(500));
+
+        assert_eq!(histogram.len(), 2);
+        assert_eq!(histogram.percentile(50), Some(ms(1)));
+        assert_eq!(histogram.percentile(99), Some(ms(500)));
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut histogram = LatencyHistogram::new();
+        histogram.record(ms(1));
+        histogram.record(ms(500));
+        histogram.clear();
+
+        assert!(histogram.is_empty());
+        histogram.record(ms(2));
+        assert_eq!(histogram.summary().unwrap().max, ms(2));
+    }
+
+    #[test]
+    fn test_summary_display() {
+        let mut histogram = LatencyHistogram::new();
+        histogram.record(ms(2));
+        let text = histogram.summary().unwrap().to_string();
+        assert_eq!(text, "1 samples, p50 2.00ms, p95 2.00ms, p99 2.00ms (mean 2.00ms, max 2.00ms)");
+    }
+
+    #[test]
+    fn test_tracker_counts_each_input_once() {
+        let mut tracker = LatencyTracker::new();
+        let input = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+
+        assert_eq!(tracker.record_flush(Some(input), input + ms(4)), Some(ms(4)));
+        assert_eq!(tracker.record_flush(Some(input), input + ms(8)), None);
+
+        let next = input + ms(10);
+        assert_eq!(tracker.record_flush(Some(next), next + ms(2)), Some(ms(2)));
+        assert_eq!(tracker.total().len(), 2);
+    }
+
+    #[test]
+    fn test_tracker_ignores_missing_input() {
+        let mut tracker = LatencyTracker::new();
+        assert_eq!(tracker.record_flush(None, SystemTime::now()), None);
+        assert!(tracker.total().is_empty());
+    }
+
+    #[test]
+    fn test_tracker_ignores_clock_going_backwards() {
+        let mut tracker = LatencyTracker::new();
+        let input = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+
+        assert_eq!(tracker.record_flush(Some(input), input - ms(1)), None);
+        assert!(tracker.total().is_empty());
+    }
+
+    #[test]
+    fn test_tracker_window() {
+        let mut tracker = LatencyTracker::new();
+        let input = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        tracker.record_flush(Some(input), input + ms(3));
+
+        assert_eq!(tracker.take_window().unwrap().samples, 1);
+        assert_eq!(tracker.take_window(), None);
+
+        // The run total is kept
+        assert_eq!(tracker.total().len(), 1);
+    }
+}
@@ -10,4 +10,17 @@ pub mod error;
 pub mod crsf;
 pub mod controller;
 pub mod serial;
+pub mod sink;
+pub mod bridge;
+pub mod capture;
+pub mod sniffer;
+pub mod dashboard;
+pub mod api;
+pub mod mavlink;
+pub mod tracker;
+pub mod navigation;
+pub mod joystick;
+pub mod scheduler;
+pub mod control;
 pub mod telemetry;
+pub mod latency;
@@ -5,129 +5,464 @@
 //! This application bridges PS5 controller inputs to CRSF (Crossfire) protocol
 //! for controlling ExpressLRS-enabled drones.
 
-use anyhow::Result;
-use tokio::time::{interval, Duration};
-use tracing::{debug, info, warn};
-use tracing_subscriber;
-
-mod config;
-mod error;
-mod crsf;
-mod controller;
-mod serial;
-mod telemetry;
-
-use crsf::encoder::encode_rc_channels_frame;
-use crsf::protocol::CRSF_CHANNEL_VALUE_CENTER;
-use serial::ElrsSerial;
-
-/// Default packet transmission rate in Hz (ELRS standard)
-///
-/// ExpressLRS uses 250Hz packet rate for control commands, resulting in
-/// a 4ms period between packets. This ensures responsive control with
-/// low latency suitable for FPV drone racing and freestyle.
-const PACKET_RATE_HZ: u32 = 250;
-
-/// Number of packets between status log messages
-///
-/// At 250Hz, logging every 1000 packets results in status updates
-/// approximately every 4 seconds, providing visibility without
-/// flooding the logs.
-const LOG_INTERVAL_PACKETS: u64 = 1000;
-
-/// Consecutive failure threshold before escalating to warning level
-///
-/// When packet transmission fails 10 times consecutively, logging
-/// escalates from debug to warning level to alert of persistent
-/// connectivity issues that may require intervention.
-const FAILURE_WARNING_THRESHOLD: u32 = 10;
+use std::io::{IsTerminal, Write};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use tokio::sync::{mpsc, watch};
+use tokio::time::{sleep_until, Instant};
+use tracing::{debug, error, info, warn};
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+
+mod cli;
+
+use cli::Command;
+use fpv_bridge::api::{ApiRequest, ApiServer, ApiState};
+use fpv_bridge::bridge::BridgeServer;
+use fpv_bridge::capture::{write_dump, write_pcapng, CaptureReader, CaptureWriter};
+use fpv_bridge::config::{ApiConfig, Config, MavlinkConfig, TrackerConfig};
+use fpv_bridge::control::{initial_status, spawn_controller_reader, ControlLoop};
+use fpv_bridge::error::FpvBridgeError;
+use fpv_bridge::controller::mapper::ControllerState;
+use fpv_bridge::controller::profile::ProfileManager;
+use fpv_bridge::controller::input::InputSource;
+use fpv_bridge::controller::ps5::DualSenseController;
+use fpv_bridge::controller::script::{InputScript, ScriptedController};
+use fpv_bridge::controller::session::{RecordingSource, Session};
+use fpv_bridge::crsf::encoder::{encode_frame, encode_rc_channels_frame};
+use fpv_bridge::crsf::protocol::CRSF_NUM_CHANNELS;
+use fpv_bridge::dashboard::{run_dashboard, BridgeStatus, LogTail};
+use fpv_bridge::mavlink::{run_mavlink, GcsSink};
+use fpv_bridge::tracker::{run_tracker, tick_period};
+use fpv_bridge::scheduler::TxScheduler;
+use fpv_bridge::serial::ElrsSerial;
+use fpv_bridge::sink::{FrameSink, TeeSink};
+use fpv_bridge::sniffer::{open_line, run_sniffer};
+
+/// How long `--latency-test` measures before printing its report
+const LATENCY_TEST_DURATION: Duration = Duration::from_secs(60);
 
 /// Main entry point for FPV Bridge application
 ///
-/// Initializes serial communication with ELRS module and runs the main control loop
-/// that continuously sends CRSF packets at 250Hz (ELRS standard rate).
+/// Initializes serial communication with ELRS module and runs the control loop
+/// ([`ControlLoop`]) that continuously sends CRSF packets at 250Hz (ELRS
+/// standard rate).
 ///
 /// # Current Implementation (Phase 2)
 ///
-/// - Sends dummy channel values (all centered at 1024) at 250Hz
-/// - Logs status every 1000 packets (~4 seconds)
+/// - Loads configuration (`--config`, default `config/default.toml`)
+/// - Reads the PS5 controller and maps it through the active model profile
+///   (`--model`, or Options + D-Pad Left/Right while disarmed)
+/// - Selects the profile's ELRS model ID (model match) on connect and on switch
+/// - Optionally puts the module into bind mode (`--bind`)
+/// - Sends RC channels at `crsf.packet_rate_hz` (250Hz default), locked to the
+///   module's RF timing once it reports RADIO_ID timing sync frames
+/// - With `crsf.send_on_change`, sends as soon as the input changes and only
+///   keep-alive frames while it does not
+/// - Logs transmit timing statistics every second (period, jitter, missed and
+///   late ticks, write time) and input latency percentiles (controller event
+///   to frame flush)
+/// - With `--latency-test`, prints the input latency report after 60 seconds
+///   and exits
 /// - Handles Ctrl+C for graceful shutdown
 /// - Tracks consecutive transmission failures with warning escalation
+/// - Bounds each serial write by `serial.timeout_ms`, drops frames while the
+///   port is not draining, and reports stalls longer than
+///   `safety.failsafe_timeout_ms`
+/// - With `serial.port = "none"`, runs without an ELRS module and sends RC
+///   frames to the `[output]` sinks only (e.g. the simulator joystick)
+/// - With `[mavlink]`, sends decoded telemetry to a ground control station
+///   as MAVLink over UDP
+/// - With `[tracker]`, re-encodes decoded telemetry as LTM or MSP for an
+///   antenna tracker or goggles, on a serial port or UDP
+/// - With `--serve`, runs as network bridge server without a controller
+///   instead (see [`run_bridge_server`])
 ///
 /// # Errors
 ///
-/// Returns error if serial port cannot be opened (no ELRS device found)
+/// Returns error if the configuration is invalid, the model profile is
+/// unknown, the controller or serial port cannot be opened, or the latency
+/// self-test saw no controller input
 #[tokio::main]
 async fn main() -> Result<()> {
+    let args = match cli::parse(std::env::args().skip(1)) {
+        Ok(Command::Run(args)) => args,
+        Ok(Command::Help) => {
+            print!("{}", cli::USAGE);
+            return Ok(());
+        }
+        Ok(Command::Version) => {
+            println!("fpv-bridge {}", env!("CARGO_PKG_VERSION"));
+            return Ok(());
+        }
+        Ok(Command::Dump { capture, pcapng }) => return dump_capture(&capture, pcapng.as_deref()),
+        Ok(Command::Sniff { port, baud }) => return sniff(&port, baud).await,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
     // Initialize logging
+    let log_level = args.log_level.as_deref().unwrap_or("info");
+    if args.tui && !std::io::stdout().is_terminal() {
+        bail!("--tui needs a terminal on stdout");
+    }
+    // `--replay --fast` prints its CSV on stdout, `--tui` shows the latest
+    // log lines in the dashboard
+    let log_tail = args.tui.then(LogTail::new);
+    let log_writer = match &log_tail {
+        Some(tail) => {
+            let tail = tail.clone();
+            BoxMakeWriter::new(move || tail.clone())
+        }
+        None if args.fast => BoxMakeWriter::new(std::io::stderr),
+        None => BoxMakeWriter::new(std::io::stdout),
+    };
     tracing_subscriber::fmt()
         .with_env_filter(
             tracing_subscriber::EnvFilter::from_default_env()
-                .add_directive(tracing::Level::INFO.into())
+                .add_directive(log_level.parse()?)
         )
+        .with_writer(log_writer)
+        .with_ansi(log_tail.is_none())
         .init();
 
     info!("FPV Bridge v{} starting...", env!("CARGO_PKG_VERSION"));
 
-    // TODO: Load configuration
-    // TODO: Initialize controller handler
+    let config = Config::load(&args.config)
+        .with_context(|| format!("Failed to load configuration from {}", args.config.display()))?;
+    info!("Loaded configuration from {}", args.config.display());
 
-    // Initialize serial communication
-    let mut serial = ElrsSerial::open()?;
-    info!("ELRS serial port opened at: {}", serial.device_path());
+    let profiles = ProfileManager::new(config, args.model.as_deref())?;
 
-    // Create dummy channel values (all centered)
-    // In the next phase, these will be replaced with actual controller input
-    let dummy_channels = [CRSF_CHANNEL_VALUE_CENTER; 16];
+    if args.dry_run {
+        info!("Configuration is valid (dry run, exiting)");
+        return Ok(());
+    }
 
-    // Create 250Hz interval (4ms period)
-    let period_ms = 1000 / PACKET_RATE_HZ;
-    let mut packet_interval = interval(Duration::from_millis(period_ms as u64));
-    // Skip missed ticks to prevent burst sends after delays
-    packet_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    if let (Some(path), true) = (&args.replay, args.fast) {
+        return print_replay_channels(path, &profiles);
+    }
+
+    // Scripted or recorded input must not arm a real quad by accident
+    if let Err(e) = args.check_playback(&profiles.active().config.serial) {
+        bail!(e);
+    }
+    if args.allow_live_playback && profiles.active().config.serial.is_enabled() {
+        warn!("Live playback: played input drives the ELRS module on {}", profiles.active().config.serial.port);
+    }
 
-    info!("Starting CRSF packet transmission loop at {}Hz", PACKET_RATE_HZ);
+    let module = profiles.active().config.serial.is_enabled();
+    if !module {
+        if args.serve.is_some() {
+            bail!("--serve needs an ELRS module, serial port is 'none'");
+        }
+        if args.bind {
+            warn!("Ignoring --bind: no ELRS module (serial port 'none')");
+        }
+        if args.capture.is_some() {
+            warn!("Ignoring --capture: no ELRS module (serial port 'none')");
+        }
+    }
+
+    if let Some(address) = &args.serve {
+        if profiles.active().config.api.enabled {
+            warn!("Ignoring [api]: the status API needs the control loop, not available with --serve");
+        }
+        if profiles.active().config.mavlink.enabled {
+            warn!("Ignoring [mavlink]: telemetry goes to the --serve client, not available with --serve");
+        }
+        if profiles.active().config.tracker.enabled {
+            warn!("Ignoring [tracker]: telemetry goes to the --serve client, not available with --serve");
+        }
+        let serial = connect_serial(&profiles, &args).await?;
+        return run_bridge_server(address, serial, &profiles).await;
+    }
+
+    // Initialize controller handler
+    let mut controller: Box<dyn InputSource> = match (&args.script, &args.replay) {
+        (Some(path), _) => {
+            let script =
+                InputScript::load(path).with_context(|| format!("Failed to load input script {}", path.display()))?;
+            info!("Playing input script {} ({:?})", path.display(), script.duration());
+            Box::new(ScriptedController::new(script, &path.display().to_string()))
+        }
+        (None, Some(path)) => {
+            let session = Session::load(path).with_context(|| format!("Failed to load session {}", path.display()))?;
+            info!(
+                "Replaying session {} recorded {} ({} events, {:?})",
+                path.display(),
+                chrono::DateTime::<chrono::Local>::from(session.started).format("%Y-%m-%d %H:%M:%S"),
+                session.events.len(),
+                session.duration()
+            );
+            Box::new(ScriptedController::new(session.to_script(), &path.display().to_string()))
+        }
+        (None, None) => {
+            let controller = DualSenseController::open()?;
+            info!("PS5 controller connected at: {}", controller.device_path());
+            Box::new(controller)
+        }
+    };
+    if let Some(path) = &args.record {
+        controller = Box::new(
+            RecordingSource::new(controller, path)
+                .with_context(|| format!("Failed to create session file {}", path.display()))?,
+        );
+        info!("Recording input session to {}", path.display());
+    }
+    let input_name = controller.describe();
+    let (state_tx, state_rx) = watch::channel(ControllerState::default());
+    spawn_controller_reader(controller, state_tx);
+
+    // Initialize serial communication, unless only the outputs get RC frames
+    let serial = if module {
+        Some(connect_serial(&profiles, &args).await?)
+    } else {
+        info!("No ELRS module (serial port 'none'), sending RC frames to the outputs only");
+        None
+    };
+
+    // Extra outputs (simulator, recording) get the same RC frames as the module
+    let config = &profiles.active().config;
+    let outputs = TeeSink::open(&config.output.sink_specs()?, &config.bridge).await?;
+    if !outputs.is_empty() {
+        info!("Also sending RC frames to: {}", outputs.describe());
+    }
+
+    // Status bus for the dashboard, next to the controller state bus
+    let (status_tx, status_rx) = watch::channel(initial_status(&profiles));
+    let api_requests = match &config.api {
+        api if api.enabled => Some(start_api(api, status_rx.clone(), state_rx.clone()).await?),
+        _ => None,
+    };
+    let mavlink = match &config.mavlink {
+        mavlink if mavlink.enabled => Some(start_mavlink(mavlink, status_rx.clone()).await?),
+        _ => None,
+    };
+    let tracker = match &config.tracker {
+        tracker if tracker.enabled => Some(start_tracker(tracker, status_rx.clone()).await?),
+        _ => None,
+    };
+    let dashboard = log_tail.map(|log| {
+        tokio::spawn(run_dashboard(status_rx, state_rx.clone(), input_name, log, std::io::stdout()))
+    });
+
+    let mut control = ControlLoop::new(profiles, serial, outputs, state_rx, status_tx);
+    if let Some(requests) = api_requests {
+        control = control.with_api(requests, &args.config);
+    }
+    if args.latency_test {
+        info!("Latency self-test: move the sticks, report in {}s", LATENCY_TEST_DURATION.as_secs());
+        control = control.with_latency_test(LATENCY_TEST_DURATION);
+    }
     info!("Press Ctrl+C to exit");
 
-    let mut packet_count: u64 = 0;
-    let mut last_log_count: u64 = 0;
-    let mut consecutive_failures: u32 = 0;
+    let latency = control
+        .run(async {
+            let _ = tokio::signal::ctrl_c().await;
+            info!("Received Ctrl+C, shutting down...");
+        })
+        .await?;
+    if let (true, Some(summary)) = (args.latency_test, latency) {
+        println!("Input latency over {}s: {}", LATENCY_TEST_DURATION.as_secs(), summary);
+    }
+
+    // The finished control loop closed the status bus. That stops the
+    // dashboard, which restores the terminal and prints the kept log lines,
+    // and the telemetry outputs
+    if let Some(dashboard) = dashboard {
+        dashboard.await??;
+    }
+    if let Some(mavlink) = mavlink {
+        mavlink.await?;
+    }
+    if let Some(tracker) = tracker {
+        tracker.await?;
+    }
+
+    Ok(())
+}
+
+/// Starts the status and control API server
+///
+/// # Returns
+///
+/// * `Result<mpsc::Receiver<ApiRequest>>` - Control requests for the control loop
+///
+/// # Errors
+///
+/// Returns error if the address cannot be bound
+async fn start_api(
+    config: &ApiConfig,
+    status: watch::Receiver<BridgeStatus>,
+    input: watch::Receiver<ControllerState>,
+) -> Result<mpsc::Receiver<ApiRequest>> {
+    let (state, requests) = ApiState::new(config, status, input);
+    let server = ApiServer::bind(&config.bind, state).await?;
+    info!("Status API listening on http://{}/api/status", server.local_addr()?);
+    if config.token.is_empty() {
+        warn!("No api.token configured, the API control endpoints are disabled");
+    }
+    tokio::spawn(async move {
+        if let Err(e) = server.run().await {
+            error!("Status API stopped: {}", e);
+        }
+    });
+    Ok(requests)
+}
+
+/// Starts the MAVLink telemetry output to a ground control station
+///
+/// # Returns
+///
+/// * `JoinHandle<()>` - Output task, ends when the status bus closes
+async fn start_mavlink(
+    config: &MavlinkConfig,
+    status: watch::Receiver<BridgeStatus>,
+) -> Result<tokio::task::JoinHandle<()>> {
+    let sink = GcsSink::bind(&config.target).await?;
+    info!("MAVLink telemetry to udp:{} (system ID {})", config.target, config.system_id);
+    Ok(tokio::spawn(run_mavlink(sink, config.system_id, status)))
+}
+
+/// Starts the LTM / MSP telemetry output to an antenna tracker or goggles
+///
+/// # Errors
+///
+/// Returns error if the serial port cannot be opened or the UDP target
+/// cannot be resolved.
+async fn start_tracker(
+    config: &TrackerConfig,
+    status: watch::Receiver<BridgeStatus>,
+) -> Result<tokio::task::JoinHandle<()>> {
+    let protocol = config.tracker_protocol()?;
+    let sink = config.tracker_target()?.open(config.baud_rate, tick_period(config.rate_hz)).await?;
+    info!("{} tracker telemetry to {} at {} Hz", protocol, sink.describe(), config.rate_hz);
+    Ok(tokio::spawn(run_tracker(sink, protocol, config.rate_hz, status)))
+}
+
+/// Connect to the ELRS module and prepare it for the active model profile
+///
+/// Applies the serial write timeout, selects the profile's model ID (model
+/// match) and, with `bind`, puts the module into bind mode.
+///
+/// # Errors
+///
+/// Returns error if the module cannot be opened or a command cannot be sent
+async fn connect_serial(profiles: &ProfileManager, args: &cli::Args) -> Result<ElrsSerial> {
+    let config = &profiles.active().config;
+    let capture = match &args.capture {
+        Some(path) => Some(
+            CaptureWriter::create(path)
+                .with_context(|| format!("Failed to create capture file {}", path.display()))?,
+        ),
+        None => None,
+    };
+    let mut serial = ElrsSerial::connect_with_capture(&config.serial, capture).await?;
+    info!("ELRS serial port opened at: {}", serial.device_path());
+    if let Some(path) = &args.capture {
+        info!("Capturing CRSF traffic to {}", path.display());
+    }
+    serial.set_write_timeout(Duration::from_millis(config.serial.timeout_ms));
+
+    // Model match: tell the module which receiver we are allowed to control
+    if let Some(model_id) = profiles.active().model_id() {
+        serial.select_model(model_id).await?;
+    }
+
+    if args.bind {
+        serial.bind().await?;
+    }
+    Ok(serial)
+}
+
+/// Network bridge server (`--serve`): forward RC frames from a UDP client
+/// to the ELRS module and the module's frames back to the client
+///
+/// Until the first client frame arrives, and whenever the client has been
+/// silent for `safety.failsafe_timeout_ms`, failsafe frames (throttle low,
+/// disarmed, mapped through the active profile's channel settings) are sent
+/// at `crsf.packet_rate_hz` instead. Only packets tagged with
+/// `bridge.secret` are accepted.
+///
+/// # Errors
+///
+/// Returns error if `bridge.secret` is not set, the address cannot be bound
+/// or the socket fails
+async fn run_bridge_server(address: &str, mut serial: ElrsSerial, profiles: &ProfileManager) -> Result<()> {
+    let active = profiles.active();
+    let failsafe_timeout = Duration::from_millis(active.config.safety.failsafe_timeout_ms);
+    let bridge = &active.config.bridge;
+    let mut server = BridgeServer::bind(address, bridge.key()?, failsafe_timeout)
+        .await?
+        .with_allowed_clients(bridge.allowed_client_addrs()?)
+        .with_bare_frames(bridge.allow_bare_frames);
+    info!("Bridge server listening on {}, waiting for a client", server.local_addr()?);
+    if bridge.allowed_clients.is_empty() {
+        info!("Accepting any client with the bridge secret (set bridge.allowed_clients to restrict)");
+    }
+
+    let failsafe_frame = encode_rc_channels_frame(&active.channel_mapper.map_to_channels(&ControllerState::failsafe()));
+    let mut scheduler = TxScheduler::with_mode(active.config.crsf.packet_rate_hz, active.config.crsf.wait_mode());
+    let mut failsafe = true;
+    let mut failsafes: u64 = 0;
+    let mut rx_enabled = true;
 
-    // Main control loop
     loop {
+        let client_deadline = server.failsafe_deadline().unwrap_or_else(Instant::now);
         tokio::select! {
-            // Send packet at regular interval
-            _ = packet_interval.tick() => {
-                // Encode and send CRSF packet with dummy values
-                let packet = encode_rc_channels_frame(&dummy_channels);
-
-                if let Err(e) = serial.send_packet(&packet).await {
-                    consecutive_failures += 1;
-
-                    if consecutive_failures >= FAILURE_WARNING_THRESHOLD {
-                        warn!("Failed to send packet (consecutive failures: {}): {}", consecutive_failures, e);
-                    } else {
-                        debug!("Failed to send packet: {}", e);
+            // RC frame from the client
+            frame = server.recv_frame() => {
+                let frame = frame?;
+                if failsafe {
+                    if let Some(client) = server.client() {
+                        info!("Forwarding RC frames from {}", client);
                     }
-                    continue;
+                    failsafe = false;
+                }
+                if let Err(e) = serial.send_packet(&frame).await {
+                    debug!("Failed to forward frame: {}", e);
                 }
+            }
 
-                // Reset failure counter on successful transmission
-                consecutive_failures = 0;
-                packet_count += 1;
+            // Client went silent
+            _ = sleep_until(client_deadline), if !failsafe => {
+                failsafe = true;
+                failsafes += 1;
+                warn!(
+                    "No RC frames from client for {}ms, sending failsafe (throttle low, disarmed)",
+                    failsafe_timeout.as_millis()
+                );
+            }
 
-                // Log status every LOG_INTERVAL_PACKETS (~4 seconds at 250Hz)
-                if packet_count - last_log_count >= LOG_INTERVAL_PACKETS {
-                    info!("Sent {} packets ({}Hz, all channels centered at {})",
-                        packet_count, PACKET_RATE_HZ, CRSF_CHANNEL_VALUE_CENTER);
-                    last_log_count = packet_count;
+            // Keep the module fed with failsafe frames while there is no client
+            _ = scheduler.tick(), if failsafe => {
+                if let Err(e) = serial.send_packet(&failsafe_frame).await {
+                    debug!("Failed to send failsafe frame: {}", e);
+                }
+            }
+
+            // Telemetry and timing frames from the module go back to the client
+            frame = serial.recv_frame(), if rx_enabled => {
+                match frame {
+                    Ok(frame) => {
+                        if let Err(e) = server.send_telemetry(&encode_frame(&frame)).await {
+                            debug!("{}", e);
+                        }
+                    }
+                    Err(e) => {
+                        warn!("Stopped reading from ELRS module, no telemetry for the client: {}", e);
+                        rx_enabled = false;
+                    }
                 }
             }
 
-            // Handle Ctrl+C for graceful shutdown
             _ = tokio::signal::ctrl_c() => {
                 info!("Received Ctrl+C, shutting down...");
-                info!("Total packets sent: {}", packet_count);
+                info!("Bridge: {}, {} failsafes", server.stats(), failsafes);
+                info!("Serial output: {}", serial.tx_stats());
                 break;
             }
         }
@@ -136,32 +471,59 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_packet_rate_constant() {
-        // Verify ELRS standard packet rate
-        assert_eq!(PACKET_RATE_HZ, 250, "Packet rate should be 250Hz (ELRS standard)");
+/// Prints a traffic capture as one decoded line per frame, or exports it
+/// as pcapng
+fn dump_capture(path: &std::path::Path, pcapng: Option<&std::path::Path>) -> Result<()> {
+    let capture = CaptureReader::open(path).with_context(|| format!("Failed to open capture {}", path.display()))?;
+    match pcapng {
+        Some(out) => {
+            let file = std::fs::File::create(out).with_context(|| format!("Failed to create {}", out.display()))?;
+            let frames = write_pcapng(capture, std::io::BufWriter::new(file))?;
+            eprintln!("Exported {} frames to {}", frames, out.display());
+        }
+        None => match write_dump(capture, std::io::BufWriter::new(std::io::stdout().lock())) {
+            // Output closed early, e.g. piped into `head`
+            Err(FpvBridgeError::Io(e)) if e.kind() == std::io::ErrorKind::BrokenPipe => {}
+            result => {
+                result?;
+            }
+        },
     }
+    Ok(())
+}
 
-    #[test]
-    fn test_log_interval_constant() {
-        // Verify log interval is reasonable
-        assert_eq!(LOG_INTERVAL_PACKETS, 1000);
+/// Passively decodes the CRSF traffic on a serial port until Ctrl+C
+async fn sniff(port: &str, baud: u32) -> Result<()> {
+    let line = open_line(port, baud)?;
+    let stdout = std::io::stdout();
+    let terminal = stdout.is_terminal();
+    let title = format!("Sniffing {} at {} baud (Ctrl+C to stop)", port, baud);
+    run_sniffer(line, &title, stdout.lock(), terminal).await?;
+    Ok(())
+}
 
-        // At 250Hz, 1000 packets = 4 seconds
-        let seconds = LOG_INTERVAL_PACKETS as f64 / PACKET_RATE_HZ as f64;
-        assert_eq!(seconds, 4.0, "Log interval should be 4 seconds at 250Hz");
+/// Replays a recorded session through the active profile's mapping as fast
+/// as possible and prints the channels sent at each packet tick as CSV
+fn print_replay_channels(path: &std::path::Path, profiles: &ProfileManager) -> Result<()> {
+    let session = Session::load(path).with_context(|| format!("Failed to load session {}", path.display()))?;
+    let active = profiles.active();
+    let period = Duration::from_secs_f64(1.0 / f64::from(active.config.crsf.packet_rate_hz));
+
+    let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+    let header: Vec<String> = (1..=CRSF_NUM_CHANNELS).map(|channel| format!("ch{}", channel)).collect();
+    writeln!(out, "time_ms,{}", header.join(","))?;
+    for (time, channels) in session.replay_channels(&active.channel_mapper, period) {
+        let values: Vec<String> = channels.iter().map(u16::to_string).collect();
+        writeln!(out, "{:.3},{}", time.as_secs_f64() * 1000.0, values.join(","))?;
     }
+    out.flush()?;
+    Ok(())
+}
 
-    #[test]
-    fn test_packet_period_calculation() {
-        // Verify period calculation is correct
-        let period_ms = 1000 / PACKET_RATE_HZ;
-        assert_eq!(period_ms, 4, "Period should be 4ms at 250Hz");
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fpv_bridge::crsf::protocol::CRSF_CHANNEL_VALUE_CENTER;
 
     #[test]
     fn test_dummy_channels_are_centered() {
@@ -174,48 +536,8 @@ mod tests {
     }
 
     #[test]
-    fn test_failure_warning_threshold() {
-        // Verify failure threshold is reasonable
-        assert_eq!(FAILURE_WARNING_THRESHOLD, 10);
-
-        // At 250Hz, 10 failures = 40ms of consecutive failures
-        // This is a reasonable threshold before escalating to warnings
-        let failure_duration_ms = FAILURE_WARNING_THRESHOLD * 4; // 4ms per packet at 250Hz
-        assert_eq!(failure_duration_ms, 40, "Should tolerate 40ms of failures before warning");
-    }
-
-    #[test]
-    fn test_constants_are_consistent() {
-        // Verify that constants work together logically
-
-        // Packet rate and period
-        let period_ms = 1000 / PACKET_RATE_HZ;
-        assert_eq!(period_ms, 4, "250Hz rate should result in 4ms period");
-
-        // Log interval timing
-        let log_interval_seconds = LOG_INTERVAL_PACKETS as f64 / PACKET_RATE_HZ as f64;
-        assert_eq!(log_interval_seconds, 4.0, "Should log every 4 seconds");
-
-        // Failure threshold timing
-        let failure_threshold_ms = FAILURE_WARNING_THRESHOLD * period_ms;
-        assert_eq!(failure_threshold_ms, 40, "Should warn after 40ms of failures");
-
-        // Sanity checks
-        assert!(PACKET_RATE_HZ > 0, "Packet rate must be positive");
-        assert!(LOG_INTERVAL_PACKETS > 0, "Log interval must be positive");
-        assert!(FAILURE_WARNING_THRESHOLD > 0, "Failure threshold must be positive");
-    }
-
-    #[test]
-    fn test_elrs_standard_packet_rate() {
-        // ExpressLRS standard specifies 250Hz for RC channels
-        // This is critical for proper operation
-        assert_eq!(PACKET_RATE_HZ, 250,
-            "ELRS requires 250Hz packet rate for RC channels");
-
-        // Verify period calculation
-        let period_ms = 1000 / PACKET_RATE_HZ;
-        assert_eq!(period_ms, 4,
-            "250Hz should result in exactly 4ms period per packet");
+    fn test_latency_test_duration() {
+        // One minute at 250Hz is 15000 frames, plenty for a stable p99
+        assert_eq!(LATENCY_TEST_DURATION, Duration::from_secs(60));
     }
 }
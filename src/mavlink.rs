@@ -0,0 +1,918 @@
+//! # MAVLink Telemetry Output
+//!
+//! Translates the CRSF telemetry decoded by the control loop into MAVLink v2
+//! messages for ground control stations (QGroundControl, Mission Planner),
+//! sent over UDP (`[mavlink]` in the configuration).
+//!
+//! | CRSF telemetry      | MAVLink messages                            |
+//! |---------------------|---------------------------------------------|
+//! | GPS                 | `GPS_RAW_INT`, `GLOBAL_POSITION_INT`        |
+//! | Battery sensor      | `BATTERY_STATUS`, `SYS_STATUS`              |
+//! | Attitude            | `ATTITUDE`                                  |
+//! | Link statistics     | `RADIO_STATUS`                              |
+//! | Flight mode, arming | `HEARTBEAT` (and `STATUSTEXT` on a change)  |
+//!
+//! [`run_mavlink`] reads the [`BridgeStatus`] bus like the dashboard and
+//! the status API, sends each piece of telemetry when it changes, and a
+//! heartbeat every [`HEARTBEAT_INTERVAL`] so the GCS keeps the vehicle
+//! connected while telemetry is missing. Messages are encoded here, like the
+//! CRSF frames in [`crate::crsf::encoder`]; only the few messages above are
+//! supported.
+
+use std::net::SocketAddr;
+
+use async_trait::async_trait;
+use tokio::net::UdpSocket;
+use tokio::sync::watch;
+use tokio::time::{interval, Duration, Instant, MissedTickBehavior};
+use tracing::{info, warn};
+
+use crate::crsf::protocol::{Attitude, BatterySensor, GpsData, LinkStatistics};
+use crate::dashboard::BridgeStatus;
+use crate::error::{FpvBridgeError, Result};
+use crate::navigation::HomePosition;
+use crate::sink::TelemetrySink;
+
+/// MAVLink v2 start-of-frame marker
+pub const MAVLINK_V2_MAGIC: u8 = 0xFD;
+
+/// MAVLink v2 header size (magic through message ID)
+pub const MAVLINK_V2_HEADER_SIZE: usize = 10;
+
+/// How often HEARTBEAT, SYS_STATUS and BATTERY_STATUS are sent
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Component ID of the messages (`MAV_COMP_ID_AUTOPILOT1`)
+pub const MAVLINK_COMPONENT_ID: u8 = 1;
+
+/// Satellites needed to report a 3D fix (CRSF has no fix type)
+pub const GPS_MIN_SATELLITES_3D: u8 = 4;
+
+/// Message IDs and CRC extra bytes of the messages sent
+pub mod message_id {
+    pub const HEARTBEAT: u32 = 0;
+    pub const SYS_STATUS: u32 = 1;
+    pub const GPS_RAW_INT: u32 = 24;
+    pub const ATTITUDE: u32 = 30;
+    pub const GLOBAL_POSITION_INT: u32 = 33;
+    pub const RADIO_STATUS: u32 = 109;
+    pub const BATTERY_STATUS: u32 = 147;
+    pub const STATUSTEXT: u32 = 253;
+
+    /// CRC extra byte of a message, derived from its definition
+    pub fn crc_extra(id: u32) -> Option<u8> {
+        Some(match id {
+            HEARTBEAT => 50,
+            SYS_STATUS => 124,
+            GPS_RAW_INT => 24,
+            ATTITUDE => 39,
+            GLOBAL_POSITION_INT => 104,
+            RADIO_STATUS => 185,
+            BATTERY_STATUS => 154,
+            STATUSTEXT => 83,
+            _ => return None,
+        })
+    }
+}
+
+/// `MAV_TYPE_QUADROTOR`
+const MAV_TYPE_QUADROTOR: u8 = 2;
+
+/// `MAV_AUTOPILOT_GENERIC`
+const MAV_AUTOPILOT_GENERIC: u8 = 0;
+
+/// `MAV_MODE_FLAG_*` bits of the heartbeat base mode
+const MAV_MODE_FLAG_SAFETY_ARMED: u8 = 0x80;
+const MAV_MODE_FLAG_MANUAL_INPUT_ENABLED: u8 = 0x40;
+const MAV_MODE_FLAG_STABILIZE_ENABLED: u8 = 0x10;
+const MAV_MODE_FLAG_GUIDED_ENABLED: u8 = 0x08;
+const MAV_MODE_FLAG_AUTO_ENABLED: u8 = 0x04;
+
+/// `MAV_STATE_*` system states
+const MAV_STATE_STANDBY: u8 = 3;
+const MAV_STATE_ACTIVE: u8 = 4;
+const MAV_STATE_CRITICAL: u8 = 5;
+
+/// `MAV_SYS_STATUS_SENSOR_*` bits
+const MAV_SYS_STATUS_SENSOR_GPS: u32 = 0x20;
+const MAV_SYS_STATUS_SENSOR_RC_RECEIVER: u32 = 0x1_0000;
+const MAV_SYS_STATUS_SENSOR_BATTERY: u32 = 0x200_0000;
+
+/// `GPS_FIX_TYPE_NO_FIX` and `GPS_FIX_TYPE_3D_FIX`
+const GPS_FIX_TYPE_NO_FIX: u8 = 1;
+const GPS_FIX_TYPE_3D_FIX: u8 = 3;
+
+/// `MAV_BATTERY_FUNCTION_ALL` and `MAV_BATTERY_TYPE_LIPO`
+const MAV_BATTERY_FUNCTION_ALL: u8 = 1;
+const MAV_BATTERY_TYPE_LIPO: u8 = 1;
+
+/// `MAV_SEVERITY_INFO`
+const MAV_SEVERITY_INFO: u8 = 6;
+
+/// Text length of a STATUSTEXT message
+const STATUSTEXT_LEN: usize = 50;
+
+/// Flight mode CRSF reports in failsafe (Betaflight, INAV)
+const FLIGHT_MODE_FAILSAFE: &str = "!FS!";
+
+/// Accumulate one byte into a MAVLink CRC (CRC-16/MCRF4XX)
+fn crc_accumulate(byte: u8, crc: u16) -> u16 {
+    let mut tmp = byte ^ (crc & 0xFF) as u8;
+    tmp ^= tmp << 4;
+    let tmp = u16::from(tmp);
+    (crc >> 8) ^ (tmp << 8) ^ (tmp << 3) ^ (tmp >> 4)
+}
+
+/// MAVLink CRC-16/MCRF4XX of `data`
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::mavlink::crc16_mcrf4xx;
+///
+/// assert_eq!(crc16_mcrf4xx(b"123456789"), 0x6F91);
+/// ```
+pub fn crc16_mcrf4xx(data: &[u8]) -> u16 {
+    data.iter().fold(0xFFFF, |crc, &byte| crc_accumulate(byte, crc))
+}
+
+/// MAVLink message payload, before framing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MavlinkMessage {
+    /// Message ID (see [`message_id`])
+    pub id: u32,
+    /// Fields in wire order (little-endian, sorted by size)
+    pub payload: Vec<u8>,
+}
+
+impl MavlinkMessage {
+    fn new(id: u32) -> Self {
+        Self { id, payload: Vec::new() }
+    }
+
+    fn u8(mut self, value: u8) -> Self {
+        self.payload.push(value);
+        self
+    }
+
+    fn u16(mut self, value: u16) -> Self {
+        self.payload.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn i16(mut self, value: i16) -> Self {
+        self.payload.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn u32(mut self, value: u32) -> Self {
+        self.payload.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn i32(mut self, value: i32) -> Self {
+        self.payload.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn u64(mut self, value: u64) -> Self {
+        self.payload.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn f32(mut self, value: f32) -> Self {
+        self.payload.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+}
+
+/// Frames messages as MAVLink v2 packets, numbering them
+#[derive(Debug, Clone)]
+pub struct MavlinkEncoder {
+    system_id: u8,
+    sequence: u8,
+}
+
+impl MavlinkEncoder {
+    /// Encoder for messages from `system_id`
+    pub fn new(system_id: u8) -> Self {
+        Self { system_id, sequence: 0 }
+    }
+
+    /// Encode a complete MAVLink v2 packet (unsigned)
+    ///
+    /// Trailing zero bytes of the payload are left out, as MAVLink v2
+    /// requires; receivers fill them back in.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - Message with an ID listed in [`message_id`]
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<u8>` - Header, payload and CRC
+    ///
+    /// # Panics
+    ///
+    /// Panics if the message ID has no known CRC extra byte.
+    pub fn encode(&mut self, message: &MavlinkMessage) -> Vec<u8> {
+        let crc_extra = message_id::crc_extra(message.id).expect("MAVLink message without CRC extra");
+        let length = message.payload.iter().rposition(|&byte| byte != 0).map_or(1, |last| last + 1);
+
+        let mut packet = Vec::with_capacity(MAVLINK_V2_HEADER_SIZE + length + 2);
+        packet.extend_from_slice(&[
+            MAVLINK_V2_MAGIC,
+            length as u8,
+            0, // incompatibility flags
+            0, // compatibility flags
+            self.sequence,
+            self.system_id,
+            MAVLINK_COMPONENT_ID,
+        ]);
+        packet.extend_from_slice(&message.id.to_le_bytes()[..3]);
+        packet.extend_from_slice(&message.payload[..length]);
+        let crc = crc_accumulate(crc_extra, crc16_mcrf4xx(&packet[1..]));
+        packet.extend_from_slice(&crc.to_le_bytes());
+
+        self.sequence = self.sequence.wrapping_add(1);
+        packet
+    }
+}
+
+/// HEARTBEAT of a generic quadrotor
+pub fn heartbeat(base_mode: u8, system_status: u8) -> MavlinkMessage {
+    MavlinkMessage::new(message_id::HEARTBEAT)
+        .u32(0) // custom_mode
+        .u8(MAV_TYPE_QUADROTOR)
+        .u8(MAV_AUTOPILOT_GENERIC)
+        .u8(base_mode)
+        .u8(system_status)
+        .u8(3) // mavlink_version
+}
+
+/// SYS_STATUS with the sensors telemetry arrived from, pack voltage and
+/// current, and the uplink packet loss as communication drop rate
+pub fn sys_status(
+    battery: Option<&BatterySensor>,
+    link: Option<&LinkStatistics>,
+    gps: Option<&GpsData>,
+) -> MavlinkMessage {
+    let mut present = 0;
+    let mut health = 0;
+    if battery.is_some() {
+        present |= MAV_SYS_STATUS_SENSOR_BATTERY;
+        health |= MAV_SYS_STATUS_SENSOR_BATTERY;
+    }
+    if let Some(link) = link {
+        present |= MAV_SYS_STATUS_SENSOR_RC_RECEIVER;
+        if link.uplink_lq > 0 {
+            health |= MAV_SYS_STATUS_SENSOR_RC_RECEIVER;
+        }
+    }
+    if let Some(gps) = gps {
+        present |= MAV_SYS_STATUS_SENSOR_GPS;
+        if gps.satellites >= GPS_MIN_SATELLITES_3D {
+            health |= MAV_SYS_STATUS_SENSOR_GPS;
+        }
+    }
+    let drop_rate = link.map_or(0, |link| u16::from(100 - link.uplink_lq.min(100)) * 100);
+
+    MavlinkMessage::new(message_id::SYS_STATUS)
+        .u32(present)
+        .u32(present) // enabled
+        .u32(health)
+        .u16(0) // load
+        .u16(battery.map_or(u16::MAX, |battery| millivolts(battery.voltage)))
+        .i16(battery.map_or(-1, |battery| centiamps(battery.current)))
+        .u16(drop_rate)
+        .u16(0) // errors_comm
+        .u16(0)
+        .u16(0)
+        .u16(0)
+        .u16(0) // errors_count1-4
+        .u8(battery.map_or(-1, |battery| battery.remaining_percent.min(100) as i8) as u8)
+}
+
+/// BATTERY_STATUS of the flight pack; CRSF only reports the pack voltage,
+/// which goes into the first cell as MAVLink asks for
+pub fn battery_status(battery: &BatterySensor) -> MavlinkMessage {
+    let mut message = MavlinkMessage::new(message_id::BATTERY_STATUS)
+        .i32(battery.capacity_used.min(i32::MAX as u32) as i32)
+        .i32(-1) // energy_consumed
+        .i16(i16::MAX) // temperature unknown
+        .u16(millivolts(battery.voltage));
+    for _ in 1..10 {
+        message = message.u16(u16::MAX);
+    }
+    message
+        .i16(centiamps(battery.current))
+        .u8(0) // id
+        .u8(MAV_BATTERY_FUNCTION_ALL)
+        .u8(MAV_BATTERY_TYPE_LIPO)
+        .u8(battery.remaining_percent.min(100))
+}
+
+/// GPS_RAW_INT; a fix is assumed from [`GPS_MIN_SATELLITES_3D`] satellites
+pub fn gps_raw_int(gps: &GpsData, time_usec: u64) -> MavlinkMessage {
+    let fix_type = if gps.satellites >= GPS_MIN_SATELLITES_3D { GPS_FIX_TYPE_3D_FIX } else { GPS_FIX_TYPE_NO_FIX };
+    MavlinkMessage::new(message_id::GPS_RAW_INT)
+        .u64(time_usec)
+        .i32(degrees_e7(gps.latitude))
+        .i32(degrees_e7(gps.longitude))
+        .i32(i32::from(gps.altitude) * 1000)
+        .u16(u16::MAX) // eph unknown
+        .u16(u16::MAX) // epv unknown
+        .u16(speed_cm_s(gps.ground_speed).min(f32::from(u16::MAX - 1)) as u16)
+        .u16(centidegrees(gps.heading))
+        .u8(fix_type)
+        .u8(gps.satellites)
+}
+
+/// GLOBAL_POSITION_INT, with the altitude relative to `home_altitude` and
+/// the velocity split from ground speed and heading
+pub fn global_position_int(gps: &GpsData, home_altitude: i16, time_boot_ms: u32) -> MavlinkMessage {
+    let speed = speed_cm_s(gps.ground_speed);
+    let heading = gps.heading.to_radians();
+    let velocity = |component: f32| component.round().clamp(f32::from(i16::MIN), f32::from(i16::MAX)) as i16;
+
+    MavlinkMessage::new(message_id::GLOBAL_POSITION_INT)
+        .u32(time_boot_ms)
+        .i32(degrees_e7(gps.latitude))
+        .i32(degrees_e7(gps.longitude))
+        .i32(i32::from(gps.altitude) * 1000)
+        .i32((i32::from(gps.altitude) - i32::from(home_altitude)) * 1000)
+        .i16(velocity(speed * heading.cos())) // vx, north
+        .i16(velocity(speed * heading.sin())) // vy, east
+        .i16(0) // vz unknown
+        .u16(centidegrees(gps.heading))
+}
+
+/// ATTITUDE; CRSF has no angular rates, they are sent as zero
+pub fn attitude(attitude: &Attitude, time_boot_ms: u32) -> MavlinkMessage {
+    MavlinkMessage::new(message_id::ATTITUDE)
+        .u32(time_boot_ms)
+        .f32(attitude.roll)
+        .f32(attitude.pitch)
+        .f32(attitude.yaw)
+        .f32(0.0)
+        .f32(0.0)
+        .f32(0.0)
+}
+
+/// RADIO_STATUS with the downlink (local) and uplink (remote) RSSI on the
+/// SiK radio scale GCSs assume (`dBm = rssi / 1.9 - 127`)
+pub fn radio_status(link: &LinkStatistics) -> MavlinkMessage {
+    let uplink_rssi = if link.active_antenna == 0 { link.uplink_rssi_1 } else { link.uplink_rssi_2 };
+    MavlinkMessage::new(message_id::RADIO_STATUS)
+        .u16(0) // rxerrors
+        .u16(0) // fixed
+        .u8(sik_rssi(link.downlink_rssi))
+        .u8(sik_rssi(uplink_rssi))
+        .u8(100) // txbuf
+        .u8(u8::MAX) // noise unknown
+        .u8(u8::MAX) // remnoise unknown
+}
+
+/// STATUSTEXT with `text` cut to 50 bytes
+pub fn statustext(severity: u8, text: &str) -> MavlinkMessage {
+    let mut message = MavlinkMessage::new(message_id::STATUSTEXT).u8(severity);
+    let mut bytes = [0u8; STATUSTEXT_LEN];
+    for (slot, byte) in bytes.iter_mut().zip(text.bytes()) {
+        *slot = byte;
+    }
+    message.payload.extend_from_slice(&bytes);
+    message
+}
+
+fn millivolts(volts: f32) -> u16 {
+    (volts * 1000.0).round().clamp(0.0, f32::from(u16::MAX - 1)) as u16
+}
+
+fn centiamps(amps: f32) -> i16 {
+    (amps * 100.0).round().clamp(0.0, f32::from(i16::MAX)) as i16
+}
+
+fn degrees_e7(degrees: f64) -> i32 {
+    (degrees * 10_000_000.0).round() as i32
+}
+
+fn centidegrees(degrees: f32) -> u16 {
+    ((degrees * 100.0).round() as i32).rem_euclid(36_000) as u16
+}
+
+/// km/h to cm/s
+fn speed_cm_s(kmh: f32) -> f32 {
+    kmh * 100_000.0 / 3600.0
+}
+
+/// CRSF RSSI (-dBm) on the SiK scale, 0-254
+fn sik_rssi(minus_dbm: u8) -> u8 {
+    ((127.0 - f32::from(minus_dbm)) * 1.9).round().clamp(0.0, 254.0) as u8
+}
+
+/// Heartbeat base mode flags for a CRSF flight mode name
+///
+/// Acro modes are manual, self-levelling modes are stabilized and GPS
+/// modes (return to home, waypoints, landing) are guided and auto. Unknown
+/// or missing modes are reported as manual.
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::mavlink::mode_flags;
+///
+/// assert_eq!(mode_flags(Some("ACRO*")), 0x40);
+/// assert_eq!(mode_flags(Some("ANGL")), 0x50);
+/// assert_eq!(mode_flags(Some("RTH")), 0x1C);
+/// ```
+pub fn mode_flags(flight_mode: Option<&str>) -> u8 {
+    let mode = flight_mode.unwrap_or_default().trim_end_matches('*');
+    match mode {
+        "ANGL" | "HOR" | "STAB" | "HOLD" | "CRUZ" | "3CRS" => {
+            MAV_MODE_FLAG_MANUAL_INPUT_ENABLED | MAV_MODE_FLAG_STABILIZE_ENABLED
+        }
+        "RTH" | "WP" | "LAND" | "WAIT" | FLIGHT_MODE_FAILSAFE => {
+            MAV_MODE_FLAG_STABILIZE_ENABLED | MAV_MODE_FLAG_GUIDED_ENABLED | MAV_MODE_FLAG_AUTO_ENABLED
+        }
+        _ => MAV_MODE_FLAG_MANUAL_INPUT_ENABLED,
+    }
+}
+
+/// Whether the drone is armed: the arm channel is on and the flight
+/// controller does not report the mode with the disarmed `*` (Betaflight)
+pub fn is_armed(status: &BridgeStatus) -> bool {
+    status.armed() && !status.flight_mode.as_deref().is_some_and(|mode| mode.ends_with('*'))
+}
+
+/// Turns [`BridgeStatus`] updates into MAVLink packets
+///
+/// Remembers what was sent, so each piece of telemetry goes out once per
+/// change. The relative altitude is measured from the home position of
+/// [`BridgeStatus::navigation`], locked at arming.
+#[derive(Debug, Clone)]
+pub struct MavlinkTelemetry {
+    encoder: MavlinkEncoder,
+    started: Instant,
+    home: Option<HomePosition>,
+    link_at: Option<Instant>,
+    battery: Option<BatterySensor>,
+    gps: Option<GpsData>,
+    attitude: Option<Attitude>,
+    flight_mode: Option<String>,
+    armed: bool,
+}
+
+impl MavlinkTelemetry {
+    /// Translator for messages from `system_id`, with boot time `now`
+    pub fn new(system_id: u8, now: Instant) -> Self {
+        Self {
+            encoder: MavlinkEncoder::new(system_id),
+            started: now,
+            home: None,
+            link_at: None,
+            battery: None,
+            gps: None,
+            attitude: None,
+            flight_mode: None,
+            armed: false,
+        }
+    }
+
+    /// Periodic packets: HEARTBEAT, SYS_STATUS and, once battery telemetry
+    /// arrived, BATTERY_STATUS
+    pub fn heartbeat(&mut self, status: &BridgeStatus, now: Instant) -> Vec<Vec<u8>> {
+        let mut messages = vec![self.heartbeat_message(status, now), Self::sys_status_message(status)];
+        messages.extend(status.battery.as_ref().map(battery_status));
+        self.encode_all(&messages)
+    }
+
+    /// Packets for the telemetry that changed since the last call
+    pub fn updates(&mut self, status: &BridgeStatus, now: Instant) -> Vec<Vec<u8>> {
+        let mut messages = Vec::new();
+
+        if let Some((link, at)) = status.link {
+            if self.link_at != Some(at) {
+                self.link_at = Some(at);
+                messages.push(radio_status(&link));
+            }
+        }
+        if status.battery.is_some() && status.battery != self.battery {
+            self.battery = status.battery;
+            messages.push(Self::sys_status_message(status));
+            messages.extend(status.battery.as_ref().map(battery_status));
+        }
+        // A newly locked home changes the relative altitude of the same position
+        let home = status.navigation.home;
+        if let Some(gps) = status.gps.filter(|&gps| Some(gps) != self.gps || home != self.home) {
+            self.gps = Some(gps);
+            self.home = home;
+            // No home yet (disarmed, or too few satellites): zero relative altitude
+            let home_altitude = home.map_or(gps.altitude, |home| home.altitude);
+            messages.push(gps_raw_int(&gps, self.elapsed(now).as_micros() as u64));
+            messages.push(global_position_int(&gps, home_altitude, self.time_boot_ms(now)));
+        }
+        if let Some(value) = status.attitude.filter(|&value| Some(value) != self.attitude) {
+            self.attitude = Some(value);
+            messages.push(attitude(&value, self.time_boot_ms(now)));
+        }
+
+        // A new mode or arm state is announced without waiting for the next heartbeat
+        let mode_changed = status.flight_mode.is_some() && status.flight_mode != self.flight_mode;
+        if mode_changed {
+            self.flight_mode = status.flight_mode.clone();
+            let mode = self.flight_mode.as_deref().unwrap_or_default();
+            messages.push(statustext(MAV_SEVERITY_INFO, &format!("Flight mode {}", mode)));
+        }
+        if mode_changed || is_armed(status) != self.armed {
+            messages.push(self.heartbeat_message(status, now));
+        }
+
+        self.encode_all(&messages)
+    }
+
+    fn heartbeat_message(&mut self, status: &BridgeStatus, now: Instant) -> MavlinkMessage {
+        self.armed = is_armed(status);
+        let mode = status.flight_mode.as_deref();
+        let mut base_mode = mode_flags(mode);
+        if self.armed {
+            base_mode |= MAV_MODE_FLAG_SAFETY_ARMED;
+        }
+        let failsafe = !status.failsafe_reasons(now).is_empty()
+            || mode.is_some_and(|mode| mode.trim_end_matches('*') == FLIGHT_MODE_FAILSAFE);
+        let system_status = match (failsafe, self.armed) {
+            (true, _) => MAV_STATE_CRITICAL,
+            (false, true) => MAV_STATE_ACTIVE,
+            (false, false) => MAV_STATE_STANDBY,
+        };
+        heartbeat(base_mode, system_status)
+    }
+
+    fn sys_status_message(status: &BridgeStatus) -> MavlinkMessage {
+        sys_status(status.battery.as_ref(), status.link.as_ref().map(|(link, _)| link), status.gps.as_ref())
+    }
+
+    fn elapsed(&self, now: Instant) -> Duration {
+        now.duration_since(self.started)
+    }
+
+    fn time_boot_ms(&self, now: Instant) -> u32 {
+        self.elapsed(now).as_millis() as u32
+    }
+
+    fn encode_all(&mut self, messages: &[MavlinkMessage]) -> Vec<Vec<u8>> {
+        messages.iter().map(|message| self.encoder.encode(message)).collect()
+    }
+}
+
+/// UDP datagrams to a ground station (GCS, antenna tracker)
+///
+/// Unlike [`UdpSink`](crate::sink::UdpSink) the socket is not connected:
+/// a receiver that is not running yet is normal, and would otherwise turn
+/// every other send into a "connection refused" error.
+#[derive(Debug)]
+pub struct GcsSink {
+    socket: UdpSocket,
+    target: SocketAddr,
+    address: String,
+}
+
+impl GcsSink {
+    /// Create a socket sending to `address` (`HOST:PORT`)
+    ///
+    /// # Errors
+    ///
+    /// Returns `Output` error if the address cannot be resolved or the
+    /// socket cannot be bound.
+    pub async fn bind(address: &str) -> Result<Self> {
+        let error = |e: std::io::Error| FpvBridgeError::Output(format!("UDP target {}: {}", address, e));
+
+        let target = tokio::net::lookup_host(address).await.map_err(error)?.next().ok_or_else(|| {
+            FpvBridgeError::Output(format!("UDP target {}: address did not resolve", address))
+        })?;
+        let local = if target.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+        let socket = UdpSocket::bind(local).await.map_err(error)?;
+
+        Ok(Self {
+            socket,
+            target,
+            address: address.to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl TelemetrySink for GcsSink {
+    async fn send(&mut self, packet: &[u8]) -> Result<()> {
+        self.socket
+            .send_to(packet, self.target)
+            .await
+            .map_err(|e| FpvBridgeError::Output(format!("UDP target {}: {}", self.address, e)))?;
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        format!("udp:{}", self.address)
+    }
+}
+
+/// Send MAVLink telemetry until the status bus closes
+///
+/// # Arguments
+///
+/// * `sink` - Where packets go, a [`GcsSink`] to the GCS
+/// * `system_id` - MAVLink system ID of the drone
+/// * `status` - Status bus of the control loop
+///
+/// Send failures are logged once, and again when sending works again;
+/// they never stop the output.
+pub async fn run_mavlink<S: TelemetrySink>(mut sink: S, system_id: u8, mut status: watch::Receiver<BridgeStatus>) {
+    let mut telemetry = MavlinkTelemetry::new(system_id, Instant::now());
+    let mut heartbeat = interval(HEARTBEAT_INTERVAL);
+    heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut failing = false;
+    let mut sent: u64 = 0;
+
+    loop {
+        let packets = tokio::select! {
+            _ = heartbeat.tick() => telemetry.heartbeat(&status.borrow(), Instant::now()),
+            changed = status.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+                telemetry.updates(&status.borrow_and_update(), Instant::now())
+            }
+        };
+
+        for packet in packets {
+            match sink.send(&packet).await {
+                Ok(()) => {
+                    sent += 1;
+                    if std::mem::take(&mut failing) {
+                        info!("MAVLink output {} recovered", sink.describe());
+                    }
+                }
+                Err(e) => {
+                    if !std::mem::replace(&mut failing, true) {
+                        warn!("MAVLink output failing: {}", e);
+                    }
+                }
+            }
+        }
+    }
+    info!("MAVLink output {}: {} packets sent", sink.describe(), sent);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crsf::protocol::CRSF_CHANNEL_VALUE_MAX;
+    use crate::controller::channel_mapper::channels;
+    use tokio::time::timeout;
+
+    /// Check a packet's framing and CRC, returning the message ID and the
+    /// payload padded back to `size` bytes
+    fn parse(packet: &[u8], size: usize) -> (u32, Vec<u8>) {
+        assert_eq!(packet[0], MAVLINK_V2_MAGIC);
+        let length = usize::from(packet[1]);
+        assert_eq!(packet.len(), MAVLINK_V2_HEADER_SIZE + length + 2);
+        let id = u32::from_le_bytes([packet[7], packet[8], packet[9], 0]);
+        let body = &packet[..MAVLINK_V2_HEADER_SIZE + length];
+        let crc = crc_accumulate(message_id::crc_extra(id).unwrap(), crc16_mcrf4xx(&body[1..]));
+        assert_eq!(packet[body.len()..], crc.to_le_bytes(), "CRC of message {}", id);
+
+        let mut payload = body[MAVLINK_V2_HEADER_SIZE..].to_vec();
+        payload.resize(size.max(length), 0);
+        (id, payload)
+    }
+
+    /// Message ID of the next datagram from system 42
+    async fn receive(gcs: &UdpSocket) -> u32 {
+        let mut buffer = [0u8; 300];
+        let length = timeout(Duration::from_secs(2), gcs.recv(&mut buffer)).await.unwrap().unwrap();
+        assert_eq!(buffer[5], 42);
+        parse(&buffer[..length], 0).0
+    }
+
+    fn ids(packets: &[Vec<u8>]) -> Vec<u32> {
+        packets.iter().map(|packet| parse(packet, 0).0).collect()
+    }
+
+    fn link(lq: u8) -> LinkStatistics {
+        LinkStatistics {
+            uplink_rssi_1: 60,
+            uplink_rssi_2: 80,
+            uplink_lq: lq,
+            uplink_snr: 9,
+            active_antenna: 1,
+            rf_mode: 7,
+            uplink_tx_power: 3,
+            downlink_rssi: 50,
+            downlink_lq: 100,
+            downlink_snr: 8,
+        }
+    }
+
+    fn gps(altitude: i16, satellites: u8) -> GpsData {
+        GpsData { latitude: 50.45, longitude: -30.5, ground_speed: 36.0, heading: 90.0, altitude, satellites }
+    }
+
+    #[test]
+    fn test_encode_heartbeat() {
+        // Same packet as the reference implementation (rust-mavlink) encodes
+        let mut encoder = MavlinkEncoder::new(1);
+        let packet = encoder.encode(&heartbeat(MAV_MODE_FLAG_MANUAL_INPUT_ENABLED, MAV_STATE_STANDBY));
+        assert_eq!(packet, [0xFD, 0x09, 0, 0, 0, 0x01, 0x01, 0, 0, 0, 0, 0, 0, 0, 0x02, 0x00, 0x40, 0x03, 0x03, 0x34, 0x50]);
+
+        // Sequence numbers count up and wrap
+        encoder.sequence = u8::MAX;
+        assert_eq!(encoder.encode(&heartbeat(0, 0))[4], u8::MAX);
+        assert_eq!(encoder.encode(&heartbeat(0, 0))[4], 0);
+    }
+
+    #[test]
+    fn test_encode_truncates_trailing_zeros() {
+        let mut encoder = MavlinkEncoder::new(7);
+        let packet = encoder.encode(&attitude(&Attitude { pitch: 0.0, roll: 0.0, yaw: 0.0 }, 0));
+        // An all-zero payload keeps one byte
+        assert_eq!(packet[1], 1);
+        assert_eq!(packet[5], 7);
+        assert_eq!(parse(&packet, 28), (message_id::ATTITUDE, vec![0; 28]));
+
+        let packet = encoder.encode(&statustext(MAV_SEVERITY_INFO, "Flight mode ANGL"));
+        let (_, payload) = parse(&packet, 51);
+        assert_eq!(packet[1], 17);
+        assert_eq!(&payload[1..17], b"Flight mode ANGL");
+    }
+
+    #[test]
+    fn test_message_fields() {
+        let battery = BatterySensor { voltage: 16.42, current: 12.5, capacity_used: 850, remaining_percent: 64 };
+        let payload = battery_status(&battery).payload;
+        assert_eq!(payload.len(), 36);
+        assert_eq!(payload[0..4], 850i32.to_le_bytes());
+        assert_eq!(payload[10..12], 16_420u16.to_le_bytes());
+        assert_eq!(payload[12..14], u16::MAX.to_le_bytes());
+        assert_eq!(payload[30..32], 1250i16.to_le_bytes());
+        assert_eq!(payload[35], 64);
+
+        let payload = sys_status(Some(&battery), Some(&link(90)), None).payload;
+        assert_eq!(payload.len(), 31);
+        let present = MAV_SYS_STATUS_SENSOR_BATTERY | MAV_SYS_STATUS_SENSOR_RC_RECEIVER;
+        assert_eq!(payload[0..4], present.to_le_bytes());
+        assert_eq!(payload[8..12], present.to_le_bytes());
+        assert_eq!(payload[14..16], 16_420u16.to_le_bytes());
+        assert_eq!(payload[18..20], 1000u16.to_le_bytes());
+        assert_eq!(payload[30], 64);
+        // Unknown battery
+        let payload = sys_status(None, None, None).payload;
+        assert_eq!(payload[14..16], u16::MAX.to_le_bytes());
+        assert_eq!(payload[30] as i8, -1);
+
+        let payload = gps_raw_int(&gps(120, 9), 5_000_000).payload;
+        assert_eq!(payload.len(), 30);
+        assert_eq!(payload[8..12], 504_500_000i32.to_le_bytes());
+        assert_eq!(payload[12..16], (-305_000_000i32).to_le_bytes());
+        assert_eq!(payload[16..20], 120_000i32.to_le_bytes());
+        assert_eq!(payload[24..26], 1000u16.to_le_bytes());
+        assert_eq!(payload[26..28], 9000u16.to_le_bytes());
+        assert_eq!(payload[28..30], [GPS_FIX_TYPE_3D_FIX, 9]);
+        assert_eq!(gps_raw_int(&gps(120, 3), 0).payload[28], GPS_FIX_TYPE_NO_FIX);
+
+        // Heading east: all velocity in vy
+        let payload = global_position_int(&gps(120, 9), 100, 1234).payload;
+        assert_eq!(payload.len(), 28);
+        assert_eq!(payload[0..4], 1234u32.to_le_bytes());
+        assert_eq!(payload[16..20], 20_000i32.to_le_bytes());
+        assert_eq!(payload[20..22], 0i16.to_le_bytes());
+        assert_eq!(payload[22..24], 1000i16.to_le_bytes());
+        assert_eq!(payload[26..28], 9000u16.to_le_bytes());
+
+        let payload = attitude(&Attitude { pitch: 0.25, roll: -0.5, yaw: 1.0 }, 10).payload;
+        assert_eq!(payload[4..8], (-0.5f32).to_le_bytes());
+        assert_eq!(payload[8..12], 0.25f32.to_le_bytes());
+        assert_eq!(payload[12..16], 1.0f32.to_le_bytes());
+
+        // Active antenna 2 at -80dBm, downlink at -50dBm
+        let payload = radio_status(&link(100)).payload;
+        assert_eq!(payload, [0, 0, 0, 0, 146, 89, 100, 255, 255]);
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(centidegrees(359.999), 0);
+        assert_eq!(centidegrees(-90.0), 27_000);
+        assert_eq!(sik_rssi(0), 241);
+        assert_eq!(sik_rssi(130), 0);
+        assert_eq!(millivolts(-1.0), 0);
+        assert_eq!(mode_flags(None), MAV_MODE_FLAG_MANUAL_INPUT_ENABLED);
+        assert_eq!(mode_flags(Some("!FS!")), mode_flags(Some("RTH")));
+    }
+
+    #[test]
+    fn test_heartbeat_state() {
+        let now = Instant::now();
+        let mut telemetry = MavlinkTelemetry::new(1, now);
+        let mut status = BridgeStatus::new("default", Duration::from_millis(500));
+
+        let state = |packets: Vec<Vec<u8>>| {
+            let (id, payload) = parse(&packets[0], 9);
+            assert_eq!(id, message_id::HEARTBEAT);
+            (payload[6], payload[7])
+        };
+        assert_eq!(state(telemetry.heartbeat(&status, now)), (MAV_MODE_FLAG_MANUAL_INPUT_ENABLED, MAV_STATE_STANDBY));
+
+        status.channels[channels::ARM] = CRSF_CHANNEL_VALUE_MAX;
+        status.flight_mode = Some("ANGL".to_string());
+        assert_eq!(state(telemetry.heartbeat(&status, now)), (0xD0, MAV_STATE_ACTIVE));
+
+        // The flight controller has not armed yet
+        status.flight_mode = Some("ANGL*".to_string());
+        assert_eq!(state(telemetry.heartbeat(&status, now)), (0x50, MAV_STATE_STANDBY));
+
+        status.flight_mode = Some("!FS!".to_string());
+        assert_eq!(state(telemetry.heartbeat(&status, now)).1, MAV_STATE_CRITICAL);
+
+        status.flight_mode = None;
+        status.record_error("Serial output stalled", true);
+        assert_eq!(state(telemetry.heartbeat(&status, now)).1, MAV_STATE_CRITICAL);
+
+        // Battery status follows once the battery is known
+        assert_eq!(ids(&telemetry.heartbeat(&status, now)), [message_id::HEARTBEAT, message_id::SYS_STATUS]);
+        status.battery = Some(BatterySensor { voltage: 16.0, current: 0.0, capacity_used: 0, remaining_percent: 90 });
+        assert_eq!(
+            ids(&telemetry.heartbeat(&status, now)),
+            [message_id::HEARTBEAT, message_id::SYS_STATUS, message_id::BATTERY_STATUS]
+        );
+    }
+
+    #[test]
+    fn test_updates_send_changes_once() {
+        let now = Instant::now();
+        let mut telemetry = MavlinkTelemetry::new(1, now);
+        let mut status = BridgeStatus::new("default", Duration::from_millis(500));
+        assert!(telemetry.updates(&status, now).is_empty());
+
+        status.link = Some((link(100), now));
+        status.battery = Some(BatterySensor { voltage: 16.0, current: 1.0, capacity_used: 10, remaining_percent: 90 });
+        status.gps = Some(gps(150, 2));
+        status.attitude = Some(Attitude { pitch: 0.0, roll: 0.1, yaw: 0.0 });
+        status.flight_mode = Some("ACRO*".to_string());
+        assert_eq!(
+            ids(&telemetry.updates(&status, now)),
+            [
+                message_id::RADIO_STATUS,
+                message_id::SYS_STATUS,
+                message_id::BATTERY_STATUS,
+                message_id::GPS_RAW_INT,
+                message_id::GLOBAL_POSITION_INT,
+                message_id::ATTITUDE,
+                message_id::STATUSTEXT,
+                message_id::HEARTBEAT,
+            ]
+        );
+        assert!(telemetry.updates(&status, now).is_empty());
+
+        // No home before arming: zero relative altitude, even with a fix
+        status.gps = Some(gps(100, 8));
+        let packets = telemetry.updates(&status, now);
+        let (_, payload) = parse(&packets[1], 28);
+        assert_eq!(payload[16..20], 0i32.to_le_bytes());
+
+        // Home is navigation's, locked at arming; relative altitude from there
+        status.navigation.set_armed(true, Some(&gps(100, 8)), now);
+        let packets = telemetry.updates(&status, now);
+        assert_eq!(ids(&packets), [message_id::GPS_RAW_INT, message_id::GLOBAL_POSITION_INT]);
+        status.gps = Some(gps(130, 8));
+        let packets = telemetry.updates(&status, now);
+        let (_, payload) = parse(&packets[1], 28);
+        assert_eq!(payload[16..20], 30_000i32.to_le_bytes());
+
+        // Arming is announced at once
+        status.channels[channels::ARM] = CRSF_CHANNEL_VALUE_MAX;
+        status.flight_mode = Some("ACRO".to_string());
+        assert_eq!(ids(&telemetry.updates(&status, now)), [message_id::STATUSTEXT, message_id::HEARTBEAT]);
+
+        // A new link statistics report is sent even with the same values
+        status.link = Some((link(100), now + Duration::from_millis(100)));
+        assert_eq!(ids(&telemetry.updates(&status, now)), [message_id::RADIO_STATUS]);
+    }
+
+    #[tokio::test]
+    async fn test_run_mavlink_over_udp() {
+        let gcs = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let sink = GcsSink::bind(&gcs.local_addr().unwrap().to_string()).await.unwrap();
+        assert_eq!(sink.describe(), format!("udp:{}", gcs.local_addr().unwrap()));
+        let (status_tx, status_rx) = watch::channel(BridgeStatus::new("default", Duration::from_millis(500)));
+        let output = tokio::spawn(run_mavlink(sink, 42, status_rx));
+
+        assert_eq!(receive(&gcs).await, message_id::HEARTBEAT);
+        assert_eq!(receive(&gcs).await, message_id::SYS_STATUS);
+
+        status_tx.send_modify(|status| status.gps = Some(gps(100, 10)));
+        assert_eq!(receive(&gcs).await, message_id::GPS_RAW_INT);
+        assert_eq!(receive(&gcs).await, message_id::GLOBAL_POSITION_INT);
+
+        drop(status_tx);
+        timeout(Duration::from_secs(2), output).await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,370 @@
+//! # Navigation
+//!
+//! Derives home-relative navigation from the CRSF GPS telemetry:
+//!
+//! - The home position is locked when the drone arms with a fix of at
+//!   least `[navigation] home_min_satellites` satellites, or at the first
+//!   such fix after arming
+//! - Every GPS update then gives the distance and bearing to home and the
+//!   altitude relative to home
+//! - While armed, the flight statistics (maximum distance, altitude and
+//!   speed, distance travelled) are kept, and logged as a flight summary
+//!   on disarm
+//!
+//! [`Navigation`] lives in the [`BridgeStatus`](crate::dashboard::BridgeStatus),
+//! so the dashboard and the status API show it like the other telemetry.
+
+use std::fmt;
+
+use serde::Serialize;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+use crate::crsf::protocol::GpsData;
+
+/// Satellites needed to lock the home position by default
+pub const DEFAULT_HOME_MIN_SATELLITES: u8 = 6;
+
+/// Mean Earth radius used for distances
+pub const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+/// Great-circle distance between two positions (haversine)
+///
+/// # Arguments
+///
+/// * `from` - Latitude and longitude in degrees
+/// * `to` - Latitude and longitude in degrees
+///
+/// # Returns
+///
+/// * `f64` - Distance in meters
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::navigation::distance_m;
+///
+/// // One hundredth of a degree of latitude
+/// let distance = distance_m((50.45, 30.5), (50.46, 30.5));
+/// assert!((distance - 1111.95).abs() < 0.01);
+/// ```
+pub fn distance_m(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (lat1, lat2) = (from.0.to_radians(), to.0.to_radians());
+    let delta_lat = lat2 - lat1;
+    let delta_lon = (to.1 - from.1).to_radians();
+
+    let a = (delta_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (delta_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * a.sqrt().atan2((1.0 - a).sqrt())
+}
+
+/// Initial great-circle bearing from one position to another
+///
+/// # Arguments
+///
+/// * `from` - Latitude and longitude in degrees
+/// * `to` - Latitude and longitude in degrees
+///
+/// # Returns
+///
+/// * `f64` - Bearing in degrees, 0-360 clockwise from north
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::navigation::bearing_deg;
+///
+/// assert!((bearing_deg((50.46, 30.5), (50.45, 30.5)) - 180.0).abs() < 1e-9);
+/// ```
+pub fn bearing_deg(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (lat1, lat2) = (from.0.to_radians(), to.0.to_radians());
+    let delta_lon = (to.1 - from.1).to_radians();
+
+    let y = delta_lon.sin() * lat2.cos();
+    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * delta_lon.cos();
+    y.atan2(x).to_degrees().rem_euclid(360.0)
+}
+
+/// Home position, locked at arming
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct HomePosition {
+    /// Latitude in degrees
+    pub latitude: f64,
+    /// Longitude in degrees
+    pub longitude: f64,
+    /// Altitude in meters
+    pub altitude: i16,
+    /// Satellites of the fix
+    pub satellites: u8,
+}
+
+/// Where home is, seen from the drone
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct HomeVector {
+    /// Distance to home in meters
+    pub distance_m: f64,
+    /// Bearing to home in degrees, 0-360 clockwise from north
+    pub bearing: f64,
+    /// Altitude above home in meters
+    pub relative_altitude_m: i32,
+}
+
+/// Statistics of the current or last flight
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize)]
+pub struct FlightStats {
+    /// Time armed in seconds
+    pub duration_s: f64,
+    /// Farthest distance from home in meters
+    pub max_distance_m: f64,
+    /// Highest altitude above home in meters
+    pub max_altitude_m: i32,
+    /// Highest ground speed in km/h
+    pub max_speed_kmh: f32,
+    /// Distance travelled over ground in meters
+    pub distance_travelled_m: f64,
+}
+
+impl fmt::Display for FlightStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seconds = self.duration_s.round() as u64;
+        write!(
+            f,
+            "{}m{:02}s, max distance {:.0}m, max altitude {}m, max speed {:.1}km/h, travelled {:.2}km",
+            seconds / 60,
+            seconds % 60,
+            self.max_distance_m,
+            self.max_altitude_m,
+            self.max_speed_kmh,
+            self.distance_travelled_m / 1000.0
+        )
+    }
+}
+
+/// Home position and flight statistics from GPS telemetry
+#[derive(Debug, Clone, Serialize)]
+pub struct Navigation {
+    /// Satellites needed to lock the home position
+    pub home_min_satellites: u8,
+    /// Home of the current or last flight
+    pub home: Option<HomePosition>,
+    /// Where home is from the latest position
+    pub to_home: Option<HomeVector>,
+    /// Statistics of the current or last flight
+    pub flight: Option<FlightStats>,
+    armed: bool,
+    #[serde(skip)]
+    armed_at: Option<Instant>,
+    #[serde(skip)]
+    last_position: Option<(f64, f64)>,
+}
+
+impl Navigation {
+    /// Navigation before the first flight
+    ///
+    /// # Arguments
+    ///
+    /// * `home_min_satellites` - Satellites needed to lock the home position
+    pub fn new(home_min_satellites: u8) -> Self {
+        Self {
+            home_min_satellites,
+            home: None,
+            to_home: None,
+            flight: None,
+            armed: false,
+            armed_at: None,
+            last_position: None,
+        }
+    }
+
+    /// Whether a flight is in progress
+    pub fn armed(&self) -> bool {
+        self.armed
+    }
+
+    /// Follow the arm state
+    ///
+    /// Arming starts a new flight: the statistics are reset and the home
+    /// position is locked from `gps` if its fix is good enough, otherwise
+    /// at the first good fix. Disarming logs the flight summary.
+    ///
+    /// # Arguments
+    ///
+    /// * `armed` - Whether the drone is armed
+    /// * `gps` - Latest GPS telemetry
+    /// * `now` - Current time
+    pub fn set_armed(&mut self, armed: bool, gps: Option<&GpsData>, now: Instant) {
+        if armed == self.armed {
+            return;
+        }
+        self.armed = armed;
+
+        if armed {
+            self.home = None;
+            self.to_home = None;
+            self.flight = Some(FlightStats::default());
+            self.armed_at = Some(now);
+            self.last_position = None;
+            match gps {
+                Some(gps) if gps.satellites >= self.home_min_satellites => self.update(gps, now),
+                Some(gps) => warn!(
+                    "Armed without home position: {} satellites, {} needed",
+                    gps.satellites, self.home_min_satellites
+                ),
+                None => {}
+            }
+        } else if let Some(flight) = &mut self.flight {
+            if let Some(armed_at) = self.armed_at.take() {
+                flight.duration_s = now.duration_since(armed_at).as_secs_f64();
+            }
+            info!("Flight summary: {}", flight);
+        }
+    }
+
+    /// Take a GPS update
+    ///
+    /// # Arguments
+    ///
+    /// * `gps` - GPS telemetry
+    /// * `now` - Current time
+    pub fn update(&mut self, gps: &GpsData, now: Instant) {
+        let position = (gps.latitude, gps.longitude);
+        let good_fix = gps.satellites >= self.home_min_satellites;
+
+        if self.armed && self.home.is_none() && good_fix {
+            let home = HomePosition {
+                latitude: gps.latitude,
+                longitude: gps.longitude,
+                altitude: gps.altitude,
+                satellites: gps.satellites,
+            };
+            info!(
+                "Home position locked at {:.6}, {:.6}, {}m ({} satellites)",
+                home.latitude, home.longitude, home.altitude, home.satellites
+            );
+            self.home = Some(home);
+        }
+
+        if let Some(home) = &self.home {
+            let home_position = (home.latitude, home.longitude);
+            self.to_home = Some(HomeVector {
+                distance_m: distance_m(position, home_position),
+                bearing: bearing_deg(position, home_position),
+                relative_altitude_m: i32::from(gps.altitude) - i32::from(home.altitude),
+            });
+        }
+
+        if !self.armed || !good_fix {
+            return;
+        }
+        if let Some(flight) = &mut self.flight {
+            if let Some(armed_at) = self.armed_at {
+                flight.duration_s = now.duration_since(armed_at).as_secs_f64();
+            }
+            if let Some(to_home) = &self.to_home {
+                flight.max_distance_m = flight.max_distance_m.max(to_home.distance_m);
+                flight.max_altitude_m = flight.max_altitude_m.max(to_home.relative_altitude_m);
+            }
+            flight.max_speed_kmh = flight.max_speed_kmh.max(gps.ground_speed);
+            if let Some(last) = self.last_position {
+                flight.distance_travelled_m += distance_m(last, position);
+            }
+            self.last_position = Some(position);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::time::Duration;
+
+    const HOME: (f64, f64) = (50.45, 30.5);
+
+    fn gps(latitude: f64, longitude: f64, altitude: i16, satellites: u8) -> GpsData {
+        GpsData { latitude, longitude, ground_speed: 36.0, heading: 0.0, altitude, satellites }
+    }
+
+    #[test]
+    fn test_distance_and_bearing() {
+        assert_eq!(distance_m(HOME, HOME), 0.0);
+
+        // 0.01° of longitude at 50.45° north
+        let east = (50.45, 30.51);
+        assert!((distance_m(HOME, east) - 708.1).abs() < 0.1);
+        assert!((bearing_deg(east, HOME) - 270.0).abs() < 0.01);
+        assert!((bearing_deg(HOME, east) - 90.0).abs() < 0.01);
+
+        // Across the antimeridian
+        assert!((distance_m((0.0, 179.99), (0.0, -179.99)) - 2223.9).abs() < 0.1);
+        assert!((bearing_deg((0.0, 179.99), (0.0, -179.99)) - 90.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_home_locks_at_arming() {
+        let start = Instant::now();
+        let mut navigation = Navigation::new(6);
+
+        // Disarmed: nothing locked
+        navigation.update(&gps(50.45, 30.5, 120, 9), start);
+        assert_eq!(navigation.home, None);
+
+        navigation.set_armed(true, Some(&gps(50.45, 30.5, 120, 9)), start);
+        assert_eq!(navigation.home.unwrap().altitude, 120);
+
+        // North of home, 30 m up
+        navigation.update(&gps(50.46, 30.5, 150, 9), start + Duration::from_secs(10));
+        let to_home = navigation.to_home.unwrap();
+        assert!((to_home.distance_m - 1111.95).abs() < 0.01);
+        assert!((to_home.bearing - 180.0).abs() < 1e-6);
+        assert_eq!(to_home.relative_altitude_m, 30);
+        assert_eq!(navigation.home.unwrap().latitude, 50.45);
+    }
+
+    #[test]
+    fn test_home_waits_for_satellites() {
+        let start = Instant::now();
+        let mut navigation = Navigation::new(6);
+        navigation.set_armed(true, Some(&gps(50.45, 30.5, 120, 5)), start);
+        assert_eq!(navigation.home, None);
+
+        navigation.update(&gps(50.45, 30.5, 120, 5), start);
+        assert_eq!(navigation.home, None);
+        assert_eq!(navigation.to_home, None);
+
+        navigation.update(&gps(50.451, 30.5, 121, 6), start);
+        assert_eq!(navigation.home.unwrap().satellites, 6);
+        assert_eq!(navigation.to_home.unwrap().distance_m, 0.0);
+    }
+
+    #[test]
+    fn test_flight_stats() {
+        let start = Instant::now();
+        let mut navigation = Navigation::new(6);
+        navigation.set_armed(true, Some(&gps(50.45, 30.5, 100, 9)), start);
+
+        // Out 0.01° north and back
+        navigation.update(&gps(50.46, 30.5, 180, 9), start + Duration::from_secs(30));
+        navigation.update(&GpsData { ground_speed: 96.5, ..gps(50.46, 30.5, 140, 9) }, start + Duration::from_secs(60));
+        // Poor fixes do not count
+        navigation.update(&gps(51.0, 30.5, 900, 4), start + Duration::from_secs(70));
+        navigation.update(&gps(50.45, 30.5, 100, 9), start + Duration::from_secs(90));
+
+        navigation.set_armed(false, None, start + Duration::from_secs(192));
+        let flight = navigation.flight.unwrap();
+        assert_eq!(flight.duration_s, 192.0);
+        assert!((flight.max_distance_m - 1111.95).abs() < 0.01);
+        assert_eq!(flight.max_altitude_m, 80);
+        assert_eq!(flight.max_speed_kmh, 96.5);
+        assert!((flight.distance_travelled_m - 2223.9).abs() < 0.1);
+        assert_eq!(
+            flight.to_string(),
+            "3m12s, max distance 1112m, max altitude 80m, max speed 96.5km/h, travelled 2.22km"
+        );
+
+        // The last flight stays visible until the next one starts
+        assert!(!navigation.armed());
+        assert!(navigation.home.is_some());
+        navigation.set_armed(true, None, start + Duration::from_secs(300));
+        assert_eq!(navigation.home, None);
+        assert_eq!(navigation.flight, Some(FlightStats::default()));
+    }
+}
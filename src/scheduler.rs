@@ -0,0 +1,1051 @@
+//! # Transmit Scheduler
+//!
+//! Paces RC frames sent to the ELRS module.
+//!
+//! The scheduler free-runs at the configured packet rate until the TX module
+//! reports its RF timing in RADIO_ID timing sync frames. It then follows the
+//! module's refresh interval and spreads the reported phase offset over the
+//! next periods, so frames reach the module just before each RF packet
+//! instead of drifting against it.
+//!
+//! Deadlines are waited for either with the tokio timer (millisecond
+//! resolution) or on a dedicated thread that sleeps until shortly before the
+//! deadline and then spins ([`WaitMode::Thread`]). Tick timing and serial
+//! write times are summarized once per second in a [`TickReport`].
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+
+use serde::Serialize;
+use tokio::sync::Notify;
+use tokio::time::{sleep_until, Duration, Instant};
+use tracing::{debug, info, warn};
+
+use crate::controller::mapper::ControllerState;
+use crate::crsf::protocol::TimingSync;
+
+/// Fall back to free-running if no timing sync arrives for this long
+pub const SYNC_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Length of a statistics window
+pub const STATS_WINDOW: Duration = Duration::from_secs(1);
+
+/// Default busy-wait before each deadline in [`WaitMode::Thread`]
+pub const DEFAULT_SPIN: Duration = Duration::from_micros(200);
+
+/// Shortest refresh interval accepted from the module (1000Hz)
+const MIN_SYNC_INTERVAL_NS: u64 = 1_000_000;
+
+/// Longest refresh interval accepted from the module (20Hz)
+const MAX_SYNC_INTERVAL_NS: u64 = 50_000_000;
+
+/// Largest phase correction applied to a single period, as 1/N of the period
+const MAX_CORRECTION_DIVISOR: i64 = 10;
+
+/// A tick is late if it fires more than 1/N of a period after its deadline
+const LATE_TICK_DIVISOR: u32 = 10;
+
+/// How the scheduler waits for deadlines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitMode {
+    /// Tokio timer on the runtime (millisecond resolution)
+    Tokio,
+    /// Dedicated thread: sleep until `spin` before the deadline, then busy-wait
+    Thread {
+        /// How long before each deadline to stop sleeping and spin
+        spin: Duration,
+    },
+}
+
+/// Timing sync state, for status logs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct SyncStats {
+    /// Following the module's timing (false while free-running)
+    pub synced: bool,
+
+    /// Current transmit period in nanoseconds
+    pub period_ns: u64,
+
+    /// Phase offset last reported by the module, in nanoseconds
+    pub offset_ns: i64,
+
+    /// Phase correction applied to the last period, in nanoseconds
+    pub correction_ns: i64,
+
+    /// Timing sync frames accepted since start
+    pub sync_frames: u64,
+}
+
+impl fmt::Display for SyncStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let us = |ns: i64| ns as f64 / 1000.0;
+        if self.synced {
+            write!(
+                f,
+                "synced {:.1}µs, offset {:+.1}µs, correction {:+.1}µs",
+                us(self.period_ns as i64),
+                us(self.offset_ns),
+                us(self.correction_ns)
+            )
+        } else {
+            write!(f, "free-running {:.1}µs", us(self.period_ns as i64))
+        }
+    }
+}
+
+/// Transmit timing for one statistics window
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize)]
+pub struct TickReport {
+    /// Ticks in the window
+    pub ticks: u64,
+
+    /// Mean time between ticks, in microseconds
+    pub mean_period_us: f64,
+
+    /// 99th percentile deviation from the scheduled period, in microseconds
+    pub p99_jitter_us: f64,
+
+    /// Largest deviation from the scheduled period, in microseconds
+    pub max_jitter_us: f64,
+
+    /// Deadlines skipped because the previous tick ran past them
+    pub missed: u64,
+
+    /// Ticks that fired more than 10% of a period after their deadline
+    pub late: u64,
+
+    /// Mean serial write time, in microseconds
+    pub mean_write_us: f64,
+
+    /// Longest serial write time, in microseconds
+    pub max_write_us: f64,
+
+    /// Timing sync state at the end of the window
+    pub sync: SyncStats,
+}
+
+impl fmt::Display for TickReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ticks, period {:.1}µs, jitter p99 {:.1}µs (max {:.1}µs), missed {}, late {}, \
+             write {:.1}µs (max {:.1}µs), timing: {}",
+            self.ticks,
+            self.mean_period_us,
+            self.p99_jitter_us,
+            self.max_jitter_us,
+            self.missed,
+            self.late,
+            self.mean_write_us,
+            self.max_write_us,
+            self.sync
+        )
+    }
+}
+
+/// Deadline computation shared with the wait thread
+#[derive(Debug)]
+struct Timing {
+    /// Period used while free-running
+    nominal_period: Duration,
+    /// Current period (module refresh interval while synced)
+    period: Duration,
+    /// When the next frame is due
+    next_deadline: Instant,
+    /// Phase offset not yet applied
+    pending_offset_ns: i64,
+    /// When the last timing sync was accepted
+    last_sync: Option<Instant>,
+    /// Deadlines fired by the wait thread and not yet taken by `tick`
+    fired: Vec<Instant>,
+    /// Deadlines skipped and not yet counted
+    missed: u64,
+    sync: SyncStats,
+}
+
+impl Timing {
+    fn new(period: Duration) -> Self {
+        Self {
+            nominal_period: period,
+            period,
+            next_deadline: Instant::now(),
+            pending_offset_ns: 0,
+            last_sync: None,
+            fired: Vec::new(),
+            missed: 0,
+            sync: SyncStats {
+                period_ns: period.as_nanos() as u64,
+                ..SyncStats::default()
+            },
+        }
+    }
+
+    fn apply_sync(&mut self, sync: &TimingSync, now: Instant) -> bool {
+        if !(MIN_SYNC_INTERVAL_NS..=MAX_SYNC_INTERVAL_NS).contains(&sync.refresh_interval_ns) {
+            debug!("Ignoring timing sync with interval {}ns", sync.refresh_interval_ns);
+            return false;
+        }
+
+        if !self.sync.synced {
+            info!(
+                "Locked to ELRS timing: {:.1}µs interval",
+                sync.refresh_interval_ns as f64 / 1000.0
+            );
+        }
+
+        self.period = Duration::from_nanos(sync.refresh_interval_ns);
+        self.pending_offset_ns = sync.offset_ns;
+        self.last_sync = Some(now);
+        self.sync.synced = true;
+        self.sync.period_ns = sync.refresh_interval_ns;
+        self.sync.offset_ns = sync.offset_ns;
+        self.sync.sync_frames += 1;
+        true
+    }
+
+    /// Schedule the frame after the one due at `next_deadline`
+    fn advance(&mut self, now: Instant) {
+        if self.last_sync.is_some_and(|last| now.duration_since(last) > SYNC_TIMEOUT) {
+            warn!("Lost ELRS timing sync, free-running at {:?}", self.nominal_period);
+            self.last_sync = None;
+            self.period = self.nominal_period;
+            self.pending_offset_ns = 0;
+            self.sync.synced = false;
+            self.sync.offset_ns = 0;
+            self.sync.period_ns = self.nominal_period.as_nanos() as u64;
+        }
+
+        let period_ns = self.period.as_nanos() as i64;
+        let max_correction = period_ns / MAX_CORRECTION_DIVISOR;
+        let correction = self.pending_offset_ns.clamp(-max_correction, max_correction);
+        self.pending_offset_ns -= correction;
+        self.sync.correction_ns = correction;
+
+        let mut next = self.next_deadline + Duration::from_nanos((period_ns + correction) as u64);
+
+        // Skip missed slots instead of bursting to catch up
+        if next <= now {
+            let skipped = now.duration_since(next).as_nanos() as i64 / period_ns + 1;
+            next += Duration::from_nanos((skipped * period_ns) as u64);
+            self.missed += skipped as u64;
+        }
+        self.next_deadline = next;
+    }
+}
+
+/// Per-window tick and write timing
+#[derive(Debug)]
+struct TickStats {
+    window_start: Instant,
+    /// Previous tick: (deadline, fired at)
+    last_tick: Option<(Instant, Instant)>,
+    periods_ns: Vec<u64>,
+    jitter_ns: Vec<u64>,
+    missed: u64,
+    late: u64,
+    write_count: u64,
+    write_total_ns: u64,
+    write_max_ns: u64,
+}
+
+impl TickStats {
+    fn new(now: Instant) -> Self {
+        Self {
+            window_start: now,
+            last_tick: None,
+            periods_ns: Vec::new(),
+            jitter_ns: Vec::new(),
+            missed: 0,
+            late: 0,
+            write_count: 0,
+            write_total_ns: 0,
+            write_max_ns: 0,
+        }
+    }
+
+    fn record_tick(&mut self, deadline: Instant, fired: Instant, period: Duration) {
+        if fired.duration_since(deadline) > period / LATE_TICK_DIVISOR {
+            self.late += 1;
+        }
+
+        if let Some((last_deadline, last_fired)) = self.last_tick {
+            let actual = fired.duration_since(last_fired).as_nanos() as i64;
+            let scheduled = deadline.duration_since(last_deadline).as_nanos() as i64;
+            self.periods_ns.push(actual as u64);
+            self.jitter_ns.push((actual - scheduled).unsigned_abs());
+        }
+        self.last_tick = Some((deadline, fired));
+    }
+
+    fn record_write(&mut self, duration: Duration) {
+        let ns = duration.as_nanos() as u64;
+        self.write_count += 1;
+        self.write_total_ns += ns;
+        self.write_max_ns = self.write_max_ns.max(ns);
+    }
+
+    /// Summarize and reset the window
+    fn report(&mut self, ticks: u64, sync: SyncStats, now: Instant) -> TickReport {
+        let us = |ns: u64| ns as f64 / 1000.0;
+        let mean = |total: u64, count: u64| if count == 0 { 0.0 } else { us(total) / count as f64 };
+
+        self.jitter_ns.sort_unstable();
+        let p99_jitter = percentile(&self.jitter_ns, 99);
+
+        let report = TickReport {
+            ticks,
+            mean_period_us: mean(self.periods_ns.iter().sum(), self.periods_ns.len() as u64),
+            p99_jitter_us: us(p99_jitter),
+            max_jitter_us: us(self.jitter_ns.last().copied().unwrap_or(0)),
+            missed: self.missed,
+            late: self.late,
+            mean_write_us: mean(self.write_total_ns, self.write_count),
+            max_write_us: us(self.write_max_ns),
+            sync,
+        };
+
+        let last_tick = self.last_tick;
+        *self = Self::new(now);
+        self.last_tick = last_tick;
+        report
+    }
+}
+
+/// Value at `pct` percent of a sorted slice (0 if empty)
+fn percentile(sorted: &[u64], pct: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (sorted.len() * pct).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+/// Wait thread for [`WaitMode::Thread`]
+#[derive(Debug)]
+struct Ticker {
+    notify: Arc<Notify>,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+/// Deadline-based transmit scheduler with ELRS timing sync
+///
+/// # Examples
+///
+/// ```no_run
+/// use fpv_bridge::scheduler::TxScheduler;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let mut scheduler = TxScheduler::new(250);
+///     loop {
+///         scheduler.tick().await;
+///         // Encode and send one RC frame
+///
+///         if let Some(report) = scheduler.take_report() {
+///             println!("{}", report);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct TxScheduler {
+    timing: Arc<Mutex<Timing>>,
+    /// Wait thread, if running in [`WaitMode::Thread`]
+    ticker: Option<Ticker>,
+    stats: TickStats,
+    /// Ticks in the current statistics window
+    window_ticks: u64,
+    /// Ticks since start
+    total_ticks: u64,
+}
+
+impl TxScheduler {
+    /// Create a scheduler free-running at `rate_hz` on the tokio timer, with
+    /// the first frame due now
+    ///
+    /// # Arguments
+    ///
+    /// * `rate_hz` - Packet rate used until the module reports its timing
+    pub fn new(rate_hz: u32) -> Self {
+        Self::with_mode(rate_hz, WaitMode::Tokio)
+    }
+
+    /// Create a scheduler free-running at `rate_hz`, waiting with `mode`
+    ///
+    /// # Arguments
+    ///
+    /// * `rate_hz` - Packet rate used until the module reports its timing
+    /// * `mode` - How to wait for deadlines
+    pub fn with_mode(rate_hz: u32, mode: WaitMode) -> Self {
+        let period = Duration::from_nanos(1_000_000_000 / u64::from(rate_hz.max(1)));
+        let timing = Arc::new(Mutex::new(Timing::new(period)));
+
+        let ticker = match mode {
+            WaitMode::Tokio => None,
+            WaitMode::Thread { spin } => Some(Self::spawn_ticker(Arc::clone(&timing), spin)),
+        };
+
+        Self {
+            timing,
+            ticker,
+            stats: TickStats::new(Instant::now()),
+            window_ticks: 0,
+            total_ticks: 0,
+        }
+    }
+
+    /// Wait until the next frame is due
+    ///
+    /// Cancel-safe: if the future is dropped (e.g. another `select!` branch
+    /// completes first), no tick is lost.
+    ///
+    /// # Returns
+    ///
+    /// * `Instant` - The deadline that was waited for
+    pub async fn tick(&mut self) -> Instant {
+        let deadline = match &self.ticker {
+            None => {
+                let deadline = self.lock_timing().next_deadline;
+                sleep_until(deadline).await;
+                self.lock_timing().advance(Instant::now());
+                deadline
+            }
+            Some(ticker) => loop {
+                ticker.notify.notified().await;
+                let mut timing = self.lock_timing();
+                let fired = std::mem::take(&mut timing.fired);
+                if let Some(&deadline) = fired.last() {
+                    // Ticks fired while nobody was waiting are missed
+                    timing.missed += fired.len() as u64 - 1;
+                    break deadline;
+                }
+            },
+        };
+
+        let now = Instant::now();
+        let (period, missed) = {
+            let mut timing = self.lock_timing();
+            (timing.period, std::mem::take(&mut timing.missed))
+        };
+        self.stats.missed += missed;
+        self.stats.record_tick(deadline, now, period);
+        self.window_ticks += 1;
+        self.total_ticks += 1;
+        deadline
+    }
+
+    /// Record how long writing the frame for the last tick took
+    pub fn record_write(&mut self, duration: Duration) {
+        self.stats.record_write(duration);
+    }
+
+    /// Take the statistics for the current window once it is complete
+    ///
+    /// # Returns
+    ///
+    /// * `Option<TickReport>` - Report, if [`STATS_WINDOW`] has elapsed since
+    ///   the last one
+    pub fn take_report(&mut self) -> Option<TickReport> {
+        let now = Instant::now();
+        if now.duration_since(self.stats.window_start) < STATS_WINDOW {
+            return None;
+        }
+
+        let sync = self.stats();
+        let ticks = std::mem::take(&mut self.window_ticks);
+        Some(self.stats.report(ticks, sync, now))
+    }
+
+    /// Ticks since the scheduler was created
+    pub fn total_ticks(&self) -> u64 {
+        self.total_ticks
+    }
+
+    /// Apply a timing sync frame reported by the module
+    ///
+    /// # Arguments
+    ///
+    /// * `sync` - Decoded RADIO_ID timing sync
+    /// * `now` - Time the frame was received
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether the sync was accepted (the interval is plausible)
+    pub fn apply_sync(&mut self, sync: &TimingSync, now: Instant) -> bool {
+        self.lock_timing().apply_sync(sync, now)
+    }
+
+    /// Current timing sync state
+    pub fn stats(&self) -> SyncStats {
+        self.lock_timing().sync
+    }
+
+    /// Current transmit period
+    pub fn period(&self) -> Duration {
+        self.lock_timing().period
+    }
+
+    /// When the next frame is due
+    pub fn next_deadline(&self) -> Instant {
+        self.lock_timing().next_deadline
+    }
+
+    fn lock_timing(&self) -> MutexGuard<'_, Timing> {
+        // Timing holds no invariants a panic could break mid-update
+        self.timing.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Start the wait thread: sleep, spin, advance, notify
+    fn spawn_ticker(timing: Arc<Mutex<Timing>>, spin: Duration) -> Ticker {
+        let notify = Arc::new(Notify::new());
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let handle = {
+            let notify = Arc::clone(&notify);
+            let stop = Arc::clone(&stop);
+            std::thread::Builder::new()
+                .name("tx-scheduler".to_string())
+                .spawn(move || {
+                    let lock = || timing.lock().unwrap_or_else(|e| e.into_inner());
+                    while !stop.load(Ordering::Relaxed) {
+                        let deadline = lock().next_deadline;
+                        wait_until(deadline.into_std(), spin);
+
+                        let mut t = lock();
+                        t.fired.push(deadline);
+                        t.advance(Instant::now());
+                        drop(t);
+                        notify.notify_one();
+                    }
+                })
+                .expect("failed to spawn scheduler thread")
+        };
+
+        Ticker {
+            notify,
+            stop,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for TxScheduler {
+    fn drop(&mut self) {
+        if let Some(ticker) = &mut self.ticker {
+            ticker.stop.store(true, Ordering::Relaxed);
+            if let Some(handle) = ticker.handle.take() {
+                let _ = handle.join();
+            }
+        }
+    }
+}
+
+/// Sleep until `spin` before `deadline`, then busy-wait until it passes
+fn wait_until(deadline: std::time::Instant, spin: Duration) {
+    loop {
+        let now = std::time::Instant::now();
+        if now >= deadline {
+            return;
+        }
+        let remaining = deadline - now;
+        if remaining > spin {
+            std::thread::sleep(remaining - spin);
+        } else {
+            std::hint::spin_loop();
+        }
+    }
+}
+
+/// Why a frame is sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendReason {
+    /// Controller input changed beyond the threshold
+    Change,
+    /// Nothing changed for the keep-alive interval
+    KeepAlive,
+}
+
+/// Send-on-change counters for one statistics window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SendCounts {
+    /// Frames sent because the input changed
+    pub change: u64,
+    /// Frames sent to keep the link alive
+    pub keep_alive: u64,
+    /// Changes held back to the next tick by the minimum frame gap
+    pub deferred: u64,
+    /// Scheduler ticks that sent nothing
+    pub idle_ticks: u64,
+}
+
+impl fmt::Display for SendCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} on change ({} deferred), {} keep-alive, {} idle ticks",
+            self.change, self.deferred, self.keep_alive, self.idle_ticks
+        )
+    }
+}
+
+/// Send-on-change policy with a minimum keep-alive rate
+///
+/// A frame goes out as soon as the controller state moves beyond the
+/// threshold from the last state sent, and the following scheduler tick is
+/// skipped. Scheduler ticks otherwise only send a keep-alive frame once
+/// nothing was sent for the keep-alive interval.
+///
+/// Frames are never closer than half the packet period, so a controller
+/// reporting faster than the packet rate cannot flood the module; a change
+/// arriving sooner is sent on the next tick instead.
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::controller::mapper::ControllerState;
+/// use fpv_bridge::scheduler::{SendOnChange, SendReason};
+/// use tokio::time::{Duration, Instant};
+///
+/// let period = Duration::from_millis(4);
+/// let mut policy = SendOnChange::new(2, Duration::from_millis(100));
+/// let start = Instant::now();
+///
+/// // First tick: nothing sent yet
+/// let mut state = ControllerState::new();
+/// assert_eq!(policy.on_tick(&state, start, period), Some(SendReason::KeepAlive));
+///
+/// // Stick moved: send right away
+/// state.right_stick_x = 200;
+/// assert!(policy.on_input(&state, start + Duration::from_millis(10), period));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SendOnChange {
+    threshold: i32,
+    keep_alive: Duration,
+    /// State in the last frame sent
+    last_state: Option<ControllerState>,
+    last_send: Option<Instant>,
+    /// A change was held back by the minimum frame gap
+    pending: bool,
+    skip_next_tick: bool,
+    counts: SendCounts,
+}
+
+impl SendOnChange {
+    /// Create the policy
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - Analog movement (raw controller units, 0-255) that
+    ///   counts as a change; any button or D-Pad change always counts
+    /// * `keep_alive` - Longest time between frames
+    pub fn new(threshold: i32, keep_alive: Duration) -> Self {
+        Self {
+            threshold,
+            keep_alive,
+            last_state: None,
+            last_send: None,
+            pending: false,
+            skip_next_tick: false,
+            counts: SendCounts::default(),
+        }
+    }
+
+    /// Handle a new controller state
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - Latest controller state
+    /// * `now` - Current time
+    /// * `period` - Current packet period ([`TxScheduler::period`])
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether to send a frame for `state` now
+    pub fn on_input(&mut self, state: &ControllerState, now: Instant, period: Duration) -> bool {
+        let changed = self
+            .last_state
+            .as_ref()
+            .is_none_or(|last| state.changed_beyond(last, self.threshold));
+        if !changed {
+            return false;
+        }
+
+        if !self.gap_elapsed(now, period) {
+            if !self.pending {
+                self.pending = true;
+                self.counts.deferred += 1;
+            }
+            return false;
+        }
+
+        self.sent(state, now);
+        self.skip_next_tick = true;
+        self.counts.change += 1;
+        true
+    }
+
+    /// Handle a scheduler tick
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - Latest controller state
+    /// * `now` - Current time
+    /// * `period` - Current packet period ([`TxScheduler::period`])
+    ///
+    /// # Returns
+    ///
+    /// * `Option<SendReason>` - Why to send a frame for `state` now, or
+    ///   `None` to skip this tick
+    pub fn on_tick(&mut self, state: &ControllerState, now: Instant, period: Duration) -> Option<SendReason> {
+        if self.pending && self.gap_elapsed(now, period) {
+            self.sent(state, now);
+            self.skip_next_tick = false;
+            self.counts.change += 1;
+            return Some(SendReason::Change);
+        }
+
+        let keep_alive_due = self
+            .last_send
+            .is_none_or(|last| now.duration_since(last) >= self.keep_alive);
+        if std::mem::take(&mut self.skip_next_tick) || !keep_alive_due {
+            self.counts.idle_ticks += 1;
+            return None;
+        }
+
+        self.sent(state, now);
+        self.counts.keep_alive += 1;
+        Some(SendReason::KeepAlive)
+    }
+
+    /// Take the counters for the current window and reset them
+    pub fn take_counts(&mut self) -> SendCounts {
+        std::mem::take(&mut self.counts)
+    }
+
+    fn gap_elapsed(&self, now: Instant, period: Duration) -> bool {
+        self.last_send
+            .is_none_or(|last| now.duration_since(last) >= period / 2)
+    }
+
+    fn sent(&mut self, state: &ControllerState, now: Instant) {
+        self.last_state = Some(state.clone());
+        self.last_send = Some(now);
+        self.pending = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sync(interval_us: u64, offset_us: i64) -> TimingSync {
+        TimingSync {
+            refresh_interval_ns: interval_us * 1000,
+            offset_ns: offset_us * 1000,
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_free_running_period() {
+        let mut scheduler = TxScheduler::new(250);
+        let start = scheduler.tick().await;
+        let second = scheduler.tick().await;
+        let third = scheduler.tick().await;
+
+        assert_eq!(second - start, Duration::from_millis(4));
+        assert_eq!(third - second, Duration::from_millis(4));
+        assert!(!scheduler.stats().synced);
+        assert_eq!(scheduler.stats().to_string(), "free-running 4000.0µs");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_sync_changes_period() {
+        let mut scheduler = TxScheduler::new(250);
+        scheduler.tick().await;
+
+        assert!(scheduler.apply_sync(&sync(2000, 0), Instant::now()));
+        let a = scheduler.tick().await; // Scheduled before the sync: still 4ms
+        let b = scheduler.tick().await;
+        let c = scheduler.tick().await;
+
+        assert_eq!(c - b, Duration::from_millis(2));
+        assert!(b > a);
+        assert_eq!(scheduler.period(), Duration::from_millis(2));
+        assert!(scheduler.stats().synced);
+        assert_eq!(scheduler.stats().sync_frames, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_offset_is_spread_over_periods() {
+        let mut scheduler = TxScheduler::new(250);
+        scheduler.tick().await;
+        scheduler.apply_sync(&sync(4000, 1000), Instant::now());
+
+        // Max correction is 400µs per period: 400 + 400 + 200
+        let mut deadlines = vec![scheduler.next_deadline()];
+        let mut corrections = Vec::new();
+        for _ in 0..4 {
+            scheduler.tick().await;
+            deadlines.push(scheduler.next_deadline());
+            corrections.push(scheduler.stats().correction_ns);
+        }
+
+        assert_eq!(corrections, vec![400_000, 400_000, 200_000, 0]);
+        let steps: Vec<_> = deadlines.windows(2).map(|w| w[1] - w[0]).collect();
+        assert_eq!(steps[0], Duration::from_micros(4400));
+        assert_eq!(steps[3], Duration::from_micros(4000));
+        assert_eq!(scheduler.stats().offset_ns, 1_000_000);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_negative_offset_advances_frames() {
+        let mut scheduler = TxScheduler::new(250);
+        scheduler.tick().await;
+        scheduler.apply_sync(&sync(4000, -150), Instant::now());
+
+        let before = scheduler.next_deadline();
+        scheduler.tick().await;
+
+        assert_eq!(scheduler.next_deadline() - before, Duration::from_micros(3850));
+        assert_eq!(scheduler.stats().correction_ns, -150_000);
+        assert!(scheduler.stats().to_string().contains("offset -150.0µs"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_new_sync_replaces_pending_offset() {
+        let mut scheduler = TxScheduler::new(250);
+        scheduler.tick().await;
+        scheduler.apply_sync(&sync(4000, 2000), Instant::now());
+        scheduler.tick().await;
+        scheduler.apply_sync(&sync(4000, 100), Instant::now());
+        scheduler.tick().await;
+
+        assert_eq!(scheduler.stats().correction_ns, 100_000);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_implausible_sync_ignored() {
+        let mut scheduler = TxScheduler::new(250);
+
+        assert!(!scheduler.apply_sync(&sync(0, 0), Instant::now()));
+        assert!(!scheduler.apply_sync(&sync(999, 0), Instant::now()));
+        assert!(!scheduler.apply_sync(&sync(50_001, 0), Instant::now()));
+        assert!(!scheduler.stats().synced);
+        assert_eq!(scheduler.period(), Duration::from_millis(4));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_sync_timeout_falls_back_to_nominal() {
+        let mut scheduler = TxScheduler::new(250);
+        scheduler.tick().await;
+        scheduler.apply_sync(&sync(2000, 0), Instant::now());
+
+        tokio::time::advance(SYNC_TIMEOUT + Duration::from_millis(10)).await;
+        scheduler.tick().await;
+
+        assert!(!scheduler.stats().synced);
+        assert_eq!(scheduler.period(), Duration::from_millis(4));
+    }
+
+    #[test]
+    fn test_percentile() {
+        assert_eq!(percentile(&[], 99), 0);
+        assert_eq!(percentile(&[7], 99), 7);
+        let values: Vec<u64> = (1..=200).collect();
+        assert_eq!(percentile(&values, 99), 198);
+        assert_eq!(percentile(&values, 50), 100);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_report_after_window() {
+        let mut scheduler = TxScheduler::new(250);
+        scheduler.tick().await;
+        assert!(scheduler.take_report().is_none());
+
+        for _ in 0..250 {
+            scheduler.tick().await;
+            scheduler.record_write(Duration::from_micros(50));
+        }
+        let report = scheduler.take_report().unwrap();
+
+        assert_eq!(report.ticks, 251);
+        assert!((report.mean_period_us - 4000.0).abs() < 1.0);
+        assert_eq!(report.p99_jitter_us, 0.0);
+        assert_eq!(report.missed, 0);
+        assert_eq!(report.late, 0);
+        assert!((report.mean_write_us - 50.0).abs() < 0.01);
+        assert!(report.to_string().contains("251 ticks"));
+
+        // Window resets
+        scheduler.tick().await;
+        assert!(scheduler.take_report().is_none());
+        assert_eq!(scheduler.total_ticks(), 252);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_report_counts_missed_late_and_jitter() {
+        let mut scheduler = TxScheduler::new(250);
+        scheduler.tick().await;
+        scheduler.tick().await;
+
+        // Stall for 2.5 periods: the 8ms tick fires late at 14ms, the 12ms
+        // slot is skipped
+        tokio::time::advance(Duration::from_micros(10_000)).await;
+        scheduler.tick().await;
+        tokio::time::advance(STATS_WINDOW).await;
+        let report = scheduler.take_report().unwrap();
+
+        assert_eq!(report.missed, 1);
+        assert_eq!(report.late, 1);
+        assert!(report.max_jitter_us >= 5000.0);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_thread_mode_holds_cadence() {
+        let mut scheduler = TxScheduler::with_mode(500, WaitMode::Thread { spin: DEFAULT_SPIN });
+        let first = scheduler.tick().await;
+        let mut last = first;
+        for _ in 0..50 {
+            last = scheduler.tick().await;
+        }
+
+        // Deadlines stay on the 2ms grid even if a tick was missed
+        let elapsed = (last - first).as_micros();
+        assert_eq!(elapsed % 2000, 0);
+        assert!(elapsed >= 100_000);
+        assert!(scheduler.total_ticks() == 51);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_thread_mode_applies_sync() {
+        let mut scheduler = TxScheduler::with_mode(250, WaitMode::Thread { spin: DEFAULT_SPIN });
+        scheduler.tick().await;
+        scheduler.apply_sync(&sync(1000, 0), Instant::now());
+
+        scheduler.tick().await;
+        scheduler.tick().await;
+        let a = scheduler.tick().await;
+        let b = scheduler.tick().await;
+
+        assert_eq!((b - a).as_micros() % 1000, 0);
+        assert_eq!(scheduler.period(), Duration::from_millis(1));
+        drop(scheduler); // Stops and joins the wait thread
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_missed_deadlines_are_skipped() {
+        let mut scheduler = TxScheduler::new(250);
+        scheduler.tick().await;
+
+        // Stall for 3.5 periods
+        tokio::time::advance(Duration::from_micros(14_000)).await;
+        let late = scheduler.tick().await;
+        let next = scheduler.next_deadline();
+
+        assert!(next > Instant::now());
+        assert_eq!((next - late).as_micros() % 4000, 0);
+        assert!(next - Instant::now() <= Duration::from_millis(4));
+    }
+
+    // ==================== Send On Change ====================
+
+    const PERIOD: Duration = Duration::from_millis(4);
+
+    fn ms(value: u64) -> Duration {
+        Duration::from_millis(value)
+    }
+
+    fn moved(x: i32) -> ControllerState {
+        ControllerState {
+            right_stick_x: x,
+            ..ControllerState::default()
+        }
+    }
+
+    #[test]
+    fn test_send_on_change_first_tick_sends() {
+        let mut policy = SendOnChange::new(2, ms(100));
+        let state = ControllerState::default();
+        assert_eq!(policy.on_tick(&state, Instant::now(), PERIOD), Some(SendReason::KeepAlive));
+    }
+
+    #[test]
+    fn test_send_on_change_idle_until_keep_alive() {
+        let mut policy = SendOnChange::new(2, ms(20));
+        let state = ControllerState::default();
+        let start = Instant::now();
+        policy.on_tick(&state, start, PERIOD);
+
+        for tick in 1..5 {
+            assert_eq!(policy.on_tick(&state, start + PERIOD * tick, PERIOD), None);
+        }
+        assert_eq!(policy.on_tick(&state, start + ms(20), PERIOD), Some(SendReason::KeepAlive));
+
+        let counts = policy.take_counts();
+        assert_eq!(counts.keep_alive, 2);
+        assert_eq!(counts.idle_ticks, 4);
+        assert_eq!(policy.take_counts(), SendCounts::default());
+    }
+
+    #[test]
+    fn test_send_on_change_sends_immediately_and_skips_next_tick() {
+        let mut policy = SendOnChange::new(2, ms(8));
+        let start = Instant::now();
+        policy.on_tick(&ControllerState::default(), start, PERIOD);
+
+        let state = moved(200);
+        assert!(policy.on_input(&state, start + ms(5), PERIOD));
+
+        // Next tick skipped even though the keep-alive is due
+        assert_eq!(policy.on_tick(&state, start + ms(8), PERIOD), None);
+        // Keep-alive counted from the change frame
+        assert_eq!(policy.on_tick(&state, start + ms(12), PERIOD), None);
+        assert_eq!(policy.on_tick(&state, start + ms(16), PERIOD), Some(SendReason::KeepAlive));
+        assert_eq!(policy.take_counts().change, 1);
+    }
+
+    #[test]
+    fn test_send_on_change_ignores_small_movement() {
+        let mut policy = SendOnChange::new(2, ms(100));
+        let start = Instant::now();
+        assert!(policy.on_input(&moved(128), start, PERIOD));
+
+        assert!(!policy.on_input(&moved(130), start + ms(10), PERIOD));
+        // Drift accumulates against the last state sent
+        assert!(policy.on_input(&moved(131), start + ms(20), PERIOD));
+    }
+
+    #[test]
+    fn test_send_on_change_defers_changes_inside_min_gap() {
+        let mut policy = SendOnChange::new(0, ms(100));
+        let start = Instant::now();
+        assert!(policy.on_input(&moved(140), start, PERIOD));
+
+        // Half a period has not passed yet
+        assert!(!policy.on_input(&moved(150), start + ms(1), PERIOD));
+        assert!(!policy.on_input(&moved(160), start + ms(1), PERIOD));
+
+        // The next tick sends the deferred change
+        assert_eq!(policy.on_tick(&moved(160), start + ms(4), PERIOD), Some(SendReason::Change));
+        assert!(!policy.on_input(&moved(160), start + ms(5), PERIOD));
+
+        let counts = policy.take_counts();
+        assert_eq!(counts.change, 2);
+        assert_eq!(counts.deferred, 1);
+    }
+
+    #[test]
+    fn test_send_counts_display() {
+        let counts = SendCounts {
+            change: 12,
+            keep_alive: 3,
+            deferred: 1,
+            idle_ticks: 230,
+        };
+        assert_eq!(counts.to_string(), "12 on change (1 deferred), 3 keep-alive, 230 idle ticks");
+    }
+}
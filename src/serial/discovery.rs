@@ -0,0 +1,379 @@
+//! # Serial Port Discovery
+//!
+//! Finds the ELRS TX module among the serial ports on the system.
+//!
+//! Ports are enumerated with their USB vendor/product IDs, serial numbers and
+//! manufacturer strings, and matched against the USB-serial bridges used on
+//! ELRS TX modules. Other USB serial devices (GPS dongles, flight controllers)
+//! are only tried after those, and only when a CRSF ping can confirm them
+//! (`serial.probe`); built-in UARTs are skipped. Set `serial.port` to a device
+//! or `/dev/serial/by-id` path to pick one explicitly.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use tokio_serial::{SerialPortInfo, SerialPortType};
+use tracing::{debug, info};
+
+use crate::error::{FpvBridgeError, Result};
+
+/// `serial.port` value that enables discovery
+pub const AUTO_PORT: &str = "auto";
+
+/// `serial.port` value for running without an ELRS module, sending RC
+/// frames to the `[output]` sinks only (e.g. a simulator joystick)
+pub const NO_PORT: &str = "none";
+
+/// `serial.port` value for an in-process virtual ELRS module (see
+/// [`virtual_module`](super::virtual_module))
+pub const VIRTUAL_PORT: &str = "virtual";
+
+/// Stable per-device symlinks maintained by udev
+pub const BY_ID_DIR: &str = "/dev/serial/by-id";
+
+/// USB-serial bridge found on ELRS TX modules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KnownBridge {
+    /// USB vendor ID
+    pub vid: u16,
+    /// USB product ID
+    pub pid: u16,
+    /// Bridge chip name, for logs
+    pub name: &'static str,
+}
+
+/// Known ELRS TX bridges, in order of preference
+pub const KNOWN_BRIDGES: &[KnownBridge] = &[
+    KnownBridge { vid: 0x10C4, pid: 0xEA60, name: "CP210x" },
+    KnownBridge { vid: 0x1A86, pid: 0x7523, name: "CH340" },
+    KnownBridge { vid: 0x1A86, pid: 0x55D4, name: "CH9102" },
+    KnownBridge { vid: 0x303A, pid: 0x1001, name: "ESP32-S3 USB" },
+];
+
+/// USB identity of a serial port
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsbDevice {
+    /// Vendor ID
+    pub vid: u16,
+    /// Product ID
+    pub pid: u16,
+    /// Serial number string
+    pub serial_number: Option<String>,
+    /// Manufacturer string
+    pub manufacturer: Option<String>,
+    /// Product string
+    pub product: Option<String>,
+}
+
+/// Serial port that may be the ELRS TX module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortCandidate {
+    /// Device node (e.g. `/dev/ttyUSB0`)
+    pub path: String,
+    /// `/dev/serial/by-id` link pointing at the device, if any
+    pub by_id: Option<PathBuf>,
+    /// USB identity (`None` for built-in and virtual ports)
+    pub usb: Option<UsbDevice>,
+}
+
+impl PortCandidate {
+    /// Build a candidate from an enumerated port
+    ///
+    /// # Arguments
+    ///
+    /// * `info` - Port reported by `tokio_serial::available_ports`
+    /// * `by_id` - Device node → `/dev/serial/by-id` link map ([`by_id_links`])
+    pub fn from_port_info(info: SerialPortInfo, by_id: &HashMap<PathBuf, PathBuf>) -> Self {
+        let usb = match info.port_type {
+            SerialPortType::UsbPort(usb) => Some(UsbDevice {
+                vid: usb.vid,
+                pid: usb.pid,
+                serial_number: usb.serial_number,
+                manufacturer: usb.manufacturer,
+                product: usb.product,
+            }),
+            _ => None,
+        };
+
+        Self {
+            by_id: by_id.get(Path::new(&info.port_name)).cloned(),
+            path: info.port_name,
+            usb,
+        }
+    }
+
+    /// Known ELRS TX bridge this port belongs to, if any
+    pub fn known_bridge(&self) -> Option<&'static KnownBridge> {
+        let usb = self.usb.as_ref()?;
+        KNOWN_BRIDGES
+            .iter()
+            .find(|bridge| bridge.vid == usb.vid && bridge.pid == usb.pid)
+    }
+
+    /// Preference rank: known bridges in table order, then other USB ports
+    fn rank(&self) -> Option<usize> {
+        let usb = self.usb.as_ref()?;
+        let known = KNOWN_BRIDGES
+            .iter()
+            .position(|bridge| bridge.vid == usb.vid && bridge.pid == usb.pid);
+        Some(known.unwrap_or(KNOWN_BRIDGES.len()))
+    }
+}
+
+impl fmt::Display for PortCandidate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path)?;
+        if let Some(usb) = &self.usb {
+            write!(f, " ({:04x}:{:04x}", usb.vid, usb.pid)?;
+            for text in [&usb.manufacturer, &usb.product].into_iter().flatten() {
+                write!(f, " {}", text)?;
+            }
+            if let Some(serial) = &usb.serial_number {
+                write!(f, ", serial {}", serial)?;
+            }
+            if let Some(bridge) = self.known_bridge() {
+                write!(f, ", {}", bridge.name)?;
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+/// Order candidates by how likely they are to be the ELRS TX module
+///
+/// Known ELRS bridges come first (in [`KNOWN_BRIDGES`] order), then other
+/// USB serial ports; ports without USB identity are dropped. The sort is
+/// stable, so equally ranked ports keep their enumeration order.
+///
+/// # Arguments
+///
+/// * `candidates` - Enumerated ports
+///
+/// # Returns
+///
+/// * `Vec<PortCandidate>` - USB ports, most likely first
+pub fn rank_candidates(candidates: Vec<PortCandidate>) -> Vec<PortCandidate> {
+    let mut ranked: Vec<_> = candidates
+        .into_iter()
+        .filter_map(|candidate| Some((candidate.rank()?, candidate)))
+        .collect();
+    ranked.sort_by_key(|(rank, _)| *rank);
+    ranked.into_iter().map(|(_, candidate)| candidate).collect()
+}
+
+/// Candidates that may be opened as the TX module, in order
+///
+/// Without a probe nothing confirms that a port is the TX module, so only
+/// known ELRS bridges are returned; another USB serial device may be a GPS
+/// or flight controller. With `probe`, every candidate is returned for a
+/// ping.
+///
+/// # Arguments
+///
+/// * `candidates` - Ranked candidates ([`rank_candidates`])
+/// * `probe` - Whether each port is pinged before it is used
+///
+/// # Returns
+///
+/// * `Vec<PortCandidate>` - Candidates to try
+pub fn candidates_to_try(candidates: &[PortCandidate], probe: bool) -> Vec<PortCandidate> {
+    candidates
+        .iter()
+        .filter(|candidate| {
+            let trusted = probe || candidate.known_bridge().is_some();
+            if !trusted {
+                info!("Skipping {}: not a known ELRS TX bridge (enable serial.probe to try it)", candidate);
+            }
+            trusted
+        })
+        .cloned()
+        .collect()
+}
+
+/// Map device nodes to their `/dev/serial/by-id` links
+///
+/// # Arguments
+///
+/// * `dir` - Directory of symlinks (normally [`BY_ID_DIR`])
+///
+/// # Returns
+///
+/// * `HashMap<PathBuf, PathBuf>` - Resolved device node → link; empty if the
+///   directory does not exist
+pub fn by_id_links(dir: &Path) -> HashMap<PathBuf, PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return HashMap::new();
+    };
+
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let link = entry.path();
+            let target = std::fs::canonicalize(&link).ok()?;
+            Some((target, link))
+        })
+        .collect()
+}
+
+/// Enumerate serial ports, most likely ELRS TX module first
+///
+/// # Returns
+///
+/// * `Result<Vec<PortCandidate>>` - Ranked USB serial ports (see
+///   [`rank_candidates`])
+///
+/// # Errors
+///
+/// Returns `Serial` error if the ports cannot be enumerated.
+pub fn discover_ports() -> Result<Vec<PortCandidate>> {
+    let ports = tokio_serial::available_ports()
+        .map_err(|e| FpvBridgeError::Serial(format!("Failed to enumerate serial ports: {}", e)))?;
+    let by_id = by_id_links(Path::new(BY_ID_DIR));
+
+    let candidates = rank_candidates(
+        ports
+            .into_iter()
+            .map(|info| PortCandidate::from_port_info(info, &by_id))
+            .collect(),
+    );
+    for candidate in &candidates {
+        debug!("Serial port candidate: {}", candidate);
+    }
+    Ok(candidates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_serial::UsbPortInfo;
+
+    fn usb_port(path: &str, vid: u16, pid: u16) -> PortCandidate {
+        PortCandidate {
+            path: path.to_string(),
+            by_id: None,
+            usb: Some(UsbDevice {
+                vid,
+                pid,
+                serial_number: None,
+                manufacturer: None,
+                product: None,
+            }),
+        }
+    }
+
+    fn builtin_port(path: &str) -> PortCandidate {
+        PortCandidate {
+            path: path.to_string(),
+            by_id: None,
+            usb: None,
+        }
+    }
+
+    #[test]
+    fn test_known_bridge_lookup() {
+        assert_eq!(usb_port("/dev/ttyUSB0", 0x10C4, 0xEA60).known_bridge().unwrap().name, "CP210x");
+        assert_eq!(usb_port("/dev/ttyUSB0", 0x1A86, 0x7523).known_bridge().unwrap().name, "CH340");
+        assert_eq!(usb_port("/dev/ttyACM0", 0x303A, 0x1001).known_bridge().unwrap().name, "ESP32-S3 USB");
+
+        // u-blox GPS receiver
+        assert_eq!(usb_port("/dev/ttyACM0", 0x1546, 0x01A7).known_bridge(), None);
+        assert_eq!(builtin_port("/dev/ttyS0").known_bridge(), None);
+    }
+
+    #[test]
+    fn test_rank_prefers_known_bridges_over_gps() {
+        let ranked = rank_candidates(vec![
+            builtin_port("/dev/ttyAMA0"),
+            usb_port("/dev/ttyACM0", 0x1546, 0x01A7), // GPS grabbed ttyACM0
+            usb_port("/dev/ttyACM1", 0x303A, 0x1001),
+            usb_port("/dev/ttyUSB0", 0x10C4, 0xEA60),
+        ]);
+
+        let paths: Vec<_> = ranked.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, ["/dev/ttyUSB0", "/dev/ttyACM1", "/dev/ttyACM0"]);
+    }
+
+    #[test]
+    fn test_candidates_to_try_without_probe_only_known_bridges() {
+        let ranked = rank_candidates(vec![
+            usb_port("/dev/ttyACM0", 0x1546, 0x01A7), // GPS
+            usb_port("/dev/ttyUSB0", 0x10C4, 0xEA60),
+            usb_port("/dev/ttyACM1", 0x0483, 0x5740), // Flight controller
+        ]);
+
+        let paths: Vec<_> = candidates_to_try(&ranked, false).into_iter().map(|c| c.path).collect();
+        assert_eq!(paths, ["/dev/ttyUSB0"]);
+
+        let paths: Vec<_> = candidates_to_try(&ranked, true).into_iter().map(|c| c.path).collect();
+        assert_eq!(paths, ["/dev/ttyUSB0", "/dev/ttyACM0", "/dev/ttyACM1"]);
+
+        // No known bridge: nothing is used unprobed
+        assert!(candidates_to_try(&[usb_port("/dev/ttyACM0", 0x1546, 0x01A7)], false).is_empty());
+        assert!(candidates_to_try(&[builtin_port("/dev/ttyACM0")], false).is_empty());
+    }
+
+    #[test]
+    fn test_rank_keeps_enumeration_order_within_rank() {
+        let ranked = rank_candidates(vec![
+            usb_port("/dev/ttyUSB1", 0x1A86, 0x7523),
+            usb_port("/dev/ttyUSB0", 0x1A86, 0x7523),
+        ]);
+        assert_eq!(ranked[0].path, "/dev/ttyUSB1");
+        assert_eq!(ranked[1].path, "/dev/ttyUSB0");
+    }
+
+    #[test]
+    fn test_from_port_info() {
+        let by_id = HashMap::from([(
+            PathBuf::from("/dev/ttyUSB0"),
+            PathBuf::from("/dev/serial/by-id/usb-Silicon_Labs_CP2102_0001-if00-port0"),
+        )]);
+        let info = SerialPortInfo {
+            port_name: "/dev/ttyUSB0".to_string(),
+            port_type: SerialPortType::UsbPort(UsbPortInfo {
+                vid: 0x10C4,
+                pid: 0xEA60,
+                serial_number: Some("0001".to_string()),
+                manufacturer: Some("Silicon Labs".to_string()),
+                product: Some("CP2102".to_string()),
+            }),
+        };
+
+        let candidate = PortCandidate::from_port_info(info, &by_id);
+        assert_eq!(candidate.by_id, by_id.get(Path::new("/dev/ttyUSB0")).cloned());
+        assert_eq!(candidate.known_bridge().unwrap().name, "CP210x");
+        assert_eq!(
+            candidate.to_string(),
+            "/dev/ttyUSB0 (10c4:ea60 Silicon Labs CP2102, serial 0001, CP210x)"
+        );
+
+        let builtin = SerialPortInfo {
+            port_name: "/dev/ttyS0".to_string(),
+            port_type: SerialPortType::Unknown,
+        };
+        let candidate = PortCandidate::from_port_info(builtin, &by_id);
+        assert_eq!(candidate.usb, None);
+        assert_eq!(candidate.to_string(), "/dev/ttyS0");
+    }
+
+    #[test]
+    fn test_by_id_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let device = dir.path().join("ttyUSB0");
+        std::fs::write(&device, b"").unwrap();
+        let links = dir.path().join("by-id");
+        std::fs::create_dir(&links).unwrap();
+        let link = links.join("usb-Silicon_Labs_CP2102_0001-if00-port0");
+        std::os::unix::fs::symlink(&device, &link).unwrap();
+
+        let map = by_id_links(&links);
+        assert_eq!(map.get(&std::fs::canonicalize(&device).unwrap()), Some(&link));
+    }
+
+    #[test]
+    fn test_by_id_links_missing_dir() {
+        assert!(by_id_links(Path::new("/nonexistent/by-id")).is_empty());
+    }
+}
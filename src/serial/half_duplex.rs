@@ -0,0 +1,209 @@
+//! # Half-Duplex Echo Cancellation
+//!
+//! On a single-wire (S.Port-style) UART, TX and RX share one line, so every
+//! byte we transmit is also received. [`EchoCanceller`] remembers what was
+//! written and strips it from the start of the received data before it
+//! reaches the frame parser.
+//!
+//! Echoed bytes arrive in order and before the module can answer, so the
+//! echo is always a prefix of what is pending. A byte that does not match
+//! (a collision on the wire) ends the echo: the rest is passed through and
+//! the parser resyncs on the next sync byte. Echo bytes that never arrive
+//! (the wiring is not actually half-duplex) are forgotten once their
+//! transmit time plus [`ECHO_SLACK`] has passed.
+
+use std::collections::VecDeque;
+use tokio::time::{Duration, Instant};
+
+/// Extra time allowed for the echo beyond the bytes' transmit time
+pub const ECHO_SLACK: Duration = Duration::from_millis(10);
+
+/// UART bits per byte (start + 8 data + stop)
+const BITS_PER_BYTE: u64 = 10;
+
+/// Echo cancellation counters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EchoStats {
+    /// Received bytes discarded as our own echo
+    pub echoed_bytes: u64,
+    /// Times the received data diverged from the pending echo
+    pub mismatches: u64,
+    /// Pending echo bytes that never arrived
+    pub missing_bytes: u64,
+}
+
+impl std::fmt::Display for EchoStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} echo bytes discarded, {} mismatches, {} missing",
+            self.echoed_bytes, self.mismatches, self.missing_bytes
+        )
+    }
+}
+
+/// Strips our own transmitted bytes from a half-duplex receive stream
+#[derive(Debug)]
+pub struct EchoCanceller {
+    /// Written bytes whose echo has not been received yet
+    pending: VecDeque<u8>,
+    /// When the pending echo should have arrived by
+    deadline: Option<Instant>,
+    /// Line speed, for the echo deadline
+    baud_rate: u32,
+    stats: EchoStats,
+}
+
+impl EchoCanceller {
+    /// Create an echo canceller for a line running at `baud_rate`
+    pub fn new(baud_rate: u32) -> Self {
+        Self {
+            pending: VecDeque::new(),
+            deadline: None,
+            baud_rate,
+            stats: EchoStats::default(),
+        }
+    }
+
+    /// Update the line speed after a baud rate switch
+    pub fn set_baud_rate(&mut self, baud_rate: u32) {
+        self.baud_rate = baud_rate;
+    }
+
+    /// Record bytes about to be written, whose echo is expected next
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Bytes being transmitted
+    /// * `now` - Current time
+    pub fn expect(&mut self, data: &[u8], now: Instant) {
+        self.expire(now);
+        self.pending.extend(data);
+
+        let bits = self.pending.len() as u64 * BITS_PER_BYTE;
+        let airtime = Duration::from_nanos(bits * 1_000_000_000 / u64::from(self.baud_rate.max(1)));
+        self.deadline = Some(now + airtime + ECHO_SLACK);
+    }
+
+    /// Remove the pending echo from the start of received data
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Bytes just read from the port
+    /// * `now` - Current time
+    ///
+    /// # Returns
+    ///
+    /// * `&[u8]` - The bytes that were not our own echo
+    pub fn strip<'a>(&mut self, data: &'a [u8], now: Instant) -> &'a [u8] {
+        self.expire(now);
+
+        for (i, &byte) in data.iter().enumerate() {
+            match self.pending.front() {
+                Some(&expected) if expected == byte => {
+                    self.pending.pop_front();
+                    self.stats.echoed_bytes += 1;
+                }
+                Some(_) => {
+                    self.stats.mismatches += 1;
+                    self.forget();
+                    return &data[i..];
+                }
+                None => return &data[i..],
+            }
+        }
+        &data[data.len()..]
+    }
+
+    /// Number of written bytes whose echo is still expected
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Echo cancellation counters since creation
+    pub fn stats(&self) -> EchoStats {
+        self.stats
+    }
+
+    /// Forget the pending echo if it is overdue
+    fn expire(&mut self, now: Instant) {
+        if self.deadline.is_some_and(|deadline| now > deadline) {
+            self.forget();
+        }
+    }
+
+    fn forget(&mut self) {
+        self.stats.missing_bytes += self.pending.len() as u64;
+        self.pending.clear();
+        self.deadline = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strips_echo_and_keeps_reply() {
+        let now = Instant::now();
+        let mut echo = EchoCanceller::new(420_000);
+
+        echo.expect(&[0xC8, 0x04, 0x28], now);
+        assert_eq!(echo.strip(&[0xC8, 0x04, 0x28, 0xEA, 0x10], now), &[0xEA, 0x10]);
+        assert_eq!(echo.pending(), 0);
+        assert_eq!(echo.stats().echoed_bytes, 3);
+    }
+
+    #[test]
+    fn test_echo_split_across_reads() {
+        let now = Instant::now();
+        let mut echo = EchoCanceller::new(420_000);
+
+        echo.expect(&[1, 2, 3, 4], now);
+        assert!(echo.strip(&[1, 2], now).is_empty());
+        assert_eq!(echo.pending(), 2);
+        assert_eq!(echo.strip(&[3, 4, 5], now), &[5]);
+    }
+
+    #[test]
+    fn test_mismatch_passes_rest_through() {
+        let now = Instant::now();
+        let mut echo = EchoCanceller::new(420_000);
+
+        echo.expect(&[1, 2, 3, 4], now);
+        assert_eq!(echo.strip(&[1, 9, 3, 4], now), &[9, 3, 4]);
+        assert_eq!(echo.pending(), 0);
+
+        let stats = echo.stats();
+        assert_eq!(stats.mismatches, 1);
+        assert_eq!(stats.echoed_bytes, 1);
+        assert_eq!(stats.missing_bytes, 3);
+    }
+
+    #[test]
+    fn test_overdue_echo_expires() {
+        let start = Instant::now();
+        let mut echo = EchoCanceller::new(420_000);
+
+        // 64 bytes take ~1.5ms at 420k baud, plus the slack
+        echo.expect(&[0x55; 64], start);
+        let later = start + Duration::from_millis(12);
+        assert_eq!(echo.strip(&[0x55, 0x01], later), &[0x55, 0x01]);
+        assert_eq!(echo.stats().missing_bytes, 64);
+    }
+
+    #[test]
+    fn test_echo_deadline_scales_with_baud_rate() {
+        let start = Instant::now();
+        let mut echo = EchoCanceller::new(115_200);
+
+        // 64 bytes take ~5.6ms at 115200 baud: still expected after 12ms
+        echo.expect(&[0x55; 64], start);
+        let later = start + Duration::from_millis(12);
+        assert!(echo.strip(&[0x55], later).is_empty());
+
+        echo.set_baud_rate(3_750_000);
+        echo.expect(&[0x55], later);
+        assert_eq!(echo.pending(), 64);
+    }
+}
@@ -3,16 +3,38 @@
 //! Handles serial communication with ELRS USB module.
 //!
 //! This module handles:
-//! - Opening serial port at 420,000 baud
+//! - Opening serial port at 420,000 baud (or the configured rate)
+//! - Switching baud rate via the CRSF speed proposal
+//! - Echo cancellation on single-wire (half-duplex) UARTs
 //! - Async read/write operations
 //! - Transmitting CRSF RC channels packets at 250Hz
 //! - Receiving telemetry packets
 //! - Error recovery and reconnection
+//! - A virtual ELRS module for testing without hardware
 
+pub mod discovery;
+pub mod half_duplex;
 mod port_trait;
-
+pub mod virtual_module;
+
+use crate::capture::{CaptureWriter, Direction};
+use crate::config::SerialConfig;
+use crate::crsf::decoder::{decode_baud_response, decode_device_info, FrameParser};
+use crate::crsf::encoder::{
+    encode_baud_proposal_frame, encode_bind_frame, encode_device_ping_frame,
+    encode_model_select_frame,
+};
+use crate::crsf::protocol::{
+    CrsfFrame, DeviceInfo, CRSF_ADDRESS_CRSF_TRANSMITTER, CRSF_FRAMETYPE_COMMAND,
+    CRSF_FRAMETYPE_DEVICE_INFO, CRSF_MAX_FRAME_SIZE, CRSF_MODEL_ID_MAX,
+};
 use crate::error::{FpvBridgeError, Result};
+use discovery::{candidates_to_try, discover_ports, PortCandidate, AUTO_PORT, VIRTUAL_PORT};
+use half_duplex::{EchoCanceller, EchoStats};
 use port_trait::{SerialPortIO, TokioSerialPort};
+use serde::Serialize;
+use std::time::SystemTime;
+use tokio::time::{timeout, timeout_at, Duration, Instant};
 use tokio_serial::SerialPortBuilderExt;
 use tracing::{debug, info, warn};
 
@@ -22,6 +44,26 @@ use port_trait::mocks::MockSerialPort;
 /// CRSF baud rate for ELRS (420,000 baud)
 pub const CRSF_BAUD_RATE: u32 = 420_000;
 
+/// Bytes requested per serial read
+const READ_BUFFER_SIZE: usize = 64;
+
+/// Write deadline until [`ElrsSerial::set_write_timeout`] is called
+/// (matches the `serial.timeout_ms` default)
+pub const DEFAULT_WRITE_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Largest backlog in the port's output buffer before new packets are
+/// dropped: one maximum-size CRSF frame
+pub const MAX_QUEUED_BYTES: u32 = CRSF_MAX_FRAME_SIZE as u32;
+
+/// How long a probed port has to answer a device ping
+pub const PROBE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// How long the TX module has to answer a baud rate proposal
+pub const BAUD_SWITCH_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// CRSF port ID of the TX module's handset UART, for baud rate proposals
+const CRSF_HANDSET_PORT_ID: u8 = 0;
+
 /// Default ELRS device paths to try (in order of preference)
 const DEFAULT_DEVICE_PATHS: &[&str] = &[
     "/dev/ttyACM0", // USB CDC devices (most common for ELRS)
@@ -36,12 +78,60 @@ pub struct ElrsSerial {
     port: Box<dyn SerialPortIO>,
     /// Device path (e.g., /dev/ttyACM0)
     device_path: String,
+    /// Model ID last sent to the module (model match), if any
+    model_id: Option<u8>,
+    /// Reassembles frames received from the module
+    parser: FrameParser,
+    /// When the last packet finished flushing, for latency measurement
+    last_flush: Option<SystemTime>,
+    /// Deadline for writing and flushing one packet
+    write_timeout: Duration,
+    /// Output queue accounting (`bytes_drained` is computed on read)
+    tx_stats: TxQueueStats,
+    /// Current line speed
+    baud_rate: u32,
+    /// Strips our own echo on a single-wire UART (`None` for full duplex)
+    echo: Option<EchoCanceller>,
+    /// Traffic capture (`--capture`), if enabled
+    capture: Option<CaptureWriter>,
+    /// Simulated module behind `port = "virtual"`
+    virtual_module: Option<virtual_module::VirtualModule>,
+}
+
+/// Serial output queue accounting
+///
+/// `bytes_queued` counts bytes of complete packets handed to the port;
+/// `bytes_drained` is what has left the port's output buffer since.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct TxQueueStats {
+    /// Bytes of packets written to the port
+    pub bytes_queued: u64,
+    /// Bytes the port has transmitted
+    pub bytes_drained: u64,
+    /// Packets dropped because earlier ones had not drained yet
+    pub dropped_frames: u64,
+    /// Writes abandoned after the write timeout (possibly partial packets)
+    pub write_timeouts: u64,
+}
+
+impl std::fmt::Display for TxQueueStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} bytes queued, {} drained, {} frames dropped, {} write timeouts",
+            self.bytes_queued, self.bytes_drained, self.dropped_frames, self.write_timeouts
+        )
+    }
 }
 
 impl std::fmt::Debug for ElrsSerial {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ElrsSerial")
             .field("device_path", &self.device_path)
+            .field("model_id", &self.model_id)
+            .field("baud_rate", &self.baud_rate)
+            .field("half_duplex", &self.echo.is_some())
+            .field("capture", &self.capture.is_some())
             .finish_non_exhaustive()
     }
 }
@@ -83,6 +173,11 @@ impl ElrsSerial {
     ///
     /// * `Result<ElrsSerial>` - Connected serial port or error
     pub fn open_with_paths(paths: &[&str]) -> Result<Self> {
+        Self::open_paths(paths, CRSF_BAUD_RATE)
+    }
+
+    /// Open the first of `paths` that works at `baud_rate`
+    fn open_paths(paths: &[&str], baud_rate: u32) -> Result<Self> {
         // Special case: empty paths list
         if paths.is_empty() {
             return Err(FpvBridgeError::SerialPortNotFound(
@@ -93,13 +188,15 @@ impl ElrsSerial {
         for path in paths {
             debug!("Trying to open serial port: {}", path);
 
-            match Self::open_port(path) {
+            match Self::open_port(path, baud_rate) {
                 Ok(port) => {
-                    info!("Successfully opened ELRS device at {}", path);
-                    return Ok(Self {
-                        port: Box::new(TokioSerialPort::new(port)),
-                        device_path: path.to_string(),
-                    });
+                    info!("Successfully opened ELRS device at {} ({} baud)", path, baud_rate);
+                    let mut serial = Self::from_port(
+                        Box::new(TokioSerialPort::new(port)),
+                        path.to_string(),
+                    );
+                    serial.baud_rate = baud_rate;
+                    return Ok(serial);
                 }
                 Err(e) => {
                     warn!("Failed to open {}: {}", path, e);
@@ -113,6 +210,136 @@ impl ElrsSerial {
         ))
     }
 
+    /// Connect to the ELRS module as configured in `[serial]`
+    ///
+    /// With `port = "auto"`, USB serial ports are enumerated and known ELRS
+    /// TX bridges are tried first (see [`discovery`]); if none are found,
+    /// the default paths are tried as with [`open`](Self::open). Any other
+    /// value is opened as a path, including `/dev/serial/by-id` links.
+    /// `port = "virtual"` starts an in-process
+    /// [`VirtualModule`](virtual_module::VirtualModule) instead.
+    ///
+    /// With `probe = true`, each discovered port must answer a CRSF device
+    /// ping from the TX module within [`PROBE_TIMEOUT`] to be picked; an
+    /// explicitly configured port is used even if it does not answer.
+    ///
+    /// The port is opened at `baud_rate`, with echo cancellation if
+    /// `half_duplex` is set. If `switch_baud_rate` is set, that speed is then
+    /// proposed to the module; if it is not accepted, the connection stays
+    /// at `baud_rate`.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Serial port configuration
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ElrsSerial>` - Connected serial port or error
+    ///
+    /// # Errors
+    ///
+    /// Returns `SerialPortNotFound` if no port could be opened, if probing
+    /// is disabled and no known ELRS TX bridge was found, or if probing is
+    /// enabled and no discovered port answered.
+    pub async fn connect(config: &SerialConfig) -> Result<Self> {
+        Self::connect_with_capture(config, None).await
+    }
+
+    /// Connect like [`connect`](Self::connect), capturing the traffic
+    ///
+    /// Capture starts once the port is open: the speed switch, model
+    /// selection and everything after it are recorded, probe pings are not.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`connect`](Self::connect).
+    pub async fn connect_with_capture(config: &SerialConfig, capture: Option<CaptureWriter>) -> Result<Self> {
+        let mut serial = Self::connect_port(config).await?;
+        serial.capture = capture;
+
+        if let Some(baud_rate) = config.switch_baud_rate.filter(|&rate| rate != serial.baud_rate) {
+            match serial.switch_baud_rate(baud_rate, BAUD_SWITCH_TIMEOUT).await {
+                Ok(true) => {}
+                Ok(false) => warn!(
+                    "TX module did not accept {} baud, staying at {} baud",
+                    baud_rate, serial.baud_rate
+                ),
+                Err(e) => warn!("Failed to propose {} baud: {}", baud_rate, e),
+            }
+        }
+        Ok(serial)
+    }
+
+    /// Open (and with `probe`, ping) the configured or discovered port
+    async fn connect_port(config: &SerialConfig) -> Result<Self> {
+        let open = |paths: &[&str]| -> Result<Self> {
+            let mut serial = Self::open_paths(paths, config.baud_rate)?;
+            serial.set_half_duplex(config.half_duplex);
+            Ok(serial)
+        };
+
+        if config.port == VIRTUAL_PORT {
+            let (mut serial, module) = virtual_module::VirtualModule::duplex();
+            info!("Using a virtual ELRS module, no RC frames leave this machine");
+            serial.virtual_module = Some(module);
+            return Ok(serial);
+        }
+
+        if config.port != AUTO_PORT {
+            let mut serial = open(&[config.port.as_str()])?;
+            if let Ok(target) = std::fs::canonicalize(&config.port) {
+                debug!("{} resolves to {}", config.port, target.display());
+            }
+            if config.probe && serial.ping(PROBE_TIMEOUT).await?.is_none() {
+                warn!("No ELRS TX module answered on {}, using it anyway", config.port);
+            }
+            return Ok(serial);
+        }
+
+        let mut candidates = discover_ports().unwrap_or_else(|e| {
+            warn!("{}", e);
+            Vec::new()
+        });
+        if candidates.is_empty() && config.probe {
+            debug!("No USB serial ports found, probing default paths");
+            candidates = DEFAULT_DEVICE_PATHS
+                .iter()
+                .map(|path| PortCandidate { path: path.to_string(), by_id: None, usb: None })
+                .collect();
+        }
+
+        for candidate in &candidates_to_try(&candidates, config.probe) {
+            let Ok(mut serial) = open(&[candidate.path.as_str()]) else {
+                continue;
+            };
+            if !config.probe {
+                info!("Using serial port {}", candidate);
+                return Ok(serial);
+            }
+
+            match serial.ping(PROBE_TIMEOUT).await {
+                Ok(Some(device)) => {
+                    info!("{} answered on {}", device.name, candidate);
+                    return Ok(serial);
+                }
+                Ok(None) => info!("No ELRS TX module answered on {}", candidate),
+                Err(e) => warn!("Failed to probe {}: {}", candidate.path, e),
+            }
+        }
+
+        let tried = if candidates.is_empty() {
+            "no USB serial ports".to_string()
+        } else {
+            let paths: Vec<_> = candidates.iter().map(|c| c.path.as_str()).collect();
+            paths.join(", ")
+        };
+        Err(FpvBridgeError::SerialPortNotFound(if config.probe {
+            tried
+        } else {
+            format!("{} (no known ELRS TX bridge; set serial.port or enable serial.probe)", tried)
+        }))
+    }
+
     /// Create a new ElrsSerial with a custom port implementation (for testing)
     ///
     /// # Arguments
@@ -125,7 +352,23 @@ impl ElrsSerial {
     /// * `ElrsSerial` - Serial handler with custom port
     #[cfg(test)]
     pub fn new_with_port(port: Box<dyn SerialPortIO>, device_path: String) -> Self {
-        Self { port, device_path }
+        Self::from_port(port, device_path)
+    }
+
+    fn from_port(port: Box<dyn SerialPortIO>, device_path: String) -> Self {
+        Self {
+            port,
+            device_path,
+            model_id: None,
+            parser: FrameParser::new(),
+            last_flush: None,
+            write_timeout: DEFAULT_WRITE_TIMEOUT,
+            tx_stats: TxQueueStats::default(),
+            baud_rate: CRSF_BAUD_RATE,
+            echo: None,
+            capture: None,
+            virtual_module: None,
+        }
     }
 
     /// Open a specific serial port with CRSF settings
@@ -133,12 +376,13 @@ impl ElrsSerial {
     /// # Arguments
     ///
     /// * `path` - Device path (e.g., "/dev/ttyACM0")
+    /// * `baud_rate` - Line speed (420,000 for USB modules)
     ///
     /// # Returns
     ///
     /// * `Result<SerialStream>` - Opened serial port
-    fn open_port(path: &str) -> Result<tokio_serial::SerialStream> {
-        let port = tokio_serial::new(path, CRSF_BAUD_RATE)
+    pub(crate) fn open_port(path: &str, baud_rate: u32) -> Result<tokio_serial::SerialStream> {
+        let port = tokio_serial::new(path, baud_rate)
             .data_bits(tokio_serial::DataBits::Eight)
             .parity(tokio_serial::Parity::None)
             .stop_bits(tokio_serial::StopBits::One)
@@ -159,6 +403,12 @@ impl ElrsSerial {
     ///
     /// * `Result<()>` - Success or error
     ///
+    /// # Errors
+    ///
+    /// Returns `SerialStall` if earlier packets have not drained (the packet
+    /// is dropped) or the write does not finish within the write timeout,
+    /// and `Serial` if the write or flush fails.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -179,16 +429,346 @@ impl ElrsSerial {
     /// }
     /// ```
     pub async fn send_packet(&mut self, packet: &[u8]) -> Result<()> {
-        self.port.write_all(packet).await
-            .map_err(|e| FpvBridgeError::Serial(format!("Failed to write packet: {}", e)))?;
+        // A backlog means the module is not draining: drop this frame rather
+        // than queue it behind stale ones
+        let backlog = self.backlog();
+        if backlog > MAX_QUEUED_BYTES {
+            self.tx_stats.dropped_frames += 1;
+            return Err(FpvBridgeError::SerialStall(format!(
+                "{} bytes not yet transmitted, dropped {}-byte packet",
+                backlog,
+                packet.len()
+            )));
+        }
+
+        if let Some(echo) = &mut self.echo {
+            echo.expect(packet, Instant::now());
+        }
 
-        self.port.flush().await
-            .map_err(|e| FpvBridgeError::Serial(format!("Failed to flush serial port: {}", e)))?;
+        let port = &mut self.port;
+        let write = async {
+            port.write_all(packet).await
+                .map_err(|e| FpvBridgeError::Serial(format!("Failed to write packet: {}", e)))?;
+
+            port.flush().await
+                .map_err(|e| FpvBridgeError::Serial(format!("Failed to flush serial port: {}", e)))
+        };
+
+        match timeout(self.write_timeout, write).await {
+            Ok(result) => result?,
+            Err(_) => {
+                // Part of the packet may have been written; the module's
+                // parser resyncs on the next sync byte
+                self.tx_stats.write_timeouts += 1;
+                return Err(FpvBridgeError::SerialStall(format!(
+                    "write did not complete within {}ms",
+                    self.write_timeout.as_millis()
+                )));
+            }
+        }
+        self.last_flush = Some(SystemTime::now());
+        self.tx_stats.bytes_queued += packet.len() as u64;
+        self.capture_frame(Direction::Tx, packet, true);
 
         debug!("Sent CRSF packet ({} bytes)", packet.len());
         Ok(())
     }
 
+    /// Set the deadline for writing and flushing one packet
+    ///
+    /// Writes that take longer are abandoned with a
+    /// [`FpvBridgeError::SerialStall`] error, so a stalled USB CDC buffer
+    /// cannot stall the control loop.
+    ///
+    /// # Arguments
+    ///
+    /// * `write_timeout` - Deadline per packet (`serial.timeout_ms`)
+    pub fn set_write_timeout(&mut self, write_timeout: Duration) {
+        self.write_timeout = write_timeout;
+    }
+
+    /// Deadline for writing and flushing one packet
+    pub fn write_timeout(&self) -> Duration {
+        self.write_timeout
+    }
+
+    /// Output queue accounting since the port was opened
+    pub fn tx_stats(&self) -> TxQueueStats {
+        TxQueueStats {
+            bytes_drained: self.tx_stats.bytes_queued.saturating_sub(u64::from(self.backlog())),
+            ..self.tx_stats
+        }
+    }
+
+    /// Bytes in the port's output buffer; ports that cannot report it count
+    /// as fully drained
+    fn backlog(&self) -> u32 {
+        self.port.bytes_to_write().unwrap_or(0)
+    }
+
+    /// When the last successful [`send_packet`](Self::send_packet) finished
+    /// flushing
+    ///
+    /// Uses the system clock, like the kernel timestamps on controller input
+    /// events, so the two can be subtracted to measure input latency.
+    ///
+    /// Returns `None` if no packet has been sent on this connection.
+    pub fn last_flush_time(&self) -> Option<SystemTime> {
+        self.last_flush
+    }
+
+    /// Receive the next CRSF frame from the ELRS module
+    ///
+    /// Waits until a complete frame with a valid CRC has been received
+    /// (telemetry, timing sync, ...). Partial frames are kept between calls,
+    /// so this is cancel-safe and can be used as a `tokio::select!` branch
+    /// next to the transmit timer.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<CrsfFrame>` - Received frame
+    ///
+    /// # Errors
+    ///
+    /// Returns `Serial` error if the read fails or the port is closed.
+    pub async fn recv_frame(&mut self) -> Result<CrsfFrame> {
+        loop {
+            if let Some(frame) = self.next_received_frame() {
+                return Ok(frame);
+            }
+
+            let mut buf = [0u8; READ_BUFFER_SIZE];
+            let n = self.port.read(&mut buf).await
+                .map_err(|e| FpvBridgeError::Serial(format!("Failed to read from serial port: {}", e)))?;
+            if n == 0 {
+                return Err(FpvBridgeError::Serial("Serial port closed".to_string()));
+            }
+            match &mut self.echo {
+                Some(echo) => self.parser.push(echo.strip(&buf[..n], Instant::now())),
+                None => self.parser.push(&buf[..n]),
+            }
+        }
+    }
+
+    /// Next complete frame from the parser, capturing every frame taken
+    /// from it (including ones with a bad CRC) while capture is enabled
+    fn next_received_frame(&mut self) -> Option<CrsfFrame> {
+        if self.capture.is_none() {
+            return self.parser.next_frame();
+        }
+        while let Some(raw) = self.parser.next_raw_frame() {
+            self.capture_frame(Direction::Rx, &raw.bytes, raw.crc_ok);
+            if raw.crc_ok {
+                return Some(CrsfFrame {
+                    frame_type: raw.bytes[2],
+                    payload: raw.bytes[3..raw.bytes.len() - 1].to_vec(),
+                });
+            }
+        }
+        None
+    }
+
+    /// Start (or with `None`, stop) capturing the traffic
+    pub fn set_capture(&mut self, capture: Option<CaptureWriter>) {
+        self.capture = capture;
+    }
+
+    /// Frames captured so far, `None` if capture is not enabled
+    pub fn captured_frames(&self) -> Option<u64> {
+        self.capture.as_ref().map(CaptureWriter::frames)
+    }
+
+    /// Flush the capture file
+    ///
+    /// # Errors
+    ///
+    /// Returns `Io` error if the write fails.
+    pub fn flush_capture(&mut self) -> Result<()> {
+        self.capture.as_mut().map_or(Ok(()), CaptureWriter::flush)
+    }
+
+    /// Record a frame in the capture; a failing capture is logged and
+    /// stopped so it cannot take the link down
+    fn capture_frame(&mut self, direction: Direction, bytes: &[u8], crc_ok: bool) {
+        if let Some(capture) = &mut self.capture {
+            if let Err(e) = capture.record(direction, bytes, crc_ok) {
+                warn!("Traffic capture failed, stopping capture: {}", e);
+                self.capture = None;
+            }
+        }
+    }
+
+    /// Number of received frames dropped because of a CRC mismatch
+    pub fn rx_crc_errors(&self) -> u64 {
+        self.parser.crc_errors()
+    }
+
+    /// Ping the TX module and wait for its device info
+    ///
+    /// Broadcasts a CRSF device ping (0x28) and waits for a device info
+    /// frame (0x29) from the TX module address. Other frames received in
+    /// the meantime are discarded.
+    ///
+    /// # Arguments
+    ///
+    /// * `wait` - How long to wait for the reply
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<DeviceInfo>>` - The module's device info, or `None`
+    ///   if it did not answer in time
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the ping cannot be sent or the port fails.
+    pub async fn ping(&mut self, wait: Duration) -> Result<Option<DeviceInfo>> {
+        self.send_packet(&encode_device_ping_frame()).await?;
+
+        let deadline = Instant::now() + wait;
+        loop {
+            let Ok(frame) = timeout_at(deadline, self.recv_frame()).await else {
+                return Ok(None);
+            };
+            let frame = frame?;
+            if frame.frame_type != CRSF_FRAMETYPE_DEVICE_INFO {
+                continue;
+            }
+            match decode_device_info(&frame.payload) {
+                Ok(device) if device.origin == CRSF_ADDRESS_CRSF_TRANSMITTER => return Ok(Some(device)),
+                Ok(device) => debug!("Ignoring device info from 0x{:02X}", device.origin),
+                Err(e) => debug!("Ignoring device info frame: {}", e),
+            }
+        }
+    }
+
+    /// Propose a new line speed to the TX module and switch if it accepts
+    ///
+    /// Sends a CRSF speed proposal (0x32 / 0x0A 0x70) at the current speed
+    /// and waits for the module's reply (0x0A 0x71). On acceptance the port
+    /// is switched to `baud_rate`; otherwise it stays at the current speed.
+    /// Other frames received in the meantime are discarded.
+    ///
+    /// # Arguments
+    ///
+    /// * `baud_rate` - Proposed speed (e.g. 1,870,000)
+    /// * `wait` - How long to wait for the reply
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool>` - Whether the module accepted and the port switched;
+    ///   `false` if it declined or did not answer in time
+    ///
+    /// # Errors
+    ///
+    /// Returns `Serial` error if the proposal cannot be sent or the port
+    /// cannot be switched to the accepted speed.
+    pub async fn switch_baud_rate(&mut self, baud_rate: u32, wait: Duration) -> Result<bool> {
+        self.send_packet(&encode_baud_proposal_frame(CRSF_HANDSET_PORT_ID, baud_rate)).await?;
+
+        let deadline = Instant::now() + wait;
+        let accepted = loop {
+            let Ok(frame) = timeout_at(deadline, self.recv_frame()).await else {
+                return Ok(false);
+            };
+            let frame = frame?;
+            if frame.frame_type != CRSF_FRAMETYPE_COMMAND {
+                continue;
+            }
+            match decode_baud_response(&frame.payload) {
+                Ok(response) if response.port_id == CRSF_HANDSET_PORT_ID => break response.accepted,
+                Ok(response) => debug!("Ignoring baud rate response for port {}", response.port_id),
+                Err(e) => debug!("Ignoring command frame: {}", e),
+            }
+        };
+        if !accepted {
+            return Ok(false);
+        }
+
+        self.port.set_baud_rate(baud_rate)
+            .map_err(|e| FpvBridgeError::Serial(format!("Failed to set {} baud: {}", baud_rate, e)))?;
+        self.baud_rate = baud_rate;
+        if let Some(echo) = &mut self.echo {
+            echo.set_baud_rate(baud_rate);
+        }
+
+        info!("Switched {} to {} baud", self.device_path, baud_rate);
+        Ok(true)
+    }
+
+    /// Current line speed
+    pub fn baud_rate(&self) -> u32 {
+        self.baud_rate
+    }
+
+    /// Enable or disable echo cancellation for single-wire UART wiring
+    ///
+    /// With TX and RX on one wire, every transmitted byte is received back;
+    /// the echo is removed before received data reaches the frame parser.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether the line is half-duplex
+    pub fn set_half_duplex(&mut self, enabled: bool) {
+        self.echo = enabled.then(|| EchoCanceller::new(self.baud_rate));
+    }
+
+    /// Echo cancellation counters, or `None` on a full-duplex port
+    pub fn echo_stats(&self) -> Option<EchoStats> {
+        self.echo.as_ref().map(EchoCanceller::stats)
+    }
+
+    /// Select the active model ID on the ELRS module (model match)
+    ///
+    /// Sends a CRSF model select command (0x32 / 0x10 0x05). With model match
+    /// enabled, the receiver only accepts control from a handset sending the
+    /// model ID it was bound with, so this must be sent on connect and
+    /// whenever the active model changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `model_id` - Model (receiver) ID (0-63)
+    ///
+    /// # Errors
+    ///
+    /// Returns `CrsfProtocol` error if `model_id` is out of range, or
+    /// `Serial` error if the command cannot be written.
+    pub async fn select_model(&mut self, model_id: u8) -> Result<()> {
+        if model_id > CRSF_MODEL_ID_MAX {
+            return Err(FpvBridgeError::CrsfProtocol(format!(
+                "Model ID {} out of range (0-{})",
+                model_id, CRSF_MODEL_ID_MAX
+            )));
+        }
+
+        self.send_packet(&encode_model_select_frame(model_id)).await?;
+        self.model_id = Some(model_id);
+
+        info!("Selected ELRS model ID {}", model_id);
+        Ok(())
+    }
+
+    /// Get the model ID last sent with [`select_model`](Self::select_model)
+    ///
+    /// Returns `None` if no model has been selected on this connection.
+    pub fn model_id(&self) -> Option<u8> {
+        self.model_id
+    }
+
+    /// Put the ELRS module into bind mode
+    ///
+    /// Sends a CRSF bind command (0x32 / 0x10 0x01). The module stays in bind
+    /// mode until a receiver binds or the module times out.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Serial` error if the command cannot be written.
+    pub async fn bind(&mut self) -> Result<()> {
+        self.send_packet(&encode_bind_frame()).await?;
+
+        info!("ELRS module entering bind mode");
+        Ok(())
+    }
+
     /// Get the device path of the opened serial port
     ///
     /// Returns the path to the serial device that was successfully opened
@@ -210,6 +790,14 @@ impl ElrsSerial {
     pub fn device_path(&self) -> &str {
         &self.device_path
     }
+
+    /// Simulated module this connection talks to (`port = "virtual"`)
+    ///
+    /// Clone the handle to inspect the module or inject faults while the
+    /// connection is in use.
+    pub fn virtual_module(&self) -> Option<&virtual_module::VirtualModule> {
+        self.virtual_module.as_ref()
+    }
 }
 
 #[cfg(test)]
@@ -263,7 +851,7 @@ mod tests {
     #[test]
     fn test_open_port_with_invalid_path_returns_error() {
         // Try to open a non-existent device
-        let result = ElrsSerial::open_port("/dev/nonexistent_serial_device_12345");
+        let result = ElrsSerial::open_port("/dev/nonexistent_serial_device_12345", CRSF_BAUD_RATE);
 
         // Should fail with Serial error
         assert!(result.is_err());
@@ -362,7 +950,7 @@ mod tests {
     fn test_error_message_contains_path_on_open_failure() {
         // Verify that error messages include the failing path for debugging
         let nonexistent_path = "/dev/this_definitely_does_not_exist_12345";
-        let result = ElrsSerial::open_port(nonexistent_path);
+        let result = ElrsSerial::open_port(nonexistent_path, CRSF_BAUD_RATE);
 
         assert!(result.is_err());
         if let Err(FpvBridgeError::Serial(msg)) = result {
@@ -382,8 +970,7 @@ mod tests {
         // This test requires actual ELRS hardware connected
         let result = ElrsSerial::open();
 
-        if result.is_ok() {
-            let serial = result.unwrap();
+        if let Ok(serial) = result {
             println!("Successfully opened ELRS device at: {}", serial.device_path());
 
             // Verify device path is one of the expected ones
@@ -492,6 +1079,82 @@ mod tests {
             }
             other => panic!("Expected Serial error, got: {:?}", other),
         }
+        assert_eq!(serial.last_flush_time(), None, "Failed flush must not be recorded");
+    }
+
+    #[tokio::test]
+    async fn test_last_flush_time_with_mock() {
+        let mut serial = ElrsSerial::new_with_port(Box::new(MockSerialPort::new()), "/dev/mock".to_string());
+        assert_eq!(serial.last_flush_time(), None);
+
+        let before = SystemTime::now();
+        serial.send_packet(&[0x01, 0x02, 0x03]).await.unwrap();
+        let flushed = serial.last_flush_time().expect("flush time recorded");
+
+        assert!(flushed >= before);
+        assert!(flushed <= SystemTime::now());
+    }
+
+    #[tokio::test]
+    async fn test_send_packet_drops_frame_when_not_draining() {
+        let mock_port = MockSerialPort::new();
+        let mut serial = ElrsSerial::new_with_port(Box::new(mock_port.clone()), "/dev/mock".to_string());
+
+        // One frame still in the buffer is fine
+        mock_port.set_bytes_to_write(MAX_QUEUED_BYTES);
+        serial.send_packet(&[0xC8; 26]).await.unwrap();
+
+        mock_port.set_bytes_to_write(MAX_QUEUED_BYTES + 1);
+        match serial.send_packet(&[0xC8; 26]).await {
+            Err(FpvBridgeError::SerialStall(msg)) => assert!(msg.contains("dropped 26-byte packet")),
+            other => panic!("Expected SerialStall error, got: {:?}", other),
+        }
+
+        assert_eq!(mock_port.get_written_data().len(), 1, "Dropped frame must not be written");
+        assert_eq!(serial.tx_stats().dropped_frames, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_send_packet_write_timeout() {
+        let mock_port = MockSerialPort::new();
+        let mut serial = ElrsSerial::new_with_port(Box::new(mock_port.clone()), "/dev/mock".to_string());
+        assert_eq!(serial.write_timeout(), DEFAULT_WRITE_TIMEOUT);
+        serial.set_write_timeout(Duration::from_millis(20));
+
+        mock_port.set_write_stall(true);
+        let start = tokio::time::Instant::now();
+        match serial.send_packet(&[0xC8; 26]).await {
+            Err(FpvBridgeError::SerialStall(msg)) => assert!(msg.contains("20ms")),
+            other => panic!("Expected SerialStall error, got: {:?}", other),
+        }
+        assert_eq!(start.elapsed(), Duration::from_millis(20));
+        assert_eq!(serial.last_flush_time(), None);
+
+        // Recovers once the port accepts data again
+        mock_port.set_write_stall(false);
+        serial.send_packet(&[0xC8; 26]).await.unwrap();
+
+        let stats = serial.tx_stats();
+        assert_eq!(stats.write_timeouts, 1);
+        assert_eq!(stats.bytes_queued, 26, "Timed-out write is not counted as queued");
+    }
+
+    #[tokio::test]
+    async fn test_tx_stats_bytes_drained() {
+        let mock_port = MockSerialPort::new();
+        let mut serial = ElrsSerial::new_with_port(Box::new(mock_port.clone()), "/dev/mock".to_string());
+
+        serial.send_packet(&[0xC8; 26]).await.unwrap();
+        serial.send_packet(&[0xC8; 26]).await.unwrap();
+        mock_port.set_bytes_to_write(10);
+
+        let stats = serial.tx_stats();
+        assert_eq!(stats.bytes_queued, 52);
+        assert_eq!(stats.bytes_drained, 42);
+        assert_eq!(
+            stats.to_string(),
+            "52 bytes queued, 42 drained, 0 frames dropped, 0 write timeouts"
+        );
     }
 
     #[tokio::test]
@@ -595,6 +1258,280 @@ mod tests {
         assert_eq!(written_data.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_select_model_with_mock() {
+        use crate::crsf::encoder::encode_model_select_frame;
+
+        let mock_port = MockSerialPort::new();
+        let mut serial = ElrsSerial::new_with_port(
+            Box::new(mock_port.clone()),
+            "/dev/mock".to_string(),
+        );
+        assert_eq!(serial.model_id(), None);
+
+        assert!(serial.select_model(5).await.is_ok());
+
+        assert_eq!(serial.model_id(), Some(5));
+        let written_data = mock_port.get_written_data();
+        assert_eq!(written_data.len(), 1);
+        assert_eq!(written_data[0], encode_model_select_frame(5));
+    }
+
+    #[tokio::test]
+    async fn test_select_model_out_of_range_with_mock() {
+        let mock_port = MockSerialPort::new();
+        let mut serial = ElrsSerial::new_with_port(
+            Box::new(mock_port.clone()),
+            "/dev/mock".to_string(),
+        );
+
+        let result = serial.select_model(64).await;
+
+        assert!(matches!(result, Err(FpvBridgeError::CrsfProtocol(_))));
+        assert_eq!(serial.model_id(), None);
+        assert!(mock_port.get_written_data().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_select_model_write_error_keeps_previous_id() {
+        let mock_port = MockSerialPort::new();
+        let mut serial = ElrsSerial::new_with_port(
+            Box::new(mock_port.clone()),
+            "/dev/mock".to_string(),
+        );
+        assert!(serial.select_model(1).await.is_ok());
+
+        mock_port.set_write_error(std::io::ErrorKind::BrokenPipe);
+        assert!(serial.select_model(2).await.is_err());
+
+        assert_eq!(serial.model_id(), Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_bind_with_mock() {
+        use crate::crsf::encoder::encode_bind_frame;
+
+        let mock_port = MockSerialPort::new();
+        let mut serial = ElrsSerial::new_with_port(
+            Box::new(mock_port.clone()),
+            "/dev/mock".to_string(),
+        );
+
+        assert!(serial.bind().await.is_ok());
+
+        let written_data = mock_port.get_written_data();
+        assert_eq!(written_data.len(), 1);
+        assert_eq!(written_data[0], encode_bind_frame());
+    }
+
+    #[tokio::test]
+    async fn test_recv_frame_with_mock() {
+        let mock = MockSerialPort::new();
+        let frame = encode_bind_frame();
+        mock.queue_read_data(&[0x00, 0xFF]);
+        mock.queue_read_data(&frame[..3]);
+        mock.queue_read_data(&frame[3..]);
+
+        let mut serial = ElrsSerial::new_with_port(Box::new(mock), "/dev/mock".to_string());
+        let received = serial.recv_frame().await.unwrap();
+
+        assert_eq!(received.frame_type, crate::crsf::protocol::CRSF_FRAMETYPE_COMMAND);
+        assert_eq!(received.payload, frame[3..frame.len() - 1]);
+        assert_eq!(serial.rx_crc_errors(), 0);
+    }
+
+    fn device_info_frame(origin: u8) -> Vec<u8> {
+        use crate::crsf::encoder::encode_frame;
+        use crate::crsf::protocol::CRSF_ADDRESS_RADIO_TRANSMITTER;
+
+        let mut payload = vec![CRSF_ADDRESS_RADIO_TRANSMITTER, origin];
+        payload.extend_from_slice(b"ELRS TX\0ELRS");
+        payload.extend_from_slice(&[0; 8]);
+        payload.extend_from_slice(&[12, 0]);
+        encode_frame(&CrsfFrame {
+            frame_type: CRSF_FRAMETYPE_DEVICE_INFO,
+            payload,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_ping_with_mock() {
+        let mock = MockSerialPort::new();
+        // A receiver answering through the link is not the TX module
+        mock.queue_read_data(&device_info_frame(0xEC));
+        mock.queue_read_data(&encode_bind_frame());
+        mock.queue_read_data(&device_info_frame(CRSF_ADDRESS_CRSF_TRANSMITTER));
+
+        let mut serial = ElrsSerial::new_with_port(Box::new(mock.clone()), "/dev/mock".to_string());
+        let device = serial.ping(PROBE_TIMEOUT).await.unwrap().expect("TX module answered");
+
+        assert_eq!(device.name, "ELRS TX");
+        assert_eq!(device.parameter_count, 12);
+        assert_eq!(mock.get_written_data(), vec![encode_device_ping_frame()]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_ping_timeout_with_mock() {
+        let mock = MockSerialPort::new();
+        mock.queue_read_data(&device_info_frame(0xEC));
+
+        let mut serial = ElrsSerial::new_with_port(Box::new(mock), "/dev/mock".to_string());
+        let start = Instant::now();
+
+        assert_eq!(serial.ping(PROBE_TIMEOUT).await.unwrap(), None);
+        assert_eq!(start.elapsed(), PROBE_TIMEOUT);
+    }
+
+    fn baud_response_frame(port_id: u8, accepted: bool) -> Vec<u8> {
+        use crate::crsf::crc::crc8_ba;
+        use crate::crsf::encoder::encode_frame;
+        use crate::crsf::protocol::{
+            CRSF_ADDRESS_RADIO_TRANSMITTER, CRSF_COMMAND_GENERAL, CRSF_COMMAND_GENERAL_BAUD_RESPONSE,
+        };
+
+        let mut payload = vec![
+            CRSF_ADDRESS_RADIO_TRANSMITTER,
+            CRSF_ADDRESS_CRSF_TRANSMITTER,
+            CRSF_COMMAND_GENERAL,
+            CRSF_COMMAND_GENERAL_BAUD_RESPONSE,
+            port_id,
+            u8::from(accepted),
+        ];
+        let mut crc_data = vec![CRSF_FRAMETYPE_COMMAND];
+        crc_data.extend_from_slice(&payload);
+        payload.push(crc8_ba(&crc_data));
+        encode_frame(&CrsfFrame {
+            frame_type: CRSF_FRAMETYPE_COMMAND,
+            payload,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_switch_baud_rate_accepted() {
+        let mock = MockSerialPort::new();
+        // A reply for another port does not count
+        mock.queue_read_data(&baud_response_frame(1, false));
+        mock.queue_read_data(&baud_response_frame(CRSF_HANDSET_PORT_ID, true));
+
+        let mut serial = ElrsSerial::new_with_port(Box::new(mock.clone()), "/dev/mock".to_string());
+        assert!(serial.switch_baud_rate(1_870_000, BAUD_SWITCH_TIMEOUT).await.unwrap());
+
+        assert_eq!(mock.get_written_data(), vec![encode_baud_proposal_frame(0, 1_870_000)]);
+        assert_eq!(mock.get_baud_rate(), Some(1_870_000));
+        assert_eq!(serial.baud_rate(), 1_870_000);
+    }
+
+    #[tokio::test]
+    async fn test_switch_baud_rate_declined() {
+        let mock = MockSerialPort::new();
+        mock.queue_read_data(&baud_response_frame(CRSF_HANDSET_PORT_ID, false));
+
+        let mut serial = ElrsSerial::new_with_port(Box::new(mock.clone()), "/dev/mock".to_string());
+        assert!(!serial.switch_baud_rate(3_750_000, BAUD_SWITCH_TIMEOUT).await.unwrap());
+
+        assert_eq!(mock.get_baud_rate(), None);
+        assert_eq!(serial.baud_rate(), CRSF_BAUD_RATE);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_switch_baud_rate_timeout() {
+        let mock = MockSerialPort::new();
+        let mut serial = ElrsSerial::new_with_port(Box::new(mock.clone()), "/dev/mock".to_string());
+        let start = Instant::now();
+
+        assert!(!serial.switch_baud_rate(921_600, BAUD_SWITCH_TIMEOUT).await.unwrap());
+        assert_eq!(start.elapsed(), BAUD_SWITCH_TIMEOUT);
+        assert_eq!(mock.get_baud_rate(), None);
+    }
+
+    #[tokio::test]
+    async fn test_half_duplex_discards_echo() {
+        let mock = MockSerialPort::new();
+        mock.set_echo(true);
+
+        let mut serial = ElrsSerial::new_with_port(Box::new(mock.clone()), "/dev/mock".to_string());
+        serial.set_half_duplex(true);
+        serial.send_packet(&encode_bind_frame()).await.unwrap();
+        mock.queue_read_data(&encode_model_select_frame(5));
+
+        // The bind frame's echo is dropped; only the module's frame is parsed
+        let frame = serial.recv_frame().await.unwrap();
+        assert_eq!(frame.payload[4], 5);
+
+        let stats = serial.echo_stats().unwrap();
+        assert_eq!(stats.echoed_bytes, encode_bind_frame().len() as u64);
+        assert_eq!(stats.mismatches, 0);
+    }
+
+    #[tokio::test]
+    async fn test_full_duplex_has_no_echo_cancellation() {
+        let mock = MockSerialPort::new();
+        mock.set_echo(true);
+
+        let mut serial = ElrsSerial::new_with_port(Box::new(mock), "/dev/mock".to_string());
+        serial.send_packet(&encode_bind_frame()).await.unwrap();
+
+        assert_eq!(serial.echo_stats(), None);
+        assert_eq!(serial.recv_frame().await.unwrap().payload.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_connect_explicit_missing_port() {
+        let config = SerialConfig {
+            port: "/dev/serial/by-id/usb-nonexistent-if00-port0".to_string(),
+            baud_rate: CRSF_BAUD_RATE,
+            timeout_ms: 100,
+            reconnect_interval_ms: 1000,
+            probe: false,
+            half_duplex: false,
+            switch_baud_rate: None,
+        };
+
+        match ElrsSerial::connect(&config).await {
+            Err(FpvBridgeError::SerialPortNotFound(msg)) => assert!(msg.contains("usb-nonexistent")),
+            other => panic!("Expected SerialPortNotFound, got: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recv_frame_multiple_frames_in_one_read() {
+        let mock = MockSerialPort::new();
+        let mut data = encode_bind_frame();
+        data.extend(encode_model_select_frame(5));
+        mock.queue_read_data(&data);
+
+        let mut serial = ElrsSerial::new_with_port(Box::new(mock), "/dev/mock".to_string());
+
+        assert_eq!(serial.recv_frame().await.unwrap().payload.len(), 5);
+        assert_eq!(serial.recv_frame().await.unwrap().payload[4], 5);
+    }
+
+    #[tokio::test]
+    async fn test_recv_frame_read_error_and_close() {
+        let mock = MockSerialPort::new();
+        mock.set_read_error(std::io::ErrorKind::BrokenPipe);
+        let mut serial = ElrsSerial::new_with_port(Box::new(mock), "/dev/mock".to_string());
+        assert!(matches!(serial.recv_frame().await, Err(FpvBridgeError::Serial(_))));
+
+        let mock = MockSerialPort::new();
+        mock.queue_read_data(&[]);
+        let mut serial = ElrsSerial::new_with_port(Box::new(mock), "/dev/mock".to_string());
+        let err = serial.recv_frame().await.unwrap_err();
+        assert!(err.to_string().contains("closed"));
+    }
+
+    #[tokio::test]
+    async fn test_recv_frame_idle_port_does_not_block_select() {
+        let mock = MockSerialPort::new();
+        let mut serial = ElrsSerial::new_with_port(Box::new(mock), "/dev/mock".to_string());
+
+        tokio::select! {
+            _ = serial.recv_frame() => panic!("No data was queued"),
+            _ = tokio::time::sleep(std::time::Duration::from_millis(5)) => {}
+        }
+        serial.send_packet(&encode_bind_frame()).await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_send_packet_preserves_data_integrity() {
         use crate::crsf::encoder::encode_rc_channels_frame;
@@ -11,6 +11,18 @@ pub trait SerialPortIO: Send {
 
     /// Flush the output buffer
     async fn flush(&mut self) -> io::Result<()>;
+
+    /// Read available data into `buf`, waiting until at least one byte arrives
+    ///
+    /// Returns the number of bytes read (0 at end of stream). Must be
+    /// cancel-safe: dropping the future before it completes loses no data.
+    async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>;
+
+    /// Bytes written but not yet transmitted (the output buffer backlog)
+    fn bytes_to_write(&self) -> io::Result<u32>;
+
+    /// Change the line speed
+    fn set_baud_rate(&mut self, baud_rate: u32) -> io::Result<()>;
 }
 
 /// Wrapper around tokio_serial::SerialStream that implements SerialPortIO
@@ -35,11 +47,27 @@ impl SerialPortIO for TokioSerialPort {
         use tokio::io::AsyncWriteExt;
         self.port.flush().await
     }
+
+    async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        use tokio::io::AsyncReadExt;
+        self.port.read(buf).await
+    }
+
+    fn bytes_to_write(&self) -> io::Result<u32> {
+        use tokio_serial::SerialPort;
+        Ok(SerialPort::bytes_to_write(&self.port)?)
+    }
+
+    fn set_baud_rate(&mut self, baud_rate: u32) -> io::Result<()> {
+        use tokio_serial::SerialPort;
+        Ok(SerialPort::set_baud_rate(&mut self.port, baud_rate)?)
+    }
 }
 
 #[cfg(test)]
 pub mod mocks {
     use super::*;
+    use std::collections::VecDeque;
     use std::sync::{Arc, Mutex};
 
     /// Mock serial port for testing
@@ -48,6 +76,12 @@ pub mod mocks {
         pub written_data: Arc<Mutex<Vec<Vec<u8>>>>,
         pub write_error: Arc<Mutex<Option<io::ErrorKind>>>,
         pub flush_error: Arc<Mutex<Option<io::ErrorKind>>>,
+        pub read_data: Arc<Mutex<VecDeque<Vec<u8>>>>,
+        pub read_error: Arc<Mutex<Option<io::ErrorKind>>>,
+        pub bytes_to_write: Arc<Mutex<u32>>,
+        pub write_stall: Arc<Mutex<bool>>,
+        pub baud_rate: Arc<Mutex<Option<u32>>>,
+        pub echo: Arc<Mutex<bool>>,
     }
 
     impl MockSerialPort {
@@ -56,6 +90,12 @@ pub mod mocks {
                 written_data: Arc::new(Mutex::new(Vec::new())),
                 write_error: Arc::new(Mutex::new(None)),
                 flush_error: Arc::new(Mutex::new(None)),
+                read_data: Arc::new(Mutex::new(VecDeque::new())),
+                read_error: Arc::new(Mutex::new(None)),
+                bytes_to_write: Arc::new(Mutex::new(0)),
+                write_stall: Arc::new(Mutex::new(false)),
+                baud_rate: Arc::new(Mutex::new(None)),
+                echo: Arc::new(Mutex::new(false)),
             }
         }
 
@@ -70,6 +110,35 @@ pub mod mocks {
         pub fn set_flush_error(&self, error: io::ErrorKind) {
             *self.flush_error.lock().unwrap() = Some(error);
         }
+
+        /// Queue bytes to be returned by a single `read` call
+        pub fn queue_read_data(&self, data: &[u8]) {
+            self.read_data.lock().unwrap().push_back(data.to_vec());
+        }
+
+        pub fn set_read_error(&self, error: io::ErrorKind) {
+            *self.read_error.lock().unwrap() = Some(error);
+        }
+
+        /// Set the output buffer backlog reported by `bytes_to_write`
+        pub fn set_bytes_to_write(&self, bytes: u32) {
+            *self.bytes_to_write.lock().unwrap() = bytes;
+        }
+
+        /// Make `write_all` never complete, like a stalled USB CDC buffer
+        pub fn set_write_stall(&self, stalled: bool) {
+            *self.write_stall.lock().unwrap() = stalled;
+        }
+
+        /// Echo written data back on RX, like a single-wire UART
+        pub fn set_echo(&self, echo: bool) {
+            *self.echo.lock().unwrap() = echo;
+        }
+
+        /// Line speed last set with `set_baud_rate`
+        pub fn get_baud_rate(&self) -> Option<u32> {
+            *self.baud_rate.lock().unwrap()
+        }
     }
 
     #[async_trait]
@@ -78,7 +147,13 @@ pub mod mocks {
             if let Some(error) = *self.write_error.lock().unwrap() {
                 return Err(io::Error::new(error, "Mock write error"));
             }
+            if *self.write_stall.lock().unwrap() {
+                std::future::pending::<()>().await;
+            }
             self.written_data.lock().unwrap().push(data.to_vec());
+            if *self.echo.lock().unwrap() {
+                self.queue_read_data(data);
+            }
             Ok(())
         }
 
@@ -88,5 +163,34 @@ pub mod mocks {
             }
             Ok(())
         }
+
+        async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if let Some(error) = *self.read_error.lock().unwrap() {
+                return Err(io::Error::new(error, "Mock read error"));
+            }
+
+            let chunk = self.read_data.lock().unwrap().pop_front();
+            match chunk {
+                Some(mut chunk) => {
+                    let n = chunk.len().min(buf.len());
+                    buf[..n].copy_from_slice(&chunk[..n]);
+                    if n < chunk.len() {
+                        self.read_data.lock().unwrap().push_front(chunk.split_off(n));
+                    }
+                    Ok(n)
+                }
+                // Nothing queued: behave like an idle port
+                None => std::future::pending().await,
+            }
+        }
+
+        fn bytes_to_write(&self) -> io::Result<u32> {
+            Ok(*self.bytes_to_write.lock().unwrap())
+        }
+
+        fn set_baud_rate(&mut self, baud_rate: u32) -> io::Result<()> {
+            *self.baud_rate.lock().unwrap() = Some(baud_rate);
+            Ok(())
+        }
     }
 }
@@ -0,0 +1,683 @@
+//! # Virtual ELRS Module
+//!
+//! A simulated ELRS TX module for testing without hardware.
+//!
+//! [`VirtualModule`] speaks CRSF over an in-process duplex stream
+//! ([`VirtualModule::duplex`]) or a pseudo-terminal pair
+//! ([`VirtualModule::pty`]), whose device path can be opened like a real
+//! module. `serial.port = "virtual"` runs the bridge against an in-process
+//! one.
+//!
+//! The module:
+//! - Parses RC channels frames (0x16 and 0x17) and keeps the latest channels
+//! - Answers device pings with device info, and accepts speed proposals
+//! - Records model select and bind commands
+//! - Sends link statistics, battery, GPS, attitude and flight mode
+//!   telemetry every [`TELEMETRY_INTERVAL`]; the flight mode gets a `*`
+//!   while the arm channel is off, as Betaflight reports it
+//!
+//! Faults are injected at runtime: link loss (telemetry stops, as when the
+//! receiver is out of range), corrupted CRCs on outgoing frames, and extra
+//! latency on everything the module sends.
+//!
+//! The module runs until the other end of its stream is closed.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, DuplexStream};
+use tokio::time::{interval, sleep_until, Duration, Instant, MissedTickBehavior};
+use tokio_serial::SerialStream;
+
+use super::discovery::VIRTUAL_PORT;
+use super::port_trait::SerialPortIO;
+use super::ElrsSerial;
+use crate::controller::channel_mapper::channels;
+use crate::crsf::decoder::{decode_rc_channels_payload, decode_subset_rc_channels_payload, FrameParser};
+use crate::crsf::encoder::{
+    encode_attitude_frame, encode_baud_response_frame, encode_battery_sensor_frame, encode_device_info_frame,
+    encode_flight_mode_frame, encode_gps_frame, encode_link_statistics_frame,
+};
+use crate::crsf::protocol::*;
+use crate::error::{FpvBridgeError, Result};
+
+/// How often the module sends each kind of telemetry
+pub const TELEMETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Name reported in the device info reply
+pub const VIRTUAL_MODULE_NAME: &str = "Virtual ELRS TX";
+
+/// Buffer size of the in-process duplex stream
+const DUPLEX_BUFFER_SIZE: usize = 4096;
+
+/// What the virtual module has received and sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct VirtualModuleStats {
+    /// RC channels frames received (0x16 and 0x17)
+    pub rc_frames: u64,
+    /// Device pings answered
+    pub pings: u64,
+    /// Bind commands received
+    pub binds: u64,
+    /// Received frames dropped because of a CRC mismatch
+    pub rx_crc_errors: u64,
+    /// Telemetry frames sent
+    pub telemetry_frames: u64,
+    /// Outgoing frames sent with a corrupted CRC
+    pub corrupted_frames: u64,
+}
+
+impl std::fmt::Display for VirtualModuleStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} RC frames, {} pings, {} binds, {} RX CRC errors, {} telemetry frames, {} corrupted",
+            self.rc_frames, self.pings, self.binds, self.rx_crc_errors, self.telemetry_frames, self.corrupted_frames
+        )
+    }
+}
+
+/// State shared between the module task and its handle
+#[derive(Debug)]
+struct ModuleState {
+    channels: Option<RcChannels>,
+    model_id: Option<u8>,
+    stats: VirtualModuleStats,
+    link_lost: bool,
+    /// Outgoing frames still to corrupt
+    corrupt_frames: u32,
+    latency: Duration,
+    accept_baud_rate: bool,
+    link_statistics: LinkStatistics,
+    battery: BatterySensor,
+    gps: GpsData,
+    attitude: Attitude,
+    flight_mode: String,
+}
+
+impl ModuleState {
+    fn new() -> Self {
+        Self {
+            channels: None,
+            model_id: None,
+            stats: VirtualModuleStats::default(),
+            link_lost: false,
+            corrupt_frames: 0,
+            latency: Duration::ZERO,
+            accept_baud_rate: true,
+            link_statistics: LinkStatistics {
+                uplink_rssi_1: 50,
+                uplink_rssi_2: 52,
+                uplink_lq: 100,
+                uplink_snr: 9,
+                active_antenna: 0,
+                rf_mode: 7,
+                uplink_tx_power: 3,
+                downlink_rssi: 55,
+                downlink_lq: 100,
+                downlink_snr: 8,
+            },
+            battery: BatterySensor {
+                voltage: 16.8,
+                current: 0.0,
+                capacity_used: 0,
+                remaining_percent: 100,
+            },
+            gps: GpsData {
+                latitude: 50.450_1,
+                longitude: 30.523_4,
+                ground_speed: 0.0,
+                heading: 0.0,
+                altitude: 120,
+                satellites: 12,
+            },
+            attitude: Attitude { pitch: 0.0, roll: 0.0, yaw: 0.0 },
+            flight_mode: "ACRO".to_string(),
+        }
+    }
+
+    /// React to a frame from the handset, returning the reply if any
+    fn handle_frame(&mut self, frame: &CrsfFrame) -> Option<Vec<u8>> {
+        match frame.frame_type {
+            CRSF_FRAMETYPE_RC_CHANNELS_PACKED => {
+                if let Ok(channels) = decode_rc_channels_payload(&frame.payload) {
+                    self.channels = Some(channels);
+                    self.stats.rc_frames += 1;
+                }
+                None
+            }
+            CRSF_FRAMETYPE_SUBSET_RC_CHANNELS_PACKED => {
+                if let Ok(subset) = decode_subset_rc_channels_payload(&frame.payload) {
+                    let channels = self.channels.get_or_insert([CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS]);
+                    subset.apply_to(channels);
+                    self.stats.rc_frames += 1;
+                }
+                None
+            }
+            CRSF_FRAMETYPE_DEVICE_PING => {
+                self.stats.pings += 1;
+                Some(encode_device_info_frame(&DeviceInfo {
+                    origin: CRSF_ADDRESS_CRSF_TRANSMITTER,
+                    name: VIRTUAL_MODULE_NAME.to_string(),
+                    serial_number: u32::from_be_bytes(*b"ELRS"),
+                    hardware_version: 0,
+                    software_version: 0x0003_0000,
+                    parameter_count: 0,
+                }))
+            }
+            CRSF_FRAMETYPE_COMMAND => self.handle_command(&frame.payload),
+            _ => None,
+        }
+    }
+
+    /// Payload layout: Dest | Origin | Cmd | SubCmd | Data... | CRC8-BA
+    fn handle_command(&mut self, payload: &[u8]) -> Option<Vec<u8>> {
+        match payload.get(2..)? {
+            [CRSF_COMMAND_SUBCMD_CRSF, CRSF_COMMAND_CRSF_MODEL_SELECT, model_id, ..] => {
+                self.model_id = Some(*model_id);
+                None
+            }
+            [CRSF_COMMAND_SUBCMD_CRSF, CRSF_COMMAND_CRSF_BIND, ..] => {
+                self.stats.binds += 1;
+                None
+            }
+            [CRSF_COMMAND_GENERAL, CRSF_COMMAND_GENERAL_BAUD_PROPOSAL, port_id, ..] => {
+                Some(encode_baud_response_frame(*port_id, self.accept_baud_rate))
+            }
+            _ => None,
+        }
+    }
+
+    /// Telemetry frames due at a telemetry tick (none while the link is lost)
+    fn telemetry_frames(&mut self) -> Vec<Vec<u8>> {
+        if self.link_lost {
+            return Vec::new();
+        }
+        let armed = self.channels.is_some_and(|rc| rc[channels::ARM] > CRSF_CHANNEL_VALUE_CENTER);
+        let flight_mode = if armed { self.flight_mode.clone() } else { format!("{}*", self.flight_mode) };
+        let frames = vec![
+            encode_link_statistics_frame(&self.link_statistics),
+            encode_battery_sensor_frame(&self.battery),
+            encode_gps_frame(&self.gps),
+            encode_attitude_frame(&self.attitude),
+            encode_flight_mode_frame(&flight_mode),
+        ];
+        self.stats.telemetry_frames += frames.len() as u64;
+        frames
+    }
+
+    /// Apply the injected faults to an outgoing frame
+    ///
+    /// Returns when the frame may be written.
+    fn prepare(&mut self, frame: &mut [u8], now: Instant) -> Instant {
+        if self.corrupt_frames > 0 {
+            if let Some(crc) = frame.last_mut() {
+                *crc ^= 0xFF;
+            }
+            self.corrupt_frames -= 1;
+            self.stats.corrupted_frames += 1;
+        }
+        now + self.latency
+    }
+}
+
+/// Handle to a running virtual ELRS module
+///
+/// Clones are handles to the same module. Dropping them does not stop the
+/// module; it stops when the other end of its stream is closed.
+#[derive(Debug, Clone)]
+pub struct VirtualModule {
+    state: Arc<Mutex<ModuleState>>,
+    /// Handset side of a pseudo-terminal pair, held open so the pty stays
+    /// usable while the bridge reopens it by path
+    pty: Option<Arc<SerialStream>>,
+    device_path: String,
+}
+
+impl VirtualModule {
+    /// Start a module on an in-process stream, connected to the returned
+    /// [`ElrsSerial`]
+    ///
+    /// Must be called from within a Tokio runtime.
+    ///
+    /// # Returns
+    ///
+    /// * `(ElrsSerial, VirtualModule)` - Serial connection to the module and
+    ///   the module handle
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fpv_bridge::serial::virtual_module::VirtualModule;
+    /// use tokio::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> fpv_bridge::error::Result<()> {
+    ///     let (mut serial, _module) = VirtualModule::duplex();
+    ///     let device = serial.ping(Duration::from_millis(100)).await?.unwrap();
+    ///     assert_eq!(device.name, "Virtual ELRS TX");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn duplex() -> (ElrsSerial, Self) {
+        let (handset, module) = tokio::io::duplex(DUPLEX_BUFFER_SIZE);
+        let serial = ElrsSerial::from_port(Box::new(DuplexPort(handset)), VIRTUAL_PORT.to_string());
+        (serial, Self::spawn(module, None, VIRTUAL_PORT.to_string()))
+    }
+
+    /// Start a module behind a pseudo-terminal
+    ///
+    /// The handset side is a tty at [`device_path`](Self::device_path),
+    /// which can be opened like a real module, e.g. as `serial.port` of the
+    /// bridge binary. Must be called from within a Tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Serial` error if the pseudo-terminal cannot be created.
+    pub fn pty() -> Result<Self> {
+        use tokio_serial::SerialPort;
+
+        let (module, handset) = SerialStream::pair()
+            .map_err(|e| FpvBridgeError::Serial(format!("Failed to create pseudo-terminal: {}", e)))?;
+        let device_path = handset
+            .name()
+            .ok_or_else(|| FpvBridgeError::Serial("Pseudo-terminal has no device path".to_string()))?;
+        Ok(Self::spawn(module, Some(handset), device_path))
+    }
+
+    fn spawn<S>(stream: S, pty: Option<SerialStream>, device_path: String) -> Self
+    where
+        S: AsyncRead + AsyncWrite + Send + 'static,
+    {
+        let state = Arc::new(Mutex::new(ModuleState::new()));
+        tokio::spawn(run_module(stream, state.clone()));
+        Self { state, pty: pty.map(Arc::new), device_path }
+    }
+
+    /// Path of the handset side: the pty device, or `"virtual"` in-process
+    pub fn device_path(&self) -> &str {
+        &self.device_path
+    }
+
+    /// Whether the handset side is a pseudo-terminal
+    pub fn is_pty(&self) -> bool {
+        self.pty.is_some()
+    }
+
+    /// Channels of the latest RC frame, `None` before the first one
+    pub fn channels(&self) -> Option<RcChannels> {
+        self.lock().channels
+    }
+
+    /// Model ID last selected by the handset
+    pub fn model_id(&self) -> Option<u8> {
+        self.lock().model_id
+    }
+
+    /// Counters since the module started
+    pub fn stats(&self) -> VirtualModuleStats {
+        self.lock().stats
+    }
+
+    /// Simulate losing (or regaining) the link to the receiver: no telemetry
+    /// is sent while it is lost
+    pub fn set_link_lost(&self, lost: bool) {
+        self.lock().link_lost = lost;
+    }
+
+    /// Corrupt the CRC of the next `frames` outgoing frames
+    pub fn corrupt_next_frames(&self, frames: u32) {
+        self.lock().corrupt_frames = frames;
+    }
+
+    /// Delay every outgoing frame by `latency`
+    pub fn set_latency(&self, latency: Duration) {
+        self.lock().latency = latency;
+    }
+
+    /// Whether speed proposals are accepted (default) or declined
+    pub fn set_accept_baud_rate(&self, accept: bool) {
+        self.lock().accept_baud_rate = accept;
+    }
+
+    /// Link statistics reported from now on
+    pub fn set_link_statistics(&self, stats: LinkStatistics) {
+        self.lock().link_statistics = stats;
+    }
+
+    /// Battery telemetry reported from now on
+    pub fn set_battery(&self, battery: BatterySensor) {
+        self.lock().battery = battery;
+    }
+
+    /// GPS telemetry reported from now on
+    pub fn set_gps(&self, gps: GpsData) {
+        self.lock().gps = gps;
+    }
+
+    /// Attitude telemetry reported from now on
+    pub fn set_attitude(&self, attitude: Attitude) {
+        self.lock().attitude = attitude;
+    }
+
+    /// Flight mode reported from now on (without the disarmed `*`)
+    pub fn set_flight_mode(&self, mode: &str) {
+        self.lock().flight_mode = mode.to_string();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ModuleState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Module task: answers frames from the handset and sends telemetry until
+/// the stream closes
+async fn run_module<S>(stream: S, state: Arc<Mutex<ModuleState>>)
+where
+    S: AsyncRead + AsyncWrite + Send,
+{
+    let lock = || state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let (mut reader, mut writer) = tokio::io::split(stream);
+    let mut parser = FrameParser::new();
+    let mut outgoing: VecDeque<(Instant, Vec<u8>)> = VecDeque::new();
+    let mut telemetry = interval(TELEMETRY_INTERVAL);
+    telemetry.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut buf = [0u8; 256];
+
+    loop {
+        let release = outgoing.front().map(|(at, _)| *at);
+        let replies = tokio::select! {
+            read = reader.read(&mut buf) => {
+                let n = match read {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => n,
+                };
+                parser.push(&buf[..n]);
+
+                let mut state = lock();
+                let mut replies = Vec::new();
+                while let Some(frame) = parser.next_frame() {
+                    replies.extend(state.handle_frame(&frame));
+                }
+                state.stats.rx_crc_errors = parser.crc_errors();
+                replies
+            }
+
+            _ = telemetry.tick() => lock().telemetry_frames(),
+
+            _ = sleep_until(release.unwrap_or_else(Instant::now)), if release.is_some() => {
+                if let Some((_, frame)) = outgoing.pop_front() {
+                    if writer.write_all(&frame).await.is_err() {
+                        break;
+                    }
+                }
+                continue;
+            }
+        };
+
+        let now = Instant::now();
+        let mut state = lock();
+        for mut frame in replies {
+            let at = state.prepare(&mut frame, now);
+            outgoing.push_back((at, frame));
+        }
+    }
+}
+
+/// In-process stream posing as the module's serial port
+struct DuplexPort(DuplexStream);
+
+#[async_trait]
+impl SerialPortIO for DuplexPort {
+    async fn write_all(&mut self, data: &[u8]) -> std::io::Result<()> {
+        self.0.write_all(data).await
+    }
+
+    async fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush().await
+    }
+
+    async fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.0.read(buf).await
+    }
+
+    fn bytes_to_write(&self) -> std::io::Result<u32> {
+        Ok(0)
+    }
+
+    fn set_baud_rate(&mut self, _baud_rate: u32) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crsf::decoder::{
+        decode_attitude, decode_battery_sensor, decode_flight_mode, decode_gps, decode_link_statistics,
+    };
+    use crate::crsf::encoder::{encode_rc_channels_frame, encode_subset_rc_channels_frame};
+    use tokio::time::timeout;
+
+    /// Telemetry frame types received within `wait`
+    async fn received_types(serial: &mut ElrsSerial, wait: Duration) -> Vec<u8> {
+        let mut types = Vec::new();
+        let deadline = Instant::now() + wait;
+        while let Ok(Ok(frame)) = tokio::time::timeout_at(deadline, serial.recv_frame()).await {
+            types.push(frame.frame_type);
+        }
+        types
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_ping_and_baud_switch() {
+        let (mut serial, module) = VirtualModule::duplex();
+
+        let device = serial.ping(Duration::from_millis(100)).await.unwrap().unwrap();
+        assert_eq!(device.name, VIRTUAL_MODULE_NAME);
+        assert_eq!(device.origin, CRSF_ADDRESS_CRSF_TRANSMITTER);
+        assert_eq!(module.stats().pings, 1);
+
+        assert!(serial.switch_baud_rate(921_600, Duration::from_millis(100)).await.unwrap());
+        assert_eq!(serial.baud_rate(), 921_600);
+
+        module.set_accept_baud_rate(false);
+        assert!(!serial.switch_baud_rate(1_870_000, Duration::from_millis(100)).await.unwrap());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_receives_rc_frames_and_commands() {
+        let (mut serial, module) = VirtualModule::duplex();
+        assert_eq!(module.device_path(), "virtual");
+        assert!(!module.is_pty());
+
+        let mut channels = [CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS];
+        channels[2] = 172;
+        serial.send_packet(&encode_rc_channels_frame(&channels)).await.unwrap();
+
+        let subset = SubsetRcChannels {
+            first_channel: 0,
+            resolution: SubsetResolution::Bits11,
+            values: vec![1500],
+        };
+        serial.send_packet(&encode_subset_rc_channels_frame(&subset).unwrap()).await.unwrap();
+        serial.select_model(7).await.unwrap();
+        serial.bind().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(1)).await;
+
+        channels[0] = 1500;
+        assert_eq!(module.channels(), Some(channels));
+        assert_eq!(module.model_id(), Some(7));
+        let stats = module.stats();
+        assert_eq!(stats.rc_frames, 2);
+        assert_eq!(stats.binds, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_sends_telemetry() {
+        let (mut serial, _module) = VirtualModule::duplex();
+
+        let mut link = None;
+        let mut battery = None;
+        let mut gps = None;
+        let mut attitude = None;
+        let mut flight_mode = None;
+        while link.is_none() || battery.is_none() || gps.is_none() || attitude.is_none() || flight_mode.is_none() {
+            let frame = serial.recv_frame().await.unwrap();
+            match frame.frame_type {
+                CRSF_FRAMETYPE_LINK_STATISTICS => link = decode_link_statistics(&frame.payload).ok(),
+                CRSF_FRAMETYPE_BATTERY_SENSOR => battery = decode_battery_sensor(&frame.payload).ok(),
+                CRSF_FRAMETYPE_GPS => gps = decode_gps(&frame.payload).ok(),
+                CRSF_FRAMETYPE_ATTITUDE => attitude = decode_attitude(&frame.payload).ok(),
+                CRSF_FRAMETYPE_FLIGHT_MODE => flight_mode = decode_flight_mode(&frame.payload).ok(),
+                other => panic!("unexpected frame type 0x{:02X}", other),
+            }
+        }
+        assert_eq!(link.unwrap().uplink_lq, 100);
+        assert_eq!(battery.unwrap().remaining_percent, 100);
+        assert_eq!(gps.unwrap().satellites, 12);
+        assert_eq!(attitude.unwrap().roll, 0.0);
+        assert_eq!(flight_mode.unwrap(), "ACRO*");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_flight_mode_follows_arm_channel() {
+        let (mut serial, module) = VirtualModule::duplex();
+        module.set_flight_mode("ANGL");
+
+        let mut rc = [CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS];
+        let mut modes = Vec::new();
+        for arm in [CRSF_CHANNEL_VALUE_MAX, 0] {
+            rc[channels::ARM] = arm;
+            serial.send_packet(&encode_rc_channels_frame(&rc)).await.unwrap();
+            // Skip frames sent before the module saw the channels
+            received_types(&mut serial, TELEMETRY_INTERVAL).await;
+            loop {
+                let frame = serial.recv_frame().await.unwrap();
+                if frame.frame_type == CRSF_FRAMETYPE_FLIGHT_MODE {
+                    modes.push(decode_flight_mode(&frame.payload).unwrap());
+                    break;
+                }
+            }
+        }
+        assert_eq!(modes, ["ANGL", "ANGL*"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_link_loss_stops_telemetry() {
+        let (mut serial, module) = VirtualModule::duplex();
+        assert!(!received_types(&mut serial, TELEMETRY_INTERVAL * 2).await.is_empty());
+
+        module.set_link_lost(true);
+        // Frames queued before the loss may still arrive
+        received_types(&mut serial, Duration::from_millis(1)).await;
+        assert!(received_types(&mut serial, TELEMETRY_INTERVAL * 5).await.is_empty());
+
+        module.set_link_lost(false);
+        let types = received_types(&mut serial, TELEMETRY_INTERVAL * 2).await;
+        assert!(types.contains(&CRSF_FRAMETYPE_LINK_STATISTICS));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_corrupted_frames_are_dropped_by_parser() {
+        let (mut serial, module) = VirtualModule::duplex();
+        module.set_link_lost(true);
+        module.corrupt_next_frames(1);
+
+        // The corrupted device info never arrives
+        assert!(serial.ping(Duration::from_millis(50)).await.unwrap().is_none());
+        assert_eq!(serial.rx_crc_errors(), 1);
+        assert_eq!(module.stats().corrupted_frames, 1);
+
+        assert!(serial.ping(Duration::from_millis(50)).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_capture_records_both_directions() {
+        use crate::capture::{CaptureReader, CaptureWriter, Direction};
+        use crate::crsf::encoder::encode_device_ping_frame;
+        use crate::crsf::protocol::CRSF_FRAMETYPE_DEVICE_INFO;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("link.fbcap");
+        let (mut serial, module) = VirtualModule::duplex();
+        serial.set_capture(Some(CaptureWriter::create(&path).unwrap()));
+        module.set_link_lost(true);
+        module.corrupt_next_frames(1);
+
+        assert!(serial.ping(Duration::from_millis(50)).await.unwrap().is_none());
+        assert!(serial.ping(Duration::from_millis(50)).await.unwrap().is_some());
+        serial.flush_capture().unwrap();
+
+        let records: Vec<_> = CaptureReader::open(&path).unwrap().map(|r| r.unwrap()).collect();
+        assert_eq!(serial.captured_frames(), Some(records.len() as u64));
+        assert_eq!(records[0].direction, Direction::Tx);
+        assert_eq!(records[0].bytes, encode_device_ping_frame());
+
+        // The corrupted reply is captured with its CRC status
+        let bad = records.iter().filter(|r| r.direction == Direction::Rx && !r.crc_ok).count();
+        assert!(bad >= 1);
+        assert_eq!(bad as u64, serial.rx_crc_errors());
+
+        let last = records.last().unwrap();
+        assert_eq!((last.direction, last.crc_ok), (Direction::Rx, true));
+        assert_eq!(last.bytes[2], CRSF_FRAMETYPE_DEVICE_INFO);
+        assert!(records.windows(2).all(|pair| pair[0].timestamp <= pair[1].timestamp));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_latency_delays_replies() {
+        let (mut serial, module) = VirtualModule::duplex();
+        module.set_link_lost(true);
+        module.set_latency(Duration::from_millis(30));
+
+        let start = Instant::now();
+        assert!(serial.ping(Duration::from_millis(100)).await.unwrap().is_some());
+        assert!(start.elapsed() >= Duration::from_millis(30));
+
+        assert!(serial.ping(Duration::from_millis(20)).await.unwrap().is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_module_stops_when_serial_is_dropped() {
+        let (serial, module) = VirtualModule::duplex();
+        let state = Arc::downgrade(&module.state);
+        drop(module);
+        drop(serial);
+
+        // The task releases the shared state once it has exited
+        timeout(Duration::from_secs(1), async {
+            while state.upgrade().is_some() {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_pty_connects_by_path() {
+        let module = match VirtualModule::pty() {
+            Ok(module) => module,
+            // No pseudo-terminals in this environment
+            Err(_) => return,
+        };
+        assert!(module.is_pty());
+        assert!(module.device_path().starts_with("/dev/"));
+
+        let mut serial = ElrsSerial::open_with_paths(&[module.device_path()]).unwrap();
+        let device = serial.ping(Duration::from_millis(500)).await.unwrap();
+        assert_eq!(device.unwrap().name, VIRTUAL_MODULE_NAME);
+
+        serial.send_packet(&encode_rc_channels_frame(&[1000; CRSF_NUM_CHANNELS])).await.unwrap();
+        timeout(Duration::from_secs(1), async {
+            while module.channels().is_none() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(module.channels(), Some([1000; CRSF_NUM_CHANNELS]));
+    }
+}
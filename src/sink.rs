@@ -0,0 +1,720 @@
+//! # Output Sinks
+//!
+//! Destinations for encoded CRSF frames.
+//!
+//! This module handles:
+//! - The [`FrameSink`] trait, implemented by [`ElrsSerial`]
+//! - The [`TelemetrySink`] trait for outputs of other protocols (MAVLink,
+//!   LTM, MSP), which must never be handed RC frames
+//! - UDP and TCP sinks (simulators, remote TX modules), and the network
+//!   bridge client ([`BridgeClient`])
+//! - A file sink recording the raw frame stream
+//! - A `uinput` virtual joystick for simulators ([`VirtualJoystick`])
+//! - A null sink for benchmarks and dry runs
+//! - Sending to several sinks at once ([`TeeSink`])
+//!
+//! Extra sinks are configured as `[output] sinks = [...]` entries, parsed by
+//! [`SinkSpec`].
+
+use async_trait::async_trait;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::sync::mpsc;
+use tokio::time::{sleep, Duration};
+use tracing::{info, warn};
+
+use crate::bridge::BridgeClient;
+use crate::config::BridgeConfig;
+use crate::error::{FpvBridgeError, Result};
+use crate::joystick::VirtualJoystick;
+use crate::serial::ElrsSerial;
+
+/// Frames buffered for a TCP sink before new ones are dropped
+pub const TCP_QUEUE_FRAMES: usize = 64;
+
+/// Delay between TCP connection attempts
+pub const TCP_RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Write buffer of a file sink
+const FILE_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Destination for complete CRSF frames
+#[async_trait]
+pub trait FrameSink: Send {
+    /// Send one complete CRSF frame (sync byte through CRC)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the frame could not be sent or was dropped.
+    async fn send_frame(&mut self, frame: &[u8]) -> Result<()>;
+
+    /// Push out buffered frames, e.g. before shutdown
+    async fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Short description for logs (e.g. `udp:127.0.0.1:7777`)
+    fn describe(&self) -> String;
+}
+
+/// Destination for telemetry packets of a protocol other than CRSF
+///
+/// Kept apart from [`FrameSink`] so a ground station or tracker output can
+/// not end up in an `[output]` list and be sent RC frames, and an RC sink
+/// can not be handed MAVLink, LTM or MSP packets.
+#[async_trait]
+pub trait TelemetrySink: Send {
+    /// Send one complete packet of the output's protocol
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the packet could not be sent.
+    async fn send(&mut self, packet: &[u8]) -> Result<()>;
+
+    /// Short description for logs (e.g. `udp:127.0.0.1:14550`)
+    fn describe(&self) -> String;
+}
+
+#[async_trait]
+impl FrameSink for ElrsSerial {
+    async fn send_frame(&mut self, frame: &[u8]) -> Result<()> {
+        self.send_packet(frame).await
+    }
+
+    fn describe(&self) -> String {
+        format!("serial:{}", self.device_path())
+    }
+}
+
+/// Parsed `[output] sinks` entry
+///
+/// | Entry              | Sink                                                       |
+/// |--------------------|------------------------------------------------------------|
+/// | `udp:HOST:PORT`    | One datagram per frame ([`UdpSink`])                       |
+/// | `tcp:HOST:PORT`    | Frame stream, reconnecting ([`TcpSink`])                   |
+/// | `bridge:HOST:PORT` | Tagged, sequenced packets to a `--serve` bridge ([`BridgeClient`]), needs `bridge.secret` |
+/// | `file:PATH`        | Raw frame stream recording ([`FileSink`])                  |
+/// | `joystick`         | `uinput` virtual joystick ([`VirtualJoystick`])            |
+/// | `null`             | Discards frames ([`NullSink`])                             |
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::sink::SinkSpec;
+///
+/// let spec: SinkSpec = "udp:127.0.0.1:7777".parse().unwrap();
+/// assert_eq!(spec, SinkSpec::Udp("127.0.0.1:7777".to_string()));
+/// assert!("udp:localhost".parse::<SinkSpec>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SinkSpec {
+    /// UDP datagrams to `HOST:PORT`
+    Udp(String),
+    /// TCP stream to `HOST:PORT`
+    Tcp(String),
+    /// Network bridge server at `HOST:PORT`
+    Bridge(String),
+    /// Append to a file
+    File(PathBuf),
+    /// Virtual joystick for simulators
+    Joystick,
+    /// Discard
+    Null,
+}
+
+impl FromStr for SinkSpec {
+    type Err = FpvBridgeError;
+
+    fn from_str(spec: &str) -> Result<Self> {
+        let invalid = |reason: &str| FpvBridgeError::Output(format!("Invalid sink '{}': {}", spec, reason));
+        let host_port = |address: &str| -> Result<String> {
+            match address.rsplit_once(':') {
+                Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => Ok(address.to_string()),
+                _ => Err(invalid("expected HOST:PORT")),
+            }
+        };
+
+        match spec {
+            "null" => return Ok(Self::Null),
+            "joystick" => return Ok(Self::Joystick),
+            _ => {}
+        }
+        match spec.split_once(':') {
+            Some(("udp", address)) => Ok(Self::Udp(host_port(address)?)),
+            Some(("tcp", address)) => Ok(Self::Tcp(host_port(address)?)),
+            Some(("bridge", address)) => Ok(Self::Bridge(host_port(address)?)),
+            Some(("file", "")) => Err(invalid("missing path")),
+            Some(("file", path)) => Ok(Self::File(PathBuf::from(path))),
+            _ => Err(invalid("expected udp:HOST:PORT, tcp:HOST:PORT, bridge:HOST:PORT, file:PATH, joystick or null")),
+        }
+    }
+}
+
+impl fmt::Display for SinkSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Udp(address) => write!(f, "udp:{}", address),
+            Self::Tcp(address) => write!(f, "tcp:{}", address),
+            Self::Bridge(address) => write!(f, "bridge:{}", address),
+            Self::File(path) => write!(f, "file:{}", path.display()),
+            Self::Joystick => write!(f, "joystick"),
+            Self::Null => write!(f, "null"),
+        }
+    }
+}
+
+impl SinkSpec {
+    /// Open the sink
+    ///
+    /// # Arguments
+    ///
+    /// * `bridge` - Shared secret for `bridge:` sinks
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Box<dyn FrameSink>>` - Ready-to-use sink
+    ///
+    /// # Errors
+    ///
+    /// Returns `Output` (or `Network`) error if a UDP (or bridge) address
+    /// cannot be resolved, `bridge.secret` is not set for a bridge sink or
+    /// the virtual joystick cannot be created, or
+    /// `Io` error if a file cannot be created. TCP sinks connect in the
+    /// background and never fail to open.
+    pub async fn open(&self, bridge: &BridgeConfig) -> Result<Box<dyn FrameSink>> {
+        Ok(match self {
+            Self::Udp(address) => Box::new(UdpSink::connect(address).await?),
+            Self::Tcp(address) => Box::new(TcpSink::connect(address)),
+            Self::Bridge(address) => Box::new(BridgeClient::connect(address, bridge.key()?).await?),
+            Self::File(path) => Box::new(FileSink::create(path.clone())?),
+            Self::Joystick => Box::new(VirtualJoystick::create()?),
+            Self::Null => Box::new(NullSink::new()),
+        })
+    }
+}
+
+/// Sends each frame as one UDP datagram
+#[derive(Debug)]
+pub struct UdpSink {
+    socket: UdpSocket,
+    address: String,
+}
+
+impl UdpSink {
+    /// Create a socket sending to `address` (`HOST:PORT`)
+    ///
+    /// # Errors
+    ///
+    /// Returns `Output` error if the address cannot be resolved or the
+    /// socket cannot be bound.
+    pub async fn connect(address: &str) -> Result<Self> {
+        let error = |e: std::io::Error| FpvBridgeError::Output(format!("UDP sink {}: {}", address, e));
+
+        let target = tokio::net::lookup_host(address).await.map_err(error)?.next().ok_or_else(|| {
+            FpvBridgeError::Output(format!("UDP sink {}: address did not resolve", address))
+        })?;
+        let local = if target.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+        let socket = UdpSocket::bind(local).await.map_err(error)?;
+        socket.connect(target).await.map_err(error)?;
+
+        Ok(Self {
+            socket,
+            address: address.to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl FrameSink for UdpSink {
+    async fn send_frame(&mut self, frame: &[u8]) -> Result<()> {
+        // Nobody listening shows up as ECONNREFUSED on a later send
+        self.socket
+            .send(frame)
+            .await
+            .map_err(|e| FpvBridgeError::Output(format!("UDP sink {}: {}", self.address, e)))?;
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        format!("udp:{}", self.address)
+    }
+}
+
+/// Streams frames over TCP, reconnecting when the connection drops
+///
+/// Frames are handed to a background task through a queue of
+/// [`TCP_QUEUE_FRAMES`] frames, so a slow or absent peer never blocks the
+/// caller: frames are dropped (with an error) while the queue is full.
+#[derive(Debug)]
+pub struct TcpSink {
+    queue: mpsc::Sender<Vec<u8>>,
+    address: String,
+}
+
+impl TcpSink {
+    /// Start connecting to `address` (`HOST:PORT`) in the background
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn connect(address: &str) -> Self {
+        let (queue, frames) = mpsc::channel(TCP_QUEUE_FRAMES);
+        tokio::spawn(run_tcp_sink(address.to_string(), frames));
+
+        Self {
+            queue,
+            address: address.to_string(),
+        }
+    }
+}
+
+/// Owns the TCP connection: connects, writes queued frames, and reconnects
+/// after errors until the sink is dropped
+async fn run_tcp_sink(address: String, mut frames: mpsc::Receiver<Vec<u8>>) {
+    let mut reported = false;
+    loop {
+        let mut stream = match TcpStream::connect(&address).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                if !reported {
+                    warn!("TCP sink {}: {}, retrying", address, e);
+                    reported = true;
+                }
+                // Frames queued while disconnected are stale by now
+                while frames.try_recv().is_ok() {}
+                if frames.is_closed() {
+                    return;
+                }
+                sleep(TCP_RECONNECT_INTERVAL).await;
+                continue;
+            }
+        };
+        let _ = stream.set_nodelay(true);
+        info!("TCP sink connected to {}", address);
+        reported = false;
+
+        while let Some(frame) = frames.recv().await {
+            if let Err(e) = stream.write_all(&frame).await {
+                warn!("TCP sink {}: {}, reconnecting", address, e);
+                break;
+            }
+        }
+        if frames.is_closed() && frames.is_empty() {
+            return;
+        }
+    }
+}
+
+#[async_trait]
+impl FrameSink for TcpSink {
+    async fn send_frame(&mut self, frame: &[u8]) -> Result<()> {
+        self.queue.try_send(frame.to_vec()).map_err(|e| {
+            FpvBridgeError::Output(match e {
+                mpsc::error::TrySendError::Full(_) => format!("TCP sink {}: queue full, frame dropped", self.address),
+                mpsc::error::TrySendError::Closed(_) => format!("TCP sink {}: closed", self.address),
+            })
+        })
+    }
+
+    fn describe(&self) -> String {
+        format!("tcp:{}", self.address)
+    }
+}
+
+/// Records the raw frame stream to a file
+///
+/// Frames are written back to back, exactly as sent to the module, so the
+/// file can be read with [`FrameParser`](crate::crsf::decoder::FrameParser).
+#[derive(Debug)]
+pub struct FileSink {
+    writer: BufWriter<File>,
+    path: PathBuf,
+}
+
+impl FileSink {
+    /// Create (or truncate) the file at `path`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Io` error if the file cannot be created.
+    pub fn create(path: PathBuf) -> Result<Self> {
+        let file = File::create(&path)?;
+        Ok(Self {
+            writer: BufWriter::with_capacity(FILE_BUFFER_SIZE, file),
+            path,
+        })
+    }
+}
+
+#[async_trait]
+impl FrameSink for FileSink {
+    async fn send_frame(&mut self, frame: &[u8]) -> Result<()> {
+        self.writer.write_all(frame)?;
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        format!("file:{}", self.path.display())
+    }
+}
+
+/// Discards frames, counting them
+#[derive(Debug, Default)]
+pub struct NullSink {
+    frames: u64,
+}
+
+impl NullSink {
+    /// Create a null sink
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Frames discarded so far
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+}
+
+#[async_trait]
+impl FrameSink for NullSink {
+    async fn send_frame(&mut self, _frame: &[u8]) -> Result<()> {
+        self.frames += 1;
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        "null".to_string()
+    }
+}
+
+/// Per-sink counters kept by [`TeeSink`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SinkStats {
+    /// Frames the sink accepted
+    pub frames: u64,
+    /// Frames the sink failed to send
+    pub errors: u64,
+}
+
+impl fmt::Display for SinkStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} frames, {} errors", self.frames, self.errors)
+    }
+}
+
+/// One sink of a [`TeeSink`]
+struct TeeOutput {
+    sink: Box<dyn FrameSink>,
+    stats: SinkStats,
+    /// Whether the last send failed (failures are logged once per outage)
+    failing: bool,
+}
+
+/// Sends every frame to several sinks
+///
+/// A failing sink does not stop the others: every sink gets every frame,
+/// and the first error is returned. Each sink's first failure (and its
+/// recovery) is logged.
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::sink::{FrameSink, NullSink, TeeSink};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> fpv_bridge::error::Result<()> {
+/// let mut tee = TeeSink::new();
+/// tee.push(Box::new(NullSink::new()));
+/// tee.push(Box::new(NullSink::new()));
+///
+/// tee.send_frame(&[0xC8, 0x02, 0x28, 0x00]).await?;
+/// assert_eq!(tee.describe(), "null, null");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct TeeSink {
+    outputs: Vec<TeeOutput>,
+}
+
+impl fmt::Debug for TeeSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TeeSink")
+            .field("sinks", &self.describe())
+            .finish()
+    }
+}
+
+impl TeeSink {
+    /// Create a tee with no sinks
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open every spec into one tee
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error from [`SinkSpec::open`].
+    pub async fn open(specs: &[SinkSpec], bridge: &BridgeConfig) -> Result<Self> {
+        let mut tee = Self::new();
+        for spec in specs {
+            tee.push(spec.open(bridge).await?);
+        }
+        Ok(tee)
+    }
+
+    /// Add a sink
+    pub fn push(&mut self, sink: Box<dyn FrameSink>) {
+        self.outputs.push(TeeOutput {
+            sink,
+            stats: SinkStats::default(),
+            failing: false,
+        });
+    }
+
+    /// Number of sinks
+    pub fn len(&self) -> usize {
+        self.outputs.len()
+    }
+
+    /// Whether the tee has no sinks
+    pub fn is_empty(&self) -> bool {
+        self.outputs.is_empty()
+    }
+
+    /// Description and counters of every sink, in insertion order
+    pub fn stats(&self) -> Vec<(String, SinkStats)> {
+        self.outputs
+            .iter()
+            .map(|output| (output.sink.describe(), output.stats))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl FrameSink for TeeSink {
+    async fn send_frame(&mut self, frame: &[u8]) -> Result<()> {
+        let mut first_error = None;
+        for output in &mut self.outputs {
+            match output.sink.send_frame(frame).await {
+                Ok(()) => {
+                    output.stats.frames += 1;
+                    if output.failing {
+                        info!("Output {} recovered", output.sink.describe());
+                        output.failing = false;
+                    }
+                }
+                Err(e) => {
+                    output.stats.errors += 1;
+                    if !output.failing {
+                        warn!("Output {} failed: {}", output.sink.describe(), e);
+                        output.failing = true;
+                    }
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        let mut first_error = None;
+        for output in &mut self.outputs {
+            if let Err(e) = output.sink.flush().await {
+                first_error.get_or_insert(e);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    fn describe(&self) -> String {
+        self.outputs
+            .iter()
+            .map(|output| output.sink.describe())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crsf::decoder::FrameParser;
+    use crate::crsf::encoder::{encode_bind_frame, encode_model_select_frame};
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    /// Sink that fails every send
+    struct FailingSink;
+
+    #[async_trait]
+    impl FrameSink for FailingSink {
+        async fn send_frame(&mut self, _frame: &[u8]) -> Result<()> {
+            Err(FpvBridgeError::Output("broken".to_string()))
+        }
+
+        fn describe(&self) -> String {
+            "failing".to_string()
+        }
+    }
+
+    #[test]
+    fn test_parse_sink_specs() {
+        assert_eq!("null".parse::<SinkSpec>().unwrap(), SinkSpec::Null);
+        assert_eq!("joystick".parse::<SinkSpec>().unwrap(), SinkSpec::Joystick);
+        assert_eq!(
+            "tcp:sim.local:9000".parse::<SinkSpec>().unwrap(),
+            SinkSpec::Tcp("sim.local:9000".to_string())
+        );
+        assert_eq!(
+            "udp:[::1]:7777".parse::<SinkSpec>().unwrap(),
+            SinkSpec::Udp("[::1]:7777".to_string())
+        );
+        assert_eq!(
+            "file:/tmp/flight.crsf".parse::<SinkSpec>().unwrap(),
+            SinkSpec::File(PathBuf::from("/tmp/flight.crsf"))
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_sink_specs() {
+        for spec in ["", "serial", "udp:", "udp::7777", "tcp:host:port", "udp:host:70000", "file:", "http://x", "joystick:0"] {
+            let err = spec.parse::<SinkSpec>().unwrap_err();
+            assert!(err.to_string().contains("Invalid sink"), "{}: {}", spec, err);
+        }
+    }
+
+    #[test]
+    fn test_sink_spec_display_round_trip() {
+        for spec in ["null", "udp:127.0.0.1:7777", "tcp:localhost:9000", "bridge:pi.local:7777", "file:rec/flight.crsf", "joystick"] {
+            assert_eq!(spec.parse::<SinkSpec>().unwrap().to_string(), spec);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bridge_sink_needs_secret() {
+        let spec = SinkSpec::Bridge("127.0.0.1:7777".to_string());
+        assert!(spec.open(&BridgeConfig::default()).await.is_err());
+
+        let bridge = BridgeConfig {
+            secret: "correct horse battery staple".to_string(),
+            ..BridgeConfig::default()
+        };
+        assert_eq!(spec.open(&bridge).await.unwrap().describe(), "bridge:127.0.0.1:7777");
+    }
+
+    #[tokio::test]
+    async fn test_udp_sink_sends_one_datagram_per_frame() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let address = receiver.local_addr().unwrap().to_string();
+
+        let mut sink = SinkSpec::Udp(address.clone()).open(&BridgeConfig::default()).await.unwrap();
+        sink.send_frame(&encode_bind_frame()).await.unwrap();
+        sink.send_frame(&encode_model_select_frame(3)).await.unwrap();
+        assert_eq!(sink.describe(), format!("udp:{}", address));
+
+        let mut buf = [0u8; 64];
+        let n = receiver.recv(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], encode_bind_frame().as_slice());
+        let n = receiver.recv(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], encode_model_select_frame(3).as_slice());
+    }
+
+    #[tokio::test]
+    async fn test_tcp_sink_streams_frames() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        let mut sink = TcpSink::connect(&address);
+        let (mut peer, _) = listener.accept().await.unwrap();
+        sink.send_frame(&encode_bind_frame()).await.unwrap();
+        sink.send_frame(&encode_model_select_frame(7)).await.unwrap();
+
+        let expected = [encode_bind_frame(), encode_model_select_frame(7)].concat();
+        let mut received = vec![0u8; expected.len()];
+        peer.read_exact(&mut received).await.unwrap();
+        assert_eq!(received, expected);
+    }
+
+    #[tokio::test]
+    async fn test_tcp_sink_drops_frames_when_queue_full() {
+        // Nothing listens on the discard port: the queue fills up
+        let mut sink = TcpSink::connect("127.0.0.1:9");
+        for _ in 0..TCP_QUEUE_FRAMES {
+            sink.send_frame(&encode_bind_frame()).await.unwrap();
+        }
+
+        let err = sink.send_frame(&encode_bind_frame()).await.unwrap_err();
+        assert!(err.to_string().contains("queue full"));
+    }
+
+    #[tokio::test]
+    async fn test_file_sink_records_parseable_stream() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("flight.crsf");
+
+        let mut sink = FileSink::create(path.clone()).unwrap();
+        sink.send_frame(&encode_bind_frame()).await.unwrap();
+        sink.send_frame(&encode_model_select_frame(2)).await.unwrap();
+        sink.flush().await.unwrap();
+
+        let mut parser = FrameParser::new();
+        parser.push(&std::fs::read(&path).unwrap());
+        assert_eq!(parser.next_frame().unwrap().payload.len(), 5);
+        assert_eq!(parser.next_frame().unwrap().payload[4], 2);
+        assert!(parser.next_frame().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_file_sink_create_error() {
+        let err = FileSink::create(PathBuf::from("/nonexistent/dir/flight.crsf")).unwrap_err();
+        assert!(matches!(err, FpvBridgeError::Io(_)));
+    }
+
+    #[tokio::test]
+    async fn test_null_sink_counts_frames() {
+        let mut sink = NullSink::new();
+        sink.send_frame(&encode_bind_frame()).await.unwrap();
+        sink.send_frame(&encode_bind_frame()).await.unwrap();
+        assert_eq!(sink.frames(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_tee_sends_to_all_despite_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tee.crsf");
+
+        let mut tee = TeeSink::new();
+        tee.push(Box::new(FailingSink));
+        tee.push(Box::new(FileSink::create(path.clone()).unwrap()));
+        assert_eq!(tee.len(), 2);
+
+        let err = tee.send_frame(&encode_bind_frame()).await.unwrap_err();
+        assert!(err.to_string().contains("broken"));
+        tee.flush().await.unwrap();
+
+        // The file still got the frame
+        assert_eq!(std::fs::read(&path).unwrap(), encode_bind_frame());
+
+        let stats = tee.stats();
+        assert_eq!(stats[0], ("failing".to_string(), SinkStats { frames: 0, errors: 1 }));
+        assert_eq!(stats[1].1, SinkStats { frames: 1, errors: 0 });
+    }
+
+    #[tokio::test]
+    async fn test_tee_open_specs() {
+        let tee = TeeSink::open(&[SinkSpec::Null, SinkSpec::Null], &BridgeConfig::default()).await.unwrap();
+        assert_eq!(tee.describe(), "null, null");
+        assert!(TeeSink::new().is_empty());
+    }
+}
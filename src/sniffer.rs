@@ -0,0 +1,415 @@
+//! # Passive CRSF Sniffer
+//!
+//! Watches CRSF traffic on a UART without taking part in it
+//! (`fpv-bridge sniff PORT`): between a radio and a TX module, or between
+//! a receiver and a flight controller.
+//!
+//! [`Sniffer`] frames the byte stream with a [`FrameParser`] and keeps the
+//! latest RC channels, the latest frame of every type (decoded with
+//! [`describe_frame`]) and per-type frame rates. [`run_sniffer`] feeds it
+//! from anything readable and redraws the statistics in the terminal. It
+//! only ever gets the read half of the port ([`open_line`]), so nothing can
+//! be written to the line.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::Write;
+
+use tokio::io::{AsyncRead, AsyncReadExt, ReadHalf};
+use tokio::time::{interval, Duration, Instant, MissedTickBehavior};
+
+use crate::capture::{describe_frame, frame_type_name};
+use crate::crsf::decoder::{
+    decode_frame, decode_rc_channels_payload, decode_subset_rc_channels_payload, FrameParser,
+};
+use crate::crsf::protocol::{
+    crsf_value_to_us, RcChannels, CRSF_CHANNEL_VALUE_CENTER, CRSF_FRAMETYPE_RC_CHANNELS_PACKED,
+    CRSF_FRAMETYPE_SUBSET_RC_CHANNELS_PACKED, CRSF_NUM_CHANNELS,
+};
+use crate::error::{FpvBridgeError, Result};
+use crate::serial::ElrsSerial;
+
+/// How often frame rates are recomputed
+pub const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// How often the terminal display is redrawn
+pub const REDRAW_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Read buffer size
+const READ_BUFFER_SIZE: usize = 256;
+
+/// Counters and last frame of one frame type
+#[derive(Debug, Clone, Default)]
+struct TypeStats {
+    /// Frames seen since the start
+    count: u64,
+    /// Frames seen in the current rate window
+    window: u64,
+    /// Frame rate over the last complete window
+    rate_hz: f64,
+    /// Bytes of the latest frame
+    last: Vec<u8>,
+}
+
+/// Decoded view of a passively observed CRSF stream
+#[derive(Debug)]
+pub struct Sniffer {
+    parser: FrameParser,
+    started: Instant,
+    bytes: u64,
+    /// Frames that passed the CRC but did not decode
+    decode_errors: u64,
+    types: BTreeMap<u8, TypeStats>,
+    /// Latest RC channels, with subset frames applied
+    channels: Option<(RcChannels, Instant)>,
+    window_start: Instant,
+}
+
+impl Sniffer {
+    /// Start watching at `now`
+    pub fn new(now: Instant) -> Self {
+        Self {
+            parser: FrameParser::new(),
+            started: now,
+            bytes: 0,
+            decode_errors: 0,
+            types: BTreeMap::new(),
+            channels: None,
+            window_start: now,
+        }
+    }
+
+    /// Feed bytes read from the line at `now`
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - Number of valid frames completed by these bytes
+    pub fn push(&mut self, data: &[u8], now: Instant) -> usize {
+        self.bytes += data.len() as u64;
+        self.parser.push(data);
+
+        let mut frames = 0;
+        while let Some(raw) = self.parser.next_raw_frame() {
+            // Bad CRCs are counted by the parser
+            if !raw.crc_ok {
+                continue;
+            }
+            let Ok(frame) = decode_frame(&raw.bytes) else {
+                self.decode_errors += 1;
+                continue;
+            };
+            frames += 1;
+            let stats = self.types.entry(frame.frame_type).or_default();
+            stats.count += 1;
+            stats.window += 1;
+            stats.last = raw.bytes;
+
+            let channels = match frame.frame_type {
+                CRSF_FRAMETYPE_RC_CHANNELS_PACKED => decode_rc_channels_payload(&frame.payload).map(Some),
+                CRSF_FRAMETYPE_SUBSET_RC_CHANNELS_PACKED => {
+                    decode_subset_rc_channels_payload(&frame.payload).map(|subset| {
+                        let mut channels = self.channels().unwrap_or([CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS]);
+                        subset.apply_to(&mut channels);
+                        Some(channels)
+                    })
+                }
+                _ => Ok(None),
+            };
+            match channels {
+                Ok(Some(channels)) => self.channels = Some((channels, now)),
+                Ok(None) => {}
+                Err(_) => self.decode_errors += 1,
+            }
+        }
+        frames
+    }
+
+    /// Close the current rate window at `now`, updating the frame rates
+    pub fn update_rates(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.window_start).as_secs_f64();
+        if elapsed <= 0.0 {
+            return;
+        }
+        for stats in self.types.values_mut() {
+            stats.rate_hz = stats.window as f64 / elapsed;
+            stats.window = 0;
+        }
+        self.window_start = now;
+    }
+
+    /// Latest RC channels, if any RC frame was seen
+    pub fn channels(&self) -> Option<RcChannels> {
+        self.channels.map(|(channels, _)| channels)
+    }
+
+    /// Frames seen and last frame rate of `frame_type`
+    pub fn frame_stats(&self, frame_type: u8) -> Option<(u64, f64)> {
+        self.types.get(&frame_type).map(|stats| (stats.count, stats.rate_hz))
+    }
+
+    /// Frames dropped because of a bad CRC
+    pub fn crc_errors(&self) -> u64 {
+        self.parser.crc_errors()
+    }
+
+    /// Frames with a valid CRC whose payload did not decode
+    pub fn decode_errors(&self) -> u64 {
+        self.decode_errors
+    }
+
+    /// Bytes received
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Text view of the statistics, the RC channels and the latest frame
+    /// of every type
+    pub fn render(&self, now: Instant) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "{:.1}s, {} bytes, {} CRC errors, {} decode errors",
+            now.duration_since(self.started).as_secs_f64(),
+            self.bytes,
+            self.crc_errors(),
+            self.decode_errors
+        );
+
+        let _ = writeln!(out, "\n{:<30} {:>8} {:>9}", "Frame type", "Count", "Rate");
+        for (&frame_type, stats) in &self.types {
+            let name = format!("{} (0x{:02X})", frame_type_name(frame_type), frame_type);
+            let _ = writeln!(out, "{:<30} {:>8} {:>7.1}Hz", name, stats.count, stats.rate_hz);
+        }
+
+        if let Some((channels, at)) = self.channels {
+            let age = now.duration_since(at).as_secs_f64() * 1000.0;
+            let _ = writeln!(out, "\nRC channels ({:.0}ms ago)", age);
+            for (row, chunk) in channels.chunks(4).enumerate() {
+                let line: Vec<String> = chunk
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &value)| format!("ch{:<2} {:>4} {:>4}us", row * 4 + i + 1, value, crsf_value_to_us(value)))
+                    .collect();
+                let _ = writeln!(out, "  {}", line.join("   "));
+            }
+        }
+
+        let _ = writeln!(out, "\nLatest frames");
+        for stats in self.types.values() {
+            let _ = writeln!(out, "  {}", describe_frame(&stats.last));
+        }
+        out
+    }
+}
+
+/// Open a serial port for sniffing (8N1, no flow control)
+///
+/// Only the read half is returned; the write half is dropped so the
+/// sniffer cannot disturb the line.
+///
+/// # Arguments
+///
+/// * `path` - Device path (e.g., "/dev/ttyUSB0")
+/// * `baud_rate` - Line speed (420,000 for CRSF)
+///
+/// # Errors
+///
+/// Returns `Serial` error if the port cannot be opened.
+pub fn open_line(path: &str, baud_rate: u32) -> Result<ReadHalf<tokio_serial::SerialStream>> {
+    let port = ElrsSerial::open_port(path, baud_rate)?;
+    let (reader, _writer) = tokio::io::split(port);
+    Ok(reader)
+}
+
+/// Sniff `reader` until it closes or Ctrl+C, redrawing the statistics on
+/// `out`
+///
+/// With `terminal` set the display is redrawn in place every
+/// [`REDRAW_INTERVAL`]; otherwise a snapshot is written every
+/// [`RATE_WINDOW`]. A final snapshot is written on exit.
+///
+/// # Errors
+///
+/// Returns `Serial` error if reading fails, or `Io` error if the output
+/// cannot be written.
+pub async fn run_sniffer<R, W>(mut reader: R, title: &str, mut out: W, terminal: bool) -> Result<Sniffer>
+where
+    R: AsyncRead + Unpin,
+    W: Write,
+{
+    let mut sniffer = Sniffer::new(Instant::now());
+    let mut rates = interval(RATE_WINDOW);
+    let mut redraw = interval(if terminal { REDRAW_INTERVAL } else { RATE_WINDOW });
+    rates.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    redraw.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut buf = [0u8; READ_BUFFER_SIZE];
+
+    let draw = |sniffer: &Sniffer, out: &mut W| -> Result<()> {
+        if terminal {
+            // Home and clear, then draw
+            write!(out, "\x1b[H\x1b[2J")?;
+        }
+        write!(out, "{}\n{}", title, sniffer.render(Instant::now()))?;
+        if !terminal {
+            writeln!(out)?;
+        }
+        Ok(out.flush()?)
+    };
+
+    loop {
+        tokio::select! {
+            read = reader.read(&mut buf) => {
+                let n = read.map_err(|e| FpvBridgeError::Serial(format!("Failed to read: {}", e)))?;
+                if n == 0 {
+                    break;
+                }
+                sniffer.push(&buf[..n], Instant::now());
+            }
+            _ = rates.tick() => sniffer.update_rates(Instant::now()),
+            _ = redraw.tick() => draw(&sniffer, &mut out)?,
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    sniffer.update_rates(Instant::now());
+    draw(&sniffer, &mut out)?;
+    Ok(sniffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crsf::encoder::{encode_link_statistics_frame, encode_rc_channels_frame, encode_subset_rc_channels_frame};
+    use crate::crsf::protocol::{
+        LinkStatistics, SubsetRcChannels, SubsetResolution, CRSF_ADDRESS_RADIO_TRANSMITTER, CRSF_FRAMETYPE_LINK_STATISTICS,
+    };
+    use tokio::io::AsyncWriteExt;
+
+    fn rc_frame(first: u16) -> Vec<u8> {
+        let mut channels = [CRSF_CHANNEL_VALUE_CENTER; CRSF_NUM_CHANNELS];
+        channels[0] = first;
+        encode_rc_channels_frame(&channels)
+    }
+
+    fn link_stats_frame() -> Vec<u8> {
+        encode_link_statistics_frame(&LinkStatistics {
+            uplink_rssi_1: 40,
+            uplink_rssi_2: 41,
+            uplink_lq: 99,
+            uplink_snr: 10,
+            active_antenna: 0,
+            rf_mode: 7,
+            uplink_tx_power: 2,
+            downlink_rssi: 45,
+            downlink_lq: 100,
+            downlink_snr: 6,
+        })
+    }
+
+    #[test]
+    fn test_counts_frames_and_errors() {
+        let start = Instant::now();
+        let mut sniffer = Sniffer::new(start);
+
+        let mut bad = rc_frame(500);
+        bad[10] ^= 0xFF;
+        let mut stream = vec![0x00, 0x42];
+        stream.extend(rc_frame(172));
+        stream.extend(&bad);
+        stream.extend(link_stats_frame());
+
+        // Split mid-frame like a real read
+        assert_eq!(sniffer.push(&stream[..20], start), 0);
+        assert_eq!(sniffer.push(&stream[20..], start), 2);
+
+        assert_eq!(sniffer.frame_stats(CRSF_FRAMETYPE_RC_CHANNELS_PACKED).unwrap().0, 1);
+        assert_eq!(sniffer.frame_stats(CRSF_FRAMETYPE_LINK_STATISTICS).unwrap().0, 1);
+        assert_eq!(sniffer.crc_errors(), 1);
+        assert_eq!(sniffer.bytes(), stream.len() as u64);
+        assert_eq!(sniffer.channels().unwrap()[0], 172);
+        assert_eq!(sniffer.types[&CRSF_FRAMETYPE_LINK_STATISTICS].last, link_stats_frame());
+    }
+
+    #[test]
+    fn test_subset_frames_update_channels() {
+        let start = Instant::now();
+        let mut sniffer = Sniffer::new(start);
+        sniffer.push(&rc_frame(172), start);
+
+        let subset = SubsetRcChannels {
+            first_channel: 2,
+            resolution: SubsetResolution::Bits11,
+            values: vec![1811],
+        };
+        sniffer.push(&encode_subset_rc_channels_frame(&subset).unwrap(), start);
+
+        let channels = sniffer.channels().unwrap();
+        assert_eq!((channels[0], channels[2]), (172, 1811));
+        assert_eq!(sniffer.frame_stats(CRSF_FRAMETYPE_SUBSET_RC_CHANNELS_PACKED).unwrap().0, 1);
+    }
+
+    #[test]
+    fn test_module_frames_keep_address_byte() {
+        let start = Instant::now();
+        let mut sniffer = Sniffer::new(start);
+        let mut frame = link_stats_frame();
+        frame[0] = CRSF_ADDRESS_RADIO_TRANSMITTER;
+
+        assert_eq!(sniffer.push(&frame, start), 1);
+        assert_eq!(sniffer.types[&CRSF_FRAMETYPE_LINK_STATISTICS].last, frame);
+        assert!(sniffer.render(start).contains("  EA LINK_STATISTICS(0x14)"));
+    }
+
+    #[test]
+    fn test_rates_per_window() {
+        let start = Instant::now();
+        let mut sniffer = Sniffer::new(start);
+        for i in 0..250 {
+            sniffer.push(&rc_frame(172), start + Duration::from_millis(i * 4));
+        }
+        for _ in 0..10 {
+            sniffer.push(&link_stats_frame(), start);
+        }
+        sniffer.update_rates(start + Duration::from_secs(1));
+        assert_eq!(sniffer.frame_stats(CRSF_FRAMETYPE_RC_CHANNELS_PACKED), Some((250, 250.0)));
+        assert_eq!(sniffer.frame_stats(CRSF_FRAMETYPE_LINK_STATISTICS), Some((10, 10.0)));
+
+        // Nothing in the next window
+        sniffer.update_rates(start + Duration::from_secs(2));
+        assert_eq!(sniffer.frame_stats(CRSF_FRAMETYPE_RC_CHANNELS_PACKED), Some((250, 0.0)));
+    }
+
+    #[test]
+    fn test_render() {
+        let start = Instant::now();
+        let mut sniffer = Sniffer::new(start);
+        sniffer.push(&rc_frame(172), start);
+        sniffer.push(&link_stats_frame(), start);
+        sniffer.update_rates(start + Duration::from_secs(1));
+
+        let text = sniffer.render(start + Duration::from_secs(1));
+        assert!(text.starts_with("1.0s, 40 bytes, 0 CRC errors"), "{}", text);
+        assert!(text.contains("RC_CHANNELS_PACKED (0x16)"), "{}", text);
+        assert!(text.contains("RC channels (1000ms ago)"), "{}", text);
+        assert!(text.contains("ch1   172  988us"), "{}", text);
+        assert!(text.contains("ch16 1024 1520us"), "{}", text);
+        assert!(text.contains("LINK_STATISTICS(0x14) len=14 crc=ok up: rssi=-40/-41dBm lq=99%"), "{}", text);
+    }
+
+    #[tokio::test]
+    async fn test_run_until_stream_closes() {
+        let (mut line, reader) = tokio::io::duplex(256);
+        line.write_all(&rc_frame(172)).await.unwrap();
+        line.write_all(&link_stats_frame()).await.unwrap();
+        drop(line);
+
+        let mut out = Vec::new();
+        let sniffer = run_sniffer(reader, "test line", &mut out, false).await.unwrap();
+        assert_eq!(sniffer.frame_stats(CRSF_FRAMETYPE_RC_CHANNELS_PACKED).unwrap().0, 1);
+
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("test line\n"));
+        assert!(!text.contains('\x1b'));
+        assert!(text.contains("RC channels"));
+    }
+}
@@ -0,0 +1,228 @@
+//! # Light Telemetry (LTM) Encoder
+//!
+//! LTM is the one-way telemetry of INAV, read by antenna trackers and
+//! ground OSDs. Every frame is `$T`, a function byte, a fixed-size
+//! little-endian payload and the XOR of the payload bytes:
+//!
+//! | Frame | Payload | Content                                              |
+//! |-------|---------|------------------------------------------------------|
+//! | `G`   | 14      | Latitude, longitude, ground speed, altitude, fix     |
+//! | `A`   | 6       | Pitch, roll, heading                                 |
+//! | `S`   | 7       | Battery voltage and use, RSSI, arming, flight mode   |
+
+use crate::crsf::protocol::{Attitude, BatterySensor, GpsData};
+use crate::mavlink::GPS_MIN_SATELLITES_3D;
+
+/// Start of every LTM frame
+pub const LTM_HEADER: [u8; 2] = [b'$', b'T'];
+
+/// GPS frame function byte
+pub const LTM_GPS_FRAME: u8 = b'G';
+
+/// Attitude frame function byte
+pub const LTM_ATTITUDE_FRAME: u8 = b'A';
+
+/// Status frame function byte
+pub const LTM_STATUS_FRAME: u8 = b'S';
+
+/// LTM flight modes (bits 2-7 of the status byte)
+pub mod mode {
+    pub const MANUAL: u8 = 0;
+    pub const RATE: u8 = 1;
+    pub const ANGLE: u8 = 2;
+    pub const HORIZON: u8 = 3;
+    pub const STABILIZED: u8 = 5;
+    pub const GPS_HOLD: u8 = 9;
+    pub const WAYPOINTS: u8 = 10;
+    pub const RTH: u8 = 13;
+    pub const LAND: u8 = 15;
+    pub const CRUISE: u8 = 18;
+}
+
+/// GPS fix byte value: receiver present but no fix
+const LTM_FIX_NONE: u8 = 1;
+
+/// GPS fix byte value: 3D fix
+const LTM_FIX_3D: u8 = 3;
+
+/// Status byte bit: armed
+const LTM_STATUS_ARMED: u8 = 0x01;
+
+/// Status byte bit: failsafe active
+const LTM_STATUS_FAILSAFE: u8 = 0x02;
+
+/// Frame `function` around `payload` with the XOR checksum
+fn frame(function: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(payload.len() + 4);
+    frame.extend_from_slice(&LTM_HEADER);
+    frame.push(function);
+    frame.extend_from_slice(payload);
+    frame.push(payload.iter().fold(0, |checksum, byte| checksum ^ byte));
+    frame
+}
+
+/// Encode a GPS (`G`) frame
+///
+/// CRSF has no fix type, so a position from [`GPS_MIN_SATELLITES_3D`]
+/// satellites on is reported as a 3D fix.
+///
+/// # Arguments
+///
+/// * `gps` - GPS telemetry
+///
+/// # Returns
+///
+/// * `Vec<u8>` - 18-byte frame
+pub fn gps_frame(gps: &GpsData) -> Vec<u8> {
+    let fix = if gps.satellites >= GPS_MIN_SATELLITES_3D { LTM_FIX_3D } else { LTM_FIX_NONE };
+    let ground_speed = (gps.ground_speed / 3.6).round().clamp(0.0, f32::from(u8::MAX)) as u8;
+
+    let mut payload = Vec::with_capacity(14);
+    payload.extend_from_slice(&((gps.latitude * 10_000_000.0).round() as i32).to_le_bytes());
+    payload.extend_from_slice(&((gps.longitude * 10_000_000.0).round() as i32).to_le_bytes());
+    payload.push(ground_speed);
+    payload.extend_from_slice(&(i32::from(gps.altitude) * 100).to_le_bytes());
+    payload.push((gps.satellites.min(63) << 2) | fix);
+    frame(LTM_GPS_FRAME, &payload)
+}
+
+/// Encode an attitude (`A`) frame
+///
+/// # Arguments
+///
+/// * `attitude` - Attitude telemetry (radians)
+///
+/// # Returns
+///
+/// * `Vec<u8>` - 10-byte frame with pitch and roll in degrees and the
+///   heading in degrees, 0-359
+pub fn attitude_frame(attitude: &Attitude) -> Vec<u8> {
+    let degrees = |radians: f32| radians.to_degrees().round() as i16;
+
+    let mut payload = Vec::with_capacity(6);
+    payload.extend_from_slice(&degrees(attitude.pitch).to_le_bytes());
+    payload.extend_from_slice(&degrees(attitude.roll).to_le_bytes());
+    payload.extend_from_slice(&degrees(attitude.yaw).rem_euclid(360).to_le_bytes());
+    frame(LTM_ATTITUDE_FRAME, &payload)
+}
+
+/// Encode a status (`S`) frame
+///
+/// # Arguments
+///
+/// * `battery` - Battery telemetry, zero voltage and use without it
+/// * `rssi` - Link quality, 0-254
+/// * `armed` - Whether the drone is armed
+/// * `failsafe` - Whether the flight controller is in failsafe
+/// * `flight_mode` - LTM flight mode ([`mode`], see [`flight_mode`])
+///
+/// # Returns
+///
+/// * `Vec<u8>` - 11-byte frame
+pub fn status_frame(battery: Option<&BatterySensor>, rssi: u8, armed: bool, failsafe: bool, flight_mode: u8) -> Vec<u8> {
+    let voltage = battery.map_or(0, |battery| (battery.voltage * 1000.0).round().clamp(0.0, f32::from(u16::MAX)) as u16);
+    let used = battery.map_or(0, |battery| battery.capacity_used.min(u32::from(u16::MAX)) as u16);
+    let mut status = flight_mode << 2;
+    if armed {
+        status |= LTM_STATUS_ARMED;
+    }
+    if failsafe {
+        status |= LTM_STATUS_FAILSAFE;
+    }
+
+    let mut payload = Vec::with_capacity(7);
+    payload.extend_from_slice(&voltage.to_le_bytes());
+    payload.extend_from_slice(&used.to_le_bytes());
+    payload.push(rssi.min(254));
+    // No airspeed sensor in CRSF telemetry
+    payload.push(0);
+    payload.push(status);
+    frame(LTM_STATUS_FRAME, &payload)
+}
+
+/// LTM flight mode for a CRSF flight mode name
+///
+/// Acro and air mode are rate mode, like INAV reports them; unknown or
+/// missing modes too.
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::tracker::ltm::{flight_mode, mode};
+///
+/// assert_eq!(flight_mode(Some("ANGL*")), mode::ANGLE);
+/// assert_eq!(flight_mode(Some("RTH")), mode::RTH);
+/// assert_eq!(flight_mode(None), mode::RATE);
+/// ```
+pub fn flight_mode(flight_mode: Option<&str>) -> u8 {
+    match flight_mode.unwrap_or_default().trim_end_matches('*') {
+        "MANU" => mode::MANUAL,
+        "ANGL" => mode::ANGLE,
+        "HOR" => mode::HORIZON,
+        "STAB" => mode::STABILIZED,
+        "HOLD" => mode::GPS_HOLD,
+        "WP" => mode::WAYPOINTS,
+        "RTH" => mode::RTH,
+        "LAND" => mode::LAND,
+        "CRUZ" | "3CRS" => mode::CRUISE,
+        _ => mode::RATE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gps(satellites: u8) -> GpsData {
+        GpsData { latitude: 50.45, longitude: -30.5, ground_speed: 36.0, heading: 90.0, altitude: 120, satellites }
+    }
+
+    #[test]
+    fn test_gps_frame() {
+        // 50.45°, -30.5°, 10 m/s, 120 m, 9 satellites with a 3D fix
+        assert_eq!(
+            gps_frame(&gps(9)),
+            [
+                0x24, 0x54, 0x47, 0x20, 0x0F, 0x12, 0x1E, 0xC0, 0x11, 0xD2, 0xED, 0x0A, 0xE0, 0x2E, 0x00, 0x00,
+                0x27, 0x2E
+            ]
+        );
+        // Too few satellites: no fix
+        assert_eq!(gps_frame(&gps(3))[16], (3 << 2) | LTM_FIX_NONE);
+    }
+
+    #[test]
+    fn test_attitude_frame() {
+        // Pitch 14°, roll -29°, heading 57°
+        let attitude = Attitude { pitch: 0.25, roll: -0.5, yaw: 1.0 };
+        assert_eq!(attitude_frame(&attitude), [0x24, 0x54, 0x41, 0x0E, 0x00, 0xE3, 0xFF, 0x39, 0x00, 0x2B]);
+
+        // Negative yaw becomes a compass heading
+        let frame = attitude_frame(&Attitude { pitch: 0.0, roll: 0.0, yaw: -1.0 });
+        assert_eq!(i16::from_le_bytes([frame[7], frame[8]]), 303);
+    }
+
+    #[test]
+    fn test_status_frame() {
+        let battery = BatterySensor { voltage: 16.42, current: 12.5, capacity_used: 850, remaining_percent: 64 };
+        // 16.42 V, 850 mAh, RSSI 229, armed in angle mode
+        assert_eq!(
+            status_frame(Some(&battery), 229, true, false, mode::ANGLE),
+            [0x24, 0x54, 0x53, 0x24, 0x40, 0x52, 0x03, 0xE5, 0x00, 0x09, 0xD9]
+        );
+
+        let frame = status_frame(None, 0, false, true, mode::RATE);
+        assert_eq!(frame[3..7], [0, 0, 0, 0]);
+        assert_eq!(frame[9], (mode::RATE << 2) | LTM_STATUS_FAILSAFE);
+    }
+
+    #[test]
+    fn test_flight_mode() {
+        assert_eq!(flight_mode(Some("MANU")), mode::MANUAL);
+        assert_eq!(flight_mode(Some("ACRO*")), mode::RATE);
+        assert_eq!(flight_mode(Some("AIR")), mode::RATE);
+        assert_eq!(flight_mode(Some("HOR")), mode::HORIZON);
+        assert_eq!(flight_mode(Some("3CRS")), mode::CRUISE);
+        assert_eq!(flight_mode(Some("!FS!")), mode::RATE);
+    }
+}
@@ -0,0 +1,449 @@
+//! # Tracker Telemetry Output
+//!
+//! Re-encodes the CRSF telemetry decoded by the control loop for antenna
+//! trackers, ground OSDs and goggle head trackers that do not read CRSF
+//! (`[tracker]` in the configuration), on a secondary serial port or UDP:
+//!
+//! | Protocol | Frames (when the telemetry is known)                      |
+//! |----------|-----------------------------------------------------------|
+//! | `ltm`    | `G` (GPS), `A` (attitude), `S` (battery, link, arming)    |
+//! | `msp`    | `MSP_RAW_GPS`, `MSP_ATTITUDE`, `MSP_ANALOG`               |
+//!
+//! [`run_tracker`] reads the [`BridgeStatus`] bus like the MAVLink output,
+//! but sends the latest telemetry at a fixed rate: trackers interpolate
+//! between positions and expect a steady stream.
+
+pub mod ltm;
+pub mod msp;
+
+use std::fmt;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::watch;
+use tokio::time::{interval, timeout, Duration, MissedTickBehavior};
+use tracing::{info, warn};
+
+use crate::dashboard::BridgeStatus;
+use crate::error::{FpvBridgeError, Result};
+use crate::mavlink::{is_armed, GcsSink};
+use crate::serial::ElrsSerial;
+use crate::sink::TelemetrySink;
+
+/// Highest supported `rate_hz`
+pub const MAX_TRACKER_RATE_HZ: u32 = 50;
+
+/// Time between two tracker updates at `rate_hz`
+///
+/// Also the write timeout of a [`SerialLine`]: a line that cannot take one
+/// update before the next is due is not draining.
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::tracker::tick_period;
+/// use std::time::Duration;
+///
+/// assert_eq!(tick_period(10), Duration::from_millis(100));
+/// assert_eq!(tick_period(0), Duration::from_secs(1));
+/// ```
+pub fn tick_period(rate_hz: u32) -> Duration {
+    Duration::from_secs(1) / rate_hz.clamp(1, MAX_TRACKER_RATE_HZ)
+}
+
+/// Flight mode name of a flight controller in failsafe
+const FLIGHT_MODE_FAILSAFE: &str = "!FS!";
+
+/// Telemetry protocol of the tracker output
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::tracker::TrackerProtocol;
+///
+/// assert_eq!("ltm".parse::<TrackerProtocol>().unwrap(), TrackerProtocol::Ltm);
+/// assert!("frsky".parse::<TrackerProtocol>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackerProtocol {
+    /// Light Telemetry ([`ltm`])
+    Ltm,
+    /// MSP v2 ([`msp`])
+    Msp,
+}
+
+impl FromStr for TrackerProtocol {
+    type Err = FpvBridgeError;
+
+    fn from_str(protocol: &str) -> Result<Self> {
+        match protocol {
+            "ltm" => Ok(Self::Ltm),
+            "msp" => Ok(Self::Msp),
+            _ => Err(FpvBridgeError::Output(format!(
+                "Invalid tracker protocol '{}': expected ltm or msp",
+                protocol
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for TrackerProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ltm => write!(f, "LTM"),
+            Self::Msp => write!(f, "MSP v2"),
+        }
+    }
+}
+
+impl TrackerProtocol {
+    /// Encode the telemetry known so far
+    ///
+    /// GPS and attitude frames are left out until that telemetry has
+    /// arrived; the LTM status and MSP analog frames are always sent, with
+    /// zero battery values and link quality if unknown.
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - Latest bridge status
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Vec<u8>>` - Complete frames
+    pub fn frames(self, status: &BridgeStatus) -> Vec<Vec<u8>> {
+        let link_quality = status.link.as_ref().map_or(0, |(link, _)| u32::from(link.uplink_lq.min(100)));
+        let mut frames = Vec::with_capacity(3);
+
+        match self {
+            Self::Ltm => {
+                frames.extend(status.gps.as_ref().map(ltm::gps_frame));
+                frames.extend(status.attitude.as_ref().map(ltm::attitude_frame));
+                frames.push(ltm::status_frame(
+                    status.battery.as_ref(),
+                    (link_quality * 254 / 100) as u8,
+                    is_armed(status),
+                    status.flight_mode.as_deref() == Some(FLIGHT_MODE_FAILSAFE),
+                    ltm::flight_mode(status.flight_mode.as_deref()),
+                ));
+            }
+            Self::Msp => {
+                frames.extend(status.gps.as_ref().map(msp::raw_gps));
+                frames.extend(status.attitude.as_ref().map(msp::attitude));
+                frames.push(msp::analog(status.battery.as_ref(), (link_quality * 1023 / 100) as u16));
+            }
+        }
+        frames
+    }
+}
+
+/// Where tracker telemetry goes
+///
+/// | Target          | Output                                      |
+/// |-----------------|---------------------------------------------|
+/// | `serial:PATH`   | Serial port at `baud_rate` ([`SerialLine`]) |
+/// | `udp:HOST:PORT` | One datagram per frame ([`GcsSink`])        |
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::tracker::TrackerTarget;
+///
+/// let target: TrackerTarget = "serial:/dev/ttyUSB1".parse().unwrap();
+/// assert_eq!(target, TrackerTarget::Serial("/dev/ttyUSB1".to_string()));
+/// assert!("udp:tracker.local".parse::<TrackerTarget>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrackerTarget {
+    /// Serial device path
+    Serial(String),
+    /// UDP datagrams to `HOST:PORT`
+    Udp(String),
+}
+
+impl FromStr for TrackerTarget {
+    type Err = FpvBridgeError;
+
+    fn from_str(target: &str) -> Result<Self> {
+        let invalid = |reason: &str| FpvBridgeError::Output(format!("Invalid tracker target '{}': {}", target, reason));
+
+        match target.split_once(':') {
+            Some(("serial", "")) => Err(invalid("missing device path")),
+            Some(("serial", path)) => Ok(Self::Serial(path.to_string())),
+            Some(("udp", address)) => match address.rsplit_once(':') {
+                Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => {
+                    Ok(Self::Udp(address.to_string()))
+                }
+                _ => Err(invalid("expected HOST:PORT")),
+            },
+            _ => Err(invalid("expected serial:PATH or udp:HOST:PORT")),
+        }
+    }
+}
+
+impl fmt::Display for TrackerTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Serial(path) => write!(f, "serial:{}", path),
+            Self::Udp(address) => write!(f, "udp:{}", address),
+        }
+    }
+}
+
+impl TrackerTarget {
+    /// Open the output
+    ///
+    /// # Arguments
+    ///
+    /// * `baud_rate` - Line speed of a serial target
+    /// * `write_timeout` - Longest a serial write may block, see [`tick_period`]
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Box<dyn TelemetrySink>>` - Ready-to-use output
+    ///
+    /// # Errors
+    ///
+    /// Returns `Serial` error if the serial port cannot be opened, or
+    /// `Output` error if the UDP address cannot be resolved.
+    pub async fn open(&self, baud_rate: u32, write_timeout: Duration) -> Result<Box<dyn TelemetrySink>> {
+        Ok(match self {
+            Self::Serial(path) => Box::new(SerialLine::open(path, baud_rate, write_timeout)?),
+            Self::Udp(address) => Box::new(GcsSink::bind(address).await?),
+        })
+    }
+}
+
+/// Write-only serial port for tracker telemetry
+#[derive(Debug)]
+pub struct SerialLine {
+    port: tokio_serial::SerialStream,
+    path: String,
+    write_timeout: Duration,
+}
+
+impl SerialLine {
+    /// Open `path` at `baud_rate`, 8N1
+    ///
+    /// Writes that do not complete within `write_timeout` (a tracker that
+    /// stopped reading, a stuck USB adapter) fail with `SerialStall`
+    /// instead of blocking the output.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Serial` error if the port cannot be opened.
+    pub fn open(path: &str, baud_rate: u32, write_timeout: Duration) -> Result<Self> {
+        Ok(Self {
+            port: ElrsSerial::open_port(path, baud_rate)?,
+            path: path.to_string(),
+            write_timeout,
+        })
+    }
+}
+
+#[async_trait]
+impl TelemetrySink for SerialLine {
+    async fn send(&mut self, frame: &[u8]) -> Result<()> {
+        let path = &self.path;
+        let port = &mut self.port;
+        let write = async {
+            port.write_all(frame)
+                .await
+                .map_err(|e| FpvBridgeError::Serial(format!("Write to {} failed: {}", path, e)))?;
+            port.flush()
+                .await
+                .map_err(|e| FpvBridgeError::Serial(format!("Flush of {} failed: {}", path, e)))
+        };
+
+        match timeout(self.write_timeout, write).await {
+            Ok(result) => result,
+            // Part of the frame may have been written; LTM and MSP parsers
+            // resync on the next header
+            Err(_) => Err(FpvBridgeError::SerialStall(format!(
+                "write to {} did not complete within {}ms",
+                self.path,
+                self.write_timeout.as_millis()
+            ))),
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("serial:{}", self.path)
+    }
+}
+
+/// Send tracker telemetry until the status bus closes
+///
+/// # Arguments
+///
+/// * `sink` - Where frames go, see [`TrackerTarget::open`]
+/// * `protocol` - Telemetry protocol
+/// * `rate_hz` - Updates per second (1 to [`MAX_TRACKER_RATE_HZ`])
+/// * `status` - Status bus of the control loop
+///
+/// Send failures are logged once, and again when sending works again;
+/// they never stop the output.
+pub async fn run_tracker(
+    mut sink: Box<dyn TelemetrySink>,
+    protocol: TrackerProtocol,
+    rate_hz: u32,
+    mut status: watch::Receiver<BridgeStatus>,
+) {
+    let mut ticker = interval(tick_period(rate_hz));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut failing = false;
+    let mut sent: u64 = 0;
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            changed = status.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+                continue;
+            }
+        }
+
+        let frames = protocol.frames(&status.borrow_and_update());
+        for frame in frames {
+            match sink.send(&frame).await {
+                Ok(()) => {
+                    sent += 1;
+                    if std::mem::take(&mut failing) {
+                        info!("Tracker output {} recovered", sink.describe());
+                    }
+                }
+                Err(e) => {
+                    if !std::mem::replace(&mut failing, true) {
+                        warn!("Tracker output failing: {}", e);
+                    }
+                }
+            }
+        }
+    }
+    info!("Tracker output {}: {} {} frames sent", sink.describe(), sent, protocol);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crsf::protocol::{Attitude, BatterySensor, GpsData, LinkStatistics, CRSF_CHANNEL_VALUE_MAX};
+    use crate::controller::channel_mapper::channels;
+    use tokio::net::UdpSocket;
+    use tokio::time::{timeout, Instant};
+
+    fn status() -> BridgeStatus {
+        BridgeStatus::new("default", Duration::from_millis(500))
+    }
+
+    fn link(lq: u8) -> LinkStatistics {
+        LinkStatistics {
+            uplink_rssi_1: 60,
+            uplink_rssi_2: 80,
+            uplink_lq: lq,
+            uplink_snr: 9,
+            active_antenna: 1,
+            rf_mode: 7,
+            uplink_tx_power: 3,
+            downlink_rssi: 50,
+            downlink_lq: 100,
+            downlink_snr: 8,
+        }
+    }
+
+    fn functions(frames: &[Vec<u8>]) -> Vec<u8> {
+        frames.iter().map(|frame| frame[2]).collect()
+    }
+
+    fn commands(frames: &[Vec<u8>]) -> Vec<u16> {
+        frames.iter().map(|frame| u16::from_le_bytes([frame[4], frame[5]])).collect()
+    }
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!("udp:192.168.4.1:5760".parse::<TrackerTarget>().unwrap(), TrackerTarget::Udp("192.168.4.1:5760".to_string()));
+        assert!("serial:".parse::<TrackerTarget>().is_err());
+        assert!("udp:tracker.local:port".parse::<TrackerTarget>().is_err());
+        assert!("/dev/ttyUSB1".parse::<TrackerTarget>().is_err());
+
+        for target in ["serial:/dev/ttyUSB1", "udp:tracker.local:5760"] {
+            assert_eq!(target.parse::<TrackerTarget>().unwrap().to_string(), target);
+        }
+        assert_eq!("msp".parse::<TrackerProtocol>().unwrap(), TrackerProtocol::Msp);
+    }
+
+    #[test]
+    fn test_frames_follow_telemetry() {
+        let mut status = status();
+        assert_eq!(functions(&TrackerProtocol::Ltm.frames(&status)), [b'S']);
+        assert_eq!(commands(&TrackerProtocol::Msp.frames(&status)), [msp::command::MSP_ANALOG]);
+
+        status.gps = Some(GpsData { latitude: 50.45, longitude: -30.5, ground_speed: 36.0, heading: 90.0, altitude: 120, satellites: 9 });
+        status.attitude = Some(Attitude { pitch: 0.25, roll: -0.5, yaw: 1.0 });
+        status.battery = Some(BatterySensor { voltage: 16.42, current: 12.5, capacity_used: 850, remaining_percent: 64 });
+        status.link = Some((link(90), Instant::now()));
+        status.flight_mode = Some("ANGL".to_string());
+        status.channels[channels::ARM] = CRSF_CHANNEL_VALUE_MAX;
+
+        let frames = TrackerProtocol::Ltm.frames(&status);
+        assert_eq!(functions(&frames), [b'G', b'A', b'S']);
+        assert_eq!(frames[2], ltm::status_frame(status.battery.as_ref(), 228, true, false, ltm::mode::ANGLE));
+
+        let frames = TrackerProtocol::Msp.frames(&status);
+        assert_eq!(
+            commands(&frames),
+            [msp::command::MSP_RAW_GPS, msp::command::MSP_ATTITUDE, msp::command::MSP_ANALOG]
+        );
+        assert_eq!(frames[2], msp::analog(status.battery.as_ref(), 920));
+
+        // Betaflight's disarmed marker and failsafe
+        status.flight_mode = Some("!FS!".to_string());
+        let frame = TrackerProtocol::Ltm.frames(&status).pop().unwrap();
+        assert_eq!(frame[9] & 0x03, 0x03);
+        status.flight_mode = Some("ANGL*".to_string());
+        let frame = TrackerProtocol::Ltm.frames(&status).pop().unwrap();
+        assert_eq!(frame[9] & 0x03, 0x00);
+    }
+
+    #[tokio::test]
+    async fn test_run_tracker_over_udp() {
+        let tracker = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let target: TrackerTarget = format!("udp:{}", tracker.local_addr().unwrap()).parse().unwrap();
+        let sink = target.open(115_200, tick_period(50)).await.unwrap();
+        assert_eq!(sink.describe(), target.to_string());
+
+        let (status_tx, status_rx) = watch::channel(status());
+        let output = tokio::spawn(run_tracker(sink, TrackerProtocol::Ltm, 50, status_rx));
+
+        let mut buffer = [0u8; 64];
+        let length = timeout(Duration::from_secs(2), tracker.recv(&mut buffer)).await.unwrap().unwrap();
+        assert_eq!(buffer[..3], [b'$', b'T', b'S']);
+        assert_eq!(length, 11);
+
+        // Steady stream without status changes
+        timeout(Duration::from_secs(2), tracker.recv(&mut buffer)).await.unwrap().unwrap();
+
+        drop(status_tx);
+        timeout(Duration::from_secs(2), output).await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_serial_line_write_times_out() {
+        // Nobody reads the other end of the pty: once its buffer is full,
+        // writes block and must give up after the write timeout
+        let (port, _tracker) = tokio_serial::SerialStream::pair().unwrap();
+        let mut line = SerialLine { port, path: "pty".to_string(), write_timeout: Duration::from_millis(20) };
+
+        let frame = [0x55u8; 256];
+        let start = Instant::now();
+        let error = loop {
+            assert!(start.elapsed() < Duration::from_secs(5), "pty buffer never filled");
+            if let Err(e) = line.send(&frame).await {
+                break e;
+            }
+        };
+        assert!(matches!(error, FpvBridgeError::SerialStall(_)), "{}", error);
+        assert!(error.to_string().contains("within 20ms"));
+    }
+}
@@ -0,0 +1,192 @@
+//! # MSP v2 Telemetry Encoder
+//!
+//! Encodes MultiWii Serial Protocol v2 replies, as a flight controller
+//! would send them, for goggles and head trackers that read MSP telemetry.
+//! Every frame is `$X>`, a flag byte, the command and payload size
+//! (little-endian `u16`), the payload and a CRC8 DVB-S2 of everything from
+//! the flag on:
+//!
+//! | Command        | ID  | Content                                             |
+//! |----------------|-----|-----------------------------------------------------|
+//! | `MSP_RAW_GPS`  | 106 | Fix, satellites, position, altitude, speed, course  |
+//! | `MSP_ATTITUDE` | 108 | Roll, pitch, heading                                |
+//! | `MSP_ANALOG`   | 110 | Battery voltage, use and current, RSSI              |
+//!
+//! Payloads use the Betaflight layouts.
+
+use crate::crsf::crc::crc8_dvb_s2;
+use crate::crsf::protocol::{Attitude, BatterySensor, GpsData};
+use crate::mavlink::GPS_MIN_SATELLITES_3D;
+
+/// Start of every MSP v2 reply
+pub const MSP_V2_REPLY_HEADER: [u8; 3] = [b'$', b'X', b'>'];
+
+/// MSP command IDs
+pub mod command {
+    pub const MSP_RAW_GPS: u16 = 106;
+    pub const MSP_ATTITUDE: u16 = 108;
+    pub const MSP_ANALOG: u16 = 110;
+}
+
+/// `MSP_RAW_GPS` fix value of a 3D fix
+const MSP_GPS_FIX_3D: u8 = 2;
+
+/// Encode an MSP v2 reply
+///
+/// # Arguments
+///
+/// * `command` - MSP command ID
+/// * `payload` - Reply payload
+///
+/// # Returns
+///
+/// * `Vec<u8>` - Complete frame
+///
+/// # Examples
+///
+/// ```
+/// use fpv_bridge::tracker::msp::encode;
+///
+/// // Empty MSP_API_VERSION reply
+/// assert_eq!(encode(1, &[]), [b'$', b'X', b'>', 0x00, 0x01, 0x00, 0x00, 0x00, 0x45]);
+/// ```
+pub fn encode(command: u16, payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(payload.len() + 9);
+    frame.extend_from_slice(&MSP_V2_REPLY_HEADER);
+    frame.push(0);
+    frame.extend_from_slice(&command.to_le_bytes());
+    frame.extend_from_slice(&(payload.len() as u16).to_le_bytes());
+    frame.extend_from_slice(payload);
+    frame.push(crc8_dvb_s2(&frame[MSP_V2_REPLY_HEADER.len()..]));
+    frame
+}
+
+/// Encode `MSP_RAW_GPS`
+///
+/// CRSF has no fix type, so a position from [`GPS_MIN_SATELLITES_3D`]
+/// satellites on is reported as a 3D fix.
+///
+/// # Arguments
+///
+/// * `gps` - GPS telemetry
+///
+/// # Returns
+///
+/// * `Vec<u8>` - Frame with the altitude in meters, the speed in cm/s and
+///   the course in tenths of a degree
+pub fn raw_gps(gps: &GpsData) -> Vec<u8> {
+    let fix = if gps.satellites >= GPS_MIN_SATELLITES_3D { MSP_GPS_FIX_3D } else { 0 };
+    let speed = (gps.ground_speed * 100_000.0 / 3600.0).round().clamp(0.0, f32::from(u16::MAX)) as u16;
+    let course = ((gps.heading * 10.0).round() as i32).rem_euclid(3600) as u16;
+
+    let mut payload = Vec::with_capacity(16);
+    payload.push(fix);
+    payload.push(gps.satellites);
+    payload.extend_from_slice(&((gps.latitude * 10_000_000.0).round() as i32).to_le_bytes());
+    payload.extend_from_slice(&((gps.longitude * 10_000_000.0).round() as i32).to_le_bytes());
+    payload.extend_from_slice(&(gps.altitude.max(0) as u16).to_le_bytes());
+    payload.extend_from_slice(&speed.to_le_bytes());
+    payload.extend_from_slice(&course.to_le_bytes());
+    encode(command::MSP_RAW_GPS, &payload)
+}
+
+/// Encode `MSP_ATTITUDE`
+///
+/// # Arguments
+///
+/// * `attitude` - Attitude telemetry (radians)
+///
+/// # Returns
+///
+/// * `Vec<u8>` - Frame with roll and pitch in tenths of a degree and the
+///   heading in degrees, 0-359
+pub fn attitude(attitude: &Attitude) -> Vec<u8> {
+    let decidegrees = |radians: f32| (radians.to_degrees() * 10.0).round() as i16;
+    let heading = (attitude.yaw.to_degrees().round() as i16).rem_euclid(360);
+
+    let mut payload = Vec::with_capacity(6);
+    payload.extend_from_slice(&decidegrees(attitude.roll).to_le_bytes());
+    payload.extend_from_slice(&decidegrees(attitude.pitch).to_le_bytes());
+    payload.extend_from_slice(&heading.to_le_bytes());
+    encode(command::MSP_ATTITUDE, &payload)
+}
+
+/// Encode `MSP_ANALOG`
+///
+/// # Arguments
+///
+/// * `battery` - Battery telemetry, zero voltage, use and current without it
+/// * `rssi` - Link quality, 0-1023
+///
+/// # Returns
+///
+/// * `Vec<u8>` - Frame with the voltage in tenths and hundredths of a
+///   volt, the capacity used in mAh and the current in hundredths of an
+///   ampere
+pub fn analog(battery: Option<&BatterySensor>, rssi: u16) -> Vec<u8> {
+    let (voltage, used, current) = battery.map_or((0, 0, 0), |battery| {
+        (
+            (battery.voltage * 100.0).round().clamp(0.0, f32::from(u16::MAX)) as u16,
+            battery.capacity_used.min(u32::from(u16::MAX)) as u16,
+            (battery.current * 100.0).round().clamp(0.0, f32::from(i16::MAX)) as i16,
+        )
+    });
+
+    let mut payload = Vec::with_capacity(9);
+    payload.push(((u32::from(voltage) + 5) / 10).min(u32::from(u8::MAX)) as u8);
+    payload.extend_from_slice(&used.to_le_bytes());
+    payload.extend_from_slice(&rssi.min(1023).to_le_bytes());
+    payload.extend_from_slice(&current.to_le_bytes());
+    payload.extend_from_slice(&voltage.to_le_bytes());
+    encode(command::MSP_ANALOG, &payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Expected frames are from the reference implementation
+    // (multiwii_serial_protocol_v2)
+
+    #[test]
+    fn test_raw_gps() {
+        // 3D fix, 9 satellites, 50.45°, -30.5°, 120 m, 1000 cm/s, 90.0°
+        let gps = GpsData { latitude: 50.45, longitude: -30.5, ground_speed: 36.0, heading: 90.0, altitude: 120, satellites: 9 };
+        assert_eq!(
+            raw_gps(&gps),
+            [
+                0x24, 0x58, 0x3E, 0x00, 0x6A, 0x00, 0x10, 0x00, 0x02, 0x09, 0x20, 0x0F, 0x12, 0x1E, 0xC0, 0x11,
+                0xD2, 0xED, 0x78, 0x00, 0xE8, 0x03, 0x84, 0x03, 0x68
+            ]
+        );
+
+        let frame = raw_gps(&GpsData { satellites: 3, altitude: -20, heading: -90.0, ..gps });
+        assert_eq!(frame[8], 0);
+        assert_eq!(frame[18..20], [0, 0]);
+        assert_eq!(u16::from_le_bytes([frame[22], frame[23]]), 2700);
+    }
+
+    #[test]
+    fn test_attitude() {
+        // Roll -28.6°, pitch 14.3°, heading 57°
+        let frame = attitude(&Attitude { pitch: 0.25, roll: -0.5, yaw: 1.0 });
+        assert_eq!(frame, [0x24, 0x58, 0x3E, 0x00, 0x6C, 0x00, 0x06, 0x00, 0xE2, 0xFE, 0x8F, 0x00, 0x39, 0x00, 0x4B]);
+    }
+
+    #[test]
+    fn test_analog() {
+        // 16.4 V, 850 mAh, RSSI 921, 12.5 A, 16.42 V
+        let battery = BatterySensor { voltage: 16.42, current: 12.5, capacity_used: 850, remaining_percent: 64 };
+        assert_eq!(
+            analog(Some(&battery), 921),
+            [
+                0x24, 0x58, 0x3E, 0x00, 0x6E, 0x00, 0x09, 0x00, 0xA4, 0x52, 0x03, 0x99, 0x03, 0xE2, 0x04, 0x6A,
+                0x06, 0xDE
+            ]
+        );
+
+        let frame = analog(None, 2000);
+        assert_eq!(frame[8..11], [0, 0, 0]);
+        assert_eq!(u16::from_le_bytes([frame[11], frame[12]]), 1023);
+    }
+}
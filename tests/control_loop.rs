@@ -0,0 +1,222 @@
+//! End-to-end test of the control loop: scripted controller input through
+//! the profile mapping and CRSF encoder to a virtual ELRS module
+//! (`serial.port = "virtual"`), and the module's telemetry back onto the
+//! status bus.
+
+use std::io::Write;
+use std::time::Duration;
+
+use tokio::sync::{oneshot, watch};
+use tokio::time::{sleep, Instant};
+
+use fpv_bridge::config::Config;
+use fpv_bridge::control::{initial_status, spawn_controller_reader, ControlLoop};
+use fpv_bridge::controller::channel_mapper::{channels, SWITCH_OFF, SWITCH_ON};
+use fpv_bridge::controller::mapper::ControllerState;
+use fpv_bridge::controller::profile::ProfileManager;
+use fpv_bridge::controller::script::{InputScript, ScriptedController};
+use fpv_bridge::crsf::protocol::{BatterySensor, LinkStatistics, CRSF_CHANNEL_VALUE_CENTER};
+use fpv_bridge::dashboard::BridgeStatus;
+use fpv_bridge::serial::virtual_module::VirtualModule;
+use fpv_bridge::serial::ElrsSerial;
+use fpv_bridge::sink::TeeSink;
+
+const CONFIG: &str = r#"
+[serial]
+port = "virtual"
+
+[controller]
+[channels]
+
+[telemetry]
+enabled = false
+
+[safety]
+failsafe_timeout_ms = 300
+
+[crsf]
+"#;
+
+/// Throttle down and L1 (arm) held, then full throttle
+const ARM_SCRIPT: &str = "
+0     ABS_Y   255
+0     BTN_TL  1
++300  ABS_Y   0
+";
+
+/// How long each condition may take to come true
+const WAIT_TIMEOUT: Duration = Duration::from_secs(3);
+
+fn link_statistics(uplink_lq: u8) -> LinkStatistics {
+    LinkStatistics {
+        uplink_rssi_1: 50,
+        uplink_rssi_2: 52,
+        uplink_lq,
+        uplink_snr: 9,
+        active_antenna: 0,
+        rf_mode: 7,
+        uplink_tx_power: 3,
+        downlink_rssi: 55,
+        downlink_lq: 100,
+        downlink_snr: 8,
+    }
+}
+
+/// Poll `condition` until it holds, failing the test after [`WAIT_TIMEOUT`]
+async fn wait_for(what: &str, mut condition: impl FnMut() -> bool) {
+    let deadline = Instant::now() + WAIT_TIMEOUT;
+    while !condition() {
+        assert!(Instant::now() < deadline, "timed out waiting for {}", what);
+        sleep(Duration::from_millis(10)).await;
+    }
+}
+
+async fn scenario(module: VirtualModule, status: watch::Receiver<BridgeStatus>) {
+    // RC frames reach the module: armed with throttle down, then full throttle
+    wait_for("armed RC frames", || module.channels().is_some_and(|rc| rc[channels::ARM] == SWITCH_ON)).await;
+    assert!(module.channels().unwrap()[channels::THROTTLE] < CRSF_CHANNEL_VALUE_CENTER);
+    wait_for("full throttle", || {
+        module.channels().is_some_and(|rc| rc[channels::THROTTLE] > CRSF_CHANNEL_VALUE_CENTER)
+    })
+    .await;
+
+    // Telemetry round trip: what the module reports ends up on the status bus
+    wait_for("telemetry", || {
+        let status = status.borrow();
+        status.link.is_some() && status.gps.is_some() && status.flight_mode.as_deref() == Some("ACRO")
+    })
+    .await;
+    assert_eq!(status.borrow().gps.unwrap().satellites, 12);
+    assert!(status.borrow().armed());
+
+    module.set_battery(BatterySensor { voltage: 14.2, current: 12.5, capacity_used: 350, remaining_percent: 60 });
+    wait_for("updated battery", || status.borrow().battery.is_some_and(|battery| battery.remaining_percent == 60))
+        .await;
+    assert!(status.borrow().failsafe_reasons(Instant::now()).is_empty());
+
+    // Link loss: no telemetry for longer than the failsafe timeout
+    module.set_link_lost(true);
+    let rc_frames = module.stats().rc_frames;
+    wait_for("no link telemetry failsafe", || {
+        status.borrow().failsafe_reasons(Instant::now()).contains(&"no link telemetry")
+    })
+    .await;
+    // RC frames keep flowing while the link is down
+    assert!(module.stats().rc_frames > rc_frames + 10);
+
+    // Link back, but the receiver reports zero link quality
+    module.set_link_statistics(link_statistics(0));
+    module.set_link_lost(false);
+    wait_for("link lost failsafe", || status.borrow().failsafe_reasons(Instant::now()) == ["link lost"]).await;
+
+    // Recovered link clears the failsafe
+    module.set_link_statistics(link_statistics(100));
+    wait_for("failsafe to clear", || status.borrow().failsafe_reasons(Instant::now()).is_empty()).await;
+
+    let status = status.borrow();
+    assert!(status.frames_sent > 100);
+    assert_eq!(status.send_errors, 0);
+    assert_eq!(status.channels, module.channels().unwrap());
+}
+
+#[tokio::test]
+async fn test_scripted_flight_against_virtual_module() {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all(CONFIG.as_bytes()).unwrap();
+    let config = Config::load(file.path()).unwrap();
+    let profiles = ProfileManager::new(config, None).unwrap();
+
+    let serial = ElrsSerial::connect(&profiles.active().config.serial).await.unwrap();
+    let module = serial.virtual_module().expect("port = \"virtual\" keeps the module handle").clone();
+
+    let script: InputScript = ARM_SCRIPT.parse().unwrap();
+    let (state_tx, state_rx) = watch::channel(ControllerState::default());
+    spawn_controller_reader(Box::new(ScriptedController::new(script, "arm")), state_tx);
+
+    let (status_tx, status_rx) = watch::channel(initial_status(&profiles));
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let control = ControlLoop::new(profiles, Some(serial), TeeSink::new(), state_rx, status_tx);
+
+    let (result, ()) = tokio::join!(
+        control.run(async {
+            let _ = shutdown_rx.await;
+        }),
+        async {
+            scenario(module.clone(), status_rx).await;
+            shutdown_tx.send(()).unwrap();
+        },
+    );
+    result.unwrap();
+    assert_eq!(module.stats().rx_crc_errors, 0);
+}
+
+#[tokio::test]
+async fn test_released_input_disarms() {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all(CONFIG.as_bytes()).unwrap();
+    let profiles = ProfileManager::new(Config::load(file.path()).unwrap(), None).unwrap();
+
+    let serial = ElrsSerial::connect(&profiles.active().config.serial).await.unwrap();
+    let module = serial.virtual_module().unwrap().clone();
+
+    // The controller state bus drives the loop directly: armed, then released
+    let (state_tx, state_rx) = watch::channel(ControllerState { btn_l1: true, ..ControllerState::default() });
+    let (status_tx, _status_rx) = watch::channel(initial_status(&profiles));
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let control = ControlLoop::new(profiles, Some(serial), TeeSink::new(), state_rx, status_tx);
+
+    let (result, ()) = tokio::join!(
+        control.run(async {
+            let _ = shutdown_rx.await;
+        }),
+        async {
+            wait_for("armed", || module.channels().is_some_and(|rc| rc[channels::ARM] == SWITCH_ON)).await;
+            state_tx.send_replace(ControllerState::default());
+            wait_for("disarmed", || module.channels().is_some_and(|rc| rc[channels::ARM] == SWITCH_OFF)).await;
+            shutdown_tx.send(()).unwrap();
+        },
+    );
+    result.unwrap();
+}
+
+#[tokio::test]
+async fn test_high_resolution_sticks_keep_switches() {
+    let config = CONFIG.replace("[crsf]", "[crsf]\nstick_resolution = 12");
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all(config.as_bytes()).unwrap();
+    let profiles = ProfileManager::new(Config::load(file.path()).unwrap(), None).unwrap();
+
+    let serial = ElrsSerial::connect(&profiles.active().config.serial).await.unwrap();
+    let module = serial.virtual_module().unwrap().clone();
+
+    // Sticks travel in 12-bit subset frames, the arm switch in standard frames
+    let armed = ControllerState { btn_l1: true, left_stick_y: 0, ..ControllerState::default() };
+    let (state_tx, state_rx) = watch::channel(armed.clone());
+    let (status_tx, _status_rx) = watch::channel(initial_status(&profiles));
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let control = ControlLoop::new(profiles, Some(serial), TeeSink::new(), state_rx, status_tx);
+
+    let (result, ()) = tokio::join!(
+        control.run(async {
+            let _ = shutdown_rx.await;
+        }),
+        async {
+            wait_for("armed at full throttle", || {
+                module.channels().is_some_and(|rc| rc[channels::ARM] == SWITCH_ON && rc[channels::THROTTLE] == SWITCH_ON)
+            })
+            .await;
+
+            // Stick movement alone keeps the switches where they are
+            state_tx.send_replace(ControllerState { left_stick_y: 255, ..armed });
+            wait_for("throttle down", || module.channels().is_some_and(|rc| rc[channels::THROTTLE] < CRSF_CHANNEL_VALUE_CENTER))
+                .await;
+            assert_eq!(module.channels().unwrap()[channels::ARM], SWITCH_ON);
+
+            state_tx.send_replace(ControllerState { left_stick_y: 255, ..ControllerState::default() });
+            wait_for("disarmed", || module.channels().is_some_and(|rc| rc[channels::ARM] == SWITCH_OFF)).await;
+            shutdown_tx.send(()).unwrap();
+        },
+    );
+    result.unwrap();
+    assert_eq!(module.stats().rx_crc_errors, 0);
+}